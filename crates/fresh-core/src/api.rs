@@ -51,6 +51,7 @@ use crate::overlay::{OverlayHandle, OverlayNamespace};
 use crate::text_property::{TextProperty, TextPropertyEntry};
 use crate::BufferId;
 use crate::SplitId;
+use crate::TerminalId;
 use lsp_types;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -147,6 +148,22 @@ pub struct VirtualBufferResult {
     pub split_id: Option<u64>,
 }
 
+/// Result of creating a terminal
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, rename_all = "camelCase")]
+pub struct TerminalResult {
+    /// The terminal ID, for sendTerminalInput and the terminal hooks
+    #[ts(type = "number")]
+    pub terminal_id: u64,
+    /// The buffer showing the terminal
+    #[ts(type = "number")]
+    pub buffer_id: u64,
+    /// The split the terminal was opened in
+    #[ts(type = "number")]
+    pub split_id: u64,
+}
+
 /// Response from the editor for async plugin operations
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -241,6 +258,36 @@ pub struct CursorInfo {
     pub selection: Option<Range<usize>>,
 }
 
+/// How a cursor's selection extends from its anchor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum SelectionKind {
+    /// Character-wise selection between anchor and position
+    #[default]
+    Normal,
+    /// Rectangular selection with anchor and position as opposite corners
+    Block,
+}
+
+/// A cursor and its selection, used by `setSelections` and the
+/// `selection_changed` hook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct SelectionSpec {
+    /// Byte offset where the selection starts (omit for a bare cursor)
+    #[serde(default)]
+    #[ts(optional)]
+    pub anchor: Option<usize>,
+    /// Byte offset of the cursor itself
+    pub position: usize,
+    /// Selection shape (default "normal")
+    #[serde(default)]
+    #[ts(optional)]
+    pub mode: Option<SelectionKind>,
+}
+
 /// Specification for an action to execute, with optional repeat count
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(deny_unknown_fields)]
@@ -272,6 +319,23 @@ pub struct BufferInfo {
     pub modified: bool,
     /// Length of buffer in bytes
     pub length: usize,
+    /// Edit counter, bumped on every change to the buffer's text.
+    /// Pass it to `applyEdits` to detect edits made since it was read.
+    #[ts(type = "number")]
+    pub version: u64,
+}
+
+/// One replacement in a transactional multi-edit
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct BufferEdit {
+    /// Start byte offset of the replaced range
+    pub start: usize,
+    /// End byte offset (exclusive); equal to `start` for a pure insertion
+    pub end: usize,
+    /// Replacement text (empty for a pure deletion)
+    pub text: String,
 }
 
 fn serialize_path<S: serde::Serializer>(path: &Option<PathBuf>, s: S) -> Result<S::Ok, S::Error> {
@@ -746,6 +810,19 @@ pub enum PluginCommand {
         range: Range<usize>,
     },
 
+    /// Apply several edits to a buffer as a single undo step, but only if
+    /// the buffer is still at `expected_version`.
+    /// Resolves the callback with `{ applied, version }`.
+    ApplyEdits {
+        buffer_id: BufferId,
+        #[ts(type = "number")]
+        expected_version: u64,
+        edits: Vec<BufferEdit>,
+        /// Undo history label (defaults to "Plugin edit")
+        label: Option<String>,
+        callback_id: JsCallbackId,
+    },
+
     /// Add an overlay to a buffer, returns handle via response channel
     ///
     /// Colors can be specified as RGB tuples or theme keys. When theme keys
@@ -767,6 +844,26 @@ pub enum PluginCommand {
     /// Set status message
     SetStatus { message: String },
 
+    /// Add or replace one of a plugin's status bar items
+    SetStatusBarItem {
+        plugin_name: String,
+        id: String,
+        options: StatusBarItemOptions,
+    },
+
+    /// Change the text of an existing status bar item, keeping its style
+    SetStatusBarItemText {
+        plugin_name: String,
+        id: String,
+        text: String,
+    },
+
+    /// Remove one of a plugin's status bar items
+    RemoveStatusBarItem { plugin_name: String, id: String },
+
+    /// Remove all status bar items of a plugin (sent when it is unloaded)
+    RemoveStatusBarItems { plugin_name: String },
+
     /// Apply a theme by name
     ApplyTheme { theme_name: String },
 
@@ -819,6 +916,55 @@ pub enum PluginCommand {
     /// Kill a background process by ID
     KillBackgroundProcess { process_id: u64 },
 
+    /// Spawn a process with piped stdin for interactive use.
+    /// Output is streamed like a background process's; the callback is
+    /// resolved with a `BackgroundProcessResult` when the process exits.
+    SpawnInteractiveProcess {
+        plugin_name: String,
+        /// Unique ID for this process (generated by plugin runtime)
+        process_id: u64,
+        /// Command to execute
+        command: String,
+        /// Arguments to pass
+        args: Vec<String>,
+        /// Working directory, environment and output mode
+        options: InteractiveProcessOptions,
+        /// Callback ID to call when process exits
+        callback_id: JsCallbackId,
+    },
+
+    /// Write text to the stdin of an interactive process
+    WriteProcessStdin {
+        plugin_name: String,
+        process_id: u64,
+        data: String,
+    },
+
+    /// Close the stdin of an interactive process
+    CloseProcessStdin {
+        plugin_name: String,
+        process_id: u64,
+    },
+
+    /// Kill all interactive processes of a plugin (sent when it is unloaded)
+    KillInteractiveProcesses { plugin_name: String },
+
+    /// Start reporting changes below a path through the `path_changed` hook
+    WatchPath {
+        plugin_name: String,
+        /// Unique ID for this watch (generated by plugin runtime)
+        watch_id: u64,
+        /// Absolute file or directory path
+        path: String,
+        options: WatchPathOptions,
+    },
+
+    /// Stop one of a plugin's path watches
+    UnwatchPath { plugin_name: String, watch_id: u64 },
+
+    /// Stop all path watches of a plugin (sent when it is unloaded)
+    UnwatchPaths { plugin_name: String },
+
     /// Wait for a process to complete and get its result
     /// Used with processes started via SpawnProcess
     SpawnProcessWait {
@@ -828,6 +974,46 @@ pub enum PluginCommand {
         callback_id: JsCallbackId,
     },
 
+    /// Open an integrated terminal running a shell or a given command.
+    /// Resolves the callback with a `TerminalResult`.
+    CreateTerminal {
+        plugin_name: String,
+        options: CreateTerminalOptions,
+        callback_id: JsCallbackId,
+    },
+
+    /// Write input to a terminal's process, as if typed
+    SendTerminalInput {
+        plugin_name: String,
+        terminal_id: TerminalId,
+        data: String,
+    },
+
+    /// Read a terminal's scrollback and screen as plain text.
+    /// Resolves the callback with a string.
+    GetTerminalScrollback {
+        plugin_name: String,
+        terminal_id: TerminalId,
+        callback_id: JsCallbackId,
+    },
+
+    /// Fire the `terminal_output_matched` hook for each line of a
+    /// terminal's output matching a regex
+    WatchTerminalOutput {
+        plugin_name: String,
+        terminal_id: TerminalId,
+        pattern: String,
+    },
+
+    /// Close a terminal, stopping its process and closing its buffer
+    CloseTerminal {
+        plugin_name: String,
+        terminal_id: TerminalId,
+    },
+
+    /// Close all terminals a plugin opened (sent when it is unloaded)
+    CloseTerminals { plugin_name: String },
+
     /// Set layout hints for a buffer/viewport
     SetLayoutHints {
         buffer_id: BufferId,
@@ -1180,6 +1366,32 @@ pub enum PluginCommand {
         request_id: u64,
     },
 
+    /// Get the syntax nodes containing a byte offset, outermost first.
+    /// Resolves the callback with `SyntaxNode[]`.
+    GetSyntaxNodePath {
+        buffer_id: BufferId,
+        offset: usize,
+        callback_id: JsCallbackId,
+    },
+
+    /// Get the innermost syntax node of `kind` containing a byte offset.
+    /// Resolves the callback with a `SyntaxNode` or null.
+    GetEnclosingSyntaxNode {
+        buffer_id: BufferId,
+        offset: usize,
+        kind: String,
+        callback_id: JsCallbackId,
+    },
+
+    /// Run a tree-sitter query against a buffer, optionally limited to a
+    /// byte range. Resolves the callback with `SyntaxCapture[]`.
+    QuerySyntax {
+        buffer_id: BufferId,
+        query: String,
+        range: Option<Range<usize>>,
+        callback_id: JsCallbackId,
+    },
+
     /// Close a split (if not the last one)
     CloseSplit { split_id: SplitId },
 
@@ -1203,6 +1415,10 @@ pub enum PluginCommand {
         position: usize,
     },
 
+    /// Replace all cursors of the active buffer with the given selections
+    /// (the last one becomes the primary cursor)
+    SetSelections { selections: Vec<SelectionSpec> },
+
     /// Send an arbitrary LSP request and return the raw JSON response
     SendLspRequest {
         language: String,
@@ -1212,6 +1428,29 @@ pub enum PluginCommand {
         request_id: u64,
     },
 
+    /// A plugin started providing a language feature for a language
+    RegisterLanguageProvider {
+        plugin_name: String,
+        feature: LanguageFeature,
+        language: String,
+    },
+
+    /// A plugin stopped providing a language feature for a language
+    UnregisterLanguageProvider {
+        plugin_name: String,
+        feature: LanguageFeature,
+        language: String,
+    },
+
+    /// A language feature provider answered a request
+    /// (`result` is null if the provider failed or had nothing to offer)
+    LanguageProviderResult {
+        plugin_name: String,
+        request_id: u64,
+        #[ts(type = "any")]
+        result: JsonValue,
+    },
+
     /// Set the internal clipboard content
     SetClipboard { text: String },
 
@@ -1290,6 +1529,14 @@ pub enum PluginCommand {
         actions: Vec<ActionPopupAction>,
     },
 
+    /// Show a modal form dialog
+    /// Edits fire the FormFieldChanged hook; pressing a button or dismissing
+    /// the form fires the FormResult hook
+    ShowForm { options: FormOptions },
+
+    /// Close a form dialog without firing FormResult
+    CloseForm { form_id: String },
+
     /// Disable LSP for a specific language and persist to config
     DisableLspForLanguage {
         /// The language to disable LSP for (e.g., "python", "rust")
@@ -1376,6 +1623,27 @@ pub enum PluginCommand {
         callback_id: JsCallbackId,
     },
 
+    /// A plugin was disabled by the runtime for exceeding its time budget or
+    /// the memory limit (sent by the plugin runtime, not by plugins)
+    PluginDisabled {
+        /// Plugin name (as registered)
+        name: String,
+        /// Which limit was exceeded
+        reason: String,
+    },
+
+    /// A plugin package needs capabilities the user has not granted yet; it
+    /// is not loaded until they are (sent by the plugin runtime, not by plugins)
+    RequestPluginCapabilities {
+        /// Plugin name (as registered)
+        name: String,
+        /// Path to the plugin's entry file, to load it once granted
+        path: PathBuf,
+        /// Capabilities declared in the package manifest
+        #[ts(type = "any")]
+        requested: crate::config::PluginCapabilities,
+    },
+
     /// Reload the theme registry from disk
     /// Call this after installing a theme package or saving a new theme
     ReloadThemes,
@@ -1539,6 +1807,101 @@ pub struct ActionPopupOptions {
     pub actions: Vec<ActionPopupAction>,
 }
 
+/// Control kinds a form field can use
+pub const FORM_FIELD_KINDS: &[&str] = &["text", "number", "toggle", "dropdown", "textList"];
+
+/// A field of a form dialog
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct FormField {
+    /// Field id, the key of the field in the form values
+    pub id: String,
+    /// Label shown next to the control
+    pub label: String,
+    /// Control kind: "text", "number", "toggle", "dropdown" or "textList"
+    pub kind: String,
+    /// Initial value: a string, number, boolean, dropdown option or string array
+    #[serde(default)]
+    #[ts(optional, type = "string | number | boolean | string[]")]
+    pub value: Option<JsonValue>,
+    /// Choices of a dropdown
+    #[serde(default)]
+    #[ts(optional)]
+    pub options: Option<Vec<String>>,
+    /// Hint shown in an empty text field
+    #[serde(default)]
+    #[ts(optional)]
+    pub placeholder: Option<String>,
+    /// Smallest value of a number field
+    #[serde(default)]
+    #[ts(optional, type = "number")]
+    pub min: Option<i64>,
+    /// Largest value of a number field
+    #[serde(default)]
+    #[ts(optional, type = "number")]
+    pub max: Option<i64>,
+    /// Increment of a number field (default: 1)
+    #[serde(default)]
+    #[ts(optional, type = "number")]
+    pub step: Option<i64>,
+}
+
+/// A button along the bottom of a form dialog
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct FormButton {
+    /// Button id, reported in the form_result hook
+    pub id: String,
+    /// Button text
+    pub label: String,
+}
+
+/// Options for showForm
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct FormOptions {
+    /// Form id, passed to the form hooks
+    pub id: String,
+    /// Title of the dialog
+    pub title: String,
+    /// Fields, top to bottom
+    pub fields: Vec<FormField>,
+    /// Buttons (default: "OK" and "Cancel" with ids "ok" and "cancel").
+    /// Enter in a text field presses the first one.
+    #[serde(default)]
+    #[ts(optional)]
+    pub buttons: Option<Vec<FormButton>>,
+}
+
+impl FormOptions {
+    /// Check field kinds, ids and dropdown options
+    pub fn validate(&self) -> Result<(), String> {
+        let mut ids = std::collections::HashSet::new();
+        for field in &self.fields {
+            if !FORM_FIELD_KINDS.contains(&field.kind.as_str()) {
+                return Err(format!(
+                    "Unknown form field kind '{}' (expected one of: {})",
+                    field.kind,
+                    FORM_FIELD_KINDS.join(", ")
+                ));
+            }
+            if !ids.insert(field.id.as_str()) {
+                return Err(format!("Duplicate form field id '{}'", field.id));
+            }
+            if field.kind == "dropdown" && field.options.as_ref().is_none_or(|o| o.is_empty()) {
+                return Err(format!("Dropdown field '{}' needs options", field.id));
+            }
+        }
+        if self.buttons.as_ref().is_some_and(|b| b.is_empty()) {
+            return Err("A form needs at least one button".to_string());
+        }
+        Ok(())
+    }
+}
+
 /// Syntax highlight span for a buffer range
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -1551,6 +1914,32 @@ pub struct TsHighlightSpan {
     pub italic: bool,
 }
 
+/// A node of a buffer's syntax tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SyntaxNode {
+    /// Grammar node type (e.g., "function_item", "identifier")
+    pub kind: String,
+    /// Start byte offset
+    pub start: usize,
+    /// End byte offset (exclusive)
+    pub end: usize,
+}
+
+/// A capture produced by running a tree-sitter query with `querySyntax`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SyntaxCapture {
+    /// Capture name from the query, without the leading `@`
+    pub name: String,
+    /// Grammar node type of the captured node
+    pub kind: String,
+    /// Start byte offset
+    pub start: usize,
+    /// End byte offset (exclusive)
+    pub end: usize,
+}
+
 /// Result from spawning a process with spawnProcess
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -1575,6 +1964,42 @@ pub struct BackgroundProcessResult {
     pub exit_code: i32,
 }
 
+/// Options for spawnInteractiveProcess
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct InteractiveProcessOptions {
+    /// Working directory (default: the editor's working directory)
+    #[serde(default)]
+    #[ts(optional)]
+    pub cwd: Option<String>,
+    /// Extra environment variables for the process
+    #[serde(default)]
+    #[ts(optional, type = "Record<string, string>")]
+    pub env: Option<HashMap<String, String>>,
+    /// How output is delivered to the process output hooks: "lines" (one
+    /// event per line, the default) or "chunks" (as soon as it is read)
+    #[serde(default)]
+    #[ts(optional)]
+    pub output: Option<String>,
+}
+
+/// Options for watchPath
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct WatchPathOptions {
+    /// Also report changes in subdirectories (default: false)
+    #[serde(default)]
+    #[ts(optional)]
+    pub recursive: Option<bool>,
+    /// Only report paths matching this glob, relative to the watched
+    /// directory (e.g. "*.xml" or "reports/**")
+    #[serde(default)]
+    #[ts(optional)]
+    pub glob: Option<String>,
+}
+
 /// Entry for virtual buffer content with optional text properties (JS API version)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(deny_unknown_fields)]
@@ -1637,6 +2062,115 @@ pub struct JsDiagnostic {
     pub source: Option<String>,
 }
 
+/// A language feature plugins can provide alongside language servers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum LanguageFeature {
+    Completion,
+    Hover,
+    Definition,
+    CodeAction,
+    Diagnostics,
+}
+
+impl LanguageFeature {
+    /// Parse the feature name used by the JS API
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "completion" => Some(Self::Completion),
+            "hover" => Some(Self::Hover),
+            "definition" => Some(Self::Definition),
+            "codeAction" => Some(Self::CodeAction),
+            "diagnostics" => Some(Self::Diagnostics),
+            _ => None,
+        }
+    }
+}
+
+/// What a language feature provider is asked for
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LanguageFeatureRequest {
+    /// The feature being requested
+    pub feature: LanguageFeature,
+    /// Language of the buffer
+    pub language: String,
+    /// Buffer the request is for
+    #[ts(type = "number")]
+    pub buffer_id: BufferId,
+    /// File path of the buffer
+    #[ts(type = "string")]
+    pub path: PathBuf,
+    /// Zero-indexed line of the cursor (0 for diagnostics)
+    pub line: u32,
+    /// Zero-indexed UTF-16 character offset of the cursor (0 for diagnostics)
+    pub character: u32,
+}
+
+/// A completion item returned by a completion provider
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProviderCompletionItem {
+    /// Text shown in the completion popup
+    pub label: String,
+    /// Extra detail shown next to the label
+    #[serde(default)]
+    #[ts(optional)]
+    pub detail: Option<String>,
+    /// Text inserted when accepted (defaults to the label)
+    #[serde(default)]
+    #[ts(optional)]
+    pub insert_text: Option<String>,
+    /// Text matched against the typed prefix (defaults to the label)
+    #[serde(default)]
+    #[ts(optional)]
+    pub filter_text: Option<String>,
+    /// "function", "method", "variable", "struct", "class", "constant" or "keyword"
+    #[serde(default)]
+    #[ts(optional)]
+    pub kind: Option<String>,
+}
+
+/// A location returned by a definition provider
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProviderLocation {
+    /// File path
+    pub path: String,
+    /// Zero-indexed line number
+    pub line: u32,
+    /// Zero-indexed character offset
+    #[serde(default)]
+    pub character: u32,
+}
+
+/// A code action returned by a code action provider
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProviderCodeAction {
+    /// Title shown in the code actions menu
+    pub title: String,
+}
+
+/// A diagnostic returned by a diagnostics provider
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProviderDiagnostic {
+    /// Diagnostic message
+    pub message: String,
+    /// Severity: 1=Error, 2=Warning, 3=Info, 4=Hint (defaults to Error)
+    #[serde(default)]
+    #[ts(optional)]
+    pub severity: Option<u8>,
+    /// Range in the document
+    pub range: JsRange,
+    /// Source of the diagnostic (defaults to the plugin name)
+    #[serde(default)]
+    #[ts(optional)]
+    pub source: Option<String>,
+}
+
 /// Options for createVirtualBuffer
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(deny_unknown_fields)]
@@ -1764,6 +2298,70 @@ pub struct CreateVirtualBufferInExistingSplitOptions {
     pub entries: Option<Vec<JsTextPropertyEntry>>,
 }
 
+/// Options for createTerminal
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct CreateTerminalOptions {
+    /// Program to run instead of the user's shell
+    #[serde(default)]
+    #[ts(optional)]
+    pub command: Option<String>,
+    /// Arguments for `command`
+    #[serde(default)]
+    #[ts(optional)]
+    pub args: Option<Vec<String>>,
+    /// Working directory (default: the editor's working directory)
+    #[serde(default)]
+    #[ts(optional)]
+    pub cwd: Option<String>,
+    /// Extra environment variables for the process
+    #[serde(default)]
+    #[ts(optional, type = "Record<string, string>")]
+    pub env: Option<HashMap<String, String>>,
+    /// Open as a tab in this existing split
+    #[serde(default, rename = "splitId")]
+    #[ts(optional, rename = "splitId")]
+    pub split_id: Option<usize>,
+    /// Open in a new split of the active split: "horizontal" or "vertical"
+    #[serde(default)]
+    #[ts(optional)]
+    pub direction: Option<String>,
+    /// Size of the new split, 0.0-1.0 (default: 0.5)
+    #[serde(default)]
+    #[ts(optional)]
+    pub ratio: Option<f32>,
+    /// Focus the terminal and enter terminal mode (default: true)
+    #[serde(default)]
+    #[ts(optional)]
+    pub focus: Option<bool>,
+}
+
+/// Options for setStatusBarItem
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct StatusBarItemOptions {
+    /// Text to show
+    pub text: String,
+    /// Theme key for the text colour (e.g. "ui.status_bar_fg")
+    #[serde(default)]
+    #[ts(optional)]
+    pub fg: Option<String>,
+    /// Theme key for the background colour (e.g. "ui.status_bar_bg")
+    #[serde(default)]
+    #[ts(optional)]
+    pub bg: Option<String>,
+    /// Side of the status bar: "left" or "right" (default: "right")
+    #[serde(default)]
+    #[ts(optional)]
+    pub alignment: Option<String>,
+    /// Items on the same side are ordered by priority, highest first (default: 0)
+    #[serde(default)]
+    #[ts(optional)]
+    pub priority: Option<i32>,
+}
+
 /// Result of getTextPropertiesAtCursor - array of property objects
 ///
 /// Each element contains the properties from a text property span that overlaps
@@ -1788,6 +2386,26 @@ mod fromjs_impls {
         }
     }
 
+    impl<'js> FromJs<'js> for SelectionSpec {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "SelectionSpec",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
+    impl<'js> FromJs<'js> for BufferEdit {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "BufferEdit",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
     impl<'js> FromJs<'js> for CreateVirtualBufferOptions {
         fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
             rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
@@ -1808,6 +2426,56 @@ mod fromjs_impls {
         }
     }
 
+    impl<'js> FromJs<'js> for FormOptions {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "FormOptions",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
+    impl<'js> FromJs<'js> for StatusBarItemOptions {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "StatusBarItemOptions",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
+    impl<'js> FromJs<'js> for InteractiveProcessOptions {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "InteractiveProcessOptions",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
+    impl<'js> FromJs<'js> for WatchPathOptions {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "WatchPathOptions",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
+    impl<'js> FromJs<'js> for CreateTerminalOptions {
+        fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+            rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
+                from: "object",
+                to: "CreateTerminalOptions",
+                message: Some(e.to_string()),
+            })
+        }
+    }
+
     impl<'js> FromJs<'js> for CreateVirtualBufferInExistingSplitOptions {
         fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
             rquickjs_serde::from_value(value).map_err(|e| rquickjs::Error::FromJs {
@@ -2483,6 +3151,7 @@ mod tests {
                 path: Some(std::path::PathBuf::from("/test/file.txt")),
                 modified: true,
                 length: 100,
+                version: 0,
             };
             snapshot.buffers.insert(BufferId(1), buffer_info);
         }
@@ -2522,6 +3191,7 @@ mod tests {
                     path: Some(std::path::PathBuf::from("/file1.txt")),
                     modified: false,
                     length: 50,
+                    version: 0,
                 },
             );
             snapshot.buffers.insert(
@@ -2531,6 +3201,7 @@ mod tests {
                     path: Some(std::path::PathBuf::from("/file2.txt")),
                     modified: true,
                     length: 100,
+                    version: 0,
                 },
             );
             snapshot.buffers.insert(
@@ -2540,6 +3211,7 @@ mod tests {
                     path: None,
                     modified: false,
                     length: 0,
+                    version: 0,
                 },
             );
         }
@@ -22,6 +22,12 @@ pub struct PluginConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[schemars(extend("readOnly" = true))]
     pub path: Option<PathBuf>,
+
+    /// Capabilities granted to this plugin (installed packages only).
+    /// You are asked the first time a package needs them; remove entries
+    /// here to revoke them.
+    #[serde(default, skip_serializing_if = "PluginCapabilities::is_empty")]
+    pub granted: PluginCapabilities,
 }
 
 impl Default for PluginConfig {
@@ -29,6 +35,7 @@ impl Default for PluginConfig {
         Self {
             enabled: true,
             path: None,
+            granted: PluginCapabilities::default(),
         }
     }
 }
@@ -38,6 +45,115 @@ impl PluginConfig {
         Self {
             enabled: true,
             path: Some(path),
+            granted: PluginCapabilities::default(),
+        }
+    }
+}
+
+/// Access to the guarded parts of the plugin API
+///
+/// Plugins installed as packages declare what they need under
+/// `fresh.capabilities` in their `package.json`, and may only read or write
+/// files, run commands, read environment variables and set the clipboard
+/// within what the user granted. Built-in plugins and plugins placed in the
+/// plugins directory by hand are not restricted.
+///
+/// Paths may start with `~`, `${workspace}`, `${config}` or `${plugin}` (the
+/// package's own directory) and cover everything below them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PluginCapabilities {
+    /// Paths the plugin may read
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read: Vec<String>,
+
+    /// Paths the plugin may write
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub write: Vec<String>,
+
+    /// Commands the plugin may run ("*" allows any command)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spawn: Vec<String>,
+
+    /// Environment variables the plugin may read ("*" allows all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+
+    /// Whether the plugin may set the clipboard
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub clipboard: bool,
+}
+
+impl PluginCapabilities {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether everything in `requested` is included in these capabilities
+    pub fn covers(&self, requested: &PluginCapabilities) -> bool {
+        fn covers_all(granted: &[String], requested: &[String]) -> bool {
+            requested.iter().all(|entry| granted.contains(entry))
+        }
+        covers_all(&self.read, &requested.read)
+            && covers_all(&self.write, &requested.write)
+            && covers_all(&self.spawn, &requested.spawn)
+            && covers_all(&self.env, &requested.env)
+            && (self.clipboard || !requested.clipboard)
+    }
+}
+
+/// Resource limits for the plugin runtime
+///
+/// A plugin call that runs past the time budget, or leaves the runtime's heap
+/// above the memory limit, gets the offending plugin disabled until the
+/// plugin runtime is restarted. Writes that would take a plugin's storage
+/// past its quota are refused.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PluginRuntimeConfig {
+    /// Longest a single call into plugin code may run, in milliseconds
+    /// (default: 5000). 0 disables the time budget.
+    #[serde(default = "default_plugin_call_timeout_ms")]
+    pub call_timeout_ms: u64,
+
+    /// Heap limit for all plugins together, in megabytes (default: 512).
+    /// 0 disables the limit.
+    #[serde(default = "default_plugin_memory_limit_mb")]
+    pub memory_limit_mb: u64,
+
+    /// Size limit for each plugin's stored data in one scope (global, or one
+    /// workspace), in kilobytes (default: 1024). 0 disables the quota.
+    #[serde(default = "default_plugin_storage_quota_kb")]
+    pub storage_quota_kb: u64,
+
+    /// Longest the editor waits for plugins to allow or change an edit, a
+    /// save or a command, in milliseconds (default: 50). When it runs out the
+    /// operation goes ahead unchanged. 0 stops the editor from asking.
+    #[serde(default = "default_plugin_hook_timeout_ms")]
+    pub hook_timeout_ms: u64,
+}
+
+fn default_plugin_call_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_plugin_memory_limit_mb() -> u64 {
+    512
+}
+
+fn default_plugin_storage_quota_kb() -> u64 {
+    1024
+}
+
+fn default_plugin_hook_timeout_ms() -> u64 {
+    50
+}
+
+impl Default for PluginRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            call_timeout_ms: default_plugin_call_timeout_ms(),
+            memory_limit_mb: default_plugin_memory_limit_mb(),
+            storage_quota_kb: default_plugin_storage_quota_kb(),
+            hook_timeout_ms: default_plugin_hook_timeout_ms(),
         }
     }
 }
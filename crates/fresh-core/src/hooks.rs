@@ -8,8 +8,8 @@ use std::ops::Range;
 use std::path::PathBuf;
 
 use crate::action::Action;
-use crate::api::{ViewTokenWire, ViewTokenWireKind};
-use crate::{BufferId, CursorId, SplitId};
+use crate::api::{SelectionSpec, ViewTokenWire, ViewTokenWireKind};
+use crate::{BufferId, CursorId, SplitId, TerminalId};
 
 /// Arguments passed to hook callbacks
 #[derive(Debug, Clone, serde::Serialize)]
@@ -21,7 +21,12 @@ pub enum HookArgs {
     AfterFileOpen { buffer_id: BufferId, path: PathBuf },
 
     /// Before a buffer is saved to disk
-    BeforeFileSave { buffer_id: BufferId, path: PathBuf },
+    BeforeFileSave {
+        buffer_id: BufferId,
+        path: PathBuf,
+        /// The text about to be written
+        content: String,
+    },
 
     /// After a buffer is successfully saved
     AfterFileSave { buffer_id: BufferId, path: PathBuf },
@@ -86,6 +91,13 @@ pub enum HookArgs {
         line: usize,
     },
 
+    /// Selections or the set of cursors changed (not fired for bare cursor moves)
+    SelectionChanged {
+        buffer_id: BufferId,
+        /// All cursors of the buffer after the change, primary last
+        selections: Vec<SelectionSpec>,
+    },
+
     /// Buffer became active
     BufferActivated { buffer_id: BufferId },
 
@@ -101,7 +113,10 @@ pub enum HookArgs {
     },
 
     /// Before a command/action is executed
-    PreCommand { action: Action },
+    PreCommand {
+        /// The action's debug description, e.g. `InsertChar('a')`
+        action: String,
+    },
 
     /// After a command/action was executed
     PostCommand { action: Action },
@@ -256,6 +271,71 @@ pub enum HookArgs {
         /// The output data
         data: String,
     },
+
+    /// A terminal printed a line matching a pattern registered with
+    /// `watchTerminalOutput`
+    TerminalOutputMatched {
+        terminal_id: TerminalId,
+        buffer_id: BufferId,
+        /// The pattern that matched, as registered
+        pattern: String,
+        /// The matching line with escape sequences removed
+        line: String,
+    },
+
+    /// The process running in a terminal exited
+    TerminalExited {
+        terminal_id: TerminalId,
+        buffer_id: BufferId,
+    },
+
+    /// A plugin's status bar item was clicked
+    StatusBarItemClicked { plugin_name: String, id: String },
+
+    /// A field of a form dialog was edited
+    FormFieldChanged {
+        form_id: String,
+        field: String,
+        /// The field's new value
+        value: serde_json::Value,
+        /// All field values, by field id
+        values: serde_json::Value,
+    },
+
+    /// A form dialog was closed with a button, or dismissed
+    FormResult {
+        form_id: String,
+        /// The button id, or "dismissed"
+        button: String,
+        /// All field values, by field id
+        values: serde_json::Value,
+    },
+
+    /// Paths below one of a plugin's path watches changed
+    PathChanged {
+        plugin_name: String,
+        watch_id: u64,
+        /// Changes since the last event, ordered by path
+        changes: Vec<PathChange>,
+        /// Why the watch could not be started; it reports nothing further
+        error: Option<String>,
+    },
+}
+
+/// What happened to a watched path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathChangeKind {
+    Create,
+    Modify,
+    Delete,
+}
+
+/// A change reported by a path watch
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PathChange {
+    pub path: PathBuf,
+    pub kind: PathChangeKind,
 }
 
 /// Information about a single line for the LinesChanged hook
@@ -282,6 +362,48 @@ pub struct LspLocation {
     pub column: u32,
 }
 
+/// Hooks whose plugin handlers run before the operation and may stop it
+///
+/// A handler that returns `false` cancels the operation. For the hooks with
+/// replaceable fields (see `replaceable_hook_fields`), a handler may instead
+/// return an object with new values for them, which the following handlers
+/// and the operation itself then see.
+pub const CANCELLABLE_HOOKS: &[&str] = &[
+    "before_insert",
+    "before_delete",
+    "before_file_save",
+    "pre_command",
+];
+
+/// Fields of a cancellable hook's data that its handlers may replace
+pub fn replaceable_hook_fields(hook_name: &str) -> &'static [&'static str] {
+    match hook_name {
+        "before_insert" => &["text"],
+        "before_file_save" => &["content"],
+        _ => &[],
+    }
+}
+
+/// What the handlers of a cancellable hook decided
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HookOutcome {
+    /// Plugin whose handler cancelled the operation
+    pub cancelled_by: Option<String>,
+    /// New values for the hook's replaceable fields
+    pub replacements: HashMap<String, String>,
+}
+
+impl HookOutcome {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled_by.is_some()
+    }
+
+    /// The new value a handler gave a field, if any
+    pub fn replacement(&self, field: &str) -> Option<&str> {
+        self.replacements.get(field).map(String::as_str)
+    }
+}
+
 /// Type for hook callbacks
 pub type HookCallback = Box<dyn Fn(&HookArgs) -> bool + Send + Sync>;
 
@@ -394,6 +516,15 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
                 "line": line,
             })
         }
+        HookArgs::SelectionChanged {
+            buffer_id,
+            selections,
+        } => {
+            serde_json::json!({
+                "buffer_id": buffer_id.0,
+                "selections": selections,
+            })
+        }
         HookArgs::BeforeInsert {
             buffer_id,
             position,
@@ -464,10 +595,15 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
                 "buffer_id": buffer_id.0,
             })
         }
-        HookArgs::BeforeFileSave { path, buffer_id } => {
+        HookArgs::BeforeFileSave {
+            path,
+            buffer_id,
+            content,
+        } => {
             serde_json::json!({
                 "path": path.to_string_lossy(),
                 "buffer_id": buffer_id.0,
+                "content": content,
             })
         }
         HookArgs::AfterFileSave { path, buffer_id } => {
@@ -477,7 +613,7 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
             })
         }
         HookArgs::PreCommand { action } => {
-            serde_json::json!({ "action": format!("{:?}", action) })
+            serde_json::json!({ "action": action })
         }
         HookArgs::PostCommand { action } => {
             serde_json::json!({ "action": format!("{:?}", action) })
@@ -683,6 +819,71 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
                 "data": data,
             })
         }
+        HookArgs::TerminalOutputMatched {
+            terminal_id,
+            buffer_id,
+            pattern,
+            line,
+        } => {
+            serde_json::json!({
+                "terminal_id": terminal_id.0,
+                "buffer_id": buffer_id.0,
+                "pattern": pattern,
+                "line": line,
+            })
+        }
+        HookArgs::TerminalExited {
+            terminal_id,
+            buffer_id,
+        } => {
+            serde_json::json!({
+                "terminal_id": terminal_id.0,
+                "buffer_id": buffer_id.0,
+            })
+        }
+        HookArgs::StatusBarItemClicked { plugin_name, id } => {
+            serde_json::json!({
+                "plugin": plugin_name,
+                "id": id,
+            })
+        }
+        HookArgs::FormFieldChanged {
+            form_id,
+            field,
+            value,
+            values,
+        } => {
+            serde_json::json!({
+                "form_id": form_id,
+                "field": field,
+                "value": value,
+                "values": values,
+            })
+        }
+        HookArgs::FormResult {
+            form_id,
+            button,
+            values,
+        } => {
+            serde_json::json!({
+                "form_id": form_id,
+                "button": button,
+                "values": values,
+            })
+        }
+        HookArgs::PathChanged {
+            plugin_name,
+            watch_id,
+            changes,
+            error,
+        } => {
+            serde_json::json!({
+                "plugin": plugin_name,
+                "watch_id": watch_id,
+                "events": changes,
+                "error": error,
+            })
+        }
     };
 
     serde_json::to_string(&json_value)
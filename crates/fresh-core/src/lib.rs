@@ -52,5 +52,6 @@ pub mod config;
 pub mod file_explorer;
 pub mod menu;
 pub mod overlay;
+pub mod path_encoding;
 pub mod services;
 pub mod text_property;
@@ -52,6 +52,9 @@ pub trait PluginServiceBridge: Send + Sync + 'static {
 
     /// Get the config directory path
     fn config_dir(&self) -> std::path::PathBuf;
+
+    /// Get the data directory path (sessions, history, plugin storage)
+    fn data_dir(&self) -> std::path::PathBuf;
 }
 
 /// A no-op implementation of the service bridge for testing
@@ -92,4 +95,7 @@ impl PluginServiceBridge for NoopServiceBridge {
     fn config_dir(&self) -> std::path::PathBuf {
         std::path::PathBuf::from("/tmp/config")
     }
+    fn data_dir(&self) -> std::path::PathBuf {
+        std::path::PathBuf::from("/tmp/data")
+    }
 }
@@ -23,6 +23,34 @@ interface ProcessHandle<T> extends PromiseLike<T> {
 	/** Cancel/kill the operation. Returns true if cancelled, false if already completed */
 	kill(): Promise<boolean>;
 }
+/** Handle for a process started with `spawnInteractiveProcess` */
+interface InteractiveProcessHandle extends ProcessHandle<BackgroundProcessResult> {
+	/** Process ID, as reported by the process output hooks */
+	readonly processId: number;
+	/** Write text to the process's stdin. Returns false if the editor is gone */
+	write(data: string): boolean;
+	/** Close the process's stdin so it sees end of input */
+	closeStdin(): boolean;
+}
+/** Subscription returned by `watchPath`; changes arrive through the `path_changed` hook */
+interface PathWatch {
+	/** Watch ID, as reported in `path_changed` events */
+	readonly watchId: number;
+	/** Stop watching. Returns false if the editor is gone */
+	close(): boolean;
+}
+/** API another plugin exported with `exportApi`; every method returns a promise of its result */
+interface PluginApi {
+	[method: string]: (...args: unknown[]) => Promise<unknown>;
+}
+/** Rejection of `applyEdits` when the buffer changed since `expectedVersion` */
+interface StaleVersionError extends Error {
+	name: "StaleVersionError";
+	bufferId: number;
+	expectedVersion: number;
+	/** The buffer's version now; re-read the text and retry with it */
+	currentVersion: number;
+}
 /** Buffer identifier */
 type BufferId = number;
 /** Split identifier */
@@ -250,6 +278,11 @@ type BufferInfo = {
 	* Length of buffer in bytes
 	*/
 	length: number;
+	/**
+	* Edit counter, bumped on every change to the buffer's text.
+	* Pass it to `applyEdits` to detect edits made since it was read.
+	*/
+	version: number;
 };
 type JsDiagnostic = {
 	/**
@@ -303,6 +336,7 @@ type ActionSpec = {
 	*/
 	count: number;
 };
+type SelectionKind = "normal" | "block";
 type TsActionPopupAction = {
 	/**
 	* Unique action identifier (returned in ActionPopupResult)
@@ -331,6 +365,54 @@ type ActionPopupOptions = {
 	*/
 	actions: Array<TsActionPopupAction>;
 };
+type FormField = {
+	/**
+	* Field id, the key of the field in the form values
+	*/
+	id: string;
+	/**
+	* Label shown next to the control
+	*/
+	label: string;
+	/**
+	* Control kind: "text", "number", "toggle", "dropdown" or "textList"
+	*/
+	kind: string;
+	/**
+	* Initial value: a string, number, boolean, dropdown option or string array
+	*/
+	value?: string | number | boolean | string[];
+	/**
+	* Choices of a dropdown
+	*/
+	options?: Array<string>;
+	/**
+	* Hint shown in an empty text field
+	*/
+	placeholder?: string;
+	/**
+	* Smallest value of a number field
+	*/
+	min?: number;
+	/**
+	* Largest value of a number field
+	*/
+	max?: number;
+	/**
+	* Increment of a number field (default: 1)
+	*/
+	step?: number;
+};
+type FormButton = {
+	/**
+	* Button id, reported in the form_result hook
+	*/
+	id: string;
+	/**
+	* Button text
+	*/
+	label: string;
+};
 type FileExplorerDecoration = {
 	/**
 	* File path to decorate
@@ -359,6 +441,93 @@ type FormatterPackConfig = {
 	*/
 	args: Array<string>;
 };
+type LanguageFeature = "completion" | "hover" | "definition" | "codeAction" | "diagnostics";
+type LanguageFeatureRequest = {
+	/**
+	* The feature being requested
+	*/
+	feature: LanguageFeature;
+	/**
+	* Language of the buffer
+	*/
+	language: string;
+	/**
+	* Buffer the request is for
+	*/
+	buffer_id: number;
+	/**
+	* File path of the buffer
+	*/
+	path: string;
+	/**
+	* Zero-indexed line of the cursor (0 for diagnostics)
+	*/
+	line: number;
+	/**
+	* Zero-indexed UTF-16 character offset of the cursor (0 for diagnostics)
+	*/
+	character: number;
+};
+type ProviderCompletionItem = {
+	/**
+	* Text shown in the completion popup
+	*/
+	label: string;
+	/**
+	* Extra detail shown next to the label
+	*/
+	detail?: string;
+	/**
+	* Text inserted when accepted (defaults to the label)
+	*/
+	insert_text?: string;
+	/**
+	* Text matched against the typed prefix (defaults to the label)
+	*/
+	filter_text?: string;
+	/**
+	* "function", "method", "variable", "struct", "class", "constant" or "keyword"
+	*/
+	kind?: string;
+};
+type ProviderLocation = {
+	/**
+	* File path
+	*/
+	path: string;
+	/**
+	* Zero-indexed line number
+	*/
+	line: number;
+	/**
+	* Zero-indexed character offset
+	*/
+	character: number;
+};
+type ProviderCodeAction = {
+	/**
+	* Title shown in the code actions menu
+	*/
+	title: string;
+};
+type ProviderDiagnostic = {
+	/**
+	* Diagnostic message
+	*/
+	message: string;
+	/**
+	* Severity: 1=Error, 2=Warning, 3=Info, 4=Hint (defaults to Error)
+	*/
+	severity?: number;
+	/**
+	* Range in the document
+	*/
+	range: JsRange;
+	/**
+	* Source of the diagnostic (defaults to the plugin name)
+	*/
+	source?: string;
+};
 type BackgroundProcessResult = {
 	/**
 	* Unique process ID for later reference
@@ -370,6 +539,20 @@ type BackgroundProcessResult = {
 	*/
 	exit_code: number;
 };
+type BufferEdit = {
+	/**
+	* Start byte offset of the replaced range
+	*/
+	start: number;
+	/**
+	* End byte offset (exclusive); equal to `start` for a pure insertion
+	*/
+	end: number;
+	/**
+	* Replacement text (empty for a pure deletion)
+	*/
+	text: string;
+};
 type BufferSavedDiff = {
 	equal: boolean;
 	byte_ranges: Array<[number, number]>;
@@ -415,6 +598,40 @@ type TsCreateCompositeBufferOptions = {
 	*/
 	hunks: Array<TsCompositeHunk> | null;
 };
+type CreateTerminalOptions = {
+	/**
+	* Program to run instead of the user's shell
+	*/
+	command?: string;
+	/**
+	* Arguments for `command`
+	*/
+	args?: Array<string>;
+	/**
+	* Working directory (default: the editor's working directory)
+	*/
+	cwd?: string;
+	/**
+	* Extra environment variables for the process
+	*/
+	env?: Record<string, string>;
+	/**
+	* Open as a tab in this existing split
+	*/
+	splitId?: number;
+	/**
+	* Open in a new split of the active split: "horizontal" or "vertical"
+	*/
+	direction?: string;
+	/**
+	* Size of the new split, 0.0-1.0 (default: 0.5)
+	*/
+	ratio?: number;
+	/**
+	* Focus the terminal and enter terminal mode (default: true)
+	*/
+	focus?: boolean;
+};
 type CreateVirtualBufferInExistingSplitOptions = {
 	/**
 	* Buffer name (displayed in tabs/title)
@@ -533,6 +750,40 @@ type CreateVirtualBufferOptions = {
 	*/
 	entries?: Array<TextPropertyEntry>;
 };
+type FormOptions = {
+	/**
+	* Form id, passed to the form hooks
+	*/
+	id: string;
+	/**
+	* Title of the dialog
+	*/
+	title: string;
+	/**
+	* Fields, top to bottom
+	*/
+	fields: Array<FormField>;
+	/**
+	* Buttons (default: "OK" and "Cancel" with ids "ok" and "cancel").
+	* Enter in a text field presses the first one.
+	*/
+	buttons?: Array<FormButton>;
+};
+type InteractiveProcessOptions = {
+	/**
+	* Working directory (default: the editor's working directory)
+	*/
+	cwd?: string;
+	/**
+	* Extra environment variables for the process
+	*/
+	env?: Record<string, string>;
+	/**
+	* How output is delivered to the process output hooks: "lines" (one
+	* event per line, the default) or "chunks" (as soon as it is read)
+	*/
+	output?: string;
+};
 type LanguagePackConfig = {
 	/**
 	* Comment prefix for line comments (e.g., "//" or "#")
@@ -581,6 +832,20 @@ type LspServerPackConfig = {
 	*/
 	initializationOptions: Record<string, unknown> | null;
 };
+type SelectionSpec = {
+	/**
+	* Byte offset where the selection starts (omit for a bare cursor)
+	*/
+	anchor?: number;
+	/**
+	* Byte offset of the cursor itself
+	*/
+	position: number;
+	/**
+	* Selection shape (default "normal")
+	*/
+	mode?: SelectionKind;
+};
 type SpawnResult = {
 	/**
 	* Complete stdout as string
@@ -595,6 +860,28 @@ type SpawnResult = {
 	*/
 	exit_code: number;
 };
+type StatusBarItemOptions = {
+	/**
+	* Text to show
+	*/
+	text: string;
+	/**
+	* Theme key for the text colour (e.g. "ui.status_bar_fg")
+	*/
+	fg?: string;
+	/**
+	* Theme key for the background colour (e.g. "ui.status_bar_bg")
+	*/
+	bg?: string;
+	/**
+	* Side of the status bar: "left" or "right" (default: "right")
+	*/
+	alignment?: string;
+	/**
+	* Items on the same side are ordered by priority, highest first (default: 0)
+	*/
+	priority?: number;
+};
 type PromptSuggestion = {
 	/**
 	* The text to display
@@ -617,6 +904,52 @@ type PromptSuggestion = {
 	*/
 	keybinding?: string;
 };
+type SyntaxCapture = {
+	/**
+	* Capture name from the query, without the leading `@`
+	*/
+	name: string;
+	/**
+	* Grammar node type of the captured node
+	*/
+	kind: string;
+	/**
+	* Start byte offset
+	*/
+	start: number;
+	/**
+	* End byte offset (exclusive)
+	*/
+	end: number;
+};
+type SyntaxNode = {
+	/**
+	* Grammar node type (e.g., "function_item", "identifier")
+	*/
+	kind: string;
+	/**
+	* Start byte offset
+	*/
+	start: number;
+	/**
+	* End byte offset (exclusive)
+	*/
+	end: number;
+};
+type TerminalResult = {
+	/**
+	* The terminal ID, for sendTerminalInput and the terminal hooks
+	*/
+	terminalId: number;
+	/**
+	* The buffer showing the terminal
+	*/
+	bufferId: number;
+	/**
+	* The split the terminal was opened in
+	*/
+	splitId: number;
+};
 type TextPropertiesAtCursor = Array<Record<string, unknown>>;
 type TsHighlightSpan = {
 	start: number;
@@ -635,6 +968,17 @@ type VirtualBufferResult = {
 	*/
 	splitId: number | null;
 };
+type WatchPathOptions = {
+	/**
+	* Also report changes in subdirectories (default: false)
+	*/
+	recursive?: boolean;
+	/**
+	* Only report paths matching this glob, relative to the watched
+	* directory (e.g. "*.xml" or "reports/**")
+	*/
+	glob?: string;
+};
 /**
 * Main editor API interface
 */
@@ -656,6 +1000,21 @@ interface EditorAPI {
 	warn(msg: string): void;
 	error(msg: string): void;
 	setStatus(msg: string): void;
+	/**
+	* Add or replace a persistent status bar item
+	* 
+	* `id` is scoped to the calling plugin. Clicking the item fires the
+	* `status_bar_item_clicked` hook of this plugin only, with `{ plugin, id }`.
+	*/
+	setStatusBarItem(id: string, options: StatusBarItemOptions): boolean;
+	/**
+	* Change the text of a status bar item, keeping its colours and position
+	*/
+	setStatusBarItemText(id: string, text: string): boolean;
+	/**
+	* Remove a status bar item
+	*/
+	removeStatusBarItem(id: string): boolean;
 	copyToClipboard(text: string): void;
 	setClipboard(text: string): void;
 	/**
@@ -747,6 +1106,15 @@ interface EditorAPI {
 	*/
 	deleteRange(bufferId: number, start: number, end: number): boolean;
 	/**
+	* Replace several ranges of a buffer as a single undo step (async)
+	* 
+	* Offsets refer to the buffer text at `expectedVersion` (see
+	* `BufferInfo.version`) and ranges must not overlap. Resolves with the
+	* buffer's new version; if the buffer changed since `expectedVersion`
+	* nothing is applied and the promise rejects with a `StaleVersionError`.
+	*/
+	applyEdits(bufferId: number, expectedVersion: number, edits: BufferEdit[], label?: string): Promise<number>;
+	/**
 	* Insert text at cursor position in active buffer
 	*/
 	insertAtCursor(text: string): boolean;
@@ -783,6 +1151,28 @@ interface EditorAPI {
 	*/
 	getCwd(): string;
 	/**
+	* Get a value this plugin stored, or `undefined` if the key is not set.
+	* `scope` is "global" (the default) or "workspace" (the current working directory)
+	*/
+	storageGet(key: string, scope?: string): unknown;
+	/**
+	* Store a JSON-serializable value for this plugin, persisted across runs.
+	* Throws if the write would take the scope past the storage quota
+	*/
+	storageSet(key: string, value: unknown, scope?: string): void;
+	/**
+	* Remove a stored key; returns true if it was set
+	*/
+	storageDelete(key: string, scope?: string): boolean;
+	/**
+	* List the keys this plugin has stored in a scope
+	*/
+	storageKeys(scope?: string): string[];
+	/**
+	* Delete everything a plugin has stored (used when uninstalling it)
+	*/
+	clearPluginStorage(name: string): boolean;
+	/**
 	* Join path components (variadic - accepts multiple string arguments)
 	* Always uses forward slashes for cross-platform consistency (like Node.js path.posix.join)
 	*/
@@ -820,6 +1210,45 @@ interface EditorAPI {
 	*/
 	readDir(path: string): DirEntry[];
 	/**
+	* Watch a file or directory for changes
+	* 
+	* Debounced batches of `{path, kind}` events ("create", "modify" or
+	* "delete") arrive through this plugin's `path_changed` handlers as
+	* `{plugin, watch_id, events, error}`. If the watch cannot start (e.g. an
+	* invalid glob), a single event carries the `error`. Relative paths are
+	* resolved against the working directory. The watch ends when the
+	* plugin is unloaded.
+	*/
+	watchPath(path: string, opts?: WatchPathOptions): PathWatch;
+	/**
+	* Stop a watch started with `watchPath`
+	*/
+	unwatchPath(watchId: number): boolean;
+	/**
+	* Export an API object for other plugins, under this plugin's name
+	* 
+	* Other plugins get it with `getPluginApi`. Its functions run in this
+	* plugin, with this plugin's capabilities; arguments and results are
+	* copied as JSON. Exporting again replaces the previous API.
+	*/
+	exportApi(api: Record<string, unknown>): void;
+	/**
+	* Get the API another plugin exported with `exportApi`
+	* 
+	* Waits until the plugin has exported it; fails if the plugin is not
+	* loaded once the editor has started, or when it is unloaded. Every
+	* method of the returned object returns a promise of its result.
+	*/
+	getPluginApi(name: string): Promise<PluginApi>;
+	/**
+	* Publish a plugin-defined event to the handlers registered with `on`
+	* 
+	* The name must be namespaced, like "git:status_changed", so it cannot
+	* be mistaken for an editor hook. Handlers get `data` (copied as JSON),
+	* after the current call into plugin code returns.
+	*/
+	emitEvent(name: string, data?: unknown): void;
+	/**
 	* Get current config as JS object
 	*/
 	getConfig(): unknown;
@@ -850,6 +1279,19 @@ interface EditorAPI {
 	*/
 	registerLspServer(language: string, config: LspServerPackConfig): boolean;
 	/**
+	* Provide a language feature for a language, merged with LSP results
+	* 
+	* `feature` is "completion", "hover", "definition", "codeAction" or
+	* "diagnostics". The handler receives a `LanguageFeatureRequest` and
+	* returns (or resolves to) the results. Registering again for the same
+	* feature and language replaces the previous handler.
+	*/
+	registerLanguageProvider(feature: string, language: string, handlerName: string): boolean;
+	/**
+	* Stop providing a language feature for a language
+	*/
+	unregisterLanguageProvider(feature: string, language: string): boolean;
+	/**
 	* Reload the grammar registry to apply registered grammars
 	* Call this after registering one or more grammars
 	*/
@@ -916,6 +1358,25 @@ interface EditorAPI {
 	*/
 	getHighlights(bufferId: number, start: number, end: number): Promise<TsHighlightSpan[]>;
 	/**
+	* Get the syntax nodes under a byte offset, outermost first (async)
+	* 
+	* Rejects if the buffer's language has no tree-sitter grammar.
+	*/
+	getSyntaxNodePath(bufferId: number, offset: number): Promise<SyntaxNode[]>;
+	/**
+	* Get the innermost syntax node of a kind containing a byte offset (async)
+	* 
+	* Resolves with null if no enclosing node has that kind.
+	*/
+	getEnclosingSyntaxNode(bufferId: number, offset: number, kind: string): Promise<SyntaxNode | null>;
+	/**
+	* Run a tree-sitter query against a buffer (async)
+	* 
+	* Pass `start` and `end` to only return matches within that byte range.
+	* Rejects if the query does not compile for the buffer's language.
+	*/
+	querySyntax(bufferId: number, query: string, start?: number, end?: number): Promise<SyntaxCapture[]>;
+	/**
 	* Add an overlay with styling options
 	* 
 	* Colors can be specified as RGB arrays `[r, g, b]` or theme key strings.
@@ -1052,6 +1513,14 @@ interface EditorAPI {
 	*/
 	setBufferCursor(bufferId: number, position: number): boolean;
 	/**
+	* Replace all cursors in the active buffer with the given selections
+	* 
+	* Each entry becomes one cursor at `position`, selecting back to
+	* `anchor` if given; `mode: "block"` makes it a rectangular selection.
+	* The last entry becomes the primary cursor. An empty array is ignored.
+	*/
+	setSelections(selections: SelectionSpec[]): boolean;
+	/**
 	* Set a line indicator in the gutter
 	*/
 	setLineIndicator(bufferId: number, line: number, namespace: string, symbol: string, r: number, g: number, b: number, priority: number): boolean;
@@ -1088,6 +1557,17 @@ interface EditorAPI {
 	*/
 	showActionPopup(opts: ActionPopupOptions): boolean;
 	/**
+	* Show a modal form dialog
+	* 
+	* Field edits fire the `form_field_changed` hook and pressing a button
+	* (or dismissing the dialog with Escape) fires `form_result`.
+	*/
+	showForm(options: FormOptions): boolean;
+	/**
+	* Close a form dialog without firing `form_result`
+	*/
+	closeForm(formId: string): boolean;
+	/**
 	* Disable LSP for a specific language
 	*/
 	disableLspForLanguage(language: string): boolean;
@@ -1155,6 +1635,54 @@ interface EditorAPI {
 	*/
 	killBackgroundProcess(processId: number): boolean;
 	/**
+	* Spawn a process with piped stdin (returns a handle to write to it)
+	* 
+	* Output arrives through the `onProcessStdout`/`onProcessStderr` hooks,
+	* split into lines or delivered in chunks (`opts.output`). Works on
+	* remote (SSH) workspaces too.
+	*/
+	spawnInteractiveProcess(command: string, args: string[], opts?: InteractiveProcessOptions): InteractiveProcessHandle;
+	/**
+	* Write text to the stdin of an interactive process
+	*/
+	writeProcessStdin(processId: number, data: string): boolean;
+	/**
+	* Close the stdin of an interactive process
+	*/
+	closeProcessStdin(processId: number): boolean;
+	/**
+	* Open an integrated terminal (async)
+	* 
+	* Runs `opts.command` (or the user's shell) in a new terminal buffer,
+	* in the active split, an existing split (`splitId`) or a new split
+	* (`direction`). Restricted plugins need spawn permission for the
+	* command, or for `$SHELL` when no command is given.
+	*/
+	createTerminal(opts?: CreateTerminalOptions): Promise<TerminalResult>;
+	/**
+	* Write input to a terminal's process, as if typed (use "\r" for Enter)
+	* 
+	* Like the other terminal methods, this only works on terminals the
+	* plugin opened with `createTerminal`.
+	*/
+	sendTerminalInput(terminalId: number, data: string): boolean;
+	/**
+	* Get a terminal's scrollback and visible screen as plain text (async)
+	*/
+	getTerminalScrollback(terminalId: number): Promise<string>;
+	/**
+	* Fire the `terminal_output_matched` hook for each line a terminal
+	* prints that matches `pattern` (a regular expression)
+	* 
+	* Output printed before the first watch was added is matched too. The
+	* hook only reaches the plugin that opened the terminal.
+	*/
+	watchTerminalOutput(terminalId: number, pattern: string): boolean;
+	/**
+	* Close a terminal, stopping its process and closing its buffer
+	*/
+	closeTerminal(terminalId: number): boolean;
+	/**
 	* Force refresh of line display
 	*/
 	refreshLines(bufferId: number): boolean;
@@ -75,6 +75,19 @@ interface PackageManifest {
       variant?: "dark" | "light";
     }>;
     config_schema?: Record<string, unknown>;
+    /** What the plugin may do; the user is asked to grant these on first run */
+    capabilities?: {
+      /** Paths the plugin may read (prefixes: ~, ${workspace}, ${config}, ${plugin}) */
+      read?: string[];
+      /** Paths the plugin may write */
+      write?: string[];
+      /** Commands the plugin may run, or "*" for any */
+      spawn?: string[];
+      /** Environment variables the plugin may read, or "*" for any */
+      env?: string[];
+      /** Whether the plugin may set the clipboard */
+      clipboard?: boolean;
+    };
 
     // Language pack fields
     grammar?: {
@@ -964,6 +977,13 @@ async function removePackage(pkg: InstalledPackage): Promise<boolean> {
     if (plugin) {
       await editor.unloadPlugin(plugin.name).catch(() => {});
     }
+
+    // Drop the plugin's stored data; the runtime names plugins after their entry file
+    const entry = pkg.manifest?.fresh?.entry;
+    const pluginName = plugin?.name ?? (entry ? editor.pathBasename(entry).replace(/\.[^.]+$/, "") : null);
+    if (pluginName) {
+      editor.clearPluginStorage(pluginName);
+    }
   }
 
   // Use trash if available, otherwise rm -rf
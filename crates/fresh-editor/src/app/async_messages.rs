@@ -72,11 +72,16 @@ impl Editor {
         if diagnostics.is_empty() {
             self.stored_diagnostics.remove(&uri);
         } else {
-            self.stored_diagnostics
-                .insert(uri.clone(), diagnostics.clone());
+            self.stored_diagnostics.insert(uri.clone(), diagnostics);
         }
 
-        if let Some(buffer_id) = self.apply_diagnostics_to_buffer(&uri, &diagnostics) {
+        self.apply_merged_diagnostics(&uri);
+    }
+
+    /// Apply a document's LSP and plugin-provided diagnostics, emit hook for plugins
+    pub(super) fn apply_merged_diagnostics(&mut self, uri: &str) {
+        let diagnostics = self.merged_diagnostics(uri);
+        if let Some(buffer_id) = self.apply_diagnostics_to_buffer(uri, &diagnostics) {
             tracing::info!(
                 "Applied {} diagnostics to buffer {:?}",
                 diagnostics.len(),
@@ -90,7 +95,7 @@ impl Editor {
         self.plugin_manager.run_hook(
             "diagnostics_updated",
             crate::services::plugins::hooks::HookArgs::DiagnosticsUpdated {
-                uri,
+                uri: uri.to_string(),
                 count: diagnostics.len(),
             },
         );
@@ -818,13 +823,18 @@ impl Editor {
         const DEBOUNCE_WINDOW: Duration = Duration::from_secs(10);
         const RAPID_REVERT_THRESHOLD: u32 = 10; // Require 10 reverts in 10 seconds to disable
 
+        let path_buf = PathBuf::from(&path);
+
+        // Appends to a followed file extend its buffer instead of reverting it
+        if self.follow_append(&path_buf) {
+            return true;
+        }
+
         // Skip if auto-revert is disabled
         if !self.auto_revert_enabled {
             return false;
         }
 
-        let path_buf = PathBuf::from(&path);
-
         // Only track events for files that are actually open in the editor
         let is_file_open = self
             .buffers
@@ -987,6 +997,8 @@ impl Editor {
                 "process_plugin_commands: handling command {:?}",
                 std::mem::discriminant(&command)
             );
+            #[cfg(feature = "plugins")]
+            self.record_plugin_console_command(&command);
             if let Err(e) = self.handle_plugin_command(command) {
                 tracing::error!("Error handling TypeScript plugin command: {}", e);
             }
@@ -0,0 +1,309 @@
+//! Headless batch editing: run a script of editor operations over files
+//!
+//! `fresh --batch script.json files...` opens each file in an editor that has
+//! no terminal, applies the script's steps, saves the result and exits with a
+//! non-zero status if any file failed. A script is a JSON object with a list
+//! of steps:
+//!
+//! ```json
+//! {
+//!   "steps": [
+//!     { "action": "move_document_end" },
+//!     { "action": "insert_char", "args": { "char": ";" } },
+//!     { "replace": "foo", "with": "bar" },
+//!     { "macro": "macros/fix-imports.json" },
+//!     { "command": "Trim Trailing Whitespace" }
+//!   ]
+//! }
+//! ```
+//!
+//! - `action` names and `args` are the ones keybindings use.
+//! - `replace` replaces every occurrence of a literal string.
+//! - `macro` is a recorded macro, as shown by "Show Macro": either the
+//!   action list itself or the path of a file it was saved to, relative to
+//!   the script.
+//! - `command` runs a command palette entry by the name it shows; plugin
+//!   commands can also be named by their action name.
+
+use super::Editor;
+use crate::input::keybindings::Action;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long a plugin command may run before the file is given up on
+const PLUGIN_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A step as written in a script
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StepSpec {
+    Action {
+        action: String,
+        #[serde(default)]
+        args: HashMap<String, serde_json::Value>,
+    },
+    Replace {
+        replace: String,
+        with: String,
+    },
+    Macro {
+        r#macro: MacroSpec,
+    },
+    Command {
+        command: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MacroSpec {
+    Actions(Vec<Action>),
+    File(PathBuf),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScriptSpec {
+    steps: Vec<serde_json::Value>,
+}
+
+/// One operation of a batch script
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchStep {
+    /// Editor actions, run in order
+    Actions(Vec<Action>),
+    /// Replace every occurrence of `search` with `replacement`
+    Replace { search: String, replacement: String },
+    /// A command palette entry, looked up once plugins have registered theirs
+    Command(String),
+}
+
+/// A parsed batch script
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchScript {
+    pub steps: Vec<BatchStep>,
+}
+
+/// What happened to one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchOutcome {
+    /// The steps changed the file and it was saved
+    Changed,
+    /// The steps left the file as it was
+    Unchanged,
+    Failed(String),
+}
+
+impl BatchScript {
+    /// Read a script; macro files are resolved relative to its directory
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", path.display(), e))?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        Self::parse(&json, base_dir)
+    }
+
+    pub fn parse(json: &str, base_dir: &Path) -> anyhow::Result<Self> {
+        let spec: ScriptSpec = serde_json::from_str(json)?;
+        let steps = spec
+            .steps
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| {
+                parse_step(value, base_dir).map_err(|e| anyhow::anyhow!("Step {}: {}", i + 1, e))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { steps })
+    }
+}
+
+fn parse_step(value: serde_json::Value, base_dir: &Path) -> Result<BatchStep, String> {
+    let spec: StepSpec = serde_json::from_value(value).map_err(|_| {
+        "expected one of \"action\", \"replace\" with \"with\", \"macro\" or \"command\""
+            .to_string()
+    })?;
+    Ok(match spec {
+        StepSpec::Action { action, args } => {
+            BatchStep::Actions(vec![Action::from_str(&action, &args)
+                .ok_or_else(|| format!("unknown action '{}'", action))?])
+        }
+        StepSpec::Replace { replace, with } => {
+            if replace.is_empty() {
+                return Err("nothing to replace".to_string());
+            }
+            BatchStep::Replace {
+                search: replace,
+                replacement: with,
+            }
+        }
+        StepSpec::Macro {
+            r#macro: MacroSpec::Actions(actions),
+        } => BatchStep::Actions(actions),
+        StepSpec::Macro {
+            r#macro: MacroSpec::File(path),
+        } => BatchStep::Actions(load_macro(&base_dir.join(path))?),
+        StepSpec::Command { command } => BatchStep::Command(command),
+    })
+}
+
+/// Read a macro saved from its "Show Macro" buffer, header comments included
+fn load_macro(path: &Path) -> Result<Vec<Action>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read macro {}: {}", path.display(), e))?;
+    let json: String = content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    serde_json::from_str(&json).map_err(|e| format!("invalid macro {}: {}", path.display(), e))
+}
+
+impl Editor {
+    /// Run a batch script over one file and save the result
+    pub fn run_batch(&mut self, script: &BatchScript, path: &Path) -> BatchOutcome {
+        let buffer_id = match self.open_file(path) {
+            Ok(id) => id,
+            Err(e) => return BatchOutcome::Failed(e.to_string()),
+        };
+        // Let plugins react to the file being opened
+        self.wait_for_plugin_work();
+
+        let outcome = self.run_batch_steps(script);
+        let outcome = match outcome {
+            Err(e) => BatchOutcome::Failed(e),
+            Ok(()) if !self.active_state().buffer.is_modified() => BatchOutcome::Unchanged,
+            Ok(()) => match self.save() {
+                Err(e) => BatchOutcome::Failed(e.to_string()),
+                // A plugin cancelled the save, or it needs a prompt answered
+                Ok(()) if self.active_state().buffer.is_modified() => BatchOutcome::Failed(
+                    self.get_status_message()
+                        .cloned()
+                        .unwrap_or_else(|| "not saved".to_string()),
+                ),
+                Ok(()) => BatchOutcome::Changed,
+            },
+        };
+        let _ = self.force_close_buffer(buffer_id);
+        outcome
+    }
+
+    fn run_batch_steps(&mut self, script: &BatchScript) -> Result<(), String> {
+        for step in &script.steps {
+            match step {
+                BatchStep::Actions(actions) => {
+                    for action in actions {
+                        self.handle_action(action.clone())
+                            .map_err(|e| e.to_string())?;
+                    }
+                }
+                BatchStep::Replace {
+                    search,
+                    replacement,
+                } => self.perform_replace(search, replacement),
+                BatchStep::Command(name) => {
+                    let action = self
+                        .find_batch_command(name)
+                        .ok_or_else(|| format!("unknown command '{}'", name))?;
+                    self.handle_action(action).map_err(|e| e.to_string())?;
+                }
+            }
+            if !self.wait_for_plugin_work() {
+                return Err("timed out waiting for plugins".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Look up a command by the name the palette shows, or a plugin command
+    /// by its action name
+    fn find_batch_command(&self, name: &str) -> Option<Action> {
+        self.command_registry
+            .read()
+            .unwrap()
+            .get_all()
+            .into_iter()
+            .find(|command| {
+                command.get_localized_name() == name
+                    || matches!(&command.action, Action::PluginAction(action) if action == name)
+            })
+            .map(|command| command.action)
+    }
+
+    /// Process plugin messages until the plugin actions that were started
+    /// have finished
+    ///
+    /// Returns false if they did not finish in time.
+    pub fn wait_for_plugin_work(&mut self) -> bool {
+        let started = Instant::now();
+        loop {
+            self.process_async_messages();
+            #[cfg(feature = "plugins")]
+            let busy = !self.pending_plugin_actions.is_empty();
+            #[cfg(not(feature = "plugins"))]
+            let busy = false;
+            if !busy {
+                // Pick up what the actions sent just before finishing
+                self.process_async_messages();
+                return true;
+            }
+            if started.elapsed() > PLUGIN_COMMAND_TIMEOUT {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("macro.json"),
+            "// Macro 'a' (2 actions)\n// This buffer can be saved as a .json file for persistence\n\n[\"MoveLineEnd\", {\"InsertChar\": \";\"}]",
+        )
+        .unwrap();
+        let script = BatchScript::parse(
+            r#"{"steps": [
+                {"action": "insert_char", "args": {"char": "x"}},
+                {"replace": "foo", "with": "bar"},
+                {"macro": "macro.json"},
+                {"macro": ["MoveDown"]},
+                {"command": "Trim Trailing Whitespace"}
+            ]}"#,
+            dir.path(),
+        )
+        .unwrap();
+        assert_eq!(
+            script.steps,
+            vec![
+                BatchStep::Actions(vec![Action::InsertChar('x')]),
+                BatchStep::Replace {
+                    search: "foo".to_string(),
+                    replacement: "bar".to_string()
+                },
+                BatchStep::Actions(vec![Action::MoveLineEnd, Action::InsertChar(';')]),
+                BatchStep::Actions(vec![Action::MoveDown]),
+                BatchStep::Command("Trim Trailing Whitespace".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_script_errors() {
+        let dir = Path::new(".");
+        let error = |json: &str| BatchScript::parse(json, dir).unwrap_err().to_string();
+        assert_eq!(
+            error(r#"{"steps": [{"action": "move_left"}, {"action": "fly"}]}"#),
+            "Step 2: unknown action 'fly'"
+        );
+        assert!(error(r#"{"steps": [{"replace": "a"}]}"#).starts_with("Step 1: expected"));
+        assert!(error(r#"{"steps": [{"macro": "missing.json"}]}"#)
+            .starts_with("Step 1: cannot read macro"));
+    }
+}
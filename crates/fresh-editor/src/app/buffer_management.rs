@@ -277,6 +277,7 @@ impl Editor {
                 path: path.to_path_buf(),
             },
         );
+        self.request_provider_diagnostics(buffer_id);
 
         Ok(buffer_id)
     }
@@ -1086,6 +1087,7 @@ impl Editor {
         if let Some(terminal_id) = self.terminal_buffers.remove(&id) {
             // Close the terminal process
             self.terminal_manager.close(terminal_id);
+            self.terminal_output_watches.remove(&terminal_id);
 
             // Clean up backing/rendering file
             let backing_file = self.terminal_backing_files.remove(&terminal_id);
@@ -1197,6 +1199,11 @@ impl Editor {
             self.focus_file_explorer();
         }
 
+        // Closing a comparison or merge also closes the buffers it created
+        self.close_file_comparison(id);
+        self.close_merge_session(id);
+        self.followed_buffers.remove(&id);
+
         Ok(())
     }
 
@@ -0,0 +1,480 @@
+//! Native side-by-side file comparison
+//!
+//! "Compare Files", "Compare with Saved" and "Compare with Clipboard" show two
+//! texts in a composite buffer, aligned by the line hunks of [`diff_hunks`].
+//! The composite renderer highlights the changed words of modified lines.
+//!
+//! The comparison follows edits: whenever either side changes, the hunks are
+//! recomputed. A hunk can be copied from one side to the other when the
+//! target side is editable (a file, or the buffer compared with its saved
+//! copy); the copy goes through the target buffer's undo history.
+
+use super::types::BufferMetadata;
+use super::Editor;
+use crate::model::composite_buffer::{
+    CompositeLayout, DiffHunk, LineAlignment, PaneStyle, RowType, SourcePane,
+};
+use crate::model::event::{BufferId, Event};
+use crate::model::line_diff::diff_hunks;
+use crate::primitives::text_property::TextPropertyEntry;
+use rust_i18n::t;
+use std::ops::Range;
+use std::path::Path;
+
+/// Buffer mode of comparison views (registered in `ModeRegistry::new`)
+const COMPARE_MODE: &str = "compare-view";
+
+/// A comparison shown in a composite buffer
+pub(super) struct FileComparison {
+    left: BufferId,
+    right: BufferId,
+    /// Source buffers created for the comparison, closed along with it
+    owned: Vec<BufferId>,
+    hunks: Vec<DiffHunk>,
+    /// Versions of the two sides the hunks were computed from
+    versions: Option<(u64, u64)>,
+}
+
+/// One side of a comparison being opened
+struct Side {
+    buffer_id: BufferId,
+    label: String,
+    editable: bool,
+}
+
+impl Editor {
+    /// Compare two files side by side
+    pub fn compare_files(&mut self, left: &Path, right: &Path) -> anyhow::Result<BufferId> {
+        let left_id = self.open_file(left)?;
+        let right_id = self.open_file(right)?;
+        Ok(self.open_comparison(self.file_side(left_id), self.file_side(right_id), vec![]))
+    }
+
+    /// Compare the active buffer with a file
+    pub fn compare_with_file(&mut self, path: &Path) -> anyhow::Result<BufferId> {
+        let left_id = self.active_buffer();
+        let right_id = self.open_file(path)?;
+        Ok(self.open_comparison(self.file_side(left_id), self.file_side(right_id), vec![]))
+    }
+
+    /// Compare the active buffer with its last saved content
+    pub fn compare_with_saved(&mut self) {
+        let buffer_id = self.active_buffer();
+        if self
+            .buffer_metadata
+            .get(&buffer_id)
+            .and_then(|m| m.file_path())
+            .is_none()
+        {
+            self.set_status_message(t!("compare.no_file").to_string());
+            return;
+        }
+        let Some(saved) = self.active_state().buffer.saved_content() else {
+            self.set_status_message(t!("compare.not_loaded").to_string());
+            return;
+        };
+
+        let name = self.get_buffer_display_name(buffer_id);
+        let saved_id = self.create_comparison_source(
+            format!("*saved* {}", name),
+            COMPARE_MODE,
+            String::from_utf8_lossy(&saved).into_owned(),
+        );
+        let saved_side = Side {
+            buffer_id: saved_id,
+            label: t!("compare.label_saved", name = name).to_string(),
+            editable: false,
+        };
+        self.open_comparison(saved_side, self.file_side(buffer_id), vec![saved_id]);
+    }
+
+    /// Compare the active buffer with the clipboard
+    pub fn compare_with_clipboard(&mut self) {
+        let Some(text) = self.clipboard.paste() else {
+            self.set_status_message(t!("compare.clipboard_empty").to_string());
+            return;
+        };
+        let buffer_id = self.active_buffer();
+        let name = self.get_buffer_display_name(buffer_id);
+        let clipboard_id =
+            self.create_comparison_source(format!("*clipboard* {}", name), COMPARE_MODE, text);
+        let clipboard_side = Side {
+            buffer_id: clipboard_id,
+            label: t!("compare.label_clipboard").to_string(),
+            editable: false,
+        };
+        self.open_comparison(
+            self.file_side(buffer_id),
+            clipboard_side,
+            vec![clipboard_id],
+        );
+    }
+
+    fn file_side(&self, buffer_id: BufferId) -> Side {
+        let metadata = self.buffer_metadata.get(&buffer_id);
+        let label = metadata
+            .and_then(|m| m.file_path())
+            .map(|path| BufferMetadata::display_name_for_path(path, &self.working_dir))
+            .unwrap_or_else(|| self.get_buffer_display_name(buffer_id));
+        Side {
+            buffer_id,
+            label,
+            editable: !metadata.is_some_and(|m| m.read_only),
+        }
+    }
+
+    /// Create a hidden, read-only buffer holding one side of a comparison
+    pub(super) fn create_comparison_source(
+        &mut self,
+        name: String,
+        mode: &str,
+        text: String,
+    ) -> BufferId {
+        let buffer_id = self.create_virtual_buffer(name.clone(), mode.to_string(), true);
+        self.buffer_metadata.insert(
+            buffer_id,
+            BufferMetadata::hidden_virtual_buffer(name, mode.to_string()),
+        );
+        for view_state in self.split_view_states.values_mut() {
+            view_state.remove_buffer(buffer_id);
+        }
+        if let Err(e) =
+            self.set_virtual_buffer_content(buffer_id, vec![TextPropertyEntry::text(text)])
+        {
+            tracing::warn!("Failed to fill comparison buffer: {}", e);
+        }
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.editing_disabled = true;
+        }
+        buffer_id
+    }
+
+    fn open_comparison(&mut self, left: Side, right: Side, owned: Vec<BufferId>) -> BufferId {
+        let short = |label: &str| {
+            Path::new(label).file_name().map_or_else(
+                || label.to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            )
+        };
+        let name = format!(
+            "*Compare: {} vs {}*",
+            short(&left.label),
+            short(&right.label)
+        );
+        let sources = vec![
+            SourcePane::new(left.buffer_id, left.label, left.editable)
+                .with_style(PaneStyle::old_diff()),
+            SourcePane::new(right.buffer_id, right.label, right.editable)
+                .with_style(PaneStyle::new_diff()),
+        ];
+        let buffer_id = self.create_composite_buffer(
+            name,
+            COMPARE_MODE.to_string(),
+            CompositeLayout::default(),
+            sources,
+        );
+        self.file_comparisons.insert(
+            buffer_id,
+            FileComparison {
+                left: left.buffer_id,
+                right: right.buffer_id,
+                owned,
+                hunks: Vec::new(),
+                versions: None,
+            },
+        );
+        self.refresh_file_comparison(buffer_id);
+        self.set_active_buffer(buffer_id);
+        let split_id = self.split_manager.active_split();
+        self.get_composite_view_state(split_id, buffer_id);
+
+        let count = self.file_comparisons[&buffer_id].hunks.len();
+        if count == 0 {
+            self.set_status_message(t!("compare.no_differences").to_string());
+        } else {
+            self.set_status_message(t!("compare.differences", count = count).to_string());
+        }
+        buffer_id
+    }
+
+    /// Recompute a comparison's hunks if either side changed
+    ///
+    /// Returns true if they were recomputed.
+    fn refresh_file_comparison(&mut self, buffer_id: BufferId) -> bool {
+        let Some(comparison) = self.file_comparisons.get(&buffer_id) else {
+            return false;
+        };
+        let (Some(left), Some(right)) = (
+            self.buffers.get(&comparison.left),
+            self.buffers.get(&comparison.right),
+        ) else {
+            return false;
+        };
+        let versions = (left.buffer.version(), right.buffer.version());
+        if comparison.versions == Some(versions) {
+            return false;
+        }
+        let (Some(old), Some(new)) = (left.buffer.to_string(), right.buffer.to_string()) else {
+            return false;
+        };
+
+        let hunks = diff_hunks(old.as_bytes(), new.as_bytes());
+        let alignment = LineAlignment::from_hunks(&hunks, line_count(&old), line_count(&new));
+        let max_row = alignment.rows.len().saturating_sub(1);
+        self.set_composite_alignment(buffer_id, alignment);
+        for ((_, id), view_state) in self.composite_view_states.iter_mut() {
+            if *id == buffer_id {
+                view_state.cursor_row = view_state.cursor_row.min(max_row);
+                view_state.scroll_row = view_state.scroll_row.min(max_row);
+            }
+        }
+        if let Some(comparison) = self.file_comparisons.get_mut(&buffer_id) {
+            comparison.hunks = hunks;
+            comparison.versions = Some(versions);
+        }
+        true
+    }
+
+    /// Keep comparisons up to date with edits to their sides (called from main loop)
+    ///
+    /// Returns true if any comparison changed.
+    pub(crate) fn poll_file_comparisons(&mut self) -> bool {
+        let ids: Vec<BufferId> = self.file_comparisons.keys().copied().collect();
+        let mut changed = false;
+        for buffer_id in ids {
+            changed |= self.refresh_file_comparison(buffer_id);
+        }
+        changed
+    }
+
+    /// Forget a comparison whose buffer is being closed, along with the
+    /// buffers it created
+    pub(super) fn close_file_comparison(&mut self, buffer_id: BufferId) {
+        let Some(comparison) = self.file_comparisons.remove(&buffer_id) else {
+            return;
+        };
+        self.close_composite_buffer(buffer_id);
+        for id in comparison.owned {
+            if let Err(e) = self.force_close_buffer(id) {
+                tracing::warn!("Failed to close comparison buffer {:?}: {}", id, e);
+            }
+        }
+    }
+
+    /// Move the cursor to the next (or previous) hunk of the active comparison
+    pub(super) fn compare_goto_hunk(&mut self, forward: bool) {
+        let buffer_id = self.active_buffer();
+        let split_id = self.split_manager.active_split();
+        if !self.file_comparisons.contains_key(&buffer_id) {
+            self.set_status_message(t!("compare.not_comparison").to_string());
+            return;
+        }
+        let (Some(composite), Some(view_state)) = (
+            self.composite_buffers.get(&buffer_id),
+            self.composite_view_states.get_mut(&(split_id, buffer_id)),
+        ) else {
+            return;
+        };
+        let row = if forward {
+            composite.alignment.next_hunk_row(view_state.cursor_row)
+        } else {
+            composite.alignment.prev_hunk_row(view_state.cursor_row)
+        };
+        match row {
+            Some(row) => {
+                view_state.cursor_row = row;
+                view_state.scroll_row = row;
+            }
+            None => self.set_status_message(t!("compare.no_more_changes").to_string()),
+        }
+    }
+
+    /// Copy the hunk at the cursor from one side of the active comparison to
+    /// the other
+    pub(super) fn compare_copy_hunk(&mut self, to_left: bool) {
+        let buffer_id = self.active_buffer();
+        let split_id = self.split_manager.active_split();
+        self.refresh_file_comparison(buffer_id);
+        let Some(comparison) = self.file_comparisons.get(&buffer_id) else {
+            self.set_status_message(t!("compare.not_comparison").to_string());
+            return;
+        };
+        let cursor_row = self
+            .composite_view_states
+            .get(&(split_id, buffer_id))
+            .map_or(0, |view_state| view_state.cursor_row);
+        let Some(composite) = self.composite_buffers.get(&buffer_id) else {
+            return;
+        };
+        let Some(hunk) = hunk_at_row(&composite.alignment, cursor_row)
+            .and_then(|index| comparison.hunks.get(index))
+        else {
+            self.set_status_message(t!("compare.no_hunk").to_string());
+            return;
+        };
+
+        let old_lines = hunk.old_start..hunk.old_start + hunk.old_count;
+        let new_lines = hunk.new_start..hunk.new_start + hunk.new_count;
+        let (source, target, source_lines, target_lines, target_pane) = if to_left {
+            (comparison.right, comparison.left, new_lines, old_lines, 0)
+        } else {
+            (comparison.left, comparison.right, old_lines, new_lines, 1)
+        };
+        if !composite
+            .sources
+            .get(target_pane)
+            .is_some_and(|pane| pane.editable)
+        {
+            self.set_status_message(t!("compare.read_only").to_string());
+            return;
+        }
+
+        let (Some(source_text), Some(target_text)) = (
+            self.buffers.get(&source).and_then(|s| s.buffer.to_string()),
+            self.buffers.get(&target).and_then(|s| s.buffer.to_string()),
+        ) else {
+            self.set_status_message(t!("compare.not_loaded").to_string());
+            return;
+        };
+        let (range, text) = replace_lines(&target_text, target_lines, &source_text, source_lines);
+        let Some(cursor_id) = self.buffers.get(&target).map(|s| s.cursors.primary_id()) else {
+            return;
+        };
+
+        let mut events = Vec::new();
+        if !range.is_empty() {
+            events.push(Event::Delete {
+                range: range.clone(),
+                deleted_text: target_text[range.clone()].to_string(),
+                cursor_id,
+            });
+        }
+        if !text.is_empty() {
+            events.push(Event::Insert {
+                position: range.start,
+                text,
+                cursor_id,
+            });
+        }
+        let description = if to_left {
+            t!("action.compare_copy_hunk_left")
+        } else {
+            t!("action.compare_copy_hunk_right")
+        };
+        if let Err(e) =
+            self.apply_events_to_buffer_as_bulk_edit(target, events, description.to_string())
+        {
+            self.set_status_message(t!("compare.copy_failed", error = e.to_string()).to_string());
+            return;
+        }
+        self.refresh_file_comparison(buffer_id);
+    }
+}
+
+/// Number of lines as [`diff_hunks`] counts them
+fn line_count(text: &str) -> usize {
+    text.split('\n').count()
+}
+
+/// Index of the hunk that an aligned row belongs to
+///
+/// Context rows belong to no hunk.
+fn hunk_at_row(alignment: &LineAlignment, row: usize) -> Option<usize> {
+    let aligned_row = alignment.rows.get(row)?;
+    if aligned_row.row_type == RowType::Context {
+        return None;
+    }
+    let headers = alignment.rows[..=row]
+        .iter()
+        .filter(|r| r.row_type == RowType::HunkHeader)
+        .count();
+    headers.checked_sub(1)
+}
+
+/// The edit that replaces `lines` of `target` with `source_lines` of `source`
+///
+/// Returns the byte range of `target` to delete and the text to insert in its
+/// place, trimmed to what actually changes.
+fn replace_lines(
+    target: &str,
+    lines: Range<usize>,
+    source: &str,
+    source_lines: Range<usize>,
+) -> (Range<usize>, String) {
+    let target_lines: Vec<&str> = target.split('\n').collect();
+    let source_lines: Vec<&str> = source.split('\n').collect::<Vec<_>>()[source_lines].to_vec();
+    let replaced = [
+        &target_lines[..lines.start],
+        &source_lines[..],
+        &target_lines[lines.end..],
+    ]
+    .concat()
+    .join("\n");
+
+    let mut prefix = target
+        .bytes()
+        .zip(replaced.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !target.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let max_suffix = (target.len() - prefix).min(replaced.len() - prefix);
+    let mut suffix = target
+        .bytes()
+        .rev()
+        .zip(replaced.bytes().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !target.is_char_boundary(target.len() - suffix) {
+        suffix -= 1;
+    }
+    (
+        prefix..target.len() - suffix,
+        replaced[prefix..replaced.len() - suffix].to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(
+        target: &str,
+        lines: Range<usize>,
+        source: &str,
+        source_lines: Range<usize>,
+    ) -> String {
+        let (range, text) = replace_lines(target, lines, source, source_lines);
+        format!("{}{}{}", &target[..range.start], text, &target[range.end..])
+    }
+
+    #[test]
+    fn test_replace_lines() {
+        // Modified line
+        assert_eq!(apply("a\nb\nc\n", 1..2, "a\nB\nc\n", 1..2), "a\nB\nc\n");
+        // Inserted and deleted lines
+        assert_eq!(apply("a\nb", 1..1, "a\nX\nb", 1..2), "a\nX\nb");
+        assert_eq!(apply("a\nX\nb", 1..2, "a\nb", 1..1), "a\nb");
+        // Last line, without a trailing newline
+        assert_eq!(apply("a\nb", 1..2, "a", 1..1), "a");
+        assert_eq!(apply("a\nb\n", 2..3, "a\nb\nX", 2..3), "a\nb\nX");
+
+        let (range, text) = replace_lines("héllo\n", 0..1, "hello\n", 0..1);
+        assert_eq!((range, text.as_str()), (1..3, "e"));
+    }
+
+    #[test]
+    fn test_hunk_at_row() {
+        let hunks = diff_hunks(b"a\nb\nc\nd\n", b"a\nB\nc\nd\nE\n");
+        let alignment = LineAlignment::from_hunks(&hunks, 5, 6);
+        let rows: Vec<_> = (0..alignment.rows.len())
+            .map(|row| hunk_at_row(&alignment, row))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![None, Some(0), Some(0), None, None, Some(1), Some(1), None]
+        );
+    }
+}
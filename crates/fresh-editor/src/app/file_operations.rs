@@ -10,6 +10,7 @@
 
 use crate::model::buffer::SudoSaveRequired;
 use crate::view::prompt::PromptType;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 use lsp_types::TextDocumentContentChangeEvent;
@@ -19,19 +20,34 @@ use crate::model::event::{BufferId, EventLog};
 use crate::services::lsp::manager::{detect_language, LspSpawnResult};
 use crate::state::EditorState;
 
-use super::{BufferMetadata, Editor};
+use super::{BufferMetadata, Editor, SaveOutcome};
 
 impl Editor {
     /// Save the active buffer
+    ///
+    /// A save that is cancelled or queued returns `Ok` but leaves the buffer
+    /// modified; use [`Self::save_with_outcome`] to tell these apart.
     pub fn save(&mut self) -> anyhow::Result<()> {
+        self.save_with_outcome().map(|_| ())
+    }
+
+    /// Save the active buffer and report whether it was written, queued until
+    /// the remote host reconnects, or not saved
+    pub fn save_with_outcome(&mut self) -> anyhow::Result<SaveOutcome> {
         let path = self
             .active_state()
             .buffer
             .file_path()
             .map(|p| p.to_path_buf());
 
+        if let Some(ref p) = path {
+            if !self.run_before_save_hook(self.active_buffer(), p.clone()) {
+                return Ok(SaveOutcome::NotSaved);
+            }
+        }
+
         match self.active_state_mut().buffer.save() {
-            Ok(()) => self.finalize_save(path),
+            Ok(()) => self.finalize_save(path).map(|()| SaveOutcome::Saved),
             Err(e) => {
                 if let Some(sudo_info) = e.downcast_ref::<SudoSaveRequired>() {
                     let info = sudo_info.clone();
@@ -39,7 +55,10 @@ impl Editor {
                         t!("prompt.sudo_save_confirm").to_string(),
                         PromptType::ConfirmSudoSave { info },
                     );
-                    Ok(())
+                    Ok(SaveOutcome::NotSaved)
+                } else if self.queue_remote_save_if_offline() {
+                    // Written automatically once the remote host is back
+                    Ok(SaveOutcome::Queued)
                 } else {
                     Err(e)
                 }
@@ -47,6 +66,56 @@ impl Editor {
         }
     }
 
+    /// Let plugins cancel saving a buffer to `path` or rewrite its content
+    ///
+    /// Every way of saving a buffer goes through here first. Returns false
+    /// when a plugin cancelled the save. Buffers that are not fully loaded
+    /// are saved without asking.
+    pub(crate) fn run_before_save_hook(&mut self, buffer_id: BufferId, path: PathBuf) -> bool {
+        if !self
+            .plugin_manager
+            .handles_cancellable_hook("before_file_save")
+        {
+            return true;
+        }
+        let Some(content) = self
+            .buffers
+            .get(&buffer_id)
+            .and_then(|state| state.buffer.to_string())
+        else {
+            return true;
+        };
+
+        let outcome = self.plugin_manager.run_cancellable_hook(
+            "before_file_save",
+            crate::services::plugins::hooks::HookArgs::BeforeFileSave {
+                buffer_id,
+                path,
+                content,
+            },
+        );
+        if let Some(plugin) = outcome.cancelled_by {
+            self.set_status_message(
+                t!("file.save_cancelled_by_plugin", plugin = plugin).to_string(),
+            );
+            return false;
+        }
+        if let Some(content) = outcome.replacement("content") {
+            // The rewrite goes through the active buffer, like on-save actions
+            let old_active = self.active_buffer();
+            if old_active != buffer_id {
+                self.set_active_buffer(buffer_id);
+            }
+            if let Err(e) = self.replace_buffer_with_output(content) {
+                tracing::warn!("Could not apply plugin changes before saving: {}", e);
+            }
+            if old_active != buffer_id {
+                self.set_active_buffer(old_active);
+            }
+        }
+        true
+    }
+
     /// Internal helper to finalize save state (mark as saved, notify LSP, etc.)
     pub(crate) fn finalize_save(&mut self, path: Option<PathBuf>) -> anyhow::Result<()> {
         // Auto-detect language if it's currently "text" and we have a path
@@ -101,6 +170,7 @@ impl Editor {
                     path: p.clone(),
                 },
             );
+            self.request_provider_diagnostics(buffer_id);
         }
 
         // Run on-save actions (formatters, linters, etc.)
@@ -224,8 +294,8 @@ impl Editor {
     /// Checks modification times of open files to detect external changes.
     /// Returns true if any file was changed (requires re-render).
     pub fn poll_file_changes(&mut self) -> bool {
-        // Skip if auto-revert is disabled
-        if !self.auto_revert_enabled {
+        // Skip if auto-revert is disabled and no file is followed
+        if !self.auto_revert_enabled && self.followed_buffers.is_empty() {
             return false;
         }
 
@@ -244,12 +314,21 @@ impl Editor {
         self.last_auto_revert_poll = self.time_source.now();
 
         // Collect paths of open files that need checking
-        let files_to_check: Vec<PathBuf> = self
+        let mut files_to_check: Vec<PathBuf> = self
             .buffers
-            .values()
-            .filter_map(|state| state.buffer.file_path().map(PathBuf::from))
+            .iter()
+            .filter(|(id, _)| self.auto_revert_enabled || self.followed_buffers.contains_key(id))
+            .filter_map(|(_, state)| state.buffer.file_path().map(PathBuf::from))
             .collect();
 
+        // With watch notifications, only files reported changed (or not yet
+        // seen) need a metadata round trip
+        if self.collect_watch_events() {
+            files_to_check.retain(|path| {
+                self.watch_events.remove(path) || !self.file_mod_times.contains_key(path)
+            });
+        }
+
         let mut any_changed = false;
 
         for path in files_to_check {
@@ -302,13 +381,19 @@ impl Editor {
 
         // Collect expanded directories (node_id, path)
         use crate::view::file_tree::NodeId;
-        let expanded_dirs: Vec<(NodeId, PathBuf)> = explorer
+        let mut expanded_dirs: Vec<(NodeId, PathBuf)> = explorer
             .tree()
             .all_nodes()
             .filter(|node| node.is_dir() && node.is_expanded())
             .map(|node| (node.id, node.entry.path.clone()))
             .collect();
 
+        if self.collect_watch_events() {
+            expanded_dirs.retain(|(_, path)| {
+                self.watch_events.remove(path) || !self.dir_mod_times.contains_key(path)
+            });
+        }
+
         // Check mtimes and collect directories that need refresh
         let mut dirs_to_refresh: Vec<NodeId> = Vec::new();
 
@@ -354,6 +439,50 @@ impl Editor {
         true
     }
 
+    /// Update the filesystem watch and collect its change notifications
+    ///
+    /// The watch covers open files, expanded file explorer directories and
+    /// plugin path watches. Returns false if the filesystem can't watch, in
+    /// which case callers must poll every path.
+    pub(super) fn collect_watch_events(&mut self) -> bool {
+        if !self.filesystem.supports_watch() {
+            return false;
+        }
+
+        let mut watched: Vec<PathBuf> = self
+            .buffers
+            .values()
+            .filter_map(|state| state.buffer.file_path().map(PathBuf::from))
+            .collect();
+        if let Some(explorer) = &self.file_explorer {
+            watched.extend(
+                explorer
+                    .tree()
+                    .all_nodes()
+                    .filter(|node| node.is_dir() && node.is_expanded())
+                    .map(|node| node.entry.path.clone()),
+            );
+        }
+        let mut all: Vec<PathBuf> = watched
+            .iter()
+            .chain(self.path_watches.remote_paths())
+            .cloned()
+            .collect();
+        all.sort();
+        all.dedup();
+
+        self.filesystem.watch(&all);
+        let changed = self.filesystem.take_changed_paths();
+        self.path_watches
+            .remote_changed(&changed, self.time_source.now());
+        self.watch_events.extend(changed);
+
+        // Drop events for files closed or directories collapsed since
+        let watched: HashSet<&PathBuf> = watched.iter().collect();
+        self.watch_events.retain(|path| watched.contains(path));
+        true
+    }
+
     /// Notify LSP server about a newly opened file
     /// Handles language detection, spawning LSP clients, and sending didOpen notifications
     pub(crate) fn notify_lsp_file_opened(
@@ -0,0 +1,363 @@
+//! Follow mode for growing files
+//!
+//! A followed buffer behaves like `tail -f`: when its file is appended to
+//! (e.g. a service log), only the new bytes are added to the buffer, as a
+//! lazily loaded chunk, instead of reverting the whole file. Splits whose
+//! cursor is at the end of the buffer stay pinned to the end; moving the
+//! cursor or scrolling up unpins them. When the file is truncated or
+//! replaced (e.g. rotated), the buffer is reloaded and pinned splits move to
+//! its new end.
+//!
+//! A followed buffer can have a filter: lines matching its regex are
+//! highlighted as they arrive.
+
+use super::Editor;
+use crate::model::event::{BufferId, Event, SplitId};
+use crate::state::EditorState;
+use crate::view::overlay::{Overlay, OverlayFace, OverlayNamespace};
+use regex::bytes::Regex;
+use rust_i18n::t;
+use std::ops::Range;
+use std::path::Path;
+
+/// Most bytes scanned for filter matches at once, counted from the end of
+/// the scanned range, so that filtering never reads a whole large file
+const FILTER_SCAN_BYTES: usize = 1024 * 1024;
+
+/// Most bytes read back from the start of a filter scan to find the start of
+/// the line it falls in
+const LINE_LOOKBACK_BYTES: usize = 64 * 1024;
+
+/// State of a followed buffer
+#[derive(Default)]
+pub(super) struct FollowState {
+    /// Lines matching this are highlighted
+    filter: Option<Regex>,
+}
+
+/// Move the primary cursor of a buffer's state, keeping the line number
+/// shown in the status bar up to date
+fn move_primary_cursor(state: &mut EditorState, position: usize) {
+    let primary = state.cursors.primary();
+    let event = Event::MoveCursor {
+        cursor_id: state.cursors.primary_id(),
+        old_position: primary.position,
+        new_position: position,
+        old_anchor: primary.anchor,
+        new_anchor: None,
+        old_sticky_column: primary.sticky_column,
+        new_sticky_column: 0,
+    };
+    state.apply(&event);
+}
+
+/// Offset into `text` of the first line to scan for filter matches, when
+/// scanning should start at `offset`: the line containing `offset` starts
+/// after the last newline before it. If there is none and `text` does not
+/// start at the beginning of the file, that line started too far back and is
+/// skipped.
+fn first_line_start(text: &[u8], offset: usize, at_file_start: bool) -> usize {
+    match text[..offset].iter().rposition(|&b| b == b'\n') {
+        Some(newline) => newline + 1,
+        None if at_file_start => 0,
+        None => text[offset..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(text.len(), |newline| offset + newline + 1),
+    }
+}
+
+/// Overlay namespace of filter highlights
+fn follow_namespace() -> OverlayNamespace {
+    OverlayNamespace::from_string("follow-filter".to_string())
+}
+
+impl Editor {
+    /// Start or stop following the active buffer's file
+    pub fn toggle_follow_mode(&mut self) {
+        let buffer_id = self.active_buffer();
+        let name = self.get_buffer_display_name(buffer_id);
+        if self.followed_buffers.remove(&buffer_id).is_some() {
+            let ns = follow_namespace();
+            let state = self.active_state_mut();
+            state.overlays.clear_namespace(&ns, &mut state.marker_list);
+            self.set_status_message(t!("follow.disabled", name = name).to_string());
+            return;
+        }
+
+        if self.active_state().buffer.file_path().is_none() {
+            self.set_status_message(t!("follow.no_file").to_string());
+            return;
+        }
+        self.followed_buffers
+            .insert(buffer_id, FollowState::default());
+
+        // Pin the view to the end right away
+        let state = self.active_state_mut();
+        let end = state.buffer.len();
+        move_primary_cursor(state, end);
+        let active_split = self.split_manager.active_split();
+        if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
+            view_state.viewport.clear_skip_ensure_visible();
+        }
+        self.set_status_message(t!("follow.enabled", name = name).to_string());
+    }
+
+    /// Set the regex whose matching lines are highlighted in the active
+    /// followed buffer; an empty filter clears it
+    pub fn set_follow_filter(&mut self, filter: &str) {
+        let buffer_id = self.active_buffer();
+        if !self.followed_buffers.contains_key(&buffer_id) {
+            self.set_status_message(t!("follow.not_following").to_string());
+            return;
+        }
+
+        let regex = if filter.is_empty() {
+            None
+        } else {
+            match Regex::new(filter) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    self.set_status_message(
+                        t!("follow.invalid_filter", error = e.to_string()).to_string(),
+                    );
+                    return;
+                }
+            }
+        };
+
+        let ns = follow_namespace();
+        let state = self.active_state_mut();
+        state.overlays.clear_namespace(&ns, &mut state.marker_list);
+        let end = state.buffer.len();
+        if let Some(follow) = self.followed_buffers.get_mut(&buffer_id) {
+            follow.filter = regex;
+        }
+
+        if filter.is_empty() {
+            self.set_status_message(t!("follow.filter_cleared").to_string());
+        } else {
+            self.highlight_follow_range(buffer_id, None, 0..end);
+            self.set_status_message(t!("follow.filter_set", filter = filter).to_string());
+        }
+    }
+
+    /// Extend the followed buffers of a changed file with what was appended
+    /// to it, or reload them if the file was truncated or replaced
+    ///
+    /// This happens whether or not auto-revert is enabled. Returns false if
+    /// no buffer follows the file or the followed buffers have unsaved
+    /// changes, in which case the change is handled like any other.
+    pub(super) fn follow_append(&mut self, path: &Path) -> bool {
+        let buffer_ids: Vec<BufferId> = self
+            .followed_buffers
+            .keys()
+            .copied()
+            .filter(|id| {
+                self.buffers
+                    .get(id)
+                    .is_some_and(|state| state.buffer.file_path() == Some(path))
+            })
+            .collect();
+
+        let mut handled = false;
+        for buffer_id in buffer_ids {
+            let Some(state) = self.buffers.get(&buffer_id) else {
+                continue;
+            };
+            if state.buffer.is_modified() {
+                continue;
+            }
+            let pinned = self.pinned_splits(buffer_id, state.buffer.len());
+            let Some(state) = self.buffers.get_mut(&buffer_id) else {
+                continue;
+            };
+            let range = match state.buffer.extend_appended() {
+                Ok(Some(range)) => range,
+                // Truncated or replaced: reload the whole file
+                Ok(None) => {
+                    if let Err(e) = self.revert_buffer_by_id(buffer_id, path) {
+                        tracing::warn!("Failed to reload followed file {:?}: {}", path, e);
+                        continue;
+                    }
+                    let new_end = self.buffers[&buffer_id].buffer.len();
+                    self.clamp_split_cursors(buffer_id, new_end);
+                    0..new_end
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to follow {:?}: {}", path, e);
+                    continue;
+                }
+            };
+            handled = true;
+            self.pin_splits(buffer_id, &pinned, range.end);
+            if self.followed_buffers[&buffer_id].filter.is_some() {
+                self.highlight_follow_range(buffer_id, Some(path), range);
+            }
+        }
+
+        if handled {
+            if let Some(mtime) = self.filesystem.metadata(path).ok().and_then(|m| m.modified) {
+                self.file_mod_times.insert(path.to_path_buf(), mtime);
+            }
+        }
+        handled
+    }
+
+    /// Splits of a buffer that are pinned to its end: their primary cursor is
+    /// at `end` without a selection and they were not scrolled away from it
+    fn pinned_splits(&self, buffer_id: BufferId, end: usize) -> Vec<SplitId> {
+        let active_split = self.split_manager.active_split();
+        self.split_manager
+            .splits_for_buffer(buffer_id)
+            .into_iter()
+            .filter(|split_id| {
+                let Some(view_state) = self.split_view_states.get(split_id) else {
+                    return false;
+                };
+                // Scrolling away from the cursor unpins the split
+                if view_state.viewport.should_skip_ensure_visible() {
+                    return false;
+                }
+                // The active split's cursors live in the buffer's state
+                let primary = if *split_id == active_split {
+                    match self.buffers.get(&buffer_id) {
+                        Some(state) => state.cursors.primary(),
+                        None => return false,
+                    }
+                } else {
+                    view_state.cursors.primary()
+                };
+                primary.position == end && primary.anchor.is_none()
+            })
+            .collect()
+    }
+
+    /// Move the primary cursor of the given splits of a followed buffer to
+    /// its new end, so they keep showing the end
+    fn pin_splits(&mut self, buffer_id: BufferId, splits: &[SplitId], end: usize) {
+        let active_split = self.split_manager.active_split();
+        for split_id in splits {
+            if *split_id == active_split {
+                if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                    move_primary_cursor(state, end);
+                }
+            } else if let Some(view_state) = self.split_view_states.get_mut(split_id) {
+                view_state.cursors.primary_mut().position = end;
+            }
+        }
+    }
+
+    /// Keep the cursors of a reloaded buffer's inactive splits within its
+    /// new length (the active split's are clamped by the reload)
+    fn clamp_split_cursors(&mut self, buffer_id: BufferId, len: usize) {
+        let active_split = self.split_manager.active_split();
+        for split_id in self.split_manager.splits_for_buffer(buffer_id) {
+            if split_id == active_split {
+                continue;
+            }
+            if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+                view_state.cursors.map(|cursor| {
+                    cursor.position = cursor.position.min(len);
+                    cursor.clear_selection();
+                });
+            }
+        }
+    }
+
+    /// Highlight the filter matches among the lines in `range` of a followed
+    /// buffer, scanning at most the last [`FILTER_SCAN_BYTES`] of it
+    ///
+    /// The scan starts at the beginning of the line it falls in, since the
+    /// range can start mid-line (e.g. when an append completes a line).
+    /// With `file`, the text is read from the file so that the buffer's
+    /// chunks for it stay unloaded.
+    fn highlight_follow_range(
+        &mut self,
+        buffer_id: BufferId,
+        file: Option<&Path>,
+        range: Range<usize>,
+    ) {
+        let scan_start = range.end.saturating_sub(FILTER_SCAN_BYTES).max(range.start);
+        let read_start = scan_start.saturating_sub(LINE_LOOKBACK_BYTES);
+        let len = range.end - read_start;
+        let text = match file {
+            Some(path) => self
+                .filesystem
+                .read_range(path, read_start as u64, len)
+                .map_err(anyhow::Error::from),
+            None => match self.buffers.get_mut(&buffer_id) {
+                Some(state) => state.buffer.get_text_range_mut(read_start, len),
+                None => return,
+            },
+        };
+        let text = match text {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::warn!("Failed to read lines to filter: {}", e);
+                return;
+            }
+        };
+        let first = first_line_start(&text, scan_start - read_start, read_start == 0);
+        self.highlight_follow_matches(buffer_id, read_start + first, &text[first..]);
+    }
+
+    /// Highlight the lines of `text` (the buffer's content at `start`) that
+    /// match the buffer's follow filter, replacing the highlights there
+    fn highlight_follow_matches(&mut self, buffer_id: BufferId, start: usize, text: &[u8]) {
+        let Some(regex) = self
+            .followed_buffers
+            .get(&buffer_id)
+            .and_then(|follow| follow.filter.clone())
+        else {
+            return;
+        };
+        let color = self.theme.search_match_bg;
+        let ns = follow_namespace();
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+
+        let mut overlays = Vec::new();
+        let mut line_start = start;
+        for line in text.split_inclusive(|&b| b == b'\n') {
+            let content = line.strip_suffix(b"\n").unwrap_or(line);
+            if regex.is_match(content) {
+                let overlay = Overlay::with_namespace(
+                    &mut state.marker_list,
+                    line_start..line_start + content.len(),
+                    OverlayFace::Background { color },
+                    ns.clone(),
+                )
+                .with_extend_to_line_end(true);
+                overlays.push(overlay);
+            }
+            line_start += line.len();
+        }
+        // A line completed by an append may already be highlighted
+        state.overlays.replace_range_in_namespace(
+            &ns,
+            &(start..start + text.len()),
+            overlays,
+            &mut state.marker_list,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_line_start() {
+        let text = b"one\ntwo\nthree\n";
+        // Mid-line offsets go back to the start of their line
+        assert_eq!(first_line_start(text, 6, false), 4);
+        assert_eq!(first_line_start(text, 4, false), 4);
+        // Without an earlier newline the line starts at the file start...
+        assert_eq!(first_line_start(text, 2, true), 0);
+        // ...or too far back, and is skipped
+        assert_eq!(first_line_start(text, 2, false), 4);
+        assert_eq!(first_line_start(b"partial", 3, false), 7);
+    }
+}
@@ -0,0 +1,566 @@
+//! Plugin form dialogs
+//!
+//! A plugin describes a form as a list of fields (text, number, toggle,
+//! dropdown, text list) and a row of buttons. The editor shows it as a modal
+//! built from the `view::controls` widgets and reports edits and the final
+//! result back through the `form_field_changed` and `form_result` hooks.
+
+use crate::view::controls::{
+    ButtonState, DropdownState, FocusState, NumberInputState, TextInputState, TextListState,
+    ToggleState,
+};
+use crate::view::form_dialog::{FormDialogLayout, FormFieldLayout};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use fresh_core::api::{FormField, FormOptions};
+use serde_json::Value as JsonValue;
+
+/// Button id reported when the dialog is closed with Escape
+pub const DISMISSED: &str = "dismissed";
+
+/// Most dropdown options shown at once while it is open
+const MAX_VISIBLE_OPTIONS: usize = 8;
+
+/// The control behind a form field
+#[derive(Debug, Clone)]
+pub enum FormControl {
+    Text(TextInputState),
+    Number(NumberInputState),
+    Toggle(ToggleState),
+    Dropdown(DropdownState),
+    TextList(TextListState),
+}
+
+impl FormControl {
+    /// Build the control for a field, using its initial value where it fits
+    fn from_field(field: &FormField) -> Self {
+        let value = field.value.as_ref();
+        let label = field.label.clone();
+        match field.kind.as_str() {
+            "number" => {
+                let initial = value
+                    .and_then(|v| v.as_i64().or_else(|| v.as_f64().map(|f| f as i64)))
+                    .unwrap_or(0);
+                let mut state = NumberInputState::new(initial, label);
+                if let Some(min) = field.min {
+                    state = state.with_min(min);
+                }
+                if let Some(max) = field.max {
+                    state = state.with_max(max);
+                }
+                if let Some(step) = field.step {
+                    state = state.with_step(step);
+                }
+                Self::Number(state)
+            }
+            "toggle" => Self::Toggle(ToggleState::new(
+                value.and_then(JsonValue::as_bool).unwrap_or(false),
+                label,
+            )),
+            "dropdown" => {
+                let options = field.options.clone().unwrap_or_default();
+                let selected = value
+                    .and_then(JsonValue::as_str)
+                    .and_then(|v| options.iter().position(|o| o == v))
+                    .unwrap_or(0);
+                Self::Dropdown(DropdownState::new(options, label).with_selected(selected))
+            }
+            "textList" => {
+                let items = value
+                    .and_then(JsonValue::as_array)
+                    .map(|items| {
+                        items
+                            .iter()
+                            .filter_map(|item| item.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Self::TextList(TextListState::new(label).with_items(items))
+            }
+            _ => {
+                let mut state = TextInputState::new(label)
+                    .with_value(value.and_then(JsonValue::as_str).unwrap_or(""));
+                if let Some(placeholder) = &field.placeholder {
+                    state = state.with_placeholder(placeholder.clone());
+                }
+                Self::Text(state)
+            }
+        }
+    }
+
+    /// Current value as reported to the plugin
+    pub fn value(&self) -> JsonValue {
+        match self {
+            Self::Text(state) => JsonValue::from(state.value.clone()),
+            Self::Number(state) => JsonValue::from(state.value),
+            Self::Toggle(state) => JsonValue::from(state.checked),
+            Self::Dropdown(state) => JsonValue::from(state.selected_option().unwrap_or_default()),
+            Self::TextList(state) => JsonValue::from(state.items.clone()),
+        }
+    }
+
+    /// Rows the control takes up in the dialog
+    pub fn height(&self) -> u16 {
+        match self {
+            Self::Dropdown(state) if state.open => {
+                1 + state.options.len().min(MAX_VISIBLE_OPTIONS) as u16
+            }
+            // Label row, one row per item and the "add" row
+            Self::TextList(state) => state.items.len() as u16 + 2,
+            _ => 1,
+        }
+    }
+
+    fn set_focus(&mut self, focus: FocusState) {
+        match self {
+            Self::Text(state) => state.focus = focus,
+            Self::Number(state) => state.focus = focus,
+            Self::Toggle(state) => state.focus = focus,
+            Self::Dropdown(state) => state.focus = focus,
+            Self::TextList(state) => state.focus = focus,
+        }
+    }
+
+    /// Apply a pending number edit or an open dropdown choice
+    fn finish_editing(&mut self) {
+        match self {
+            Self::Number(state) if state.editing() => state.confirm_editing(),
+            Self::Dropdown(state) if state.open => state.confirm(),
+            _ => {}
+        }
+    }
+
+    /// Whether the control uses Up/Down itself instead of moving between fields
+    fn captures_vertical(&self) -> bool {
+        match self {
+            Self::Number(_) | Self::TextList(_) => true,
+            Self::Dropdown(state) => state.open,
+            _ => false,
+        }
+    }
+
+    /// Whether the control is mid-edit (a number being typed, an open dropdown)
+    fn is_editing(&self) -> bool {
+        match self {
+            Self::Number(state) => state.editing(),
+            Self::Dropdown(state) => state.open,
+            _ => false,
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        match self {
+            Self::Text(state) => {
+                state.handle_key(key);
+            }
+            Self::Number(state) => {
+                // Typing a digit starts editing, replacing the current value
+                if !state.editing() && matches!(key.code, KeyCode::Char(c) if c.is_ascii_digit()) {
+                    state.start_editing();
+                }
+                state.handle_key(key);
+            }
+            Self::Toggle(state) => {
+                state.handle_key(key);
+            }
+            Self::Dropdown(state) => {
+                state.handle_key(key);
+            }
+            Self::TextList(state) => {
+                state.handle_key(key);
+            }
+        }
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent, layout: &FormFieldLayout) {
+        match (self, layout) {
+            (Self::Text(state), FormFieldLayout::Text(layout)) => {
+                state.handle_mouse(event, layout);
+            }
+            (Self::Number(state), FormFieldLayout::Number(layout)) => {
+                state.handle_mouse(event, layout);
+            }
+            (Self::Toggle(state), FormFieldLayout::Toggle(layout)) => {
+                state.handle_mouse(event, layout);
+            }
+            (Self::Dropdown(state), FormFieldLayout::Dropdown(layout)) => {
+                state.handle_mouse(event, layout);
+            }
+            (Self::TextList(state), FormFieldLayout::TextList(layout)) => {
+                state.handle_mouse(event, layout);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A field of an open form dialog
+#[derive(Debug, Clone)]
+pub struct FormDialogField {
+    /// Field id, the key of the field in the form values
+    pub id: String,
+    pub control: FormControl,
+    /// Value last reported to the plugin
+    reported: JsonValue,
+}
+
+/// What the editor should do after the dialog handled an event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormAction {
+    /// Keep the dialog open
+    Continue,
+    /// The dialog was closed with a button, or dismissed
+    Closed { button: String },
+}
+
+/// State of an open form dialog
+#[derive(Debug, Clone)]
+pub struct FormDialog {
+    /// Form id from the plugin
+    pub form_id: String,
+    pub title: String,
+    pub fields: Vec<FormDialogField>,
+    /// Buttons as (id, state)
+    pub buttons: Vec<(String, ButtonState)>,
+    /// Focused element: fields first, then buttons
+    focus: usize,
+}
+
+impl FormDialog {
+    /// Build a dialog from the plugin's description
+    pub fn new(options: FormOptions) -> Self {
+        let fields = options
+            .fields
+            .iter()
+            .map(|field| {
+                let control = FormControl::from_field(field);
+                FormDialogField {
+                    id: field.id.clone(),
+                    reported: control.value(),
+                    control,
+                }
+            })
+            .collect();
+        let buttons = match options.buttons {
+            Some(buttons) if !buttons.is_empty() => buttons
+                .into_iter()
+                .map(|button| (button.id, ButtonState::new(button.label)))
+                .collect(),
+            _ => vec![
+                ("ok".to_string(), ButtonState::new("OK")),
+                ("cancel".to_string(), ButtonState::new("Cancel")),
+            ],
+        };
+        let mut dialog = Self {
+            form_id: options.id,
+            title: options.title,
+            fields,
+            buttons,
+            focus: 0,
+        };
+        dialog.set_focus(0);
+        dialog
+    }
+
+    /// All field values, keyed by field id
+    pub fn values(&self) -> JsonValue {
+        JsonValue::Object(
+            self.fields
+                .iter()
+                .map(|field| (field.id.clone(), field.control.value()))
+                .collect(),
+        )
+    }
+
+    /// Current value of a field
+    pub fn value(&self, field_id: &str) -> JsonValue {
+        self.fields
+            .iter()
+            .find(|field| field.id == field_id)
+            .map(|field| field.control.value())
+            .unwrap_or(JsonValue::Null)
+    }
+
+    /// Index of the focused field, if focus is not on a button
+    pub fn focused_field(&self) -> Option<usize> {
+        (self.focus < self.fields.len()).then_some(self.focus)
+    }
+
+    /// Index of the focused button, if any
+    pub fn focused_button(&self) -> Option<usize> {
+        self.focus.checked_sub(self.fields.len())
+    }
+
+    fn element_count(&self) -> usize {
+        self.fields.len() + self.buttons.len()
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        let field_count = self.fields.len();
+        for (i, field) in self.fields.iter_mut().enumerate() {
+            if i == focus {
+                field.control.set_focus(FocusState::Focused);
+            } else {
+                if i == self.focus {
+                    field.control.finish_editing();
+                }
+                field.control.set_focus(FocusState::Normal);
+            }
+        }
+        for (i, (_, button)) in self.buttons.iter_mut().enumerate() {
+            button.focus = if field_count + i == focus {
+                FocusState::Focused
+            } else {
+                FocusState::Normal
+            };
+        }
+        self.focus = focus;
+    }
+
+    fn move_focus(&mut self, forward: bool) {
+        let count = self.element_count();
+        let next = if forward {
+            (self.focus + 1) % count
+        } else {
+            (self.focus + count - 1) % count
+        };
+        self.set_focus(next);
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, key: KeyEvent) -> FormAction {
+        match self.focused_field() {
+            Some(index) => self.handle_field_key(index, key),
+            None => self.handle_button_key(key),
+        }
+    }
+
+    /// Ids of fields whose value settled on something new since the last call
+    ///
+    /// A number being typed or an open dropdown is not reported until the
+    /// edit is applied.
+    pub fn take_changes(&mut self) -> Vec<String> {
+        let mut changed = Vec::new();
+        for field in &mut self.fields {
+            if field.control.is_editing() {
+                continue;
+            }
+            let value = field.control.value();
+            if value != field.reported {
+                field.reported = value;
+                changed.push(field.id.clone());
+            }
+        }
+        changed
+    }
+
+    fn handle_field_key(&mut self, index: usize, key: KeyEvent) -> FormAction {
+        let control = &mut self.fields[index].control;
+        match key.code {
+            KeyCode::Tab => self.move_focus(true),
+            KeyCode::BackTab => self.move_focus(false),
+            // Escape ends an edit inside the control before it closes the dialog
+            KeyCode::Esc if !control.is_editing() => {
+                return FormAction::Closed {
+                    button: DISMISSED.to_string(),
+                }
+            }
+            KeyCode::Up if !control.captures_vertical() => self.move_focus(false),
+            KeyCode::Down if !control.captures_vertical() => self.move_focus(true),
+            // Enter in a text field presses the first button
+            KeyCode::Enter if matches!(control, FormControl::Text(_)) => {
+                return FormAction::Closed {
+                    button: self.buttons[0].0.clone(),
+                }
+            }
+            _ => control.handle_key(key),
+        }
+        FormAction::Continue
+    }
+
+    fn handle_button_key(&mut self, key: KeyEvent) -> FormAction {
+        let Some(index) = self.focused_button() else {
+            return FormAction::Continue;
+        };
+        match key.code {
+            KeyCode::Tab | KeyCode::Down => self.move_focus(true),
+            KeyCode::BackTab | KeyCode::Up => self.move_focus(false),
+            KeyCode::Left if index > 0 => self.move_focus(false),
+            KeyCode::Right if index + 1 < self.buttons.len() => self.move_focus(true),
+            KeyCode::Esc => {
+                return FormAction::Closed {
+                    button: DISMISSED.to_string(),
+                }
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                return FormAction::Closed {
+                    button: self.buttons[index].0.clone(),
+                }
+            }
+            _ => {}
+        }
+        FormAction::Continue
+    }
+
+    /// Handle a mouse event against the last rendered layout
+    pub fn handle_mouse(&mut self, event: MouseEvent, layout: &FormDialogLayout) -> FormAction {
+        if event.kind != MouseEventKind::Down(MouseButton::Left) {
+            return FormAction::Continue;
+        }
+        let (x, y) = (event.column, event.row);
+
+        if let Some(index) = layout.buttons.iter().position(|b| b.contains(x, y)) {
+            if let Some((id, _)) = self.buttons.get(index) {
+                return FormAction::Closed { button: id.clone() };
+            }
+        }
+
+        let Some(index) = layout.fields.iter().position(|f| f.contains(x, y)) else {
+            return FormAction::Continue;
+        };
+        if index >= self.fields.len() {
+            return FormAction::Continue;
+        }
+        if self.focus != index {
+            self.set_focus(index);
+        }
+        self.fields[index]
+            .control
+            .handle_mouse(event, &layout.fields[index]);
+        FormAction::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use fresh_core::api::FormButton;
+    use serde_json::json;
+
+    fn field(id: &str, kind: &str, value: JsonValue) -> FormField {
+        FormField {
+            id: id.to_string(),
+            label: id.to_string(),
+            kind: kind.to_string(),
+            value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    fn dialog() -> FormDialog {
+        FormDialog::new(FormOptions {
+            id: "new-file".to_string(),
+            title: "New File".to_string(),
+            fields: vec![
+                field("name", "text", json!("main")),
+                field("count", "number", json!(3)),
+                field("open", "toggle", json!(false)),
+                FormField {
+                    options: Some(vec!["rs".into(), "ts".into(), "py".into()]),
+                    ..field("ext", "dropdown", json!("ts"))
+                },
+            ],
+            buttons: None,
+        })
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_initial_values_and_default_buttons() {
+        let dialog = dialog();
+        assert_eq!(
+            dialog.values(),
+            json!({"name": "main", "count": 3, "open": false, "ext": "ts"})
+        );
+        let ids: Vec<&str> = dialog.buttons.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["ok", "cancel"]);
+        assert_eq!(dialog.focused_field(), Some(0));
+    }
+
+    #[test]
+    fn test_edits_report_changed_fields() {
+        let mut dialog = dialog();
+        dialog.handle_key(key(KeyCode::Char('s')));
+        assert_eq!(dialog.take_changes(), vec!["name"]);
+        assert_eq!(dialog.value("name"), json!("mains"));
+        assert!(dialog.take_changes().is_empty());
+
+        dialog.handle_key(key(KeyCode::Tab));
+        dialog.handle_key(key(KeyCode::Up));
+        assert_eq!(dialog.take_changes(), vec!["count"]);
+        assert_eq!(dialog.value("count"), json!(4));
+
+        // Typed numbers are applied when focus leaves the field
+        dialog.handle_key(key(KeyCode::Char('9')));
+        assert!(dialog.take_changes().is_empty());
+        dialog.handle_key(key(KeyCode::Tab));
+        assert_eq!(dialog.take_changes(), vec!["count"]);
+        assert_eq!(dialog.value("count"), json!(9));
+
+        dialog.handle_key(key(KeyCode::Char(' ')));
+        assert_eq!(dialog.value("open"), json!(true));
+        assert_eq!(dialog.take_changes(), vec!["open"]);
+
+        dialog.handle_key(key(KeyCode::Down));
+        dialog.handle_key(key(KeyCode::Enter));
+        dialog.handle_key(key(KeyCode::Down));
+        assert!(dialog.take_changes().is_empty());
+        // Escape closes the open dropdown, not the dialog
+        assert_eq!(dialog.handle_key(key(KeyCode::Esc)), FormAction::Continue);
+        assert_eq!(dialog.value("ext"), json!("ts"));
+        assert!(dialog.take_changes().is_empty());
+
+        dialog.handle_key(key(KeyCode::Enter));
+        dialog.handle_key(key(KeyCode::Down));
+        dialog.handle_key(key(KeyCode::Enter));
+        assert_eq!(dialog.take_changes(), vec!["ext"]);
+        assert_eq!(dialog.value("ext"), json!("py"));
+    }
+
+    #[test]
+    fn test_buttons_close_the_dialog() {
+        let mut dialog = FormDialog::new(FormOptions {
+            id: "commit".into(),
+            title: "Commit".into(),
+            fields: vec![field("message", "text", json!(""))],
+            buttons: Some(vec![
+                FormButton {
+                    id: "create".into(),
+                    label: "Create".into(),
+                },
+                FormButton {
+                    id: "skip".into(),
+                    label: "Skip".into(),
+                },
+            ]),
+        });
+
+        // Enter in a text field presses the first button
+        assert_eq!(
+            dialog.handle_key(key(KeyCode::Enter)),
+            FormAction::Closed {
+                button: "create".into()
+            }
+        );
+
+        dialog.handle_key(key(KeyCode::BackTab));
+        assert_eq!(dialog.focused_button(), Some(1));
+        dialog.handle_key(key(KeyCode::Left));
+        dialog.handle_key(key(KeyCode::Right));
+        assert_eq!(
+            dialog.handle_key(key(KeyCode::Enter)),
+            FormAction::Closed {
+                button: "skip".into()
+            }
+        );
+        assert_eq!(
+            dialog.handle_key(key(KeyCode::Esc)),
+            FormAction::Closed {
+                button: DISMISSED.into()
+            }
+        );
+    }
+}
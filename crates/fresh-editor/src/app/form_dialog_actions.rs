@@ -0,0 +1,93 @@
+//! Plugin form dialog handling
+//!
+//! Opens and closes plugin form dialogs, routes input to the open dialog and
+//! fires the `form_field_changed` and `form_result` hooks.
+
+use super::form_dialog::{FormAction, FormDialog, DISMISSED};
+use super::Editor;
+use crate::input::handler::InputResult;
+use crate::services::plugins::hooks::HookArgs;
+use crossterm::event::{KeyEvent, MouseEvent};
+use fresh_core::api::FormOptions;
+
+impl Editor {
+    /// Show a plugin form dialog, dismissing any form that is already open
+    pub(crate) fn show_form_dialog(&mut self, options: FormOptions) {
+        if let Some(previous) = self.form_dialog.take() {
+            self.fire_form_result(&previous, DISMISSED.to_string());
+        }
+        self.form_dialog = Some(FormDialog::new(options));
+    }
+
+    /// Close a plugin form dialog without firing `form_result`
+    pub(crate) fn close_form_dialog(&mut self, form_id: &str) {
+        if self
+            .form_dialog
+            .as_ref()
+            .is_some_and(|dialog| dialog.form_id == form_id)
+        {
+            self.form_dialog = None;
+            self.cached_layout.form_dialog_layout = None;
+        }
+    }
+
+    /// Handle a key press while a form dialog is open
+    pub fn handle_form_dialog_input(&mut self, event: &KeyEvent) -> InputResult {
+        let Some(dialog) = self.form_dialog.as_mut() else {
+            return InputResult::Ignored;
+        };
+        let action = dialog.handle_key(*event);
+        self.finish_form_dialog_event(action);
+        InputResult::Consumed
+    }
+
+    /// Handle a mouse event while a form dialog is open
+    ///
+    /// Clicks outside the dialog are ignored; the dialog stays modal.
+    pub(crate) fn handle_form_dialog_mouse(&mut self, mouse_event: MouseEvent) -> bool {
+        let (Some(dialog), Some(layout)) = (
+            self.form_dialog.as_mut(),
+            self.cached_layout.form_dialog_layout.as_ref(),
+        ) else {
+            return false;
+        };
+        let action = dialog.handle_mouse(mouse_event, layout);
+        self.finish_form_dialog_event(action);
+        true
+    }
+
+    /// Report settled field changes and close the dialog if a button was pressed
+    fn finish_form_dialog_event(&mut self, action: FormAction) {
+        let Some(dialog) = self.form_dialog.as_mut() else {
+            return;
+        };
+        for field in dialog.take_changes() {
+            self.plugin_manager.run_hook(
+                "form_field_changed",
+                HookArgs::FormFieldChanged {
+                    form_id: dialog.form_id.clone(),
+                    value: dialog.value(&field),
+                    values: dialog.values(),
+                    field,
+                },
+            );
+        }
+        if let FormAction::Closed { button } = action {
+            if let Some(dialog) = self.form_dialog.take() {
+                self.cached_layout.form_dialog_layout = None;
+                self.fire_form_result(&dialog, button);
+            }
+        }
+    }
+
+    fn fire_form_result(&self, dialog: &FormDialog, button: String) {
+        self.plugin_manager.run_hook(
+            "form_result",
+            HookArgs::FormResult {
+                form_id: dialog.form_id.clone(),
+                button,
+                values: dialog.values(),
+            },
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use super::merge_tool::MergeChoice;
 use super::*;
 use crate::services::plugins::hooks::HookArgs;
 use anyhow::Result as AnyhowResult;
@@ -223,6 +224,19 @@ impl Editor {
     pub(super) fn handle_action(&mut self, action: Action) -> AnyhowResult<()> {
         use crate::input::keybindings::Action;
 
+        // Plugins may veto the action
+        if action != Action::None && self.plugin_manager.handles_cancellable_hook("pre_command") {
+            let outcome = self.plugin_manager.run_cancellable_hook(
+                "pre_command",
+                crate::services::plugins::hooks::HookArgs::PreCommand {
+                    action: format!("{:?}", action),
+                },
+            );
+            if outcome.is_cancelled() {
+                return Ok(());
+            }
+        }
+
         // Record action to macro if recording
         self.record_macro_action(&action);
 
@@ -323,6 +337,13 @@ impl Editor {
             Action::ToggleAutoRevert => {
                 self.toggle_auto_revert();
             }
+            Action::ToggleFollowMode => self.toggle_follow_mode(),
+            Action::SetFollowFilter => {
+                self.start_prompt(
+                    t!("follow.filter_prompt").to_string(),
+                    PromptType::SetFollowFilter,
+                );
+            }
             Action::FormatBuffer => {
                 if let Err(e) = self.format_buffer() {
                     self.set_status_message(
@@ -420,6 +441,27 @@ impl Editor {
             Action::ClearWarnings => {
                 self.clear_warnings();
             }
+            Action::RestartPluginRuntime => {
+                self.restart_plugin_runtime();
+            }
+            #[cfg(feature = "plugins")]
+            Action::PluginConsole => {
+                self.open_plugin_console();
+            }
+            #[cfg(feature = "plugins")]
+            Action::PluginConsoleSelectPlugin => {
+                self.start_plugin_console_select();
+            }
+            #[cfg(feature = "plugins")]
+            Action::PluginConsoleToggleAutoReload => {
+                self.toggle_plugin_console_auto_reload();
+            }
+            #[cfg(not(feature = "plugins"))]
+            Action::PluginConsole
+            | Action::PluginConsoleSelectPlugin
+            | Action::PluginConsoleToggleAutoReload => {
+                self.set_status_message(t!("plugin.runtime_unavailable").to_string());
+            }
             Action::CommandPalette => {
                 // Toggle command palette: close if already open, otherwise open it
                 if let Some(prompt) = &self.prompt {
@@ -1059,6 +1101,23 @@ impl Editor {
                 // Run shell command on buffer/selection, replace content
                 self.start_shell_command_prompt(true);
             }
+            Action::CompareFiles => {
+                self.start_prompt(
+                    t!("compare.prompt").to_string(),
+                    PromptType::CompareWithFile,
+                );
+            }
+            Action::CompareWithSaved => self.compare_with_saved(),
+            Action::CompareWithClipboard => self.compare_with_clipboard(),
+            Action::CompareNextHunk => self.compare_goto_hunk(true),
+            Action::ComparePrevHunk => self.compare_goto_hunk(false),
+            Action::CompareCopyHunkLeft => self.compare_copy_hunk(true),
+            Action::CompareCopyHunkRight => self.compare_copy_hunk(false),
+            Action::MergeNextConflict => self.merge_goto_conflict(true),
+            Action::MergePrevConflict => self.merge_goto_conflict(false),
+            Action::MergeAcceptOurs => self.merge_accept(MergeChoice::Ours),
+            Action::MergeAcceptTheirs => self.merge_accept(MergeChoice::Theirs),
+            Action::MergeAcceptBoth => self.merge_accept(MergeChoice::Both),
             Action::OpenSettings => {
                 self.open_settings();
             }
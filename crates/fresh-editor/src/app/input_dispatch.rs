@@ -25,7 +25,8 @@ impl Editor {
             || self.active_state().popups.is_visible()
             || self.menu_state.active_menu.is_some()
             || self.settings_state.as_ref().is_some_and(|s| s.visible)
-            || self.calibration_wizard.is_some();
+            || self.calibration_wizard.is_some()
+            || self.form_dialog.is_some();
 
         if in_modal {
             return None;
@@ -72,6 +73,11 @@ impl Editor {
             return Some(result);
         }
 
+        // Plugin form dialogs are modal too
+        if self.form_dialog.is_some() {
+            return Some(self.handle_form_dialog_input(event));
+        }
+
         // Menu is next
         if self.menu_state.active_menu.is_some() {
             let all_menus: Vec<crate::config::Menu> = self
@@ -0,0 +1,79 @@
+//! Serving `fresh --remote` and `fresh --wait` from the running editor
+
+use super::Editor;
+use crate::services::instance_server::{InstanceServer, PendingOpen};
+use rust_i18n::t;
+
+impl Editor {
+    /// Listen for `--remote` requests on the per-user socket
+    pub fn start_instance_server(&mut self) {
+        let path = self.dir_context.server_socket_path();
+        match InstanceServer::bind(&path) {
+            Ok(server) => self.instance_server = Some(server),
+            Err(e) => {
+                tracing::warn!("Failed to start instance server: {}", e);
+                self.set_status_message(
+                    t!("server.unavailable", error = e.to_string()).to_string(),
+                );
+            }
+        }
+    }
+
+    /// Hand over a server started by a previous editor (after a restart)
+    pub fn set_instance_server(&mut self, server: InstanceServer) {
+        self.instance_server = Some(server);
+    }
+
+    /// Take the server, so it outlives this editor across a restart
+    pub fn take_instance_server(&mut self) -> Option<InstanceServer> {
+        self.instance_server.take()
+    }
+
+    /// Open files sent by `--remote` clients and release `--wait` clients
+    /// whose files were closed (called from main loop)
+    ///
+    /// Returns true if any files were opened.
+    pub(crate) fn poll_instance_server(&mut self) -> bool {
+        let Some(server) = self.instance_server.as_mut() else {
+            return false;
+        };
+        let requests = server.take_requests();
+        let opened_any = !requests.is_empty();
+        for pending in requests {
+            self.open_remote_request(pending);
+        }
+        if let Some(server) = self.instance_server.as_mut() {
+            let buffers = &self.buffers;
+            server.release_closed(|id| buffers.contains_key(&id));
+        }
+        opened_any
+    }
+
+    fn open_remote_request(&mut self, pending: PendingOpen) {
+        if pending.request.split && !pending.request.files.is_empty() {
+            self.split_pane_vertical();
+        }
+        let mut buffers = Vec::new();
+        let mut error = None;
+        for file in &pending.request.files {
+            match self.open_file(&file.path) {
+                Ok(buffer_id) => {
+                    if file.line.is_some() || file.column.is_some() {
+                        self.jump_to_line_column(file.line, file.column);
+                    }
+                    buffers.push(buffer_id);
+                }
+                Err(e) => {
+                    let message = format!("{}: {}", file.path.display(), e);
+                    self.set_status_message(
+                        t!("file.error_opening", error = message.clone()).to_string(),
+                    );
+                    error = Some(message);
+                }
+            }
+        }
+        if let Some(server) = self.instance_server.as_mut() {
+            server.answer(pending, buffers, error);
+        }
+    }
+}
@@ -0,0 +1,532 @@
+//! Language feature providers registered by plugins.
+//!
+//! Plugins can provide completions, hovers, definitions, code actions and
+//! diagnostics for a language (`editor.registerLanguageProvider`). Whenever the
+//! editor asks the language server for one of these, it asks the matching
+//! providers too, under the same request ID. Answers are merged as they
+//! arrive, so a slow provider never holds back the LSP results (or the other
+//! way around):
+//! - completions, hovers and code actions are added to the popup, which is
+//!   replaced with the merged one;
+//! - go-to-definition jumps to the first location anyone returns;
+//! - provider diagnostics are stored per plugin and shown together with the
+//!   language server's (they are requested when a file is opened or saved).
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use fresh_core::api::{
+    LanguageFeature, LanguageFeatureRequest, ProviderCodeAction, ProviderCompletionItem,
+    ProviderDiagnostic, ProviderLocation,
+};
+use rust_i18n::t;
+use serde::de::DeserializeOwned;
+
+use crate::model::event::BufferId;
+use crate::services::lsp::manager::detect_language;
+use crate::view::popup::PopupPosition;
+
+use super::Editor;
+
+/// Providers registered by plugins, and the results gathered from them
+#[derive(Default)]
+pub(crate) struct LanguageProviders {
+    /// Plugins providing each feature, by feature and language
+    registered: HashMap<(LanguageFeature, String), Vec<String>>,
+    /// Results gathered for the latest request of each interactive feature
+    gathered: HashMap<LanguageFeature, GatheredResults>,
+    /// Outstanding diagnostics requests: request ID -> (buffer, providers yet to answer)
+    diagnostics_requests: HashMap<u64, (BufferId, usize)>,
+    /// Diagnostics from each provider, by document URI and plugin name
+    diagnostics: HashMap<String, HashMap<String, Vec<lsp_types::Diagnostic>>>,
+}
+
+/// Hover contents: text, whether it is markdown, and the LSP range of the
+/// hovered symbol ((start line, start char), (end line, end char))
+pub(super) type HoverContents = (String, bool, Option<((u32, u32), (u32, u32))>);
+
+/// LSP and plugin results gathered for one request
+#[derive(Default)]
+pub(crate) struct GatheredResults {
+    pub request_id: u64,
+    /// Providers that have not answered yet
+    pub waiting: usize,
+    /// Whether the language server has not answered yet
+    pub lsp_waiting: bool,
+    /// Whether the results were acted upon (go-to-definition jumped)
+    pub done: bool,
+    pub completions: Vec<lsp_types::CompletionItem>,
+    /// Hover contents from the language server (is_markdown, range)
+    pub lsp_hover: Option<HoverContents>,
+    /// Hover contents (markdown) from providers
+    pub hovers: Vec<String>,
+    pub locations: Vec<lsp_types::Location>,
+    pub code_actions: Vec<lsp_types::CodeActionOrCommand>,
+}
+
+impl GatheredResults {
+    /// Whether everyone asked has answered
+    pub fn is_complete(&self) -> bool {
+        self.waiting == 0 && !self.lsp_waiting
+    }
+}
+
+impl LanguageProviders {
+    /// Plugins providing `feature` for `language`
+    fn providers(&self, feature: LanguageFeature, language: &str) -> usize {
+        self.registered
+            .get(&(feature, language.to_string()))
+            .map_or(0, |plugins| plugins.len())
+    }
+
+    /// Results gathered for `request_id`, if it is the latest request for `feature`
+    pub fn gathered_mut(
+        &mut self,
+        feature: LanguageFeature,
+        request_id: u64,
+    ) -> Option<&mut GatheredResults> {
+        self.gathered
+            .get_mut(&feature)
+            .filter(|gathered| gathered.request_id == request_id)
+    }
+
+    /// Forget the gathered results of interactive requests
+    pub fn cancel(&mut self, feature: LanguageFeature) {
+        self.gathered.remove(&feature);
+    }
+}
+
+impl Editor {
+    /// Record that a plugin provides a language feature
+    pub(super) fn register_language_provider(
+        &mut self,
+        plugin_name: String,
+        feature: LanguageFeature,
+        language: String,
+    ) {
+        let plugins = self
+            .language_providers
+            .registered
+            .entry((feature, language.clone()))
+            .or_default();
+        if !plugins.contains(&plugin_name) {
+            plugins.push(plugin_name);
+        }
+
+        // Diagnostics are pushed rather than asked for: catch up on open files
+        if feature == LanguageFeature::Diagnostics {
+            let buffer_ids: Vec<BufferId> = self
+                .buffer_metadata
+                .iter()
+                .filter(|(_, meta)| {
+                    meta.file_path()
+                        .and_then(|path| detect_language(path, &self.config.languages))
+                        .as_deref()
+                        == Some(language.as_str())
+                })
+                .map(|(buffer_id, _)| *buffer_id)
+                .collect();
+            for buffer_id in buffer_ids {
+                self.request_provider_diagnostics(buffer_id);
+            }
+        }
+    }
+
+    /// Forget a plugin's language feature provider (and any diagnostics it gave)
+    pub(super) fn unregister_language_provider(
+        &mut self,
+        plugin_name: &str,
+        feature: LanguageFeature,
+        language: String,
+    ) {
+        let key = (feature, language);
+        if let Some(plugins) = self.language_providers.registered.get_mut(&key) {
+            plugins.retain(|name| name != plugin_name);
+            if plugins.is_empty() {
+                self.language_providers.registered.remove(&key);
+            }
+        }
+
+        if feature == LanguageFeature::Diagnostics {
+            let uris: Vec<String> = self
+                .language_providers
+                .diagnostics
+                .iter_mut()
+                .filter_map(|(uri, by_plugin)| by_plugin.remove(plugin_name).map(|_| uri.clone()))
+                .collect();
+            for uri in uris {
+                self.apply_merged_diagnostics(&uri);
+            }
+        }
+    }
+
+    /// Ask the providers of `feature` for the buffer's language, under `request_id`
+    ///
+    /// `lsp_sent` says whether the language server was asked too. Returns
+    /// whether any provider was asked; if so, answers are gathered until the
+    /// next request for the same feature.
+    pub(super) fn ask_language_providers(
+        &mut self,
+        feature: LanguageFeature,
+        request_id: u64,
+        buffer_id: BufferId,
+        (line, character): (usize, usize),
+        lsp_sent: bool,
+    ) -> bool {
+        let Some((path, language)) = self.buffer_path_and_language(buffer_id) else {
+            self.language_providers.cancel(feature);
+            return false;
+        };
+        let waiting = self.language_providers.providers(feature, &language);
+        if waiting == 0 {
+            self.language_providers.cancel(feature);
+            return false;
+        }
+
+        self.language_providers.gathered.insert(
+            feature,
+            GatheredResults {
+                request_id,
+                waiting,
+                lsp_waiting: lsp_sent,
+                ..Default::default()
+            },
+        );
+        self.plugin_manager.request_language_feature(
+            request_id,
+            LanguageFeatureRequest {
+                feature,
+                language,
+                buffer_id,
+                path,
+                line: line as u32,
+                character: character as u32,
+            },
+        );
+        true
+    }
+
+    /// Ask the diagnostics providers for a buffer's language to check it
+    pub(crate) fn request_provider_diagnostics(&mut self, buffer_id: BufferId) {
+        let Some((path, language)) = self.buffer_path_and_language(buffer_id) else {
+            return;
+        };
+        let waiting = self
+            .language_providers
+            .providers(LanguageFeature::Diagnostics, &language);
+        if waiting == 0 {
+            return;
+        }
+
+        let request_id = self.next_lsp_request_id;
+        self.next_lsp_request_id += 1;
+        self.language_providers
+            .diagnostics_requests
+            .insert(request_id, (buffer_id, waiting));
+        self.plugin_manager.request_language_feature(
+            request_id,
+            LanguageFeatureRequest {
+                feature: LanguageFeature::Diagnostics,
+                language,
+                buffer_id,
+                path,
+                line: 0,
+                character: 0,
+            },
+        );
+    }
+
+    fn buffer_path_and_language(&self, buffer_id: BufferId) -> Option<(PathBuf, String)> {
+        let path = self.buffer_metadata.get(&buffer_id)?.file_path()?.clone();
+        let language = detect_language(&path, &self.config.languages)?;
+        Some((path, language))
+    }
+
+    /// Handle a provider's answer to a language feature request
+    pub(super) fn handle_language_provider_result(
+        &mut self,
+        plugin_name: String,
+        request_id: u64,
+        result: serde_json::Value,
+    ) {
+        if let Some((buffer_id, waiting)) = self
+            .language_providers
+            .diagnostics_requests
+            .get_mut(&request_id)
+        {
+            let buffer_id = *buffer_id;
+            *waiting -= 1;
+            if *waiting == 0 {
+                self.language_providers
+                    .diagnostics_requests
+                    .remove(&request_id);
+            }
+            let diagnostics: Vec<ProviderDiagnostic> = parse_result(&plugin_name, result);
+            self.store_provider_diagnostics(buffer_id, plugin_name, diagnostics);
+            return;
+        }
+
+        let Some(feature) = self
+            .language_providers
+            .gathered
+            .iter()
+            .find(|(_, gathered)| gathered.request_id == request_id)
+            .map(|(feature, _)| *feature)
+        else {
+            tracing::debug!(
+                "Ignoring language provider result for outdated request {}",
+                request_id
+            );
+            return;
+        };
+        let Some(gathered) = self.language_providers.gathered_mut(feature, request_id) else {
+            return;
+        };
+        gathered.waiting = gathered.waiting.saturating_sub(1);
+
+        match feature {
+            LanguageFeature::Completion => {
+                let items: Vec<ProviderCompletionItem> = parse_result(&plugin_name, result);
+                gathered
+                    .completions
+                    .extend(items.into_iter().map(completion_item_from_provider));
+                let items = gathered.completions.clone();
+                self.replace_popup_titled(&t!("lsp.popup_completion"));
+                let _ = self.show_completion_popup(items);
+            }
+            LanguageFeature::Hover => {
+                let hover: Option<String> = parse_result(&plugin_name, result);
+                gathered.hovers.extend(hover.filter(|h| !h.is_empty()));
+                let (contents, is_markdown, range) = merged_hover(gathered);
+                let complete = gathered.is_complete();
+                if contents.is_empty() && !complete {
+                    return;
+                }
+                let position = self.replace_popup_titled(&t!("lsp.popup_hover"));
+                self.show_hover_popup(contents, is_markdown, range, position);
+            }
+            LanguageFeature::Definition => {
+                let locations: Vec<ProviderLocation> = parse_result(&plugin_name, result);
+                let working_dir = self.working_dir.clone();
+                gathered.locations.extend(
+                    locations
+                        .into_iter()
+                        .filter_map(|loc| location_from_provider(loc, &working_dir)),
+                );
+                self.goto_gathered_definition(request_id);
+            }
+            LanguageFeature::CodeAction => {
+                let actions: Vec<ProviderCodeAction> = parse_result(&plugin_name, result);
+                gathered
+                    .code_actions
+                    .extend(actions.into_iter().map(|action| {
+                        lsp_types::CodeActionOrCommand::Command(lsp_types::Command {
+                            title: action.title,
+                            command: plugin_name.clone(),
+                            arguments: None,
+                        })
+                    }));
+                let actions = gathered.code_actions.clone();
+                let complete = gathered.is_complete();
+                if actions.is_empty() && !complete {
+                    return;
+                }
+                self.replace_popup_titled(&t!("lsp.popup_code_actions"));
+                self.show_code_actions_popup(actions);
+            }
+            LanguageFeature::Diagnostics => {}
+        }
+    }
+
+    /// Jump to the first gathered definition, once there is one
+    ///
+    /// Reports that there is no definition only after everyone has answered.
+    pub(super) fn goto_gathered_definition(&mut self, request_id: u64) {
+        let Some(gathered) = self
+            .language_providers
+            .gathered_mut(LanguageFeature::Definition, request_id)
+        else {
+            return;
+        };
+        if gathered.done {
+            return;
+        }
+        if gathered.locations.is_empty() {
+            if gathered.is_complete() {
+                self.status_message = Some(t!("lsp.no_definition").to_string());
+            }
+            return;
+        }
+        gathered.done = true;
+        let locations = gathered.locations.clone();
+        if let Err(e) = self.goto_definition_location(locations) {
+            tracing::warn!("Failed to open definition: {}", e);
+        }
+    }
+
+    /// Hide the topmost popup if it has the given title (it is about to be
+    /// shown again with merged results); returns where it was shown
+    pub(super) fn replace_popup_titled(&mut self, title: &str) -> Option<PopupPosition> {
+        let state = self.buffers.get_mut(&self.active_buffer())?;
+        if state.popups.top()?.title.as_deref() != Some(title) {
+            return None;
+        }
+        state.popups.hide().map(|popup| popup.position)
+    }
+
+    fn store_provider_diagnostics(
+        &mut self,
+        buffer_id: BufferId,
+        plugin_name: String,
+        diagnostics: Vec<ProviderDiagnostic>,
+    ) {
+        let Some(uri) = self
+            .buffer_metadata
+            .get(&buffer_id)
+            .and_then(|meta| meta.file_uri())
+            .map(|uri| uri.as_str().to_string())
+        else {
+            return;
+        };
+
+        let diagnostics: Vec<lsp_types::Diagnostic> = diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic_from_provider(diagnostic, &plugin_name))
+            .collect();
+        let by_plugin = self
+            .language_providers
+            .diagnostics
+            .entry(uri.clone())
+            .or_default();
+        if diagnostics.is_empty() {
+            by_plugin.remove(&plugin_name);
+            if by_plugin.is_empty() {
+                self.language_providers.diagnostics.remove(&uri);
+            }
+        } else {
+            by_plugin.insert(plugin_name, diagnostics);
+        }
+        self.apply_merged_diagnostics(&uri);
+    }
+
+    /// LSP diagnostics for a document together with those from providers
+    pub(crate) fn merged_diagnostics(&self, uri: &str) -> Vec<lsp_types::Diagnostic> {
+        let mut merged = self
+            .stored_diagnostics
+            .get(uri)
+            .cloned()
+            .unwrap_or_default();
+        if let Some(by_plugin) = self.language_providers.diagnostics.get(uri) {
+            let mut plugins: Vec<&String> = by_plugin.keys().collect();
+            plugins.sort();
+            for plugin in plugins {
+                merged.extend(by_plugin[plugin].iter().cloned());
+            }
+        }
+        merged
+    }
+
+    /// All documents' diagnostics, LSP and provider ones merged
+    pub(crate) fn all_merged_diagnostics(&self) -> HashMap<String, Vec<lsp_types::Diagnostic>> {
+        self.stored_diagnostics
+            .keys()
+            .chain(self.language_providers.diagnostics.keys())
+            .map(|uri| (uri.clone(), self.merged_diagnostics(uri)))
+            .collect()
+    }
+}
+
+/// Parse a provider's result, treating `null` and malformed results as empty
+fn parse_result<T: DeserializeOwned + Default>(plugin_name: &str, result: serde_json::Value) -> T {
+    if result.is_null() {
+        return T::default();
+    }
+    serde_json::from_value(result).unwrap_or_else(|e| {
+        tracing::warn!(
+            "Ignoring malformed language provider result from '{}': {}",
+            plugin_name,
+            e
+        );
+        T::default()
+    })
+}
+
+/// Hover contents from LSP and providers, as one document
+pub(super) fn merged_hover(gathered: &GatheredResults) -> HoverContents {
+    let (lsp_contents, lsp_markdown, range) =
+        gathered
+            .lsp_hover
+            .clone()
+            .unwrap_or((String::new(), true, None));
+    if gathered.hovers.is_empty() {
+        return (lsp_contents, lsp_markdown, range);
+    }
+    let sections: Vec<&str> = std::iter::once(lsp_contents.as_str())
+        .filter(|contents| !contents.is_empty())
+        .chain(gathered.hovers.iter().map(String::as_str))
+        .collect();
+    (sections.join("\n\n---\n\n"), true, range)
+}
+
+fn completion_item_from_provider(item: ProviderCompletionItem) -> lsp_types::CompletionItem {
+    use lsp_types::CompletionItemKind;
+    let kind = match item.kind.as_deref() {
+        Some("function") => Some(CompletionItemKind::FUNCTION),
+        Some("method") => Some(CompletionItemKind::METHOD),
+        Some("variable") => Some(CompletionItemKind::VARIABLE),
+        Some("struct") => Some(CompletionItemKind::STRUCT),
+        Some("class") => Some(CompletionItemKind::CLASS),
+        Some("constant") => Some(CompletionItemKind::CONSTANT),
+        Some("keyword") => Some(CompletionItemKind::KEYWORD),
+        _ => None,
+    };
+    lsp_types::CompletionItem {
+        label: item.label,
+        detail: item.detail,
+        insert_text: item.insert_text,
+        filter_text: item.filter_text,
+        kind,
+        ..Default::default()
+    }
+}
+
+fn location_from_provider(
+    location: ProviderLocation,
+    working_dir: &std::path::Path,
+) -> Option<lsp_types::Location> {
+    let path = working_dir.join(&location.path);
+    let uri = url::Url::from_file_path(&path)
+        .ok()?
+        .as_str()
+        .parse::<lsp_types::Uri>()
+        .ok()?;
+    let position = lsp_types::Position::new(location.line, location.character);
+    Some(lsp_types::Location {
+        uri,
+        range: lsp_types::Range::new(position, position),
+    })
+}
+
+fn diagnostic_from_provider(
+    diagnostic: ProviderDiagnostic,
+    plugin_name: &str,
+) -> lsp_types::Diagnostic {
+    use lsp_types::DiagnosticSeverity;
+    let severity = match diagnostic.severity {
+        Some(2) => DiagnosticSeverity::WARNING,
+        Some(3) => DiagnosticSeverity::INFORMATION,
+        Some(4) => DiagnosticSeverity::HINT,
+        _ => DiagnosticSeverity::ERROR,
+    };
+    let range = diagnostic.range;
+    lsp_types::Diagnostic {
+        range: lsp_types::Range::new(
+            lsp_types::Position::new(range.start.line, range.start.character),
+            lsp_types::Position::new(range.end.line, range.end.character),
+        ),
+        severity: Some(severity),
+        source: Some(diagnostic.source.unwrap_or_else(|| plugin_name.to_string())),
+        message: diagnostic.message,
+        ..Default::default()
+    }
+}
@@ -11,6 +11,7 @@
 //! - Inlay hints
 
 use anyhow::Result as AnyhowResult;
+use fresh_core::api::LanguageFeature;
 use rust_i18n::t;
 use std::io;
 use std::time::{Duration, Instant};
@@ -47,6 +48,28 @@ impl Editor {
         self.pending_completion_request = None;
         self.lsp_status.clear();
 
+        // Merge with completions plugins have provided so far
+        let items = match self
+            .language_providers
+            .gathered_mut(LanguageFeature::Completion, request_id)
+        {
+            Some(gathered) => {
+                gathered.lsp_waiting = false;
+                gathered.completions.extend(items);
+                let items = gathered.completions.clone();
+                self.replace_popup_titled(&t!("lsp.popup_completion"));
+                items
+            }
+            None => items,
+        };
+        self.show_completion_popup(items)
+    }
+
+    /// Show the completion popup for the items matching the typed prefix
+    pub(crate) fn show_completion_popup(
+        &mut self,
+        items: Vec<lsp_types::CompletionItem>,
+    ) -> AnyhowResult<()> {
         if items.is_empty() {
             tracing::debug!("No completion items received");
             return Ok(());
@@ -187,11 +210,29 @@ impl Editor {
 
         self.pending_goto_definition_request = None;
 
+        // Merge with definitions plugins have provided so far
+        if let Some(gathered) = self
+            .language_providers
+            .gathered_mut(LanguageFeature::Definition, request_id)
+        {
+            gathered.lsp_waiting = false;
+            gathered.locations.extend(locations);
+            self.goto_gathered_definition(request_id);
+            return Ok(());
+        }
+
         if locations.is_empty() {
             self.status_message = Some(t!("lsp.no_definition").to_string());
             return Ok(());
         }
+        self.goto_definition_location(locations)
+    }
 
+    /// Open the first of the definition locations and move the cursor there
+    pub(crate) fn goto_definition_location(
+        &mut self,
+        locations: Vec<lsp_types::Location>,
+    ) -> AnyhowResult<()> {
         // For now, just jump to the first location
         let location = &locations[0];
 
@@ -270,6 +311,8 @@ impl Editor {
     /// This should be called when the user performs an action that would make
     /// the pending request's results stale (e.g., cursor movement, text editing)
     pub(crate) fn cancel_pending_lsp_requests(&mut self) {
+        self.language_providers.cancel(LanguageFeature::Completion);
+        self.language_providers.cancel(LanguageFeature::Definition);
         if let Some(request_id) = self.pending_completion_request.take() {
             tracing::debug!("Canceling pending LSP completion request {}", request_id);
             // Send cancellation to the LSP server
@@ -412,8 +455,17 @@ impl Editor {
             })
             .unwrap_or(false);
 
-        if sent {
+        let asked = self.ask_language_providers(
+            LanguageFeature::Completion,
+            request_id,
+            buffer_id,
+            (line, character),
+            sent,
+        );
+        if sent || asked {
             self.next_lsp_request_id += 1;
+        }
+        if sent {
             self.pending_completion_request = Some(request_id);
             self.lsp_status = "LSP: completion...".to_string();
         }
@@ -513,8 +565,17 @@ impl Editor {
             })
             .unwrap_or(false);
 
-        if sent {
+        let asked = self.ask_language_providers(
+            LanguageFeature::Definition,
+            request_id,
+            buffer_id,
+            (line, character),
+            sent,
+        );
+        if sent || asked {
             self.next_lsp_request_id += 1;
+        }
+        if sent {
             self.pending_goto_definition_request = Some(request_id);
         }
 
@@ -561,8 +622,17 @@ impl Editor {
             })
             .unwrap_or(false);
 
-        if sent {
+        let asked = self.ask_language_providers(
+            LanguageFeature::Hover,
+            request_id,
+            buffer_id,
+            (line, character),
+            sent,
+        );
+        if sent || asked {
             self.next_lsp_request_id += 1;
+        }
+        if sent {
             self.pending_hover_request = Some(request_id);
             self.lsp_status = "LSP: hover...".to_string();
         }
@@ -610,8 +680,17 @@ impl Editor {
             })
             .unwrap_or(false);
 
-        if sent {
+        let asked = self.ask_language_providers(
+            LanguageFeature::Hover,
+            request_id,
+            buffer_id,
+            (line, character),
+            sent,
+        );
+        if sent || asked {
             self.next_lsp_request_id += 1;
+        }
+        if sent {
             self.pending_hover_request = Some(request_id);
             self.lsp_status = "LSP: hover...".to_string();
         }
@@ -636,6 +715,37 @@ impl Editor {
         self.pending_hover_request = None;
         self.lsp_status.clear();
 
+        // Merge with hovers plugins have provided so far
+        let mut position = None;
+        let (contents, is_markdown, range) = match self
+            .language_providers
+            .gathered_mut(LanguageFeature::Hover, request_id)
+        {
+            Some(gathered) => {
+                gathered.lsp_waiting = false;
+                gathered.lsp_hover = Some((contents, is_markdown, range));
+                let (contents, is_markdown, range) =
+                    super::language_providers::merged_hover(gathered);
+                if contents.is_empty() && !gathered.is_complete() {
+                    return;
+                }
+                position = self.replace_popup_titled(&t!("lsp.popup_hover"));
+                (contents, is_markdown, range)
+            }
+            None => (contents, is_markdown, range),
+        };
+        self.show_hover_popup(contents, is_markdown, range, position);
+    }
+
+    /// Show the hover popup, at `position` if given (when replacing an
+    /// earlier one) or else below the mouse or cursor
+    pub(crate) fn show_hover_popup(
+        &mut self,
+        contents: String,
+        is_markdown: bool,
+        range: Option<((u32, u32), (u32, u32))>,
+        position: Option<crate::view::popup::PopupPosition>,
+    ) {
         if contents.is_empty() {
             self.set_status_message(t!("lsp.no_hover").to_string());
             self.hover_symbol_range = None;
@@ -730,7 +840,9 @@ impl Editor {
         popup.title = Some(t!("lsp.popup_hover").to_string());
         popup.transient = true;
         // Use mouse position if this was a mouse-triggered hover, otherwise use cursor position
-        popup.position = if let Some((x, y)) = self.mouse_hover_screen_position.take() {
+        popup.position = if let Some(position) = position {
+            position
+        } else if let Some((x, y)) = self.mouse_hover_screen_position.take() {
             // Position below the mouse, offset by 1 row
             PopupPosition::Fixed { x, y: y + 1 }
         } else {
@@ -1128,8 +1240,17 @@ impl Editor {
             })
             .unwrap_or(false);
 
-        if sent {
+        let asked = self.ask_language_providers(
+            LanguageFeature::CodeAction,
+            request_id,
+            buffer_id,
+            (line, character),
+            sent,
+        );
+        if sent || asked {
             self.next_lsp_request_id += 1;
+        }
+        if sent {
             self.pending_code_actions_request = Some(request_id);
             self.lsp_status = "LSP: code actions...".to_string();
         }
@@ -1152,6 +1273,28 @@ impl Editor {
         self.pending_code_actions_request = None;
         self.lsp_status.clear();
 
+        // Merge with code actions plugins have provided so far
+        let actions = match self
+            .language_providers
+            .gathered_mut(LanguageFeature::CodeAction, request_id)
+        {
+            Some(gathered) => {
+                gathered.lsp_waiting = false;
+                gathered.code_actions.extend(actions);
+                let actions = gathered.code_actions.clone();
+                if actions.is_empty() && !gathered.is_complete() {
+                    return;
+                }
+                self.replace_popup_titled(&t!("lsp.popup_code_actions"));
+                actions
+            }
+            None => actions,
+        };
+        self.show_code_actions_popup(actions);
+    }
+
+    /// Show the code actions popup
+    pub(crate) fn show_code_actions_popup(&mut self, actions: Vec<lsp_types::CodeActionOrCommand>) {
         if actions.is_empty() {
             self.set_status_message(t!("lsp.no_code_actions").to_string());
             return;
@@ -0,0 +1,420 @@
+//! Three-way merge tool
+//!
+//! `fresh --merge BASE LOCAL REMOTE MERGED` (for `git mergetool`) shows ours,
+//! base and theirs side by side in a composite buffer, above the MERGED file
+//! in a split of its own. The MERGED file is filled with the result of
+//! [`three_way_merge`]: changes that merge cleanly are applied, and every
+//! conflict is left as a marker block in the text.
+//!
+//! Conflicts are resolved by accepting ours, theirs or both, which replaces
+//! the conflict's marker block, or by editing the MERGED file by hand. The
+//! actions work in either split: in the composite they act on the conflict at
+//! the cursor row, in the MERGED file on the marker block at the cursor.
+
+use super::Editor;
+use crate::model::composite_buffer::{
+    CompositeLayout, LineAlignment, PaneStyle, RowType, SourcePane,
+};
+use crate::model::event::{BufferId, Event, SplitDirection};
+use crate::model::merge::{
+    find_conflict_blocks, merged_text, split_lines, three_way_merge, ConflictBlock, MergeKind,
+};
+use crate::view::split::SplitViewState;
+use rust_i18n::t;
+use std::path::Path;
+
+/// Buffer mode of merge views (registered in `ModeRegistry::new`)
+const MERGE_MODE: &str = "merge-view";
+
+/// A merge shown in a composite buffer
+pub(super) struct MergeSession {
+    /// The MERGED file
+    result: BufferId,
+    /// Hidden buffers holding base, ours and theirs, closed along with the merge
+    sources: Vec<BufferId>,
+    /// Ours and theirs text of each conflict, as in its marker block
+    conflicts: Vec<(String, String)>,
+}
+
+/// Which side of a conflict to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MergeChoice {
+    Ours,
+    Theirs,
+    /// Ours followed by theirs
+    Both,
+}
+
+impl Editor {
+    /// Open a three-way merge of `local` and `remote` into `merged`
+    pub fn open_merge(
+        &mut self,
+        base: &Path,
+        local: &Path,
+        remote: &Path,
+        merged: &Path,
+    ) -> anyhow::Result<BufferId> {
+        let read = |path: &Path| -> anyhow::Result<String> {
+            let bytes = self.filesystem.read_file(path)?;
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        };
+        let (base_text, ours_text, theirs_text) = (read(base)?, read(local)?, read(remote)?);
+        let result_id = self.open_file(merged)?;
+
+        let regions = three_way_merge(&base_text, &ours_text, &theirs_text);
+        let (text, _) = merged_text(&regions, &ours_text, &theirs_text, "LOCAL", "REMOTE");
+        let ours_lines = split_lines(&ours_text);
+        let theirs_lines = split_lines(&theirs_text);
+        let block_text = |lines: &[&str]| lines.iter().map(|l| format!("{}\n", l)).collect();
+        let conflicts = regions
+            .iter()
+            .filter(|r| r.kind == MergeKind::Conflict)
+            .map(|r| {
+                (
+                    block_text(&ours_lines[r.ours.clone()]),
+                    block_text(&theirs_lines[r.theirs.clone()]),
+                )
+            })
+            .collect();
+        self.set_merge_result(result_id, text);
+
+        let name = self.get_buffer_display_name(result_id);
+        let base_id =
+            self.create_comparison_source(format!("*base* {}", name), MERGE_MODE, base_text);
+        let ours_id =
+            self.create_comparison_source(format!("*local* {}", name), MERGE_MODE, ours_text);
+        let theirs_id =
+            self.create_comparison_source(format!("*remote* {}", name), MERGE_MODE, theirs_text);
+        let sources = vec![
+            SourcePane::new(ours_id, t!("merge.label_ours").to_string(), false)
+                .with_style(PaneStyle::new_diff()),
+            SourcePane::new(base_id, t!("merge.label_base").to_string(), false),
+            SourcePane::new(theirs_id, t!("merge.label_theirs").to_string(), false)
+                .with_style(PaneStyle::new_diff()),
+        ];
+        let buffer_id = self.create_composite_buffer(
+            format!("*Merge: {}*", name),
+            MERGE_MODE.to_string(),
+            CompositeLayout::SideBySide {
+                ratios: vec![1.0 / 3.0; 3],
+                show_separator: true,
+            },
+            sources,
+        );
+        self.set_composite_alignment(buffer_id, LineAlignment::from_merge(&regions));
+        self.merge_sessions.insert(
+            buffer_id,
+            MergeSession {
+                result: result_id,
+                sources: vec![base_id, ours_id, theirs_id],
+                conflicts,
+            },
+        );
+        self.set_active_buffer(buffer_id);
+        let split_id = self.split_manager.active_split();
+        self.get_composite_view_state(split_id, buffer_id);
+
+        // The MERGED file goes below the three versions
+        self.save_current_split_view_state();
+        match self
+            .split_manager
+            .split_active(SplitDirection::Horizontal, result_id, 0.5)
+        {
+            Ok(new_split_id) => {
+                let mut view_state = SplitViewState::with_buffer(
+                    self.terminal_width,
+                    self.terminal_height,
+                    result_id,
+                );
+                view_state.viewport.line_wrap_enabled = self.config.editor.line_wrap;
+                self.split_view_states.insert(new_split_id, view_state);
+                self.restore_current_split_view_state();
+            }
+            Err(e) => tracing::warn!("Failed to split for merge result: {}", e),
+        }
+
+        if self.merge_conflict_blocks(result_id).is_empty() {
+            self.set_status_message(t!("merge.clean").to_string());
+        } else {
+            self.merge_goto_conflict(true);
+        }
+        Ok(buffer_id)
+    }
+
+    /// Replace the content of the MERGED file with the merge result, as one
+    /// undoable edit
+    fn set_merge_result(&mut self, result_id: BufferId, text: String) {
+        let Some(state) = self.buffers.get(&result_id) else {
+            return;
+        };
+        let current = state.buffer.to_string().unwrap_or_default();
+        if current == text {
+            return;
+        }
+        let cursor_id = state.cursors.primary_id();
+        let mut events = Vec::new();
+        if !current.is_empty() {
+            events.push(Event::Delete {
+                range: 0..current.len(),
+                deleted_text: current,
+                cursor_id,
+            });
+        }
+        events.push(Event::Insert {
+            position: 0,
+            text,
+            cursor_id,
+        });
+        if let Err(e) = self.apply_events_to_buffer_as_bulk_edit(
+            result_id,
+            events,
+            t!("merge.description").to_string(),
+        ) {
+            tracing::warn!("Failed to write merge result: {}", e);
+        }
+    }
+
+    /// The merge whose composite or MERGED file is the active buffer
+    fn active_merge(&self) -> Option<BufferId> {
+        let active = self.active_buffer();
+        if self.merge_sessions.contains_key(&active) {
+            return Some(active);
+        }
+        self.merge_sessions
+            .iter()
+            .find(|(_, session)| session.result == active)
+            .map(|(id, _)| *id)
+    }
+
+    fn merge_conflict_blocks(&self, result_id: BufferId) -> Vec<ConflictBlock> {
+        self.buffers
+            .get(&result_id)
+            .and_then(|state| state.buffer.to_string())
+            .map(|text| find_conflict_blocks(&text))
+            .unwrap_or_default()
+    }
+
+    /// Find the marker block of each conflict in the MERGED file
+    ///
+    /// A conflict whose block is gone (because it was resolved) maps to None.
+    fn match_conflict_blocks(
+        session: &MergeSession,
+        blocks: &[ConflictBlock],
+    ) -> Vec<Option<usize>> {
+        let mut next = 0;
+        session
+            .conflicts
+            .iter()
+            .map(|(ours, theirs)| {
+                let found = blocks[next..]
+                    .iter()
+                    .position(|b| &b.ours == ours && &b.theirs == theirs)
+                    .map(|i| next + i);
+                if let Some(index) = found {
+                    next = index + 1;
+                }
+                found
+            })
+            .collect()
+    }
+
+    /// Move to the next (or previous) conflict of the active merge
+    pub(super) fn merge_goto_conflict(&mut self, forward: bool) {
+        let Some(merge_id) = self.active_merge() else {
+            self.set_status_message(t!("merge.not_merge").to_string());
+            return;
+        };
+        let split_id = self.split_manager.active_split();
+
+        if self.active_buffer() == merge_id {
+            let (Some(composite), Some(view_state)) = (
+                self.composite_buffers.get(&merge_id),
+                self.composite_view_states.get_mut(&(split_id, merge_id)),
+            ) else {
+                return;
+            };
+            let row = if forward {
+                composite.alignment.next_hunk_row(view_state.cursor_row)
+            } else {
+                composite.alignment.prev_hunk_row(view_state.cursor_row)
+            };
+            match row {
+                Some(row) => {
+                    view_state.cursor_row = row;
+                    view_state.scroll_row = row;
+                }
+                None => self.set_status_message(t!("merge.no_more_conflicts").to_string()),
+            }
+            return;
+        }
+
+        let result_id = self.merge_sessions[&merge_id].result;
+        let blocks = self.merge_conflict_blocks(result_id);
+        let cursor = self.active_state().cursors.primary().position;
+        let target = if forward {
+            blocks.iter().position(|b| b.range.start > cursor)
+        } else {
+            blocks.iter().rposition(|b| b.range.end <= cursor)
+        };
+        let Some(index) = target else {
+            self.set_status_message(t!("merge.no_more_conflicts").to_string());
+            return;
+        };
+        self.goto_line_col(blocks[index].start_line + 1, None);
+
+        // Show the same conflict in the three versions above
+        let matches = Self::match_conflict_blocks(&self.merge_sessions[&merge_id], &blocks);
+        if let Some(conflict) = matches.iter().position(|m| *m == Some(index)) {
+            self.merge_show_conflict(merge_id, conflict);
+        }
+        self.set_status_message(
+            t!(
+                "merge.conflict_position",
+                index = index + 1,
+                count = blocks.len()
+            )
+            .to_string(),
+        );
+    }
+
+    /// Scroll the composite views of a merge to one of its conflicts
+    fn merge_show_conflict(&mut self, merge_id: BufferId, conflict: usize) {
+        let Some(row) = self.composite_buffers.get(&merge_id).and_then(|composite| {
+            composite
+                .alignment
+                .rows
+                .iter()
+                .enumerate()
+                .filter(|(_, row)| row.row_type == RowType::HunkHeader)
+                .nth(conflict)
+                .map(|(i, _)| i)
+        }) else {
+            return;
+        };
+        for ((_, id), view_state) in self.composite_view_states.iter_mut() {
+            if *id == merge_id {
+                view_state.cursor_row = row;
+                view_state.scroll_row = row;
+            }
+        }
+    }
+
+    /// Resolve the conflict at the cursor of the active merge
+    pub(super) fn merge_accept(&mut self, choice: MergeChoice) {
+        let Some(merge_id) = self.active_merge() else {
+            self.set_status_message(t!("merge.not_merge").to_string());
+            return;
+        };
+        let result_id = self.merge_sessions[&merge_id].result;
+        let blocks = self.merge_conflict_blocks(result_id);
+
+        let block = if self.active_buffer() == merge_id {
+            let split_id = self.split_manager.active_split();
+            let cursor_row = self
+                .composite_view_states
+                .get(&(split_id, merge_id))
+                .map_or(0, |view_state| view_state.cursor_row);
+            let Some(conflict) = self
+                .composite_buffers
+                .get(&merge_id)
+                .and_then(|composite| conflict_at_row(&composite.alignment, cursor_row))
+            else {
+                self.set_status_message(t!("merge.no_conflict").to_string());
+                return;
+            };
+            let matches = Self::match_conflict_blocks(&self.merge_sessions[&merge_id], &blocks);
+            let Some(index) = matches.get(conflict).copied().flatten() else {
+                self.set_status_message(t!("merge.already_resolved").to_string());
+                return;
+            };
+            &blocks[index]
+        } else {
+            let cursor = self.active_state().cursors.primary().position;
+            let Some(block) = blocks
+                .iter()
+                .find(|b| b.range.start <= cursor && cursor < b.range.end)
+            else {
+                self.set_status_message(t!("merge.no_conflict").to_string());
+                return;
+            };
+            block
+        };
+
+        let text = match choice {
+            MergeChoice::Ours => block.ours.clone(),
+            MergeChoice::Theirs => block.theirs.clone(),
+            MergeChoice::Both => format!("{}{}", block.ours, block.theirs),
+        };
+        let Some(state) = self.buffers.get(&result_id) else {
+            return;
+        };
+        let Some(deleted_text) = state
+            .buffer
+            .to_string()
+            .map(|t| t[block.range.clone()].to_string())
+        else {
+            return;
+        };
+        let cursor_id = state.cursors.primary_id();
+        let mut events = vec![Event::Delete {
+            range: block.range.clone(),
+            deleted_text,
+            cursor_id,
+        }];
+        if !text.is_empty() {
+            events.push(Event::Insert {
+                position: block.range.start,
+                text,
+                cursor_id,
+            });
+        }
+        let description = match choice {
+            MergeChoice::Ours => t!("action.merge_accept_ours"),
+            MergeChoice::Theirs => t!("action.merge_accept_theirs"),
+            MergeChoice::Both => t!("action.merge_accept_both"),
+        };
+        if let Err(e) =
+            self.apply_events_to_buffer_as_bulk_edit(result_id, events, description.to_string())
+        {
+            self.set_status_message(t!("merge.resolve_failed", error = e.to_string()).to_string());
+            return;
+        }
+
+        let remaining = blocks.len() - 1;
+        if remaining == 0 {
+            self.set_status_message(t!("merge.all_resolved").to_string());
+        } else {
+            self.set_status_message(t!("merge.remaining", count = remaining).to_string());
+        }
+    }
+
+    /// Forget a merge whose composite buffer is being closed, along with the
+    /// buffers it created (the MERGED file stays open)
+    pub(super) fn close_merge_session(&mut self, buffer_id: BufferId) {
+        let Some(session) = self.merge_sessions.remove(&buffer_id) else {
+            return;
+        };
+        self.close_composite_buffer(buffer_id);
+        for id in session.sources {
+            if let Err(e) = self.force_close_buffer(id) {
+                tracing::warn!("Failed to close merge buffer {:?}: {}", id, e);
+            }
+        }
+    }
+}
+
+/// Index of the conflict that an aligned row of a merge belongs to
+fn conflict_at_row(alignment: &LineAlignment, row: usize) -> Option<usize> {
+    let aligned_row = alignment.rows.get(row)?;
+    if !matches!(
+        aligned_row.row_type,
+        RowType::HunkHeader | RowType::Modification
+    ) {
+        return None;
+    }
+    let headers = alignment.rows[..=row]
+        .iter()
+        .filter(|r| r.row_type == RowType::HunkHeader)
+        .count();
+    headers.checked_sub(1)
+}
@@ -1,31 +1,45 @@
 mod async_messages;
+pub mod batch;
 mod buffer_management;
 mod calibration_actions;
 pub mod calibration_wizard;
 mod clipboard;
 mod composite_buffer_actions;
+mod file_compare;
 mod file_explorer;
 pub mod file_open;
 mod file_open_input;
 mod file_operations;
+mod follow_mode;
+pub mod form_dialog;
+mod form_dialog_actions;
 mod help;
 mod input;
 mod input_dispatch;
+#[cfg(unix)]
+mod instance_server;
+mod language_providers;
 mod lsp_actions;
 mod lsp_requests;
 mod menu_actions;
 mod menu_context;
+mod merge_tool;
 mod mouse_input;
 mod on_save_actions;
+mod path_watches;
 mod plugin_commands;
+#[cfg(feature = "plugins")]
+mod plugin_console;
 mod popup_actions;
 mod prompt_actions;
 mod recovery_actions;
+mod remote_connection;
 mod render;
 pub mod session;
 mod settings_actions;
 mod shell_command;
 mod split_actions;
+pub mod status_bar_items;
 mod tab_drag;
 mod terminal;
 mod terminal_input;
@@ -110,7 +124,7 @@ use crossterm::event::{KeyCode, KeyModifiers};
 use fresh_core::api::BufferSavedDiff;
 #[cfg(feature = "plugins")]
 use fresh_core::api::JsCallbackId;
-use fresh_core::api::PluginCommand;
+use fresh_core::api::{PluginCommand, SelectionKind, SelectionSpec};
 use lsp_types::{Position, Range as LspRange, TextDocumentContentChangeEvent};
 use ratatui::{
     layout::{Constraint, Direction, Layout},
@@ -123,10 +137,10 @@ use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
 // Re-export BufferId from event module for backward compatibility
-pub use self::types::{BufferKind, BufferMetadata, HoverTarget};
+pub use self::types::{BufferKind, BufferMetadata, HoverTarget, SaveOutcome};
 pub use self::warning_domains::{
-    GeneralWarningDomain, LspWarningDomain, WarningAction, WarningActionId, WarningDomain,
-    WarningDomainRegistry, WarningLevel, WarningPopupContent,
+    GeneralWarningDomain, LspWarningDomain, PluginWarningDomain, WarningAction, WarningActionId,
+    WarningDomain, WarningDomainRegistry, WarningLevel, WarningPopupContent,
 };
 pub use crate::model::event::BufferId;
 
@@ -272,6 +286,10 @@ pub struct Editor {
     /// Used to detect viewport changes that occur between renders (e.g., scroll events)
     previous_viewports: HashMap<SplitId, (usize, u16, u16)>,
 
+    /// Active buffer's selections at the end of the last render frame,
+    /// for selection_changed hook detection
+    previous_selections: Option<(BufferId, Vec<SelectionSpec>)>,
+
     /// Scroll sync manager for anchor-based synchronized scrolling
     /// Used for side-by-side diff views where two panes need to scroll together
     scroll_sync_manager: ScrollSyncManager,
@@ -475,6 +493,18 @@ pub struct Editor {
     /// Maps process_id to abort handle
     background_process_handles: HashMap<u64, tokio::task::AbortHandle>,
 
+    /// Interactive plugin processes, keyed by process_id
+    #[cfg(feature = "plugins")]
+    interactive_processes: HashMap<u64, plugin_commands::InteractiveProcess>,
+
+    /// Plugin developer console, once opened
+    #[cfg(feature = "plugins")]
+    plugin_console: Option<plugin_console::PluginConsole>,
+
+    /// Server for `fresh --remote` requests, when started with `--server`
+    #[cfg(unix)]
+    instance_server: Option<crate::services::instance_server::InstanceServer>,
+
     /// Prompt histories keyed by prompt type name (e.g., "search", "replace", "goto_line", "plugin:custom_name")
     /// This provides a generic history system that works for all prompt types including plugin prompts.
     prompt_histories: HashMap<String, crate::input::input_history::InputHistory>,
@@ -505,6 +535,9 @@ pub struct Editor {
     /// Maps file URI string to Vec of diagnostics for that file
     stored_diagnostics: HashMap<String, Vec<lsp_types::Diagnostic>>,
 
+    /// Language feature providers registered by plugins, and their results
+    language_providers: language_providers::LanguageProviders,
+
     /// Event broadcaster for control events (observable by external systems)
     event_broadcaster: crate::model::control_event::EventBroadcaster,
 
@@ -566,10 +599,20 @@ pub struct Editor {
     /// Maps file path to last known modification time
     file_mod_times: HashMap<PathBuf, std::time::SystemTime>,
 
+    /// Buffers in follow mode, extended in place when their file grows
+    followed_buffers: HashMap<BufferId, follow_mode::FollowState>,
+
     /// Last known modification times for expanded directories (for file tree refresh)
     /// Maps directory path to last known modification time
     dir_mod_times: HashMap<PathBuf, std::time::SystemTime>,
 
+    /// Paths reported changed by a watching filesystem, not yet checked
+    /// by the auto-revert or file tree polls
+    watch_events: HashSet<PathBuf>,
+
+    /// File-system watches requested by plugins
+    path_watches: path_watches::PathWatchRegistry,
+
     /// Tracks rapid file change events for debouncing
     /// Maps file path to (last event time, event count)
     file_rapid_change_counts: HashMap<PathBuf, (std::time::Instant, u32)>,
@@ -592,6 +635,13 @@ pub struct Editor {
     /// Last auto-save time for rate limiting
     last_auto_save: std::time::Instant,
 
+    /// Last observed state of the remote connection (always true when local)
+    remote_connected: bool,
+
+    /// Buffers whose save failed while the remote host was unreachable,
+    /// written once the connection is re-established
+    queued_remote_saves: Vec<BufferId>,
+
     /// Active custom contexts for command visibility
     /// Plugin-defined contexts like "config-editor" that control command availability
     active_custom_contexts: HashSet<String>,
@@ -600,6 +650,10 @@ pub struct Editor {
     /// When set, this mode's keybindings take precedence over normal key handling
     editor_mode: Option<String>,
 
+    /// Plugin packages waiting for the user to grant their capabilities,
+    /// asked one at a time while no other prompt is open
+    pending_capability_requests: std::collections::VecDeque<PromptType>,
+
     /// Warning log receiver and path (for tracking warnings)
     warning_log: Option<(std::sync::mpsc::Receiver<()>, PathBuf)>,
 
@@ -610,6 +664,9 @@ pub struct Editor {
     /// Contains LSP warnings, general warnings, and can be extended by plugins
     warning_domains: WarningDomainRegistry,
 
+    /// Persistent status bar items contributed by plugins
+    status_bar_items: status_bar_items::StatusBarItemRegistry,
+
     /// Periodic update checker (checks for new releases every hour)
     update_checker: Option<crate::services::release_checker::PeriodicUpdateChecker>,
 
@@ -625,6 +682,13 @@ pub struct Editor {
     /// Maps terminal ID to raw log file path (full PTY capture)
     terminal_log_files: HashMap<crate::services::terminal::TerminalId, std::path::PathBuf>,
 
+    /// Output patterns plugins are watching for, per terminal
+    terminal_output_watches:
+        HashMap<crate::services::terminal::TerminalId, terminal::TerminalOutputWatch>,
+
+    /// Plugin that opened each plugin terminal; only it may use the terminal
+    terminal_owners: HashMap<crate::services::terminal::TerminalId, String>,
+
     /// Whether terminal mode is active (input goes to terminal)
     terminal_mode: bool,
 
@@ -651,6 +715,9 @@ pub struct Editor {
     /// Calibration wizard state (when calibration modal is open)
     pub(crate) calibration_wizard: Option<calibration_wizard::CalibrationWizard>,
 
+    /// Plugin form dialog state (when a plugin form is open)
+    pub(crate) form_dialog: Option<form_dialog::FormDialog>,
+
     /// Key translator for input calibration (loaded from config)
     pub(crate) key_translator: crate::input::key_translator::KeyTranslator,
 
@@ -673,6 +740,12 @@ pub struct Editor {
     composite_view_states:
         HashMap<(SplitId, BufferId), crate::view::composite_view::CompositeViewState>,
 
+    /// Native file comparisons, keyed by their composite buffer
+    file_comparisons: HashMap<BufferId, file_compare::FileComparison>,
+
+    /// Three-way merges, keyed by their composite buffer
+    merge_sessions: HashMap<BufferId, merge_tool::MergeSession>,
+
     /// Stdin streaming state (if reading from stdin)
     stdin_streaming: Option<StdinStreamingState>,
 }
@@ -908,6 +981,7 @@ impl Editor {
             enable_plugins,
             Arc::clone(&command_registry),
             dir_context.clone(),
+            &config.plugin_runtime,
         );
 
         // Update the plugin state snapshot with working_dir BEFORE loading plugins
@@ -918,101 +992,12 @@ impl Editor {
             snapshot.working_dir = working_dir.clone();
         }
 
-        // Load TypeScript plugins from multiple directories:
-        // 1. Next to the executable (for cargo-dist installations)
-        // 2. In the working directory (for development/local usage)
-        // 3. From embedded plugins (for cargo-binstall, when embed-plugins feature is enabled)
-        // 4. User plugins directory (~/.config/fresh/plugins)
-        // 5. Package manager installed plugins (~/.config/fresh/plugins/packages/*)
-        if plugin_manager.is_active() {
-            let mut plugin_dirs: Vec<std::path::PathBuf> = vec![];
-
-            // Check next to executable first (for cargo-dist installations)
-            if let Ok(exe_path) = std::env::current_exe() {
-                if let Some(exe_dir) = exe_path.parent() {
-                    let exe_plugin_dir = exe_dir.join("plugins");
-                    if exe_plugin_dir.exists() {
-                        plugin_dirs.push(exe_plugin_dir);
-                    }
-                }
-            }
-
-            // Then check working directory (for development)
-            let working_plugin_dir = working_dir.join("plugins");
-            if working_plugin_dir.exists() && !plugin_dirs.contains(&working_plugin_dir) {
-                plugin_dirs.push(working_plugin_dir);
-            }
-
-            // If no disk plugins found, try embedded plugins (cargo-binstall builds)
-            #[cfg(feature = "embed-plugins")]
-            if plugin_dirs.is_empty() {
-                if let Some(embedded_dir) =
-                    crate::services::plugins::embedded::get_embedded_plugins_dir()
-                {
-                    tracing::info!("Using embedded plugins from: {:?}", embedded_dir);
-                    plugin_dirs.push(embedded_dir.clone());
-                }
-            }
-
-            // Always check user config plugins directory (~/.config/fresh/plugins)
-            let user_plugins_dir = dir_context.config_dir.join("plugins");
-            if user_plugins_dir.exists() && !plugin_dirs.contains(&user_plugins_dir) {
-                tracing::info!("Found user plugins directory: {:?}", user_plugins_dir);
-                plugin_dirs.push(user_plugins_dir.clone());
-            }
-
-            // Check for package manager installed plugins (~/.config/fresh/plugins/packages/*)
-            let packages_dir = dir_context.config_dir.join("plugins").join("packages");
-            if packages_dir.exists() {
-                if let Ok(entries) = std::fs::read_dir(&packages_dir) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        // Skip hidden directories (like .index for registry cache)
-                        if path.is_dir() {
-                            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                                if !name.starts_with('.') {
-                                    tracing::info!("Found package manager plugin: {:?}", path);
-                                    plugin_dirs.push(path);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            if plugin_dirs.is_empty() {
-                tracing::debug!(
-                    "No plugins directory found next to executable or in working dir: {:?}",
-                    working_dir
-                );
-            }
-
-            // Load from all found plugin directories, respecting config
-            for plugin_dir in plugin_dirs {
-                tracing::info!("Loading TypeScript plugins from: {:?}", plugin_dir);
-                let (errors, discovered_plugins) =
-                    plugin_manager.load_plugins_from_dir_with_config(&plugin_dir, &config.plugins);
-
-                // Merge discovered plugins into config
-                // discovered_plugins already contains the merged config (saved enabled state + discovered path)
-                for (name, plugin_config) in discovered_plugins {
-                    config.plugins.insert(name, plugin_config);
-                }
-
-                if !errors.is_empty() {
-                    for err in &errors {
-                        tracing::error!("TypeScript plugin load error: {}", err);
-                    }
-                    // In debug/test builds, panic to surface plugin loading errors
-                    #[cfg(debug_assertions)]
-                    panic!(
-                        "TypeScript plugin loading failed with {} error(s): {}",
-                        errors.len(),
-                        errors.join("; ")
-                    );
-                }
-            }
-        }
+        Self::load_plugins(
+            &plugin_manager,
+            &working_dir,
+            &dir_context,
+            &mut config.plugins,
+        );
 
         // Extract config values before moving config into the struct
         let file_explorer_width = config.file_explorer.width;
@@ -1068,6 +1053,7 @@ impl Editor {
             split_manager,
             split_view_states,
             previous_viewports: HashMap::new(),
+            previous_selections: None,
             scroll_sync_manager: ScrollSyncManager::new(),
             file_explorer: None,
             fs_manager,
@@ -1135,6 +1121,12 @@ impl Editor {
             seen_byte_ranges: HashMap::new(),
             panel_ids: HashMap::new(),
             background_process_handles: HashMap::new(),
+            #[cfg(feature = "plugins")]
+            interactive_processes: HashMap::new(),
+            #[cfg(feature = "plugins")]
+            plugin_console: None,
+            #[cfg(unix)]
+            instance_server: None,
             prompt_histories: {
                 // Load prompt histories from disk if available
                 let mut histories = HashMap::new();
@@ -1156,6 +1148,7 @@ impl Editor {
             lsp_log_messages: Vec::new(),
             diagnostic_result_ids: HashMap::new(),
             stored_diagnostics: HashMap::new(),
+            language_providers: Default::default(),
             event_broadcaster: crate::model::control_event::EventBroadcaster::default(),
             bookmarks: HashMap::new(),
             search_case_sensitive: true,
@@ -1177,7 +1170,10 @@ impl Editor {
             last_auto_revert_poll: time_source.now(),
             last_file_tree_poll: time_source.now(),
             file_mod_times: HashMap::new(),
+            followed_buffers: HashMap::new(),
             dir_mod_times: HashMap::new(),
+            watch_events: HashSet::new(),
+            path_watches: path_watches::PathWatchRegistry::new(time_source.now()),
             file_rapid_change_counts: HashMap::new(),
             file_open_state: None,
             file_browser_layout: None,
@@ -1192,16 +1188,22 @@ impl Editor {
             full_redraw_requested: false,
             time_source: time_source.clone(),
             last_auto_save: time_source.now(),
+            remote_connected: true,
+            queued_remote_saves: Vec::new(),
+            pending_capability_requests: std::collections::VecDeque::new(),
             active_custom_contexts: HashSet::new(),
             editor_mode: None,
             warning_log: None,
             status_log_path: None,
             warning_domains: WarningDomainRegistry::new(),
+            status_bar_items: status_bar_items::StatusBarItemRegistry::new(),
             update_checker,
             terminal_manager: crate::services::terminal::TerminalManager::new(),
             terminal_buffers: HashMap::new(),
             terminal_backing_files: HashMap::new(),
             terminal_log_files: HashMap::new(),
+            terminal_output_watches: HashMap::new(),
+            terminal_owners: HashMap::new(),
             terminal_mode: false,
             keyboard_capture: false,
             terminal_mode_resume: std::collections::HashSet::new(),
@@ -1209,6 +1211,7 @@ impl Editor {
             previous_click_position: None,
             settings_state: None,
             calibration_wizard: None,
+            form_dialog: None,
             key_translator: crate::input::key_translator::KeyTranslator::load_default()
                 .unwrap_or_default(),
             color_capability,
@@ -1217,6 +1220,8 @@ impl Editor {
             active_action_popup: None,
             composite_buffers: HashMap::new(),
             composite_view_states: HashMap::new(),
+            file_comparisons: HashMap::new(),
+            merge_sessions: HashMap::new(),
         };
 
         #[cfg(feature = "plugins")]
@@ -1233,6 +1238,112 @@ impl Editor {
         Ok(editor)
     }
 
+    /// Load TypeScript plugins from multiple directories:
+    /// 1. Next to the executable (for cargo-dist installations)
+    /// 2. In the working directory (for development/local usage)
+    /// 3. From embedded plugins (for cargo-binstall, when embed-plugins feature is enabled)
+    /// 4. User plugins directory (~/.config/fresh/plugins)
+    /// 5. Package manager installed plugins (~/.config/fresh/plugins/packages/*)
+    ///
+    /// Discovered plugins are merged into `plugin_configs`.
+    fn load_plugins(
+        plugin_manager: &PluginManager,
+        working_dir: &Path,
+        dir_context: &DirectoryContext,
+        plugin_configs: &mut HashMap<String, crate::config::PluginConfig>,
+    ) {
+        if !plugin_manager.is_active() {
+            return;
+        }
+        let mut plugin_dirs: Vec<std::path::PathBuf> = vec![];
+
+        // Check next to executable first (for cargo-dist installations)
+        if let Ok(exe_path) = std::env::current_exe() {
+            if let Some(exe_dir) = exe_path.parent() {
+                let exe_plugin_dir = exe_dir.join("plugins");
+                if exe_plugin_dir.exists() {
+                    plugin_dirs.push(exe_plugin_dir);
+                }
+            }
+        }
+
+        // Then check working directory (for development)
+        let working_plugin_dir = working_dir.join("plugins");
+        if working_plugin_dir.exists() && !plugin_dirs.contains(&working_plugin_dir) {
+            plugin_dirs.push(working_plugin_dir);
+        }
+
+        // If no disk plugins found, try embedded plugins (cargo-binstall builds)
+        #[cfg(feature = "embed-plugins")]
+        if plugin_dirs.is_empty() {
+            if let Some(embedded_dir) =
+                crate::services::plugins::embedded::get_embedded_plugins_dir()
+            {
+                tracing::info!("Using embedded plugins from: {:?}", embedded_dir);
+                plugin_dirs.push(embedded_dir.clone());
+            }
+        }
+
+        // Always check user config plugins directory (~/.config/fresh/plugins)
+        let user_plugins_dir = dir_context.config_dir.join("plugins");
+        if user_plugins_dir.exists() && !plugin_dirs.contains(&user_plugins_dir) {
+            tracing::info!("Found user plugins directory: {:?}", user_plugins_dir);
+            plugin_dirs.push(user_plugins_dir.clone());
+        }
+
+        // Check for package manager installed plugins (~/.config/fresh/plugins/packages/*)
+        let packages_dir = dir_context.config_dir.join("plugins").join("packages");
+        if packages_dir.exists() {
+            if let Ok(entries) = std::fs::read_dir(&packages_dir) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    // Skip hidden directories (like .index for registry cache)
+                    if path.is_dir() {
+                        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                            if !name.starts_with('.') {
+                                tracing::info!("Found package manager plugin: {:?}", path);
+                                plugin_dirs.push(path);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if plugin_dirs.is_empty() {
+            tracing::debug!(
+                "No plugins directory found next to executable or in working dir: {:?}",
+                working_dir
+            );
+        }
+
+        // Load from all found plugin directories, respecting config
+        for plugin_dir in plugin_dirs {
+            tracing::info!("Loading TypeScript plugins from: {:?}", plugin_dir);
+            let (errors, discovered_plugins) =
+                plugin_manager.load_plugins_from_dir_with_config(&plugin_dir, plugin_configs);
+
+            // Merge discovered plugins into config
+            // discovered_plugins already contains the merged config (saved enabled state + discovered path)
+            for (name, plugin_config) in discovered_plugins {
+                plugin_configs.insert(name, plugin_config);
+            }
+
+            if !errors.is_empty() {
+                for err in &errors {
+                    tracing::error!("TypeScript plugin load error: {}", err);
+                }
+                // In debug/test builds, panic to surface plugin loading errors
+                #[cfg(debug_assertions)]
+                panic!(
+                    "TypeScript plugin loading failed with {} error(s): {}",
+                    errors.len(),
+                    errors.join("; ")
+                );
+            }
+        }
+    }
+
     /// Get a reference to the event broadcaster
     pub fn event_broadcaster(&self) -> &crate::model::control_event::EventBroadcaster {
         &self.event_broadcaster
@@ -1511,10 +1622,41 @@ impl Editor {
 
     /// Set the process spawner for plugin command execution
     /// Use RemoteProcessSpawner for remote editing, LocalProcessSpawner for local
+    ///
+    /// For remote editing, language servers and integrated terminals are launched
+    /// through the same spawner so they run on the remote host next to the project.
     pub fn set_process_spawner(
         &mut self,
         spawner: Arc<dyn crate::services::remote::ProcessSpawner>,
     ) {
+        if self.filesystem.remote_connection_info().is_some() {
+            use crate::services::lsp::remote::{RemoteLspTarget, UriTranslator};
+
+            // Servers report canonical paths; translate them back to the
+            // spelling the editor uses for the workspace root.
+            let remote_root = self.filesystem.canonicalize(&self.working_dir).ok();
+            let target = RemoteLspTarget {
+                spawner: spawner.clone(),
+                cwd: remote_root
+                    .as_ref()
+                    .map(|root| root.to_string_lossy().into_owned()),
+                uris: remote_root
+                    .as_deref()
+                    .and_then(|root| UriTranslator::new(&self.working_dir, root)),
+            };
+            if let Some(lsp) = self.lsp.as_mut() {
+                lsp.set_remote_target(Some(target));
+            }
+
+            if let Some(runtime) = self.tokio_runtime.as_ref() {
+                self.terminal_manager.set_remote_target(Some(
+                    crate::services::terminal::RemoteTerminalTarget {
+                        spawner: spawner.clone(),
+                        runtime: runtime.handle().clone(),
+                    },
+                ));
+            }
+        }
         self.process_spawner = spawner;
     }
 
@@ -1595,6 +1737,7 @@ impl Editor {
     pub fn clear_warnings(&mut self) {
         self.warning_domains.general.clear();
         self.warning_domains.lsp.clear();
+        self.warning_domains.plugins.clear();
         self.status_message = Some("Warnings cleared".to_string());
     }
 
@@ -2377,6 +2520,60 @@ impl Editor {
         }
     }
 
+    /// Cursors of the active buffer in the form reported to plugins:
+    /// sorted by position, with the primary cursor last
+    fn active_selections(&self) -> Vec<SelectionSpec> {
+        let cursors = &self.active_state().cursors;
+        let primary_id = cursors.primary_id();
+        let mut list: Vec<_> = cursors.iter().collect();
+        list.sort_by_key(|(id, cursor)| (*id == primary_id, cursor.position));
+        list.into_iter()
+            .map(|(_, cursor)| SelectionSpec {
+                anchor: cursor.anchor,
+                position: cursor.position,
+                mode: Some(if cursor.has_block_selection() {
+                    SelectionKind::Block
+                } else {
+                    SelectionKind::Normal
+                }),
+            })
+            .collect()
+    }
+
+    /// Fire `selection_changed` if the active buffer's selections or number
+    /// of cursors changed since the last frame. Compared once per frame
+    /// (like `viewport_changed`) so plugins see the finished state, e.g.
+    /// block mode that is set after the cursor events. Moving a bare cursor
+    /// only fires `cursor_moved`.
+    ///
+    /// The comparison is local; the hook is fired without waiting on the
+    /// plugin thread, so a busy plugin never holds up rendering.
+    fn check_selection_changed(&mut self) {
+        let buffer_id = self.active_buffer();
+        let current = self.active_selections();
+        let changed = match &self.previous_selections {
+            // Switching buffers is not a selection change
+            Some((previous_buffer, previous)) if *previous_buffer == buffer_id => {
+                previous.len() != current.len()
+                    || previous.iter().zip(&current).any(|(old, new)| {
+                        (old.anchor.is_some() || new.anchor.is_some()) && old != new
+                    })
+            }
+            _ => false,
+        };
+        self.previous_selections = Some((buffer_id, current.clone()));
+
+        if changed {
+            self.plugin_manager.run_hook(
+                "selection_changed",
+                crate::services::plugins::hooks::HookArgs::SelectionChanged {
+                    buffer_id,
+                    selections: current,
+                },
+            );
+        }
+    }
+
     /// Handle scroll events using the SplitViewState's viewport
     ///
     /// View events (like Scroll) go to SplitViewState, not EditorState.
@@ -2672,7 +2869,27 @@ impl Editor {
             // Prompt user for confirmation with translated keys
             let discard_key = t!("prompt.key.discard").to_string();
             let cancel_key = t!("prompt.key.cancel").to_string();
-            let msg = if modified_count == 1 {
+            // Queued buffers that were since saved or reverted have nothing
+            // left to lose
+            let queued_count = self
+                .queued_remote_saves
+                .iter()
+                .filter(|id| {
+                    self.buffers
+                        .get(id)
+                        .is_some_and(|state| state.buffer.is_modified())
+                })
+                .count();
+            let msg = if queued_count > 0 {
+                // Quitting drops saves that were never written
+                t!(
+                    "prompt.quit_queued_saves",
+                    count = queued_count,
+                    discard_key = discard_key,
+                    cancel_key = cancel_key
+                )
+                .to_string()
+            } else if modified_count == 1 {
                 t!(
                     "prompt.quit_modified_one",
                     discard_key = discard_key,
@@ -2695,6 +2912,9 @@ impl Editor {
     }
 
     /// Count the number of modified buffers
+    ///
+    /// Buffers whose save is queued until the remote host reconnects are
+    /// still modified, so they are counted too.
     fn count_modified_buffers(&self) -> usize {
         self.buffers
             .values()
@@ -3302,6 +3522,7 @@ impl Editor {
                     | PromptType::SelectLocale
                     | PromptType::SwitchToTab
                     | PromptType::SetLanguage
+                    | PromptType::PluginConsoleSelect
                     | PromptType::Plugin { .. }
             ) {
                 // Use the selected suggestion if any
@@ -3395,6 +3616,7 @@ impl Editor {
                 Some("replace".to_string())
             }
             PromptType::GotoLine => Some("goto_line".to_string()),
+            PromptType::PluginConsoleEval => Some("plugin_console".to_string()),
             PromptType::Plugin { custom_type } => Some(format!("plugin:{}", custom_type)),
             _ => None,
         }
@@ -3557,7 +3779,8 @@ impl Editor {
             PromptType::SwitchToTab
             | PromptType::SelectTheme { .. }
             | PromptType::StopLspServer
-            | PromptType::SetLanguage => {
+            | PromptType::SetLanguage
+            | PromptType::PluginConsoleSelect => {
                 if let Some(prompt) = &mut self.prompt {
                     prompt.filter_suggestions(false);
                 }
@@ -3835,6 +4058,8 @@ impl Editor {
                             exit_code,
                         } => {
                             self.background_process_handles.remove(&process_id);
+                            #[cfg(feature = "plugins")]
+                            self.interactive_processes.remove(&process_id);
                             let result = fresh_core::api::BackgroundProcessResult {
                                 process_id,
                                 exit_code,
@@ -3891,6 +4116,8 @@ impl Editor {
                     // Terminal output received - check if we should auto-jump back to terminal mode
                     tracing::trace!("Terminal output received for {:?}", terminal_id);
 
+                    self.check_terminal_output_watches(terminal_id);
+
                     // If viewing scrollback for this terminal and jump_to_end_on_output is enabled,
                     // automatically re-enter terminal mode
                     if self.config.terminal.jump_to_end_on_output && !self.terminal_mode {
@@ -3915,6 +4142,9 @@ impl Editor {
                 }
                 AsyncMessage::TerminalExited { terminal_id } => {
                     tracing::info!("Terminal {:?} exited", terminal_id);
+                    // Report output that arrived just before the exit
+                    self.check_terminal_output_watches(terminal_id);
+                    self.terminal_output_watches.remove(&terminal_id);
                     // Find the buffer associated with this terminal
                     if let Some((&buffer_id, _)) = self
                         .terminal_buffers
@@ -3960,7 +4190,18 @@ impl Editor {
                         self.set_status_message(
                             t!("terminal.exited", id = terminal_id.0).to_string(),
                         );
+                        if let Some(owner) = self.terminal_owners.get(&terminal_id) {
+                            self.plugin_manager.run_plugin_hook(
+                                owner,
+                                "terminal_exited",
+                                crate::services::plugins::hooks::HookArgs::TerminalExited {
+                                    terminal_id,
+                                    buffer_id,
+                                },
+                            );
+                        }
                     }
+                    self.terminal_owners.remove(&terminal_id);
                     self.terminal_manager.close(terminal_id);
                 }
 
@@ -4002,6 +4243,7 @@ impl Editor {
 
         // Process TypeScript plugin commands
         let processed_any_commands = self.process_plugin_commands();
+        self.show_next_capability_request();
 
         // Process pending plugin action completions
         #[cfg(feature = "plugins")]
@@ -4026,12 +4268,39 @@ impl Editor {
             let _ = checker.poll_result();
         }
 
+        // Track remote link drops and reconnects (flushes queued saves)
+        let remote_changes = self.poll_remote_connection();
+
         // Poll for file changes (auto-revert) and file tree changes
         let file_changes = self.poll_file_changes();
         let tree_changes = self.poll_file_tree_changes();
+        self.poll_path_watches();
+
+        // Keep the plugin console up to date and reload watched plugins
+        #[cfg(feature = "plugins")]
+        let console_changes = self.poll_plugin_console();
+        #[cfg(not(feature = "plugins"))]
+        let console_changes = false;
+
+        // Open files sent by `fresh --remote`
+        #[cfg(unix)]
+        let server_changes = self.poll_instance_server();
+        #[cfg(not(unix))]
+        let server_changes = false;
+
+        // Recompute comparisons whose sides were edited
+        let compare_changes = self.poll_file_comparisons();
 
         // Trigger render if any async messages, plugin commands were processed, or plugin requested render
-        needs_render || processed_any_commands || plugin_render || file_changes || tree_changes
+        needs_render
+            || processed_any_commands
+            || plugin_render
+            || remote_changes
+            || file_changes
+            || tree_changes
+            || console_changes
+            || server_changes
+            || compare_changes
     }
 
     /// Update LSP status bar string from active progress operations
@@ -4118,6 +4387,7 @@ impl Editor {
                     path: state.buffer.file_path().map(|p| p.to_path_buf()),
                     modified: state.buffer.is_modified(),
                     length: state.buffer.len(),
+                    version: state.buffer.version(),
                 };
                 snapshot.buffers.insert(*buffer_id, buffer_info);
 
@@ -4206,7 +4476,7 @@ impl Editor {
             snapshot.working_dir = self.working_dir.clone();
 
             // Update LSP diagnostics
-            snapshot.diagnostics = self.stored_diagnostics.clone();
+            snapshot.diagnostics = self.all_merged_diagnostics();
 
             // Update config (serialize the runtime config for plugins)
             snapshot.config = serde_json::to_value(&self.config).unwrap_or(serde_json::Value::Null);
@@ -4234,6 +4504,15 @@ impl Editor {
             PluginCommand::DeleteRange { buffer_id, range } => {
                 self.handle_delete_range(buffer_id, range);
             }
+            PluginCommand::ApplyEdits {
+                buffer_id,
+                expected_version,
+                edits,
+                label,
+                callback_id,
+            } => {
+                self.handle_apply_edits(buffer_id, expected_version, edits, label, callback_id);
+            }
             PluginCommand::InsertAtCursor { text } => {
                 self.handle_insert_at_cursor(text);
             }
@@ -4364,6 +4643,66 @@ impl Editor {
             } => {
                 self.handle_request_highlights(buffer_id, range, request_id);
             }
+            PluginCommand::GetSyntaxNodePath {
+                buffer_id,
+                offset,
+                callback_id,
+            } => {
+                self.handle_get_syntax_node_path(buffer_id, offset, callback_id);
+            }
+            PluginCommand::GetEnclosingSyntaxNode {
+                buffer_id,
+                offset,
+                kind,
+                callback_id,
+            } => {
+                self.handle_get_enclosing_syntax_node(buffer_id, offset, kind, callback_id);
+            }
+            PluginCommand::CreateTerminal {
+                plugin_name,
+                options,
+                callback_id,
+            } => {
+                self.handle_create_terminal(plugin_name, options, callback_id);
+            }
+            PluginCommand::SendTerminalInput {
+                plugin_name,
+                terminal_id,
+                data,
+            } => {
+                self.handle_send_terminal_input(&plugin_name, terminal_id, data);
+            }
+            PluginCommand::GetTerminalScrollback {
+                plugin_name,
+                terminal_id,
+                callback_id,
+            } => {
+                self.handle_get_terminal_scrollback(&plugin_name, terminal_id, callback_id);
+            }
+            PluginCommand::WatchTerminalOutput {
+                plugin_name,
+                terminal_id,
+                pattern,
+            } => {
+                self.handle_watch_terminal_output(&plugin_name, terminal_id, pattern);
+            }
+            PluginCommand::CloseTerminal {
+                plugin_name,
+                terminal_id,
+            } => {
+                self.handle_close_terminal(&plugin_name, terminal_id);
+            }
+            PluginCommand::CloseTerminals { plugin_name } => {
+                self.close_plugin_terminals(&plugin_name);
+            }
+            PluginCommand::QuerySyntax {
+                buffer_id,
+                query,
+                range,
+                callback_id,
+            } => {
+                self.handle_query_syntax(buffer_id, query, range, callback_id);
+            }
             PluginCommand::CloseSplit { split_id } => {
                 self.handle_close_split(split_id);
             }
@@ -4379,6 +4718,9 @@ impl Editor {
             } => {
                 self.handle_set_buffer_cursor(buffer_id, position);
             }
+            PluginCommand::SetSelections { selections } => {
+                self.handle_set_selections(selections);
+            }
 
             // ==================== View/Layout Commands ====================
             PluginCommand::SetLayoutHints {
@@ -4438,6 +4780,49 @@ impl Editor {
             PluginCommand::SetStatus { message } => {
                 self.handle_set_status(message);
             }
+            PluginCommand::SetStatusBarItem {
+                plugin_name,
+                id,
+                options,
+            } => {
+                self.status_bar_items.set(plugin_name, id, options);
+            }
+            PluginCommand::SetStatusBarItemText {
+                plugin_name,
+                id,
+                text,
+            } => {
+                if !self.status_bar_items.set_text(&plugin_name, &id, text) {
+                    tracing::warn!(
+                        "Plugin '{}' has no status bar item '{}' to update",
+                        plugin_name,
+                        id
+                    );
+                }
+            }
+            PluginCommand::RemoveStatusBarItem { plugin_name, id } => {
+                self.status_bar_items.remove(&plugin_name, &id);
+            }
+            PluginCommand::RemoveStatusBarItems { plugin_name } => {
+                self.status_bar_items.remove_plugin(&plugin_name);
+            }
+            PluginCommand::WatchPath {
+                plugin_name,
+                watch_id,
+                path,
+                options,
+            } => {
+                self.handle_watch_path(plugin_name, watch_id, path, options);
+            }
+            PluginCommand::UnwatchPath {
+                plugin_name,
+                watch_id,
+            } => {
+                self.path_watches.remove(&plugin_name, watch_id);
+            }
+            PluginCommand::UnwatchPaths { plugin_name } => {
+                self.path_watches.remove_plugin(&plugin_name);
+            }
             PluginCommand::ApplyTheme { theme_name } => {
                 self.apply_theme(&theme_name);
             }
@@ -4531,6 +4916,27 @@ impl Editor {
             } => {
                 self.handle_send_lsp_request(language, method, params, request_id);
             }
+            PluginCommand::RegisterLanguageProvider {
+                plugin_name,
+                feature,
+                language,
+            } => {
+                self.register_language_provider(plugin_name, feature, language);
+            }
+            PluginCommand::UnregisterLanguageProvider {
+                plugin_name,
+                feature,
+                language,
+            } => {
+                self.unregister_language_provider(&plugin_name, feature, language);
+            }
+            PluginCommand::LanguageProviderResult {
+                plugin_name,
+                request_id,
+                result,
+            } => {
+                self.handle_language_provider_result(plugin_name, request_id, result);
+            }
 
             // ==================== Clipboard Commands ====================
             PluginCommand::SetClipboard { text } => {
@@ -4745,6 +5151,53 @@ impl Editor {
                     handle.abort();
                     tracing::debug!("Killed background process {}", process_id);
                 }
+                #[cfg(feature = "plugins")]
+                self.interactive_processes.remove(&process_id);
+            }
+            #[cfg(feature = "plugins")]
+            PluginCommand::SpawnInteractiveProcess {
+                plugin_name,
+                process_id,
+                command,
+                args,
+                options,
+                callback_id,
+            } => {
+                self.handle_spawn_interactive_process(
+                    plugin_name,
+                    process_id,
+                    command,
+                    args,
+                    options,
+                    callback_id,
+                );
+            }
+            #[cfg(feature = "plugins")]
+            PluginCommand::WriteProcessStdin {
+                plugin_name,
+                process_id,
+                data,
+            } => {
+                self.handle_process_stdin(
+                    &plugin_name,
+                    process_id,
+                    crate::services::plugins::process::StdinMessage::Write(data),
+                );
+            }
+            #[cfg(feature = "plugins")]
+            PluginCommand::CloseProcessStdin {
+                plugin_name,
+                process_id,
+            } => {
+                self.handle_process_stdin(
+                    &plugin_name,
+                    process_id,
+                    crate::services::plugins::process::StdinMessage::Close,
+                );
+            }
+            #[cfg(feature = "plugins")]
+            PluginCommand::KillInteractiveProcesses { plugin_name } => {
+                self.kill_interactive_processes(Some(&plugin_name));
             }
 
             // ==================== Virtual Buffer Commands (complex, kept inline) ====================
@@ -5178,6 +5631,13 @@ impl Editor {
                 );
             }
 
+            PluginCommand::ShowForm { options } => {
+                self.show_form_dialog(options);
+            }
+            PluginCommand::CloseForm { form_id } => {
+                self.close_form_dialog(&form_id);
+            }
+
             PluginCommand::DisableLspForLanguage { language } => {
                 tracing::info!("Disabling LSP for language: {}", language);
 
@@ -5325,6 +5785,21 @@ impl Editor {
             PluginCommand::ListPlugins { callback_id } => {
                 self.handle_list_plugins(callback_id);
             }
+            PluginCommand::PluginDisabled { name, reason } => {
+                self.handle_plugin_disabled(name, reason);
+            }
+            PluginCommand::RequestPluginCapabilities {
+                name,
+                path,
+                requested,
+            } => {
+                self.pending_capability_requests
+                    .push_back(PromptType::ConfirmPluginCapabilities {
+                        name,
+                        path,
+                        requested,
+                    });
+            }
             // When plugins feature is disabled, these commands are no-ops
             #[cfg(not(feature = "plugins"))]
             PluginCommand::LoadPlugin { .. }
@@ -5333,12 +5808,24 @@ impl Editor {
             | PluginCommand::ListPlugins { .. } => {
                 tracing::warn!("Plugin management commands require the 'plugins' feature");
             }
+            #[cfg(not(feature = "plugins"))]
+            PluginCommand::SpawnInteractiveProcess { .. }
+            | PluginCommand::WriteProcessStdin { .. }
+            | PluginCommand::CloseProcessStdin { .. }
+            | PluginCommand::KillInteractiveProcesses { .. } => {
+                tracing::warn!("Interactive processes require the 'plugins' feature");
+            }
         }
         Ok(())
     }
 
     /// Save a buffer to a specific file path (for :w filename)
     fn handle_save_buffer_to_path(&mut self, buffer_id: BufferId, path: std::path::PathBuf) {
+        if self.buffers.contains_key(&buffer_id)
+            && !self.run_before_save_hook(buffer_id, path.clone())
+        {
+            return;
+        }
         if let Some(state) = self.buffers.get_mut(&buffer_id) {
             // Save to the specified path
             match state.buffer.save_to_file(&path) {
@@ -5360,6 +5847,71 @@ impl Editor {
         }
     }
 
+    /// Record a plugin the runtime disabled for exceeding its time budget or
+    /// memory limit
+    fn handle_plugin_disabled(&mut self, name: String, reason: String) {
+        // Covers commands registered by a plugin interrupted while loading
+        self.command_registry
+            .read()
+            .unwrap()
+            .unregister_by_plugin(&name);
+        self.set_status_message(t!("plugin.disabled", name = &name, reason = &reason).to_string());
+        self.warning_domains.plugins.add_disabled(name, reason);
+    }
+
+    /// Rebuild the plugin runtime and reload all enabled plugins
+    ///
+    /// This is the way back after a plugin was disabled by the watchdog, and
+    /// also recovers from a plugin that left the runtime in a bad state.
+    pub fn restart_plugin_runtime(&mut self) {
+        // Plugins register their commands again as they load
+        self.command_registry
+            .read()
+            .unwrap()
+            .unregister_all_plugin_commands();
+
+        if !self.plugin_manager.restart() {
+            self.set_status_message(t!("plugin.runtime_unavailable").to_string());
+            return;
+        }
+        self.warning_domains.plugins.clear();
+        self.status_bar_items = status_bar_items::StatusBarItemRegistry::new();
+        self.path_watches.clear();
+        self.form_dialog = None;
+        // The processes and terminals of the old plugins have no one to
+        // report to any more
+        #[cfg(feature = "plugins")]
+        self.kill_interactive_processes(None);
+        let plugin_names: std::collections::HashSet<String> =
+            self.terminal_owners.values().cloned().collect();
+        for plugin_name in plugin_names {
+            self.close_plugin_terminals(&plugin_name);
+        }
+
+        #[cfg(feature = "plugins")]
+        if let Some(snapshot_handle) = self.plugin_manager.state_snapshot_handle() {
+            let mut snapshot = snapshot_handle.write().unwrap();
+            snapshot.working_dir = self.working_dir.clone();
+        }
+        Self::load_plugins(
+            &self.plugin_manager,
+            &self.working_dir,
+            &self.dir_context,
+            &mut self.config.plugins,
+        );
+
+        #[cfg(feature = "plugins")]
+        {
+            self.update_plugin_state_snapshot();
+            self.plugin_manager.run_hook(
+                "editor_initialized",
+                crate::services::plugins::hooks::HookArgs::EditorInitialized,
+            );
+        }
+
+        self.set_status_message(t!("plugin.runtime_restarted").to_string());
+    }
+
     /// Load a plugin from a file path
     #[cfg(feature = "plugins")]
     fn handle_load_plugin(&mut self, path: std::path::PathBuf, callback_id: JsCallbackId) {
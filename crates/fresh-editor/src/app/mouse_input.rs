@@ -68,6 +68,11 @@ impl Editor {
             return Ok(false);
         }
 
+        // When a plugin form dialog is open, it captures all mouse events
+        if self.form_dialog.is_some() {
+            return Ok(self.handle_form_dialog_mouse(mouse_event));
+        }
+
         // Cancel LSP rename prompt on any mouse interaction
         let mut needs_render = false;
         if let Some(ref prompt) = self.prompt {
@@ -1289,6 +1294,28 @@ impl Editor {
                     }
                 }
 
+                // Check plugin items - click notifies the owning plugin
+                let clicked_item = self
+                    .cached_layout
+                    .status_bar_plugin_item_areas
+                    .iter()
+                    .find(|(_, _, (item_row, start, end))| {
+                        row == *item_row && col >= *start && col < *end
+                    })
+                    .map(|(plugin_name, id, _)| (plugin_name.clone(), id.clone()));
+                if let Some((plugin_name, id)) = clicked_item {
+                    let args = crate::services::plugins::hooks::HookArgs::StatusBarItemClicked {
+                        plugin_name: plugin_name.clone(),
+                        id,
+                    };
+                    self.plugin_manager.run_plugin_hook(
+                        &plugin_name,
+                        "status_bar_item_clicked",
+                        args,
+                    );
+                    return Ok(());
+                }
+
                 // Check message area - click opens status log
                 if let Some((msg_row, msg_start, msg_end)) =
                     self.cached_layout.status_bar_message_area
@@ -411,7 +411,7 @@ impl Editor {
     }
 
     /// Replace the active buffer's content with new output.
-    fn replace_buffer_with_output(&mut self, output: &str) -> Result<(), String> {
+    pub(super) fn replace_buffer_with_output(&mut self, output: &str) -> Result<(), String> {
         let cursor_id = self.active_state().cursors.primary_id();
 
         // Get current buffer content
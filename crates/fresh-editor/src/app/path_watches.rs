@@ -0,0 +1,1113 @@
+//! File-system watches requested by plugins
+//!
+//! A plugin watches a file or directory (optionally recursively, optionally
+//! filtered by a glob) and receives debounced batches of create / modify /
+//! delete events through the `path_changed` hook.
+//!
+//! Each watch keeps a snapshot of the entries below its root and changes are
+//! found by comparing snapshots. Scans run off the UI thread, a watch tracks
+//! at most `MAX_WATCHED_ENTRIES` entries, and `.git` and `node_modules` are
+//! never descended into. Local watches on Linux are driven by inotify and
+//! remote watches by the agent's watch, so only directories reported changed
+//! are listed again. Everywhere else (other platforms, agents that cannot
+//! watch, or when inotify runs out of watches) the whole tree is rescanned
+//! at the auto-revert poll interval.
+
+use super::Editor;
+use crate::model::filesystem::{DirEntry, EntryType, FileSystem};
+use fresh_core::api::WatchPathOptions;
+use fresh_core::hooks::{HookArgs, PathChange, PathChangeKind};
+use ignore::overrides::{Override, OverrideBuilder};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Quiet period after the last reported change before events are delivered
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Most entries a single watch keeps track of
+const MAX_WATCHED_ENTRIES: usize = 20_000;
+
+/// Directories whose contents are never scanned
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules"];
+
+/// What is compared to detect a change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fingerprint {
+    is_dir: bool,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+fn fingerprint(fs: &dyn FileSystem, entry: &DirEntry) -> Option<Fingerprint> {
+    let metadata = match &entry.metadata {
+        Some(metadata) => metadata.clone(),
+        None => fs.metadata(&entry.path).ok()?,
+    };
+    Some(Fingerprint {
+        // Symlinked directories are not followed, to avoid cycles
+        is_dir: entry.entry_type == EntryType::Directory,
+        len: metadata.size,
+        modified: metadata.modified,
+    })
+}
+
+fn is_skipped(dir: &Path) -> bool {
+    dir.file_name().is_some_and(|name| {
+        SKIPPED_DIRS
+            .iter()
+            .any(|skipped| name == OsStr::new(skipped))
+    })
+}
+
+/// Add the entries of `dir` to `out`, descending into subdirectories if `recursive`
+///
+/// Returns false if the scan stopped because `out` reached `limit` entries.
+fn scan_dir(
+    fs: &dyn FileSystem,
+    dir: &Path,
+    recursive: bool,
+    limit: usize,
+    out: &mut BTreeMap<PathBuf, Fingerprint>,
+) -> bool {
+    let Ok(entries) = fs.read_dir(dir) else {
+        return true;
+    };
+    for entry in entries {
+        if out.len() >= limit {
+            return false;
+        }
+        let Some(print) = fingerprint(fs, &entry) else {
+            continue;
+        };
+        out.insert(entry.path.clone(), print);
+        if print.is_dir
+            && recursive
+            && !is_skipped(&entry.path)
+            && !scan_dir(fs, &entry.path, true, limit, out)
+        {
+            return false;
+        }
+    }
+    true
+}
+
+/// The entries of one watch, handed to a background thread while scanned
+#[derive(Debug)]
+struct Tree {
+    root: PathBuf,
+    recursive: bool,
+    root_is_dir: bool,
+    /// Entries below the root (or the root itself when it is a file)
+    entries: BTreeMap<PathBuf, Fingerprint>,
+    /// The entry limit was reached, so part of the tree is not tracked
+    truncated: bool,
+}
+
+impl Tree {
+    /// The root and, for recursive watches, every directory below it
+    fn dirs(&self) -> BTreeSet<PathBuf> {
+        let mut dirs = BTreeSet::from([self.root.clone()]);
+        if self.recursive {
+            dirs.extend(
+                self.entries
+                    .iter()
+                    .filter(|(path, print)| print.is_dir && !is_skipped(path))
+                    .map(|(path, _)| path.clone()),
+            );
+        }
+        dirs
+    }
+
+    /// Scan everything again and report the differences
+    fn rescan_all(&mut self, fs: &dyn FileSystem) -> Vec<PathChange> {
+        let mut fresh = BTreeMap::new();
+        self.root_is_dir = fs.is_dir(&self.root).unwrap_or(false);
+        self.truncated = false;
+        if self.root_is_dir {
+            self.truncated = !scan_dir(
+                fs,
+                &self.root,
+                self.recursive,
+                MAX_WATCHED_ENTRIES,
+                &mut fresh,
+            );
+        } else if let Ok(metadata) = fs.metadata(&self.root) {
+            fresh.insert(
+                self.root.clone(),
+                Fingerprint {
+                    is_dir: false,
+                    len: metadata.size,
+                    modified: metadata.modified,
+                },
+            );
+        }
+
+        let old = std::mem::replace(&mut self.entries, fresh);
+        let mut changes: Vec<PathChange> = old
+            .iter()
+            .filter(|(path, _)| !self.entries.contains_key(*path))
+            .map(|(path, _)| change(path, PathChangeKind::Delete))
+            .collect();
+        for (path, print) in &self.entries {
+            match old.get(path) {
+                None => changes.push(change(path, PathChangeKind::Create)),
+                Some(previous) if !print.is_dir && previous != print => {
+                    changes.push(change(path, PathChangeKind::Modify))
+                }
+                _ => {}
+            }
+        }
+        changes
+    }
+
+    /// List one changed directory again and report the differences
+    fn rescan_dir(&mut self, fs: &dyn FileSystem, dir: &Path) -> Vec<PathChange> {
+        if !self.root_is_dir || (dir != self.root && !dir.starts_with(&self.root)) {
+            // A watched file, or its parent
+            return self.rescan_all(fs);
+        }
+
+        let mut fresh = BTreeMap::new();
+        for entry in fs.read_dir(dir).unwrap_or_default() {
+            let Some(print) = fingerprint(fs, &entry) else {
+                continue;
+            };
+            let known_dir = self.entries.get(&entry.path).is_some_and(|p| p.is_dir);
+            if print.is_dir && self.recursive && !known_dir && !is_skipped(&entry.path) {
+                // A new directory: its contents are new too
+                let limit = fresh.len() + MAX_WATCHED_ENTRIES.saturating_sub(self.entries.len());
+                if !scan_dir(fs, &entry.path, true, limit, &mut fresh) {
+                    self.truncated = true;
+                }
+            }
+            fresh.insert(entry.path, print);
+        }
+
+        // Subdirectories that went away take their contents with them
+        let gone_dirs: Vec<PathBuf> = self
+            .entries
+            .iter()
+            .filter(|(path, print)| {
+                print.is_dir
+                    && path.parent() == Some(dir)
+                    && !fresh.get(*path).is_some_and(|p| p.is_dir)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        let stale: Vec<PathBuf> = self
+            .entries
+            .keys()
+            .filter(|path| {
+                (path.parent() == Some(dir) || gone_dirs.iter().any(|d| path.starts_with(d)))
+                    && !fresh.contains_key(*path)
+            })
+            .cloned()
+            .collect();
+
+        let mut changes = Vec::new();
+        for path in stale {
+            self.entries.remove(&path);
+            changes.push(change(&path, PathChangeKind::Delete));
+        }
+        for (path, print) in fresh {
+            match self.entries.insert(path.clone(), print) {
+                None => changes.push(change(&path, PathChangeKind::Create)),
+                Some(previous) if !print.is_dir && previous != print => {
+                    changes.push(change(&path, PathChangeKind::Modify))
+                }
+                _ => {}
+            }
+        }
+        changes
+    }
+}
+
+fn change(path: &Path, kind: PathChangeKind) -> PathChange {
+    PathChange {
+        path: path.to_path_buf(),
+        kind,
+    }
+}
+
+/// A scan of one watch, to be run off the UI thread
+pub(crate) struct PathScan {
+    watch_id: u64,
+    generation: u64,
+    tree: Tree,
+    /// Directories to list again, or `None` for the whole tree
+    dirs: Option<BTreeSet<PathBuf>>,
+    results: mpsc::Sender<ScanResult>,
+}
+
+impl PathScan {
+    /// Scan and hand the changes back to the registry
+    pub fn run(self, fs: &dyn FileSystem) {
+        let mut tree = self.tree;
+        let was_truncated = tree.truncated;
+        let changes = match &self.dirs {
+            None => tree.rescan_all(fs),
+            Some(dirs) => dirs
+                .iter()
+                .flat_map(|dir| tree.rescan_dir(fs, dir))
+                .collect(),
+        };
+        if tree.truncated && !was_truncated {
+            tracing::warn!(
+                "Path watch on {:?} tracks only its first {} entries",
+                tree.root,
+                MAX_WATCHED_ENTRIES
+            );
+        }
+        // Fails only if the editor is shutting down
+        let _ = self.results.send(ScanResult {
+            watch_id: self.watch_id,
+            generation: self.generation,
+            tree,
+            changes,
+        });
+    }
+}
+
+struct ScanResult {
+    watch_id: u64,
+    generation: u64,
+    tree: Tree,
+    changes: Vec<PathChange>,
+}
+
+/// How a watch learns which directories changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Notifier {
+    /// Nothing reports changes: the whole tree is rescanned periodically
+    Poll,
+    #[cfg(target_os = "linux")]
+    Inotify,
+    /// The remote agent's watch
+    Remote,
+}
+
+/// One plugin watch
+struct PathWatch {
+    plugin_name: String,
+    glob: Option<Override>,
+    /// Tells this watch apart from an earlier one with the same id
+    generation: u64,
+    /// The watched entries, or `None` while a scan has them
+    tree: Option<Tree>,
+    /// No scan has finished yet, so there is nothing to compare with
+    initial: bool,
+    /// Directories reported changed since the last scan
+    dirty: BTreeSet<PathBuf>,
+    /// The whole tree has to be scanned again
+    rescan: bool,
+    /// When the latest change was reported, for debouncing
+    last_event: Option<Instant>,
+    notifier: Notifier,
+    /// Directories the notifier reports changes for
+    watched_dirs: BTreeSet<PathBuf>,
+    /// Files the remote watch reports changes for
+    watched_files: BTreeSet<PathBuf>,
+}
+
+impl PathWatch {
+    /// Drop changes the glob filters out and order them by path
+    fn filter(&self, mut changes: Vec<PathChange>) -> Vec<PathChange> {
+        if let Some(glob) = &self.glob {
+            changes.retain(|c| {
+                let is_dir = self
+                    .tree
+                    .as_ref()
+                    .and_then(|tree| tree.entries.get(&c.path))
+                    .is_some_and(|p| p.is_dir);
+                glob.matched(&c.path, is_dir).is_whitelist()
+            });
+        }
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+}
+/// A shared inotify instance with directories reference-counted across watches
+#[cfg(target_os = "linux")]
+struct Inotify {
+    inotify: nix::sys::inotify::Inotify,
+    dirs: std::collections::HashMap<PathBuf, (nix::sys::inotify::WatchDescriptor, usize)>,
+    wds: std::collections::HashMap<nix::sys::inotify::WatchDescriptor, PathBuf>,
+}
+
+#[cfg(target_os = "linux")]
+impl Inotify {
+    fn new() -> Option<Self> {
+        use nix::sys::inotify::InitFlags;
+        match nix::sys::inotify::Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC) {
+            Ok(inotify) => Some(Self {
+                inotify,
+                dirs: Default::default(),
+                wds: Default::default(),
+            }),
+            Err(e) => {
+                tracing::warn!("inotify unavailable, polling plugin path watches: {}", e);
+                None
+            }
+        }
+    }
+
+    fn add(&mut self, dir: &Path) -> bool {
+        use nix::sys::inotify::AddWatchFlags;
+        if let Some((_, count)) = self.dirs.get_mut(dir) {
+            *count += 1;
+            return true;
+        }
+        let mask = AddWatchFlags::IN_CREATE
+            | AddWatchFlags::IN_DELETE
+            | AddWatchFlags::IN_MODIFY
+            | AddWatchFlags::IN_CLOSE_WRITE
+            | AddWatchFlags::IN_ATTRIB
+            | AddWatchFlags::IN_MOVED_FROM
+            | AddWatchFlags::IN_MOVED_TO
+            | AddWatchFlags::IN_ONLYDIR;
+        match self.inotify.add_watch(dir, mask) {
+            Ok(wd) => {
+                self.dirs.insert(dir.to_path_buf(), (wd, 1));
+                self.wds.insert(wd, dir.to_path_buf());
+                true
+            }
+            Err(e) => {
+                tracing::debug!("inotify cannot watch {:?}: {}", dir, e);
+                false
+            }
+        }
+    }
+
+    fn remove(&mut self, dir: &Path) {
+        let Some((wd, count)) = self.dirs.get_mut(dir) else {
+            return;
+        };
+        *count -= 1;
+        if *count == 0 {
+            let wd = *wd;
+            self.dirs.remove(dir);
+            self.wds.remove(&wd);
+            // Fails harmlessly if the directory is already gone
+            let _ = self.inotify.rm_watch(wd);
+        }
+    }
+
+    /// Directories with pending events, and whether the event queue overflowed
+    fn read(&mut self) -> (BTreeSet<PathBuf>, bool) {
+        use nix::sys::inotify::AddWatchFlags;
+        let mut dirs = BTreeSet::new();
+        let mut overflow = false;
+        while let Ok(events) = self.inotify.read_events() {
+            if events.is_empty() {
+                break;
+            }
+            for event in events {
+                if event.mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+                    overflow = true;
+                } else if let Some(dir) = self.wds.get(&event.wd) {
+                    dirs.insert(dir.clone());
+                }
+            }
+        }
+        (dirs, overflow)
+    }
+}
+
+/// All plugin path watches, keyed by watch id
+pub(crate) struct PathWatchRegistry {
+    watches: BTreeMap<u64, PathWatch>,
+    #[cfg(target_os = "linux")]
+    inotify: Option<Inotify>,
+    last_poll: Instant,
+    /// When remote watch notifications were last collected
+    last_remote_sync: Instant,
+    next_generation: u64,
+    scans_tx: mpsc::Sender<ScanResult>,
+    scans_rx: mpsc::Receiver<ScanResult>,
+}
+
+impl PathWatchRegistry {
+    pub fn new(now: Instant) -> Self {
+        let (scans_tx, scans_rx) = mpsc::channel();
+        Self {
+            watches: BTreeMap::new(),
+            #[cfg(target_os = "linux")]
+            inotify: None,
+            last_poll: now,
+            last_remote_sync: now,
+            next_generation: 0,
+            scans_tx,
+            scans_rx,
+        }
+    }
+
+    /// Start a watch; its first scan is returned by the next `poll`
+    ///
+    /// `local` allows inotify. Remote filesystems use their watch if they
+    /// have one, and anything else is polled.
+    pub fn add(
+        &mut self,
+        fs: &dyn FileSystem,
+        local: bool,
+        plugin_name: String,
+        watch_id: u64,
+        root: PathBuf,
+        options: &WatchPathOptions,
+    ) -> Result<(), String> {
+        let glob = match &options.glob {
+            Some(pattern) => {
+                let mut builder = OverrideBuilder::new(&root);
+                builder
+                    .add(pattern)
+                    .map_err(|e| format!("invalid glob '{}': {}", pattern, e))?;
+                Some(
+                    builder
+                        .build()
+                        .map_err(|e| format!("invalid glob '{}': {}", pattern, e))?,
+                )
+            }
+            None => None,
+        };
+        let notifier = if local {
+            self.local_notifier()
+        } else if fs.supports_watch() {
+            Notifier::Remote
+        } else {
+            Notifier::Poll
+        };
+        self.next_generation += 1;
+        let watch = PathWatch {
+            plugin_name,
+            glob,
+            generation: self.next_generation,
+            tree: Some(Tree {
+                root,
+                recursive: options.recursive.unwrap_or(false),
+                root_is_dir: false,
+                entries: BTreeMap::new(),
+                truncated: false,
+            }),
+            initial: true,
+            dirty: BTreeSet::new(),
+            rescan: true,
+            last_event: None,
+            notifier,
+            watched_dirs: BTreeSet::new(),
+            watched_files: BTreeSet::new(),
+        };
+
+        if let Some(old) = self.watches.insert(watch_id, watch) {
+            self.release(old);
+        }
+        Ok(())
+    }
+
+    fn local_notifier(&mut self) -> Notifier {
+        #[cfg(target_os = "linux")]
+        {
+            if self.inotify.is_none() {
+                self.inotify = Inotify::new();
+            }
+            if self.inotify.is_some() {
+                return Notifier::Inotify;
+            }
+        }
+        Notifier::Poll
+    }
+
+    /// Stop one of a plugin's watches
+    pub fn remove(&mut self, plugin_name: &str, watch_id: u64) {
+        if self
+            .watches
+            .get(&watch_id)
+            .is_some_and(|w| w.plugin_name == plugin_name)
+        {
+            if let Some(watch) = self.watches.remove(&watch_id) {
+                self.release(watch);
+            }
+        }
+    }
+
+    /// Stop all watches of a plugin
+    pub fn remove_plugin(&mut self, plugin_name: &str) {
+        let ids: Vec<u64> = self
+            .watches
+            .iter()
+            .filter(|(_, w)| w.plugin_name == plugin_name)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in ids {
+            self.remove(plugin_name, id);
+        }
+    }
+
+    /// Stop all watches
+    pub fn clear(&mut self) {
+        for (_, watch) in std::mem::take(&mut self.watches) {
+            self.release(watch);
+        }
+    }
+
+    fn release(&mut self, watch: PathWatch) {
+        #[cfg(target_os = "linux")]
+        if let Some(inotify) = self.inotify.as_mut() {
+            if watch.notifier != Notifier::Remote {
+                for dir in &watch.watched_dirs {
+                    inotify.remove(dir);
+                }
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = watch;
+    }
+
+    fn remote_watches(&self) -> impl Iterator<Item = &PathWatch> {
+        self.watches
+            .values()
+            .filter(|w| w.notifier == Notifier::Remote)
+    }
+
+    /// Paths the remote filesystem watch has to cover
+    pub fn remote_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.remote_watches()
+            .flat_map(|w| w.watched_dirs.iter().chain(&w.watched_files))
+    }
+
+    /// Whether remote notifications should be collected now
+    pub fn remote_sync_due(&mut self, now: Instant) -> bool {
+        if self.remote_watches().next().is_none()
+            || now.saturating_duration_since(self.last_remote_sync) < DEBOUNCE
+        {
+            return false;
+        }
+        self.last_remote_sync = now;
+        true
+    }
+
+    /// Note paths the remote filesystem watch reported changed
+    pub fn remote_changed(&mut self, paths: &[PathBuf], now: Instant) {
+        for watch in self
+            .watches
+            .values_mut()
+            .filter(|w| w.notifier == Notifier::Remote)
+        {
+            let mut changed = false;
+            for path in paths {
+                if watch.watched_dirs.contains(path) {
+                    watch.dirty.insert(path.clone());
+                    changed = true;
+                } else if watch.watched_files.contains(path) {
+                    // Files are listed again with their directory
+                    if let Some(parent) = path.parent() {
+                        watch.dirty.insert(parent.to_path_buf());
+                        changed = true;
+                    }
+                }
+            }
+            if changed {
+                watch.last_event = Some(now);
+            }
+        }
+    }
+
+    /// Collect change notifications and return the scans that are due
+    ///
+    /// Scans may run on any thread; their changes are picked up by `finish`.
+    pub fn poll(&mut self, now: Instant, poll_interval: Duration) -> Vec<PathScan> {
+        if self.watches.is_empty() {
+            return Vec::new();
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(inotify) = self.inotify.as_mut() {
+            let (dirs, overflow) = inotify.read();
+            for watch in self
+                .watches
+                .values_mut()
+                .filter(|w| w.notifier == Notifier::Inotify)
+            {
+                let before = watch.dirty.len();
+                watch
+                    .dirty
+                    .extend(dirs.intersection(&watch.watched_dirs).cloned());
+                if overflow {
+                    watch.rescan = true;
+                }
+                if overflow || watch.dirty.len() > before {
+                    watch.last_event = Some(now);
+                }
+            }
+        }
+
+        if now.saturating_duration_since(self.last_poll) >= poll_interval {
+            self.last_poll = now;
+            for watch in self
+                .watches
+                .values_mut()
+                .filter(|w| w.notifier == Notifier::Poll)
+            {
+                watch.rescan = true;
+            }
+        }
+
+        let mut scans = Vec::new();
+        for (id, watch) in self.watches.iter_mut() {
+            let pending = watch.rescan || !watch.dirty.is_empty();
+            let settled = watch
+                .last_event
+                .is_none_or(|t| now.saturating_duration_since(t) >= DEBOUNCE);
+            if !pending || !settled {
+                continue;
+            }
+            // Changes reported while a scan runs wait for the next one
+            let Some(tree) = watch.tree.take() else {
+                continue;
+            };
+            watch.last_event = None;
+
+            let dirs = if std::mem::take(&mut watch.rescan) {
+                watch.dirty.clear();
+                None
+            } else {
+                Some(std::mem::take(&mut watch.dirty))
+            };
+            scans.push(PathScan {
+                watch_id: *id,
+                generation: watch.generation,
+                tree,
+                dirs,
+                results: self.scans_tx.clone(),
+            });
+        }
+        scans
+    }
+
+    /// Take back finished scans, returning `(plugin, watch id, changes)`
+    /// for every watch with changes to report
+    pub fn finish(&mut self) -> Vec<(String, u64, Vec<PathChange>)> {
+        let mut results = Vec::new();
+        while let Ok(scan) = self.scans_rx.try_recv() {
+            // The watch may have been stopped or replaced meanwhile
+            let Some(watch) = self
+                .watches
+                .get_mut(&scan.watch_id)
+                .filter(|w| w.generation == scan.generation)
+            else {
+                continue;
+            };
+            watch.tree = Some(scan.tree);
+
+            match watch.notifier {
+                #[cfg(target_os = "linux")]
+                Notifier::Inotify => {
+                    if let Some(inotify) = self.inotify.as_mut() {
+                        sync_inotify(inotify, watch);
+                    }
+                }
+                Notifier::Remote => sync_remote(watch),
+                Notifier::Poll => {}
+            }
+
+            if std::mem::take(&mut watch.initial) {
+                continue;
+            }
+            let changes = watch.filter(scan.changes);
+            if !changes.is_empty() {
+                results.push((watch.plugin_name.clone(), scan.watch_id, changes));
+            }
+        }
+        results
+    }
+}
+
+/// Register the directories a watch needs with inotify and drop the rest
+///
+/// Falls back to polling for the watch if a directory cannot be added
+/// (e.g. the root does not exist yet, or the system limit is reached).
+#[cfg(target_os = "linux")]
+fn sync_inotify(inotify: &mut Inotify, watch: &mut PathWatch) {
+    let Some(tree) = &watch.tree else {
+        return;
+    };
+    let wanted = if tree.root_is_dir {
+        tree.dirs()
+    } else {
+        // Files are watched through their directory, so replacing saves are seen
+        tree.root
+            .parent()
+            .map(Path::to_path_buf)
+            .into_iter()
+            .collect()
+    };
+    let stale: Vec<PathBuf> = watch.watched_dirs.difference(&wanted).cloned().collect();
+    for dir in stale {
+        inotify.remove(&dir);
+        watch.watched_dirs.remove(&dir);
+    }
+    for dir in wanted {
+        if watch.watched_dirs.contains(&dir) {
+            continue;
+        }
+        if !inotify.add(&dir) {
+            watch.notifier = Notifier::Poll;
+            return;
+        }
+        watch.watched_dirs.insert(dir);
+    }
+}
+
+/// Point the remote watch at a watch's directories, and at its files so
+/// that changes to their contents are seen too
+fn sync_remote(watch: &mut PathWatch) {
+    let Some(tree) = &watch.tree else {
+        return;
+    };
+    if tree.root_is_dir {
+        watch.watched_dirs = tree.dirs();
+        watch.watched_files = tree
+            .entries
+            .iter()
+            .filter(|(_, print)| !print.is_dir)
+            .map(|(path, _)| path.clone())
+            .collect();
+    } else {
+        // The agent watches files through their directory, so this also
+        // reports the root being created
+        watch.watched_dirs.clear();
+        watch.watched_files = BTreeSet::from([tree.root.clone()]);
+    }
+}
+
+impl Editor {
+    /// Start a plugin path watch
+    pub(crate) fn handle_watch_path(
+        &mut self,
+        plugin_name: String,
+        watch_id: u64,
+        path: String,
+        options: WatchPathOptions,
+    ) {
+        let local = self.filesystem.remote_connection_info().is_none();
+        if let Err(e) = self.path_watches.add(
+            self.filesystem.as_ref(),
+            local,
+            plugin_name.clone(),
+            watch_id,
+            PathBuf::from(&path),
+            &options,
+        ) {
+            tracing::warn!("Plugin '{}' cannot watch '{}': {}", plugin_name, path, e);
+            // The plugin already holds the watch id, so tell it the watch is dead
+            self.plugin_manager.run_plugin_hook(
+                &plugin_name.clone(),
+                "path_changed",
+                HookArgs::PathChanged {
+                    plugin_name,
+                    watch_id,
+                    changes: Vec::new(),
+                    error: Some(e),
+                },
+            );
+        }
+    }
+
+    /// Start due path watch scans and deliver settled changes to the
+    /// watching plugins (called from main loop)
+    pub(crate) fn poll_path_watches(&mut self) {
+        let poll_interval = Duration::from_millis(self.config.editor.auto_revert_poll_interval_ms);
+        let now = self.time_source.now();
+        if self.path_watches.remote_sync_due(now) {
+            self.collect_watch_events();
+        }
+
+        for scan in self.path_watches.poll(now, poll_interval) {
+            let fs = self.filesystem.clone();
+            match &self.tokio_runtime {
+                Some(runtime) => {
+                    runtime.spawn_blocking(move || scan.run(fs.as_ref()));
+                }
+                None => scan.run(fs.as_ref()),
+            }
+        }
+
+        for (plugin_name, watch_id, changes) in self.path_watches.finish() {
+            self.plugin_manager.run_plugin_hook(
+                &plugin_name.clone(),
+                "path_changed",
+                HookArgs::PathChanged {
+                    plugin_name,
+                    watch_id,
+                    changes,
+                    error: None,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::filesystem::StdFileSystem;
+
+    fn step(registry: &mut PathWatchRegistry, now: Instant) -> Vec<(String, u64, Vec<PathChange>)> {
+        for scan in registry.poll(now, Duration::from_secs(1)) {
+            scan.run(&StdFileSystem);
+        }
+        registry.finish()
+    }
+
+    fn poll_until_settled(
+        registry: &mut PathWatchRegistry,
+        now: &mut Instant,
+    ) -> Vec<(String, u64, Vec<PathChange>)> {
+        // Let inotify events arrive, then step past the debounce and poll interval
+        std::thread::sleep(Duration::from_millis(20));
+        let mut results = step(registry, *now);
+        *now += Duration::from_secs(2);
+        results.extend(step(registry, *now));
+        results
+    }
+
+    fn kinds(changes: &[PathChange], root: &Path) -> Vec<(String, PathChangeKind)> {
+        changes
+            .iter()
+            .map(|c| {
+                let path = c.path.strip_prefix(root).unwrap();
+                (path.to_string_lossy().into_owned(), c.kind)
+            })
+            .collect()
+    }
+
+    fn check_create_modify_delete(local: bool) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/old.xml"), "a").unwrap();
+
+        let mut now = Instant::now();
+        let mut registry = PathWatchRegistry::new(now);
+        let options = WatchPathOptions {
+            recursive: Some(true),
+            glob: Some("*.xml".to_string()),
+        };
+        registry
+            .add(&StdFileSystem, local, "p".into(), 1, root.clone(), &options)
+            .unwrap();
+        assert_eq!(registry.watches[&1].notifier == Notifier::Poll, !local);
+        assert!(poll_until_settled(&mut registry, &mut now).is_empty());
+
+        std::fs::write(root.join("sub/old.xml"), "changed").unwrap();
+        std::fs::create_dir(root.join("new")).unwrap();
+        std::fs::write(root.join("new/a.xml"), "x").unwrap();
+        std::fs::write(root.join("new/ignored.txt"), "x").unwrap();
+        let results = poll_until_settled(&mut registry, &mut now);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, "p");
+        assert_eq!(results[0].1, 1);
+        assert_eq!(
+            kinds(&results[0].2, &root),
+            vec![
+                ("new/a.xml".to_string(), PathChangeKind::Create),
+                ("sub/old.xml".to_string(), PathChangeKind::Modify),
+            ]
+        );
+
+        // Files in the new directory are watched too
+        std::fs::remove_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("new/b.xml"), "y").unwrap();
+        let results = poll_until_settled(&mut registry, &mut now);
+        assert_eq!(
+            kinds(&results[0].2, &root),
+            vec![
+                ("new/b.xml".to_string(), PathChangeKind::Create),
+                ("sub/old.xml".to_string(), PathChangeKind::Delete),
+            ]
+        );
+
+        registry.remove_plugin("p");
+        std::fs::write(root.join("new/c.xml"), "z").unwrap();
+        assert!(poll_until_settled(&mut registry, &mut now).is_empty());
+    }
+
+    #[test]
+    fn test_polled_watch_reports_changes() {
+        check_create_modify_delete(false);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_inotify_watch_reports_changes() {
+        check_create_modify_delete(true);
+    }
+
+    #[test]
+    fn test_file_watch_and_non_recursive_watch() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let file = root.join("status.json");
+        std::fs::create_dir(root.join("sub")).unwrap();
+
+        let mut now = Instant::now();
+        let mut registry = PathWatchRegistry::new(now);
+        registry
+            .add(
+                &StdFileSystem,
+                true,
+                "p".into(),
+                1,
+                file.clone(),
+                &Default::default(),
+            )
+            .unwrap();
+        registry
+            .add(
+                &StdFileSystem,
+                true,
+                "q".into(),
+                2,
+                root.clone(),
+                &Default::default(),
+            )
+            .unwrap();
+        assert!(poll_until_settled(&mut registry, &mut now).is_empty());
+
+        std::fs::write(&file, "{}").unwrap();
+        std::fs::write(root.join("sub/deep.txt"), "x").unwrap();
+        let results = poll_until_settled(&mut registry, &mut now);
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            kinds(&results[0].2, &root),
+            vec![("status.json".to_string(), PathChangeKind::Create)]
+        );
+        // Not recursive: the new file in `sub` is not reported
+        assert_eq!(results[1].1, 2);
+        assert_eq!(
+            kinds(&results[1].2, &root),
+            vec![("status.json".to_string(), PathChangeKind::Create)]
+        );
+
+        // Another plugin cannot stop the watch
+        registry.remove("q", 1);
+        std::fs::remove_file(&file).unwrap();
+        let results = poll_until_settled(&mut registry, &mut now);
+        assert_eq!(results[0].1, 1);
+        assert_eq!(
+            kinds(&results[0].2, &root),
+            vec![("status.json".to_string(), PathChangeKind::Delete)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_glob_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = PathWatchRegistry::new(Instant::now());
+        let options = WatchPathOptions {
+            recursive: None,
+            glob: Some("[".to_string()),
+        };
+        assert!(registry
+            .add(
+                &StdFileSystem,
+                false,
+                "p".into(),
+                1,
+                dir.path().to_path_buf(),
+                &options
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_scan_skips_git_and_node_modules_and_stops_at_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for sub in [".git/objects", "node_modules/pkg", "src"] {
+            std::fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        std::fs::write(root.join("node_modules/pkg/index.js"), "").unwrap();
+        std::fs::write(root.join("src/main.rs"), "").unwrap();
+
+        let mut entries = BTreeMap::new();
+        assert!(scan_dir(&StdFileSystem, root, true, 100, &mut entries));
+        let names: Vec<String> = entries
+            .keys()
+            .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec![".git", "node_modules", "src", "src/main.rs"]);
+
+        let mut entries = BTreeMap::new();
+        assert!(!scan_dir(&StdFileSystem, root, true, 2, &mut entries));
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[test]
+    fn test_remote_watch_rescans_reported_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let file = root.join("a.txt");
+        std::fs::write(&file, "a").unwrap();
+
+        let mut now = Instant::now();
+        let mut registry = PathWatchRegistry::new(now);
+        registry
+            .add(
+                &StdFileSystem,
+                false,
+                "p".into(),
+                1,
+                root.clone(),
+                &Default::default(),
+            )
+            .unwrap();
+        registry.watches.get_mut(&1).unwrap().notifier = Notifier::Remote;
+        assert!(poll_until_settled(&mut registry, &mut now).is_empty());
+        let paths: Vec<&PathBuf> = registry.remote_paths().collect();
+        assert_eq!(paths, vec![&root, &file]);
+
+        // Nothing is rescanned until the remote watch reports a change
+        std::fs::write(&file, "changed").unwrap();
+        assert!(poll_until_settled(&mut registry, &mut now).is_empty());
+        registry.remote_changed(std::slice::from_ref(&file), now);
+        let results = poll_until_settled(&mut registry, &mut now);
+        assert_eq!(
+            kinds(&results[0].2, &root),
+            vec![("a.txt".to_string(), PathChangeKind::Modify)]
+        );
+    }
+
+    #[test]
+    fn test_scan_of_replaced_watch_is_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let now = Instant::now();
+        let mut registry = PathWatchRegistry::new(now);
+        let add = |registry: &mut PathWatchRegistry| {
+            registry
+                .add(
+                    &StdFileSystem,
+                    false,
+                    "p".into(),
+                    1,
+                    root.clone(),
+                    &Default::default(),
+                )
+                .unwrap()
+        };
+        add(&mut registry);
+        let stale = registry.poll(now, Duration::from_secs(1));
+        add(&mut registry);
+        std::fs::write(root.join("a.txt"), "a").unwrap();
+        for scan in stale {
+            scan.run(&StdFileSystem);
+        }
+        registry.finish();
+
+        // The new watch still takes its own first snapshot
+        assert!(registry.watches[&1].initial);
+        assert_eq!(registry.poll(now, Duration::from_secs(1)).len(), 1);
+    }
+}
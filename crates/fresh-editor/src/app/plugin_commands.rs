@@ -2,16 +2,31 @@
 //!
 //! This module groups plugin commands by domain for better maintainability.
 
+use crate::config::{PluginCapabilities, PluginConfig};
 use crate::model::event::{BufferId, CursorId, Event, OverlayFace, SplitId};
+use crate::primitives::syntax_query;
+use crate::services::terminal::{TerminalId, TerminalLaunch};
 use crate::view::overlay::{OverlayHandle, OverlayNamespace};
+use crate::view::prompt::PromptType;
 use crate::view::split::SplitViewState;
 use anyhow::Result as AnyhowResult;
 use fresh_core::api::{
-    LayoutHints, MenuPosition, OverlayOptions, PluginResponse, ViewTransformPayload,
+    BufferEdit, CreateTerminalOptions, JsCallbackId, LayoutHints, MenuPosition, OverlayOptions,
+    PluginResponse, SelectionKind, SelectionSpec, TerminalResult, ViewTransformPayload,
 };
+use rust_i18n::t;
 
 use super::Editor;
 
+/// An interactive process a plugin spawned
+#[cfg(feature = "plugins")]
+pub(super) struct InteractiveProcess {
+    /// The plugin that spawned it, the only one that may use its stdin
+    pub(super) plugin_name: String,
+    pub(super) stdin:
+        tokio::sync::mpsc::UnboundedSender<crate::services::plugins::process::StdinMessage>,
+}
+
 impl Editor {
     // ==================== Menu Helpers ====================
 
@@ -528,6 +543,94 @@ impl Editor {
         }
     }
 
+    /// Handle SetSelections command
+    ///
+    /// Replaces every cursor of the active buffer in one undoable batch:
+    /// the original cursor takes the first selection and the rest are added
+    /// as new cursors, so the last one ends up primary.
+    pub(super) fn handle_set_selections(&mut self, selections: Vec<SelectionSpec>) {
+        let state = self.active_state();
+        let buffer_len = state.buffer.len();
+        let first_id = state
+            .cursors
+            .iter()
+            .map(|(id, _)| id)
+            .min_by_key(|id| id.0)
+            .expect("Should have at least one cursor");
+        let mut next_id = state.cursors.iter().map(|(id, _)| id.0).max().unwrap_or(0) + 1;
+
+        let mut events = Vec::new();
+        for (cursor_id, cursor) in state.cursors.iter() {
+            if cursor_id != first_id {
+                events.push(Event::RemoveCursor {
+                    cursor_id,
+                    position: cursor.position,
+                    anchor: cursor.anchor,
+                });
+            }
+        }
+
+        let mut cursor_ids = Vec::with_capacity(selections.len());
+        for (i, selection) in selections.iter().enumerate() {
+            let position = selection.position.min(buffer_len);
+            let anchor = selection.anchor.map(|anchor| anchor.min(buffer_len));
+            if i == 0 {
+                let first = state
+                    .cursors
+                    .get(first_id)
+                    .expect("First cursor should exist");
+                events.push(Event::MoveCursor {
+                    cursor_id: first_id,
+                    old_position: first.position,
+                    new_position: position,
+                    old_anchor: first.anchor,
+                    new_anchor: anchor,
+                    old_sticky_column: first.sticky_column,
+                    new_sticky_column: 0,
+                });
+                cursor_ids.push(first_id);
+            } else {
+                let cursor_id = CursorId(next_id);
+                next_id += 1;
+                events.push(Event::AddCursor {
+                    cursor_id,
+                    position,
+                    anchor,
+                });
+                cursor_ids.push(cursor_id);
+            }
+        }
+
+        let batch = Event::Batch {
+            events,
+            description: "Set selections".to_string(),
+        };
+        self.active_event_log_mut().append(batch.clone());
+        self.apply_event_to_active_buffer(&batch);
+
+        // Event::MoveCursor can't carry the selection mode, so set it afterwards
+        // (the same way block selection actions do)
+        let state = self.active_state_mut();
+        for (cursor_id, selection) in cursor_ids.into_iter().zip(&selections) {
+            let block_anchor = match (selection.mode, selection.anchor) {
+                (Some(SelectionKind::Block), Some(anchor)) => Some(anchor.min(buffer_len)),
+                _ => None,
+            };
+            let anchor_2d = block_anchor.map(|anchor| {
+                let line = state.buffer.get_line_number(anchor);
+                let line_start = state.buffer.line_start_offset(line).unwrap_or(0);
+                (line, anchor.saturating_sub(line_start))
+            });
+            if let Some(cursor) = state.cursors.get_mut(cursor_id) {
+                match anchor_2d {
+                    Some((line, column)) => cursor.start_block_selection(line, column),
+                    None => cursor.clear_block_selection(),
+                }
+            }
+        }
+        self.sync_editor_state_to_split_view_state();
+    }
+
     /// Handle SetSplitScroll command
     pub(super) fn handle_set_split_scroll(&mut self, split_id: SplitId, top_byte: usize) {
         if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
@@ -610,6 +713,436 @@ impl Editor {
         self.send_plugin_response(PluginResponse::HighlightsComputed { request_id, spans });
     }
 
+    // ==================== Syntax Tree Commands ====================
+
+    /// Run a syntax tree query against a buffer and settle the plugin's
+    /// callback with its JSON result, or reject it if the buffer has no
+    /// tree-sitter grammar or the query fails
+    fn settle_syntax_query<T: serde::Serialize>(
+        &mut self,
+        buffer_id: BufferId,
+        callback_id: JsCallbackId,
+        query: impl FnOnce(&syntax_query::SyntaxTree) -> Result<T, String>,
+    ) {
+        let result = match self.buffers.get_mut(&buffer_id) {
+            None => Err(format!("Buffer {} not found", buffer_id.0)),
+            Some(state) => match state.highlighter.language().copied() {
+                None => Err(format!(
+                    "No syntax tree available for language '{}'",
+                    state.language
+                )),
+                Some(language) => state
+                    .syntax_tree
+                    .get(&state.buffer, &language)
+                    .and_then(query),
+            },
+        };
+        match result.and_then(|value| serde_json::to_string(&value).map_err(|e| e.to_string())) {
+            Ok(json) => self.plugin_manager.resolve_callback(callback_id, json),
+            Err(e) => self.plugin_manager.reject_callback(callback_id, e),
+        }
+    }
+
+    /// Handle GetSyntaxNodePath command
+    pub(super) fn handle_get_syntax_node_path(
+        &mut self,
+        buffer_id: BufferId,
+        offset: usize,
+        callback_id: JsCallbackId,
+    ) {
+        self.settle_syntax_query(buffer_id, callback_id, |tree| {
+            Ok(syntax_query::node_path(tree, offset))
+        });
+    }
+
+    /// Handle GetEnclosingSyntaxNode command
+    pub(super) fn handle_get_enclosing_syntax_node(
+        &mut self,
+        buffer_id: BufferId,
+        offset: usize,
+        kind: String,
+        callback_id: JsCallbackId,
+    ) {
+        self.settle_syntax_query(buffer_id, callback_id, |tree| {
+            Ok(syntax_query::enclosing_node(tree, offset, &kind))
+        });
+    }
+
+    /// Handle QuerySyntax command
+    pub(super) fn handle_query_syntax(
+        &mut self,
+        buffer_id: BufferId,
+        query: String,
+        range: Option<std::ops::Range<usize>>,
+        callback_id: JsCallbackId,
+    ) {
+        self.settle_syntax_query(buffer_id, callback_id, |tree| {
+            syntax_query::query_captures(tree, &query, range)
+        });
+    }
+
+    // ==================== Terminal Commands ====================
+
+    /// Handle CreateTerminal command
+    pub(super) fn handle_create_terminal(
+        &mut self,
+        plugin_name: String,
+        options: CreateTerminalOptions,
+        callback_id: JsCallbackId,
+    ) {
+        let target_split = options.split_id.map(SplitId);
+        if let Some(split_id) = target_split {
+            if !self.split_view_states.contains_key(&split_id) {
+                self.plugin_manager
+                    .reject_callback(callback_id, format!("Split {} not found", split_id.0));
+                return;
+            }
+        }
+
+        let launch = TerminalLaunch {
+            program: options.command,
+            args: options.args.unwrap_or_default(),
+            env: options.env.unwrap_or_default().into_iter().collect(),
+        };
+        let cwd = options
+            .cwd
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|| self.working_dir.clone());
+        let terminal_id = match self.spawn_terminal_process(cwd, &launch) {
+            Ok(terminal_id) => terminal_id,
+            Err(e) => {
+                self.plugin_manager.reject_callback(callback_id, e);
+                return;
+            }
+        };
+        self.terminal_owners.insert(terminal_id, plugin_name);
+        // Keep output from the start for watches registered after this resolves
+        if let Some(handle) = self.terminal_manager.get(terminal_id) {
+            if let Ok(mut state) = handle.state.lock() {
+                state.capture_output();
+            }
+        }
+
+        let focus = options.focus.unwrap_or(true);
+        let previous_split = self.split_manager.active_split();
+        let (buffer_id, split_id) = if let Some(split_id) = target_split {
+            let buffer_id = self.create_terminal_buffer_attached(terminal_id, split_id);
+            if focus {
+                self.split_manager.set_active_split(split_id);
+            }
+            self.handle_set_split_buffer(split_id, buffer_id);
+            (buffer_id, split_id)
+        } else if let Some(direction) = options.direction {
+            let buffer_id = self.create_terminal_buffer_detached(terminal_id);
+            self.save_current_split_view_state();
+            let split_dir = match direction.as_str() {
+                "vertical" => crate::model::event::SplitDirection::Vertical,
+                _ => crate::model::event::SplitDirection::Horizontal,
+            };
+            let ratio = options.ratio.unwrap_or(0.5);
+            match self.split_manager.split_active(split_dir, buffer_id, ratio) {
+                Ok(new_split_id) => {
+                    let mut view_state = SplitViewState::with_buffer(
+                        self.terminal_width,
+                        self.terminal_height,
+                        buffer_id,
+                    );
+                    // Terminal buffers should not wrap lines so escape sequences stay intact
+                    view_state.viewport.line_wrap_enabled = false;
+                    self.split_view_states.insert(new_split_id, view_state);
+                    if focus {
+                        self.split_manager.set_active_split(new_split_id);
+                    } else {
+                        self.split_manager.set_active_split(previous_split);
+                    }
+                    (buffer_id, new_split_id)
+                }
+                Err(e) => {
+                    self.close_terminal_buffer(buffer_id);
+                    self.plugin_manager.reject_callback(callback_id, e);
+                    return;
+                }
+            }
+        } else {
+            let buffer_id = self.create_terminal_buffer_attached(terminal_id, previous_split);
+            self.set_active_buffer(buffer_id);
+            (buffer_id, previous_split)
+        };
+
+        if focus {
+            self.terminal_mode = true;
+            self.key_context = crate::input::keybindings::KeyContext::Terminal;
+        }
+        self.resize_visible_terminals();
+        tracing::info!(
+            "Plugin opened terminal {:?} with buffer {:?} in split {:?}",
+            terminal_id,
+            buffer_id,
+            split_id
+        );
+
+        let result = TerminalResult {
+            terminal_id: terminal_id.0 as u64,
+            buffer_id: buffer_id.0 as u64,
+            split_id: split_id.0 as u64,
+        };
+        self.plugin_manager.resolve_callback(
+            callback_id,
+            serde_json::to_string(&result).unwrap_or_default(),
+        );
+    }
+
+    /// Check that a terminal was opened by the plugin using it
+    fn plugin_owns_terminal(&self, plugin_name: &str, terminal_id: TerminalId) -> bool {
+        let owned = self.terminal_owners.get(&terminal_id).map(String::as_str) == Some(plugin_name);
+        if !owned {
+            tracing::warn!(
+                "Plugin '{}' may not use terminal {:?}, which it did not open",
+                plugin_name,
+                terminal_id
+            );
+        }
+        owned
+    }
+
+    /// Handle SendTerminalInput command
+    pub(super) fn handle_send_terminal_input(
+        &mut self,
+        plugin_name: &str,
+        terminal_id: TerminalId,
+        data: String,
+    ) {
+        if !self.plugin_owns_terminal(plugin_name, terminal_id) {
+            return;
+        }
+        match self.terminal_manager.get(terminal_id) {
+            Some(handle) => handle.write(data.as_bytes()),
+            None => tracing::warn!("SendTerminalInput: terminal {:?} not found", terminal_id),
+        }
+    }
+
+    /// Handle GetTerminalScrollback command
+    pub(super) fn handle_get_terminal_scrollback(
+        &mut self,
+        plugin_name: &str,
+        terminal_id: TerminalId,
+        callback_id: JsCallbackId,
+    ) {
+        let content = self
+            .plugin_owns_terminal(plugin_name, terminal_id)
+            .then(|| self.terminal_manager.get(terminal_id))
+            .flatten()
+            .and_then(|handle| handle.state.lock().ok().map(|s| s.full_content_string()));
+        match content {
+            Some(content) => self.plugin_manager.resolve_callback(
+                callback_id,
+                serde_json::to_string(&content).unwrap_or_default(),
+            ),
+            None => self
+                .plugin_manager
+                .reject_callback(callback_id, format!("Terminal {} not found", terminal_id.0)),
+        }
+    }
+
+    /// Handle WatchTerminalOutput command
+    pub(super) fn handle_watch_terminal_output(
+        &mut self,
+        plugin_name: &str,
+        terminal_id: TerminalId,
+        pattern: String,
+    ) {
+        if !self.plugin_owns_terminal(plugin_name, terminal_id) {
+            return;
+        }
+        let Some(handle) = self.terminal_manager.get(terminal_id) else {
+            tracing::warn!("WatchTerminalOutput: terminal {:?} not found", terminal_id);
+            return;
+        };
+        let watch = self.terminal_output_watches.entry(terminal_id).or_default();
+        if let Err(e) = watch.add_pattern(pattern) {
+            tracing::warn!("WatchTerminalOutput: invalid pattern: {}", e);
+            return;
+        }
+        if let Ok(mut state) = handle.state.lock() {
+            state.capture_output();
+        }
+    }
+
+    /// Handle CloseTerminal command
+    pub(super) fn handle_close_terminal(&mut self, plugin_name: &str, terminal_id: TerminalId) {
+        if !self.plugin_owns_terminal(plugin_name, terminal_id) {
+            return;
+        }
+        match self.buffer_for_terminal(terminal_id) {
+            Some(buffer_id) => {
+                self.close_terminal_buffer(buffer_id);
+            }
+            None => {
+                self.terminal_manager.close(terminal_id);
+                self.terminal_output_watches.remove(&terminal_id);
+                self.terminal_owners.remove(&terminal_id);
+            }
+        }
+    }
+
+    /// Close all terminals a plugin opened (when it is unloaded)
+    pub(super) fn close_plugin_terminals(&mut self, plugin_name: &str) {
+        let terminal_ids: Vec<TerminalId> = self
+            .terminal_owners
+            .iter()
+            .filter(|(_, owner)| owner.as_str() == plugin_name)
+            .map(|(&terminal_id, _)| terminal_id)
+            .collect();
+        for terminal_id in terminal_ids {
+            self.handle_close_terminal(plugin_name, terminal_id);
+        }
+    }
+
+    // ==================== Interactive Process Commands ====================
+
+    /// Handle SpawnInteractiveProcess command
+    ///
+    /// The process is started through the process spawner, so it runs on the
+    /// remote host for SSH workspaces. Output is streamed through the process
+    /// output hooks and the callback resolves when the process exits.
+    #[cfg(feature = "plugins")]
+    pub(super) fn handle_spawn_interactive_process(
+        &mut self,
+        plugin_name: String,
+        process_id: u64,
+        command: String,
+        args: Vec<String>,
+        options: fresh_core::api::InteractiveProcessOptions,
+        callback_id: JsCallbackId,
+    ) {
+        use crate::services::async_bridge::AsyncMessage;
+        use crate::services::plugins::process::{
+            feed_process_stdin, forward_process_output, OutputMode, OutputStream,
+        };
+        use fresh_core::api::PluginAsyncMessage;
+
+        let (Some(runtime), Some(bridge)) = (&self.tokio_runtime, &self.async_bridge) else {
+            self.plugin_manager
+                .reject_callback(callback_id, "Async runtime not available".to_string());
+            return;
+        };
+
+        let mode = options
+            .output
+            .as_deref()
+            .and_then(OutputMode::parse)
+            .unwrap_or_default();
+        let cwd = options
+            .cwd
+            .unwrap_or_else(|| self.working_dir.to_string_lossy().to_string());
+        let mut env: Vec<(String, String)> = options.env.unwrap_or_default().into_iter().collect();
+        env.sort();
+
+        let (stdin_tx, stdin_rx) = tokio::sync::mpsc::unbounded_channel();
+        let sender = bridge.sender();
+        let spawner = self.process_spawner.clone();
+        let callback_id = callback_id.as_u64();
+
+        let handle = runtime.spawn(async move {
+            let send = |msg| {
+                let _ = sender.send(AsyncMessage::Plugin(msg));
+            };
+            let exit_code = match spawner
+                .spawn_piped_with_env(command.clone(), args, Some(cwd), env)
+                .await
+            {
+                Ok(process) => {
+                    let mut guard = process.guard;
+                    let stdin_task = tokio::spawn(feed_process_stdin(process.stdin, stdin_rx));
+                    let (_, _, exit) = tokio::join!(
+                        forward_process_output(
+                            process_id,
+                            process.stdout,
+                            OutputStream::Stdout,
+                            mode,
+                            send
+                        ),
+                        forward_process_output(
+                            process_id,
+                            process.stderr,
+                            OutputStream::Stderr,
+                            mode,
+                            send
+                        ),
+                        guard.wait(),
+                    );
+                    stdin_task.abort();
+                    exit.unwrap_or(-1)
+                }
+                Err(e) => {
+                    tracing::error!("Failed to spawn interactive process {}: {}", command, e);
+                    send(PluginAsyncMessage::ProcessStderr {
+                        process_id,
+                        data: format!("{}\n", e),
+                    });
+                    -1
+                }
+            };
+            send(PluginAsyncMessage::ProcessExit {
+                process_id,
+                callback_id,
+                exit_code,
+            });
+        });
+
+        self.background_process_handles
+            .insert(process_id, handle.abort_handle());
+        self.interactive_processes.insert(
+            process_id,
+            InteractiveProcess {
+                plugin_name,
+                stdin: stdin_tx,
+            },
+        );
+    }
+
+    /// Handle WriteProcessStdin and CloseProcessStdin commands
+    ///
+    /// Only the plugin that spawned a process may use its stdin.
+    #[cfg(feature = "plugins")]
+    pub(super) fn handle_process_stdin(
+        &mut self,
+        plugin_name: &str,
+        process_id: u64,
+        message: crate::services::plugins::process::StdinMessage,
+    ) {
+        match self.interactive_processes.get(&process_id) {
+            Some(process) if process.plugin_name == plugin_name => {
+                let _ = process.stdin.send(message);
+            }
+            Some(_) => tracing::warn!(
+                "Plugin '{}' may not use interactive process {}, which it did not spawn",
+                plugin_name,
+                process_id
+            ),
+            None => tracing::warn!("Interactive process {} not found", process_id),
+        }
+    }
+
+    /// Kill the interactive processes of a plugin (when it is unloaded), or
+    /// of all plugins (when the plugin runtime restarts)
+    #[cfg(feature = "plugins")]
+    pub(super) fn kill_interactive_processes(&mut self, plugin_name: Option<&str>) {
+        let process_ids: Vec<u64> = self
+            .interactive_processes
+            .iter()
+            .filter(|(_, process)| plugin_name.is_none_or(|name| process.plugin_name == name))
+            .map(|(&process_id, _)| process_id)
+            .collect();
+        for process_id in process_ids {
+            self.interactive_processes.remove(&process_id);
+            // Dropping the process kills it
+            if let Some(handle) = self.background_process_handles.remove(&process_id) {
+                handle.abort();
+            }
+        }
+    }
+
     // ==================== Text Editing Commands ====================
 
     /// Handle InsertText command
@@ -652,6 +1185,102 @@ impl Editor {
         }
     }
 
+    /// Handle ApplyEdits command
+    ///
+    /// All edits are applied as one `Event::Batch` (a single undo step) or
+    /// not at all. A buffer that changed since `expected_version` resolves
+    /// with `applied: false` so the plugin can re-read and retry; invalid
+    /// edits reject the callback.
+    pub(super) fn handle_apply_edits(
+        &mut self,
+        buffer_id: BufferId,
+        expected_version: u64,
+        mut edits: Vec<BufferEdit>,
+        label: Option<String>,
+        callback_id: JsCallbackId,
+    ) {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            self.plugin_manager
+                .reject_callback(callback_id, format!("Buffer {} not found", buffer_id.0));
+            return;
+        };
+
+        let current_version = state.buffer.version();
+        if current_version != expected_version {
+            let result = serde_json::json!({ "applied": false, "version": current_version });
+            self.plugin_manager
+                .resolve_callback(callback_id, result.to_string());
+            return;
+        }
+
+        // Sort by position; a stable sort keeps insertions at the same
+        // offset in the order the plugin listed them
+        edits.sort_by_key(|edit| (edit.start, edit.end));
+        let buffer_len = state.buffer.len();
+        let mut prev_end = 0;
+        for edit in &edits {
+            if edit.start > edit.end || edit.end > buffer_len {
+                self.plugin_manager.reject_callback(
+                    callback_id,
+                    format!(
+                        "Edit range {}..{} is outside the buffer (length {})",
+                        edit.start, edit.end, buffer_len
+                    ),
+                );
+                return;
+            }
+            if edit.start < prev_end {
+                self.plugin_manager.reject_callback(
+                    callback_id,
+                    format!(
+                        "Edit range {}..{} overlaps another edit",
+                        edit.start, edit.end
+                    ),
+                );
+                return;
+            }
+            prev_end = edit.end;
+        }
+
+        // Apply back to front so earlier offsets stay valid
+        let mut events = Vec::new();
+        for edit in edits.into_iter().rev() {
+            if edit.start < edit.end {
+                events.push(Event::Delete {
+                    range: edit.start..edit.end,
+                    deleted_text: state.get_text_range(edit.start, edit.end),
+                    cursor_id: CursorId(0),
+                });
+            }
+            if !edit.text.is_empty() {
+                events.push(Event::Insert {
+                    position: edit.start,
+                    text: edit.text,
+                    cursor_id: CursorId(0),
+                });
+            }
+        }
+
+        if !events.is_empty() {
+            let batch = Event::Batch {
+                events,
+                description: label.unwrap_or_else(|| "Plugin edit".to_string()),
+            };
+            state.apply(&batch);
+            if let Some(log) = self.event_logs.get_mut(&buffer_id) {
+                log.append(batch);
+            }
+        }
+
+        let version = self
+            .buffers
+            .get(&buffer_id)
+            .map_or(expected_version, |state| state.buffer.version());
+        let result = serde_json::json!({ "applied": true, "version": version });
+        self.plugin_manager
+            .resolve_callback(callback_id, result.to_string());
+    }
+
     /// Handle InsertAtCursor command
     pub(super) fn handle_insert_at_cursor(&mut self, text: String) {
         // Insert text at current cursor position in active buffer
@@ -1204,6 +1833,75 @@ impl Editor {
         }
     }
 
+    // ==================== Plugin Capability Commands ====================
+
+    /// Ask about the next plugin package waiting for capability consent
+    ///
+    /// Requests wait while another prompt is open, so the question never
+    /// replaces something the user is in the middle of.
+    pub(super) fn show_next_capability_request(&mut self) {
+        if self.prompt.is_some() {
+            return;
+        }
+        let Some(prompt_type) = self.pending_capability_requests.pop_front() else {
+            return;
+        };
+        if let PromptType::ConfirmPluginCapabilities {
+            name, requested, ..
+        } = &prompt_type
+        {
+            let message = t!(
+                "plugin.capabilities_confirm",
+                name = name,
+                capabilities = describe_capabilities(requested)
+            )
+            .to_string();
+            self.start_prompt(message, prompt_type);
+        }
+    }
+
+    /// Record the user's answer to a capability request, persisting it so
+    /// the question is not asked again
+    ///
+    /// Granting loads the plugin right away; refusing disables it.
+    pub(super) fn answer_capability_request(
+        &mut self,
+        name: String,
+        path: std::path::PathBuf,
+        requested: PluginCapabilities,
+        allow: bool,
+    ) {
+        let config = self
+            .config
+            .plugins
+            .entry(name.clone())
+            .or_insert_with(|| PluginConfig::new_with_path(path.clone()));
+        config.enabled = allow;
+        if allow {
+            config.granted = requested.clone();
+        }
+        if let Err(e) = self.save_config() {
+            tracing::warn!("Failed to save plugin capability grant: {}", e);
+        }
+
+        if !allow {
+            self.set_status_message(t!("plugin.capabilities_denied", name = &name).to_string());
+            return;
+        }
+        self.plugin_manager.set_capability_grant(&name, requested);
+        match self.plugin_manager.load_plugin(&path) {
+            Ok(()) => {
+                self.set_status_message(
+                    t!("plugin.capabilities_granted", name = &name).to_string(),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Failed to load plugin '{}': {}", name, e);
+                self.set_status_message(format!("Failed to load plugin '{}': {}", name, e));
+            }
+        }
+    }
+
     // ==================== Clipboard Commands ====================
 
     /// Handle SetClipboard command
@@ -1372,3 +2070,46 @@ impl Editor {
         }
     }
 }
+
+/// Summarize capabilities for the consent prompt, e.g.
+/// "read ${workspace}; run git; set the clipboard"
+fn describe_capabilities(capabilities: &PluginCapabilities) -> String {
+    let mut parts = Vec::new();
+    if !capabilities.read.is_empty() {
+        parts.push(
+            t!(
+                "plugin.capability_read",
+                paths = capabilities.read.join(", ")
+            )
+            .to_string(),
+        );
+    }
+    if !capabilities.write.is_empty() {
+        parts.push(
+            t!(
+                "plugin.capability_write",
+                paths = capabilities.write.join(", ")
+            )
+            .to_string(),
+        );
+    }
+    if !capabilities.spawn.is_empty() {
+        parts.push(
+            t!(
+                "plugin.capability_spawn",
+                commands = capabilities.spawn.join(", ")
+            )
+            .to_string(),
+        );
+    }
+    if !capabilities.env.is_empty() {
+        parts.push(t!("plugin.capability_env", names = capabilities.env.join(", ")).to_string());
+    }
+    if capabilities.clipboard {
+        parts.push(t!("plugin.capability_clipboard").to_string());
+    }
+    if parts.is_empty() {
+        return t!("plugin.capability_none").to_string();
+    }
+    parts.join("; ")
+}
@@ -0,0 +1,453 @@
+//! Plugin developer console
+//!
+//! A read-only `*Plugin Console*` buffer for plugin authors: a REPL that
+//! evaluates JavaScript/TypeScript in a chosen plugin's context, the commands
+//! and hook handlers each plugin registered (with call timings), the plugin
+//! commands most recently sent to the editor, and optional auto-reload of the
+//! plugin when its source file changes.
+
+use super::Editor;
+use crate::input::commands::{CommandSource, Suggestion};
+use crate::model::event::BufferId;
+use crate::primitives::text_property::TextPropertyEntry;
+use crate::view::prompt::PromptType;
+use fresh_core::api::PluginCommand;
+use rust_i18n::t;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Display name of the console buffer
+pub const PLUGIN_CONSOLE_BUFFER_NAME: &str = "*Plugin Console*";
+/// Number of plugin commands kept for the console
+const RECENT_COMMANDS: usize = 50;
+/// Longest plugin command description shown
+const COMMAND_SUMMARY_CHARS: usize = 160;
+/// Number of evaluations kept in the transcript
+const TRANSCRIPT_ENTRIES: usize = 100;
+/// How often the console is refreshed and watched sources are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// One line of console history: an evaluation or an automatic reload
+#[derive(Debug, Clone)]
+enum ConsoleEntry {
+    Eval {
+        plugin: String,
+        input: String,
+        output: Result<String, String>,
+    },
+    Reload {
+        plugin: String,
+        result: Result<(), String>,
+    },
+}
+
+/// A plugin source file reloaded when it changes
+#[derive(Debug, Clone)]
+struct WatchedSource {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// State of the plugin developer console
+#[derive(Debug)]
+pub(crate) struct PluginConsole {
+    /// The console buffer
+    pub buffer_id: BufferId,
+    /// Plugin that snippets are evaluated in
+    pub target: Option<String>,
+    transcript: VecDeque<ConsoleEntry>,
+    recent_commands: VecDeque<String>,
+    /// Commands were recorded since the buffer was last written
+    commands_changed: bool,
+    watched: HashMap<String, WatchedSource>,
+    last_poll: std::time::Instant,
+}
+
+impl PluginConsole {
+    fn new(buffer_id: BufferId, now: std::time::Instant) -> Self {
+        Self {
+            buffer_id,
+            target: None,
+            transcript: VecDeque::new(),
+            recent_commands: VecDeque::new(),
+            commands_changed: false,
+            watched: HashMap::new(),
+            last_poll: now,
+        }
+    }
+
+    fn push_entry(&mut self, entry: ConsoleEntry) {
+        if self.transcript.len() == TRANSCRIPT_ENTRIES {
+            self.transcript.pop_front();
+        }
+        self.transcript.push_back(entry);
+    }
+
+    /// Remember a command a plugin sent to the editor
+    pub fn record_command(&mut self, command: &PluginCommand) {
+        let description = format!("{:?}", command).replace('\n', "\\n");
+        let mut summary: String = description.chars().take(COMMAND_SUMMARY_CHARS).collect();
+        if summary.len() < description.len() {
+            summary.push('…');
+        }
+        if self.recent_commands.len() == RECENT_COMMANDS {
+            self.recent_commands.pop_back();
+        }
+        self.recent_commands.push_front(summary);
+        self.commands_changed = true;
+    }
+}
+
+fn modified_time(path: &PathBuf) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn indent(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| format!("{}{}\n", prefix, line))
+        .collect()
+}
+
+impl Editor {
+    /// Open the plugin developer console and start evaluating code
+    ///
+    /// Asks for the plugin to evaluate in first if none was chosen yet.
+    pub(crate) fn open_plugin_console(&mut self) {
+        self.show_plugin_console();
+        if self
+            .plugin_console
+            .as_ref()
+            .is_some_and(|c| c.target.is_some())
+        {
+            self.start_plugin_console_eval();
+        } else {
+            self.start_plugin_console_select();
+        }
+    }
+
+    /// Create the console buffer if needed, show it and bring it up to date
+    fn show_plugin_console(&mut self) {
+        let existing = self
+            .plugin_console
+            .as_ref()
+            .map(|c| c.buffer_id)
+            .filter(|id| self.buffers.contains_key(id));
+        let buffer_id = match existing {
+            Some(buffer_id) => buffer_id,
+            None => {
+                let buffer_id = self.create_virtual_buffer(
+                    PLUGIN_CONSOLE_BUFFER_NAME.to_string(),
+                    "plugin-console".to_string(),
+                    true,
+                );
+                match self.plugin_console.as_mut() {
+                    Some(console) => console.buffer_id = buffer_id,
+                    None => {
+                        self.plugin_console =
+                            Some(PluginConsole::new(buffer_id, self.time_source.now()))
+                    }
+                }
+                buffer_id
+            }
+        };
+        self.set_active_buffer(buffer_id);
+        self.refresh_plugin_console();
+    }
+
+    /// Ask which plugin the console evaluates code in
+    pub(crate) fn start_plugin_console_select(&mut self) {
+        let current = self.plugin_console.as_ref().and_then(|c| c.target.clone());
+        let mut plugins = self.plugin_manager.list_plugins();
+        if plugins.is_empty() {
+            self.set_status_message(t!("plugin_console.no_plugins").to_string());
+            return;
+        }
+        plugins.sort_by(|a, b| a.name.cmp(&b.name));
+        let selected = plugins
+            .iter()
+            .position(|p| Some(&p.name) == current.as_ref())
+            .unwrap_or(0);
+        let suggestions = plugins
+            .into_iter()
+            .map(|plugin| Suggestion {
+                text: plugin.name.clone(),
+                description: Some(plugin.path.display().to_string()),
+                value: Some(plugin.name),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+        self.start_prompt_with_suggestions(
+            t!("plugin_console.select_prompt").to_string(),
+            PromptType::PluginConsoleSelect,
+            suggestions,
+        );
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.selected_suggestion = Some(selected);
+        }
+    }
+
+    /// Make `name` the plugin the console evaluates code in
+    pub(crate) fn select_plugin_console_target(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        if self.plugin_console.is_none() {
+            self.show_plugin_console();
+        }
+        if let Some(console) = self.plugin_console.as_mut() {
+            console.target = Some(name.to_string());
+        }
+        self.refresh_plugin_console();
+        self.start_plugin_console_eval();
+    }
+
+    fn start_plugin_console_eval(&mut self) {
+        let Some(target) = self.plugin_console.as_ref().and_then(|c| c.target.clone()) else {
+            return;
+        };
+        self.start_prompt(
+            t!("plugin_console.eval_prompt", plugin = target).to_string(),
+            PromptType::PluginConsoleEval,
+        );
+    }
+
+    /// Evaluate console input in the target plugin and prompt for more
+    ///
+    /// Confirming an empty line leaves the REPL.
+    pub(crate) fn evaluate_plugin_console_input(&mut self, input: &str) {
+        if input.trim().is_empty() {
+            return;
+        }
+        let Some(target) = self.plugin_console.as_ref().and_then(|c| c.target.clone()) else {
+            return;
+        };
+        let output = self
+            .plugin_manager
+            .evaluate_in_plugin(&target, input)
+            .map_err(|e| e.to_string());
+        if let Some(console) = self.plugin_console.as_mut() {
+            console.push_entry(ConsoleEntry::Eval {
+                plugin: target,
+                input: input.to_string(),
+                output,
+            });
+        }
+        // Show what the snippet did, e.g. commands it sent
+        self.process_plugin_commands();
+        self.refresh_plugin_console();
+        self.start_plugin_console_eval();
+    }
+
+    /// Turn reloading the console's plugin on source changes on or off
+    pub(crate) fn toggle_plugin_console_auto_reload(&mut self) {
+        let Some(target) = self.plugin_console.as_ref().and_then(|c| c.target.clone()) else {
+            self.set_status_message(t!("plugin_console.no_target").to_string());
+            return;
+        };
+        let Some(console) = self.plugin_console.as_mut() else {
+            return;
+        };
+        if console.watched.remove(&target).is_some() {
+            self.set_status_message(
+                t!("plugin_console.auto_reload_off", name = target).to_string(),
+            );
+        } else {
+            let Some(path) = self
+                .plugin_manager
+                .list_plugins()
+                .into_iter()
+                .find(|p| p.name == target)
+                .map(|p| p.path)
+            else {
+                self.set_status_message(t!("plugin_console.no_plugins").to_string());
+                return;
+            };
+            let modified = modified_time(&path);
+            if let Some(console) = self.plugin_console.as_mut() {
+                console
+                    .watched
+                    .insert(target.clone(), WatchedSource { path, modified });
+            }
+            self.set_status_message(t!("plugin_console.auto_reload_on", name = target).to_string());
+        }
+        self.refresh_plugin_console();
+    }
+
+    /// Refresh the console and reload changed plugins (called from main loop)
+    ///
+    /// Returns true if the console buffer was rewritten.
+    pub(crate) fn poll_plugin_console(&mut self) -> bool {
+        let Some(console) = self.plugin_console.as_mut() else {
+            return false;
+        };
+        if (console.watched.is_empty() && !console.commands_changed)
+            || self.time_source.elapsed_since(console.last_poll) < POLL_INTERVAL
+        {
+            return false;
+        }
+        console.last_poll = self.time_source.now();
+
+        let mut changed = Vec::new();
+        for (name, source) in console.watched.iter_mut() {
+            let modified = modified_time(&source.path);
+            if modified.is_some() && modified != source.modified {
+                source.modified = modified;
+                changed.push(name.clone());
+            }
+        }
+        if changed.is_empty() && !console.commands_changed {
+            return false;
+        }
+
+        for name in changed {
+            let result = self
+                .plugin_manager
+                .reload_plugin(&name)
+                .map_err(|e| e.to_string());
+            match &result {
+                Ok(()) => {
+                    self.set_status_message(t!("plugin_console.reloaded", name = name).to_string())
+                }
+                Err(e) => self.set_status_message(
+                    t!("plugin_console.reload_failed", name = name, error = e).to_string(),
+                ),
+            }
+            if let Some(console) = self.plugin_console.as_mut() {
+                console.push_entry(ConsoleEntry::Reload {
+                    plugin: name,
+                    result,
+                });
+            }
+        }
+        self.refresh_plugin_console();
+        true
+    }
+
+    /// Remember a plugin command while the console is open
+    pub(crate) fn record_plugin_console_command(&mut self, command: &PluginCommand) {
+        if let Some(console) = self.plugin_console.as_mut() {
+            if self.buffers.contains_key(&console.buffer_id) {
+                console.record_command(command);
+            }
+        }
+    }
+
+    /// Rewrite the console buffer from the current plugin state
+    pub(crate) fn refresh_plugin_console(&mut self) {
+        let Some(console) = self.plugin_console.as_mut() else {
+            return;
+        };
+        console.commands_changed = false;
+        let buffer_id = console.buffer_id;
+        if !self.buffers.contains_key(&buffer_id) {
+            return;
+        }
+        let Some(console) = self.plugin_console.as_ref() else {
+            return;
+        };
+
+        let mut text = String::new();
+        text.push_str(&format!(
+            "Plugin Console - evaluating in: {}\n\n",
+            console.target.as_deref().unwrap_or("(no plugin selected)")
+        ));
+
+        text.push_str("Console\n");
+        if console.transcript.is_empty() {
+            text.push_str("  (nothing evaluated yet)\n");
+        }
+        for entry in &console.transcript {
+            match entry {
+                ConsoleEntry::Eval {
+                    plugin,
+                    input,
+                    output,
+                } => {
+                    text.push_str(&indent(input, &format!("  {}> ", plugin)));
+                    match output {
+                        Ok(value) => text.push_str(&indent(value, "    ")),
+                        Err(error) => text.push_str(&indent(error, "    ! ")),
+                    }
+                }
+                ConsoleEntry::Reload { plugin, result } => match result {
+                    Ok(()) => text.push_str(&format!("  [reloaded {}]\n", plugin)),
+                    Err(error) => {
+                        text.push_str(&format!("  [reload of {} failed]\n", plugin));
+                        text.push_str(&indent(error, "    ! "));
+                    }
+                },
+            }
+        }
+
+        // Commands and hook handlers, grouped by plugin
+        let mut commands: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for command in self.command_registry.read().unwrap().get_all() {
+            if let CommandSource::Plugin(plugin) = command.source {
+                commands.entry(plugin).or_default().push(command.name);
+            }
+        }
+        let mut hooks: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for handler in self.plugin_manager.list_hook_handlers() {
+            let stats = if handler.calls == 0 {
+                "never called".to_string()
+            } else {
+                format!(
+                    "{} calls, avg {}, max {}",
+                    handler.calls,
+                    format_duration(handler.total_time / handler.calls as u32),
+                    format_duration(handler.max_time)
+                )
+            };
+            hooks.entry(handler.plugin_name).or_default().push(format!(
+                "{} -> {} ({})",
+                handler.hook_name, handler.handler_name, stats
+            ));
+        }
+        let mut plugins = self.plugin_manager.list_plugins();
+        plugins.sort_by(|a, b| a.name.cmp(&b.name));
+
+        text.push_str("\nPlugins\n");
+        for plugin in &plugins {
+            let auto_reload = if console.watched.contains_key(&plugin.name) {
+                " [auto-reload]"
+            } else {
+                ""
+            };
+            text.push_str(&format!(
+                "  {} ({}){}\n",
+                plugin.name,
+                plugin.path.display(),
+                auto_reload
+            ));
+            if let Some(names) = commands.get_mut(&plugin.name) {
+                names.sort();
+                text.push_str(&format!("    commands: {}\n", names.join(", ")));
+            }
+            for hook in hooks.get(&plugin.name).into_iter().flatten() {
+                text.push_str(&format!("    hook {}\n", hook));
+            }
+        }
+
+        text.push_str("\nRecent plugin commands (newest first)\n");
+        if console.recent_commands.is_empty() {
+            text.push_str("  (none)\n");
+        }
+        for command in &console.recent_commands {
+            text.push_str(&format!("  {}\n", command));
+        }
+
+        if let Err(e) =
+            self.set_virtual_buffer_content(buffer_id, vec![TextPropertyEntry::text(text)])
+        {
+            tracing::warn!("Failed to update plugin console: {}", e);
+        }
+    }
+}
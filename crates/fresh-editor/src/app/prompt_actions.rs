@@ -151,6 +151,20 @@ impl Editor {
                     );
                 }
             }
+            PromptType::CompareWithFile => {
+                let expanded_path = expand_tilde(&input);
+                let resolved_path = if expanded_path.is_absolute() {
+                    normalize_path(&expanded_path)
+                } else {
+                    normalize_path(&self.working_dir.join(&expanded_path))
+                };
+                if let Err(e) = self.compare_with_file(&resolved_path) {
+                    self.set_status_message(
+                        t!("file.error_opening", error = e.to_string()).to_string(),
+                    );
+                }
+            }
+            PromptType::SetFollowFilter => self.set_follow_filter(&input),
             PromptType::SetBackgroundBlend => match input.trim().parse::<f32>() {
                 Ok(val) => {
                     let clamped = val.clamp(0.0, 1.0);
@@ -326,6 +340,15 @@ impl Editor {
             } => {
                 self.perform_file_explorer_rename(original_path, original_name, input, is_new_file);
             }
+            PromptType::ConfirmPluginCapabilities {
+                name,
+                path,
+                requested,
+            } => {
+                let input_lower = input.trim().to_lowercase();
+                let allow = input_lower == "y" || input_lower == "yes";
+                self.answer_capability_request(name, path, requested, allow);
+            }
             PromptType::ConfirmDeleteFile { path, is_dir } => {
                 let input_lower = input.trim().to_lowercase();
                 if input_lower == "y" || input_lower == "yes" {
@@ -376,6 +399,16 @@ impl Editor {
             PromptType::ShellCommand { replace } => {
                 self.handle_shell_command(&input, replace);
             }
+            #[cfg(feature = "plugins")]
+            PromptType::PluginConsoleSelect => {
+                self.select_plugin_console_target(&input);
+            }
+            #[cfg(feature = "plugins")]
+            PromptType::PluginConsoleEval => {
+                self.evaluate_plugin_console_input(&input);
+            }
+            #[cfg(not(feature = "plugins"))]
+            PromptType::PluginConsoleSelect | PromptType::PluginConsoleEval => {}
             PromptType::AsyncPrompt => {
                 // Resolve the pending async prompt callback with the input text
                 if let Some(callback_id) = self.pending_async_prompt_callback.take() {
@@ -425,6 +458,10 @@ impl Editor {
 
     /// Perform the actual SaveFileAs operation (called after confirmation if needed).
     pub(crate) fn perform_save_file_as(&mut self, full_path: std::path::PathBuf) {
+        if !self.run_before_save_hook(self.active_buffer(), full_path.clone()) {
+            return;
+        }
+
         let before_idx = self.active_event_log().current_index();
         let before_len = self.active_event_log().len();
         tracing::debug!(
@@ -483,6 +520,7 @@ impl Editor {
                         path: full_path.clone(),
                     },
                 );
+                self.request_provider_diagnostics(self.active_buffer());
 
                 if let Some(buffer_to_close) = self.pending_close_buffer.take() {
                     if let Err(e) = self.force_close_buffer(buffer_to_close) {
@@ -679,6 +717,15 @@ impl Editor {
                     return true; // Early return
                 }
                 self.set_active_buffer(old_active);
+                // A save queued while the remote host is offline, or cancelled
+                // by a plugin, hasn't been written yet; keep the buffer open
+                let unsaved = self
+                    .buffers
+                    .get(&buffer_id)
+                    .is_some_and(|s| s.buffer.is_modified());
+                if unsaved || self.queued_remote_saves.contains(&buffer_id) {
+                    return true;
+                }
                 if let Err(e) = self.force_close_buffer(buffer_id) {
                     self.set_status_message(
                         t!("file.cannot_close", error = e.to_string()).to_string(),
@@ -153,6 +153,25 @@ impl Editor {
             return Ok(0);
         }
 
+        self.save_dirty_buffers_for_recovery(false)
+    }
+
+    /// Save recovery files for all modified buffers right away
+    ///
+    /// Unlike `auto_save_dirty_buffers`, this ignores the auto-save interval and
+    /// per-buffer throttling. Used when in-memory edits are suddenly at risk,
+    /// e.g. when the connection to a remote host drops.
+    pub(crate) fn save_recovery_now(&mut self) -> AnyhowResult<usize> {
+        if !self.recovery_service.is_enabled() {
+            return Ok(0);
+        }
+        self.save_dirty_buffers_for_recovery(true)
+    }
+
+    /// Write recovery files for buffers with pending recovery changes
+    ///
+    /// With `force`, buffers are saved even if they were saved recently.
+    fn save_dirty_buffers_for_recovery(&mut self, force: bool) -> AnyhowResult<usize> {
         // Collect buffer IDs that need recovery first (immutable pass)
         // Skip composite buffers and hidden buffers (they should not be saved for recovery)
         let buffers_needing_recovery: Vec<_> = self
@@ -217,9 +236,10 @@ impl Editor {
 
                 // Only save if enough time has passed since last recovery save
                 let recovery_pending = state.buffer.is_recovery_pending();
-                if self
-                    .recovery_service
-                    .needs_auto_save(&recovery_id, recovery_pending)
+                if force
+                    || self
+                        .recovery_service
+                        .needs_auto_save(&recovery_id, recovery_pending)
                 {
                     Some((buffer_id, recovery_id, path))
                 } else {
@@ -0,0 +1,139 @@
+//! Remote connection handling for the Editor.
+//!
+//! During SSH editing the link to the remote host can drop (suspended laptop,
+//! VPN hiccup). The connection reconnects on its own in the background; this
+//! module reacts to those transitions:
+//! - Showing the connection state in the status bar
+//! - Snapshotting unsaved remote buffers to recovery files when the link drops
+//! - Queuing saves made while offline
+//! - Flushing queued saves after reconnecting, unless the remote file changed
+
+use rust_i18n::t;
+
+use super::{Editor, SaveOutcome};
+
+impl Editor {
+    /// Remote connection text for the status bar
+    ///
+    /// Returns `Some("user@host")` while connected, with an offline marker
+    /// appended while the link is down, and `None` for local editing.
+    pub fn remote_connection_status(&self) -> Option<String> {
+        let info = self.filesystem.remote_connection_info()?;
+        if self.filesystem.is_remote_connected() {
+            Some(info.to_string())
+        } else {
+            Some(format!("{} ({})", info, t!("remote.offline")))
+        }
+    }
+
+    /// Poll the remote connection for drops and reconnects (called from main loop)
+    ///
+    /// Returns true if the connection state changed (requires re-render).
+    pub fn poll_remote_connection(&mut self) -> bool {
+        let Some(host) = self.filesystem.remote_connection_info().map(str::to_string) else {
+            return false;
+        };
+
+        let connected = self.filesystem.is_remote_connected();
+        if connected == self.remote_connected {
+            return false;
+        }
+        self.remote_connected = connected;
+
+        if connected {
+            tracing::info!("Remote connection to {} restored", host);
+            self.flush_queued_remote_saves(&host);
+        } else {
+            tracing::warn!("Remote connection to {} lost", host);
+            self.set_status_message(t!("remote.connection_lost", host = &host).to_string());
+            // Unsaved remote edits now only exist in memory; persist them
+            // right away instead of waiting for the auto-save interval.
+            if let Err(e) = self.save_recovery_now() {
+                tracing::warn!("Failed to save recovery files after disconnect: {}", e);
+            }
+        }
+        true
+    }
+
+    /// Queue a save of the active buffer if it failed because the remote
+    /// host is unreachable
+    ///
+    /// Returns true if the save was queued (the caller should treat it as
+    /// handled), false if the failure is unrelated to the connection.
+    pub(crate) fn queue_remote_save_if_offline(&mut self) -> bool {
+        if self.filesystem.remote_connection_info().is_none()
+            || self.filesystem.is_remote_connected()
+        {
+            return false;
+        }
+
+        let buffer_id = self.active_buffer();
+        if !self.queued_remote_saves.contains(&buffer_id) {
+            self.queued_remote_saves.push(buffer_id);
+        }
+        let path = self
+            .active_state()
+            .buffer
+            .file_path()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        self.set_status_message(t!("remote.save_queued", path = path).to_string());
+        true
+    }
+
+    /// Write the saves queued while offline
+    ///
+    /// A buffer whose file changed on the remote host in the meantime is not
+    /// overwritten; the next manual save goes through the usual conflict prompt.
+    fn flush_queued_remote_saves(&mut self, host: &str) {
+        let queued = std::mem::take(&mut self.queued_remote_saves);
+        let original_active = self.active_buffer();
+        let mut saved = 0;
+        let mut conflict = None;
+        let mut failure = None;
+
+        for buffer_id in queued {
+            let Some(state) = self.buffers.get(&buffer_id) else {
+                continue;
+            };
+            // Nothing left to write (reverted or saved elsewhere)
+            if !state.buffer.is_modified() {
+                continue;
+            }
+            let Some(path) = state.buffer.file_path().map(|p| p.display().to_string()) else {
+                continue;
+            };
+
+            self.set_active_buffer(buffer_id);
+            if self.check_save_conflict().is_some() {
+                tracing::warn!("Not writing queued save of {}: changed remotely", path);
+                conflict = Some(path);
+                continue;
+            }
+            match self.save_with_outcome() {
+                Ok(SaveOutcome::Saved) => saved += 1,
+                // Re-queued by save() if the link dropped again
+                Ok(SaveOutcome::Queued | SaveOutcome::NotSaved) => {}
+                Err(e) => {
+                    tracing::warn!("Queued save of {} failed: {}", path, e);
+                    failure = Some(e.to_string());
+                }
+            }
+        }
+
+        if self.buffers.contains_key(&original_active) {
+            self.set_active_buffer(original_active);
+        }
+
+        let message = if let Some(path) = conflict {
+            t!("remote.save_conflict", path = path)
+        } else if let Some(error) = failure {
+            t!("file.save_failed", error = error)
+        } else if saved > 0 {
+            t!("remote.reconnected_saved", host = host, count = saved)
+        } else {
+            t!("remote.reconnected", host = host)
+        };
+        self.set_status_message(message.to_string());
+    }
+}
@@ -448,6 +448,10 @@ impl Editor {
             );
         }
 
+        if self.plugin_manager.is_active() {
+            self.check_selection_changed();
+        }
+
         // Render terminal content on top of split content for terminal buffers
         self.render_terminal_splits(frame, &split_areas);
 
@@ -548,6 +552,7 @@ impl Editor {
         let theme = self.theme.clone();
         let keybindings_cloned = self.keybindings.clone(); // Clone the keybindings
         let chord_state_cloned = self.chord_state.clone(); // Clone the chord state
+        let status_bar_items = self.status_bar_items.clone();
 
         // Get update availability info
         let update_available = self.latest_version().map(|v| v.to_string());
@@ -577,8 +582,8 @@ impl Editor {
                 _ => StatusBarHover::None,
             };
 
-            // Get remote connection info if editing remote files
-            let remote_connection = self.remote_connection_info().map(|s| s.to_string());
+            // Get remote connection info (and offline state) if editing remote files
+            let remote_connection = self.remote_connection_status();
 
             let status_bar_layout = StatusBarRenderer::render_status_bar(
                 frame,
@@ -596,6 +601,7 @@ impl Editor {
                 general_warning_count,        // Pass general warning count for badge
                 status_bar_hover,             // Pass hover state for indicator styling
                 remote_connection.as_deref(), // Pass remote connection info
+                &status_bar_items,
             );
 
             // Store status bar layout for click detection
@@ -608,6 +614,7 @@ impl Editor {
                 status_bar_layout.line_ending_indicator;
             self.cached_layout.status_bar_language_area = status_bar_layout.language_indicator;
             self.cached_layout.status_bar_message_area = status_bar_layout.message_area;
+            self.cached_layout.status_bar_plugin_item_areas = status_bar_layout.plugin_items;
         }
 
         // Render search options bar when in search prompt
@@ -838,6 +845,14 @@ impl Editor {
             );
         }
 
+        // Render plugin form dialog if open
+        if let Some(ref dialog) = self.form_dialog {
+            crate::view::dimming::apply_dimming(frame, size);
+            self.cached_layout.form_dialog_layout = Some(
+                crate::view::form_dialog::render_form_dialog(frame, size, dialog, &self.theme),
+            );
+        }
+
         if self.menu_bar_visible {
             self.cached_layout.menu_layout = Some(crate::view::ui::MenuRenderer::render(
                 frame,
@@ -1952,13 +1967,101 @@ impl Editor {
             .map(|vs| vs.viewport.height)
             .unwrap_or(24);
 
-        convert_action_to_events(
+        let events = convert_action_to_events(
             self.active_state_mut(),
             action,
             tab_size,
             auto_indent,
             estimated_line_length,
             viewport_height,
+        )?;
+        self.run_before_edit_hooks(events)
+    }
+
+    /// Let plugins cancel or rewrite the edits an action is about to make
+    ///
+    /// Runs the `before_insert` and `before_delete` hooks for each insertion
+    /// and deletion, all in one request so the hook timeout applies to the
+    /// action as a whole. A cancelled edit drops the whole action. When a
+    /// plugin replaces the inserted text, the cursor moves planned for the
+    /// original text are dropped, leaving that cursor after the new text.
+    fn run_before_edit_hooks(&mut self, mut events: Vec<Event>) -> Option<Vec<Event>> {
+        use crate::services::plugins::hooks::HookArgs;
+
+        let hook_inserts = self
+            .plugin_manager
+            .handles_cancellable_hook("before_insert");
+        let hook_deletes = self
+            .plugin_manager
+            .handles_cancellable_hook("before_delete");
+        if !hook_inserts && !hook_deletes {
+            return Some(events);
+        }
+
+        let buffer_id = self.active_buffer();
+        // Indexes of the events the hooks are run for
+        let mut edits = Vec::new();
+        let mut hooks = Vec::new();
+        for (index, event) in events.iter().enumerate() {
+            let (hook_name, args) = match event {
+                Event::Insert { position, text, .. } if hook_inserts => (
+                    "before_insert",
+                    HookArgs::BeforeInsert {
+                        buffer_id,
+                        position: *position,
+                        text: text.clone(),
+                    },
+                ),
+                Event::Delete { range, .. } if hook_deletes => (
+                    "before_delete",
+                    HookArgs::BeforeDelete {
+                        buffer_id,
+                        range: range.clone(),
+                    },
+                ),
+                _ => continue,
+            };
+            edits.push(index);
+            hooks.push((hook_name.to_string(), args));
+        }
+        if hooks.is_empty() {
+            return Some(events);
+        }
+
+        let outcomes = self.plugin_manager.run_cancellable_hooks(hooks);
+        if outcomes.iter().any(|outcome| outcome.is_cancelled()) {
+            return None;
+        }
+        // (index of the rewritten insert, its cursor)
+        let mut rewritten = Vec::new();
+        for (index, outcome) in edits.into_iter().zip(&outcomes) {
+            if let (
+                Event::Insert {
+                    text, cursor_id, ..
+                },
+                Some(new_text),
+            ) = (&mut events[index], outcome.replacement("text"))
+            {
+                if new_text != text {
+                    *text = new_text.to_string();
+                    rewritten.push((index, *cursor_id));
+                }
+            }
+        }
+
+        if rewritten.is_empty() {
+            return Some(events);
+        }
+        Some(
+            events
+                .into_iter()
+                .enumerate()
+                .filter(|(index, event)| {
+                    !matches!(event, Event::MoveCursor { cursor_id, .. }
+                        if rewritten.iter().any(|(at, c)| at < index && c == cursor_id))
+                })
+                .map(|(_, event)| event)
+                .collect(),
         )
     }
 
@@ -393,7 +393,8 @@ impl Editor {
             if let Some(handle) = self.terminal_manager.get(terminal_id) {
                 if let Ok(state) = handle.state.lock() {
                     // Append visible screen to backing file
-                    if let Ok(mut file) = self.filesystem.open_file_for_append(&backing_path) {
+                    if let Ok(mut file) = self.local_filesystem.open_file_for_append(&backing_path)
+                    {
                         let mut writer = BufWriter::new(&mut *file);
                         if let Err(e) = state.append_visible_screen(&mut writer) {
                             tracing::warn!(
@@ -647,7 +648,7 @@ impl Editor {
             terminals_root.join(&terminal.backing_path)
         };
 
-        let _ = self.filesystem.create_dir_all(
+        let _ = self.local_filesystem.create_dir_all(
             log_path
                 .parent()
                 .or_else(|| backing_path.parent())
@@ -718,7 +719,7 @@ impl Editor {
             large_file_threshold,
             &self.grammar_registry,
             &self.config.languages,
-            std::sync::Arc::clone(&self.filesystem),
+            std::sync::Arc::clone(&self.local_filesystem),
         ) {
             if let Some(state) = self.buffers.get_mut(&buffer_id) {
                 *state = new_state;
@@ -538,9 +538,18 @@ impl Editor {
             .plugins
             .iter()
             .filter_map(|(name, new_config)| {
-                let was_enabled = old_plugins.get(name).map(|c| c.enabled).unwrap_or(true);
-                if new_config.enabled != was_enabled {
-                    Some((name.clone(), new_config.enabled, new_config.path.clone()))
+                let old_config = old_plugins.get(name);
+                let was_enabled = old_config.map(|c| c.enabled).unwrap_or(true);
+                let grant_changed = old_config
+                    .map(|c| c.granted != new_config.granted)
+                    .unwrap_or(false);
+                if new_config.enabled != was_enabled || (new_config.enabled && grant_changed) {
+                    Some((
+                        name.clone(),
+                        new_config.enabled,
+                        new_config.path.clone(),
+                        grant_changed.then(|| new_config.granted.clone()),
+                    ))
                 } else {
                     None
                 }
@@ -548,11 +557,17 @@ impl Editor {
             .collect();
 
         // Apply changes
-        for (name, now_enabled, path) in changes {
+        for (name, now_enabled, path, new_grant) in changes {
+            if let Some(granted) = new_grant {
+                self.plugin_manager.set_capability_grant(&name, granted);
+            }
             if now_enabled {
-                // Plugin was disabled, now enabled - load it
+                // Plugin was disabled, now enabled - load it. A plugin whose
+                // grant changed is reloaded so it is held to the new grant (and
+                // asks for consent again if the grant no longer covers it).
                 if let Some(ref path) = path {
-                    tracing::info!("Loading newly enabled plugin: {}", name);
+                    tracing::info!("Loading plugin with updated settings: {}", name);
+                    let _ = self.plugin_manager.unload_plugin(&name);
                     if let Err(e) = self.plugin_manager.load_plugin(path) {
                         tracing::error!("Failed to load plugin '{}': {}", name, e);
                         self.set_status_message(format!("Failed to load plugin '{}': {}", name, e));
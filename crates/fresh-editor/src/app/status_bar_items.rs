@@ -0,0 +1,186 @@
+//! Status bar items contributed by plugins
+//!
+//! Plugins keep persistent segments in the status bar (a git branch, a test
+//! summary, a timer). Each item belongs to the plugin that set it and its id
+//! is scoped to that plugin. The status bar renders the items next to its
+//! built-in indicators and clicks on them are reported through the
+//! `status_bar_item_clicked` hook.
+
+use fresh_core::api::StatusBarItemOptions;
+
+/// Which side of the status bar an item is drawn on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusBarAlignment {
+    /// After the file name and cursor position
+    Left,
+    /// Before the line ending and language indicators
+    #[default]
+    Right,
+}
+
+impl StatusBarAlignment {
+    /// Parse the alignment name used by the plugin API
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
+
+/// A status bar item set by a plugin
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusBarItem {
+    /// Plugin that owns the item
+    pub plugin_name: String,
+    /// Item id, unique within the plugin
+    pub id: String,
+    /// Text to show
+    pub text: String,
+    /// Theme key for the text colour
+    pub fg: Option<String>,
+    /// Theme key for the background colour
+    pub bg: Option<String>,
+    /// Side of the status bar the item is drawn on
+    pub alignment: StatusBarAlignment,
+    /// Higher priority items are drawn first on their side
+    pub priority: i32,
+}
+
+/// All plugin status bar items, in the order they were first set
+#[derive(Debug, Clone, Default)]
+pub struct StatusBarItemRegistry {
+    items: Vec<StatusBarItem>,
+}
+
+impl StatusBarItemRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item, or replace the plugin's item with the same id in place
+    pub fn set(&mut self, plugin_name: String, id: String, options: StatusBarItemOptions) {
+        let item = StatusBarItem {
+            alignment: options
+                .alignment
+                .as_deref()
+                .and_then(StatusBarAlignment::parse)
+                .unwrap_or_default(),
+            priority: options.priority.unwrap_or(0),
+            text: options.text,
+            fg: options.fg,
+            bg: options.bg,
+            plugin_name,
+            id,
+        };
+        match self.find_mut(&item.plugin_name, &item.id) {
+            Some(existing) => *existing = item,
+            None => self.items.push(item),
+        }
+    }
+
+    /// Change an item's text. Returns false if the plugin has no such item.
+    pub fn set_text(&mut self, plugin_name: &str, id: &str, text: String) -> bool {
+        match self.find_mut(plugin_name, id) {
+            Some(item) => {
+                item.text = text;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove one of a plugin's items
+    pub fn remove(&mut self, plugin_name: &str, id: &str) {
+        self.items
+            .retain(|item| !(item.plugin_name == plugin_name && item.id == id));
+    }
+
+    /// Remove all of a plugin's items
+    pub fn remove_plugin(&mut self, plugin_name: &str) {
+        self.items.retain(|item| item.plugin_name != plugin_name);
+    }
+
+    /// Non-empty items on one side, highest priority first
+    pub fn items(&self, alignment: StatusBarAlignment) -> Vec<&StatusBarItem> {
+        let mut items: Vec<&StatusBarItem> = self
+            .items
+            .iter()
+            .filter(|item| item.alignment == alignment && !item.text.is_empty())
+            .collect();
+        // Stable sort keeps insertion order between equal priorities
+        items.sort_by_key(|item| std::cmp::Reverse(item.priority));
+        items
+    }
+
+    fn find_mut(&mut self, plugin_name: &str, id: &str) -> Option<&mut StatusBarItem> {
+        self.items
+            .iter_mut()
+            .find(|item| item.plugin_name == plugin_name && item.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(text: &str, alignment: &str, priority: i32) -> StatusBarItemOptions {
+        StatusBarItemOptions {
+            text: text.to_string(),
+            alignment: Some(alignment.to_string()),
+            priority: Some(priority),
+            ..Default::default()
+        }
+    }
+
+    fn texts(registry: &StatusBarItemRegistry, alignment: StatusBarAlignment) -> Vec<&str> {
+        registry
+            .items(alignment)
+            .iter()
+            .map(|item| item.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_items_ordered_by_priority_per_side() {
+        let mut registry = StatusBarItemRegistry::new();
+        registry.set("git".into(), "branch".into(), options("main", "left", 0));
+        registry.set(
+            "tests".into(),
+            "summary".into(),
+            options("3 failed", "right", 1),
+        );
+        registry.set(
+            "pomodoro".into(),
+            "timer".into(),
+            options("12:00", "right", 5),
+        );
+        registry.set("clock".into(), "time".into(), options("09:30", "right", 1));
+
+        assert_eq!(texts(&registry, StatusBarAlignment::Left), vec!["main"]);
+        assert_eq!(
+            texts(&registry, StatusBarAlignment::Right),
+            vec!["12:00", "3 failed", "09:30"]
+        );
+    }
+
+    #[test]
+    fn test_ids_are_scoped_to_plugins() {
+        let mut registry = StatusBarItemRegistry::new();
+        registry.set("a".into(), "status".into(), options("from a", "right", 0));
+        registry.set("b".into(), "status".into(), options("from b", "right", 0));
+
+        assert!(registry.set_text("a", "status", "updated".into()));
+        assert!(!registry.set_text("c", "status", "nobody".into()));
+        assert_eq!(
+            texts(&registry, StatusBarAlignment::Right),
+            vec!["updated", "from b"]
+        );
+
+        registry.remove_plugin("a");
+        assert_eq!(texts(&registry, StatusBarAlignment::Right), vec!["from b"]);
+        registry.remove("b", "status");
+        assert!(registry.items(StatusBarAlignment::Right).is_empty());
+    }
+}
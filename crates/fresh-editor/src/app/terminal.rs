@@ -24,60 +24,15 @@
 //!   - Performance: O(1) ≈ 1ms
 
 use super::{BufferId, BufferMetadata, Editor};
-use crate::services::terminal::TerminalId;
+use crate::services::terminal::{TerminalId, TerminalLaunch};
 use crate::state::EditorState;
 use rust_i18n::t;
 
 impl Editor {
     /// Open a new terminal in the current split
     pub fn open_terminal(&mut self) {
-        // Get the current split dimensions for the terminal size
-        let (cols, rows) = self.get_terminal_dimensions();
-
-        // Set up async bridge for terminal manager if not already done
-        if let Some(ref bridge) = self.async_bridge {
-            self.terminal_manager.set_async_bridge(bridge.clone());
-        }
-
-        // Prepare persistent storage paths under the user's data directory
-        let terminal_root = self.dir_context.terminal_dir_for(&self.working_dir);
-        let _ = self.filesystem.create_dir_all(&terminal_root);
-        // Precompute paths using the next terminal ID so we capture from the first byte
-        let predicted_terminal_id = self.terminal_manager.next_terminal_id();
-        let log_path =
-            terminal_root.join(format!("fresh-terminal-{}.log", predicted_terminal_id.0));
-        let backing_path =
-            terminal_root.join(format!("fresh-terminal-{}.txt", predicted_terminal_id.0));
-        // Stash backing path now so buffer creation can reuse it
-        self.terminal_backing_files
-            .insert(predicted_terminal_id, backing_path);
-
-        // Spawn terminal with incremental scrollback streaming
-        let backing_path_for_spawn = self
-            .terminal_backing_files
-            .get(&predicted_terminal_id)
-            .cloned();
-        match self.terminal_manager.spawn(
-            cols,
-            rows,
-            Some(self.working_dir.clone()),
-            Some(log_path.clone()),
-            backing_path_for_spawn,
-        ) {
+        match self.spawn_terminal_process(self.working_dir.clone(), &TerminalLaunch::default()) {
             Ok(terminal_id) => {
-                // Track log file path (use actual ID in case it differs)
-                let actual_log_path = log_path.clone();
-                self.terminal_log_files
-                    .insert(terminal_id, actual_log_path.clone());
-                // If predicted differs, move backing path entry
-                if terminal_id != predicted_terminal_id {
-                    self.terminal_backing_files.remove(&predicted_terminal_id);
-                    let backing_path =
-                        terminal_root.join(format!("fresh-terminal-{}.txt", terminal_id.0));
-                    self.terminal_backing_files
-                        .insert(terminal_id, backing_path);
-                }
-
                 // Create a buffer for this terminal
                 let buffer_id = self.create_terminal_buffer_attached(
                     terminal_id,
@@ -120,8 +75,63 @@ impl Editor {
         }
     }
 
+    /// Spawn a terminal process with persistent log and backing files
+    ///
+    /// The caller creates the buffer that shows it.
+    pub(crate) fn spawn_terminal_process(
+        &mut self,
+        cwd: std::path::PathBuf,
+        launch: &TerminalLaunch,
+    ) -> Result<TerminalId, String> {
+        // Get the current split dimensions for the terminal size
+        let (cols, rows) = self.get_terminal_dimensions();
+
+        // Set up async bridge for terminal manager if not already done
+        if let Some(ref bridge) = self.async_bridge {
+            self.terminal_manager.set_async_bridge(bridge.clone());
+        }
+
+        // Prepare persistent storage paths under the user's data directory
+        let terminal_root = self.dir_context.terminal_dir_for(&self.working_dir);
+        let _ = self.local_filesystem.create_dir_all(&terminal_root);
+        // Precompute paths using the next terminal ID so we capture from the first byte
+        let predicted_terminal_id = self.terminal_manager.next_terminal_id();
+        let log_path =
+            terminal_root.join(format!("fresh-terminal-{}.log", predicted_terminal_id.0));
+        let backing_path =
+            terminal_root.join(format!("fresh-terminal-{}.txt", predicted_terminal_id.0));
+        // Stash backing path now so buffer creation can reuse it
+        self.terminal_backing_files
+            .insert(predicted_terminal_id, backing_path);
+
+        // Spawn terminal with incremental scrollback streaming
+        let backing_path_for_spawn = self
+            .terminal_backing_files
+            .get(&predicted_terminal_id)
+            .cloned();
+        let terminal_id = self.terminal_manager.spawn_with(
+            cols,
+            rows,
+            Some(cwd),
+            launch,
+            Some(log_path.clone()),
+            backing_path_for_spawn,
+        )?;
+
+        // Track log file path (use actual ID in case it differs)
+        self.terminal_log_files.insert(terminal_id, log_path);
+        // If predicted differs, move backing path entry
+        if terminal_id != predicted_terminal_id {
+            self.terminal_backing_files.remove(&predicted_terminal_id);
+            let backing_path = terminal_root.join(format!("fresh-terminal-{}.txt", terminal_id.0));
+            self.terminal_backing_files
+                .insert(terminal_id, backing_path);
+        }
+        Ok(terminal_id)
+    }
+
     /// Create a buffer for a terminal session
-    fn create_terminal_buffer_attached(
+    pub(crate) fn create_terminal_buffer_attached(
         &mut self,
         terminal_id: TerminalId,
         split_id: crate::model::event::SplitId,
@@ -139,14 +149,14 @@ impl Editor {
             .cloned()
             .unwrap_or_else(|| {
                 let root = self.dir_context.terminal_dir_for(&self.working_dir);
-                let _ = self.filesystem.create_dir_all(&root);
+                let _ = self.local_filesystem.create_dir_all(&root);
                 root.join(format!("fresh-terminal-{}.txt", terminal_id.0))
             });
 
         // Ensure the file exists - but DON'T truncate if it already has content
         // The PTY read loop may have already started writing scrollback
-        if !self.filesystem.exists(&backing_file) {
-            if let Err(e) = self.filesystem.write_file(&backing_file, &[]) {
+        if !self.local_filesystem.exists(&backing_file) {
+            if let Err(e) = self.local_filesystem.write_file(&backing_file, &[]) {
                 tracing::warn!("Failed to create terminal backing file: {}", e);
             }
         }
@@ -160,7 +170,7 @@ impl Editor {
             self.terminal_width,
             self.terminal_height,
             large_file_threshold,
-            std::sync::Arc::clone(&self.filesystem),
+            std::sync::Arc::clone(&self.local_filesystem),
         );
         state.buffer.set_file_path(backing_file.clone());
         // Terminal buffers should never show line numbers
@@ -207,13 +217,13 @@ impl Editor {
             .cloned()
             .unwrap_or_else(|| {
                 let root = self.dir_context.terminal_dir_for(&self.working_dir);
-                let _ = self.filesystem.create_dir_all(&root);
+                let _ = self.local_filesystem.create_dir_all(&root);
                 root.join(format!("fresh-terminal-{}.txt", terminal_id.0))
             });
 
         // Create the file only if it doesn't exist (preserve existing scrollback for restore)
-        if !self.filesystem.exists(&backing_file) {
-            if let Err(e) = self.filesystem.write_file(&backing_file, &[]) {
+        if !self.local_filesystem.exists(&backing_file) {
+            if let Err(e) = self.local_filesystem.write_file(&backing_file, &[]) {
                 tracing::warn!("Failed to create terminal backing file: {}", e);
             }
         }
@@ -223,7 +233,7 @@ impl Editor {
             self.terminal_width,
             self.terminal_height,
             large_file_threshold,
-            std::sync::Arc::clone(&self.filesystem),
+            std::sync::Arc::clone(&self.local_filesystem),
         );
         state.buffer.set_file_path(backing_file.clone());
         state.margins.set_line_numbers(false);
@@ -246,36 +256,92 @@ impl Editor {
     pub fn close_terminal(&mut self) {
         let buffer_id = self.active_buffer();
 
-        if let Some(&terminal_id) = self.terminal_buffers.get(&buffer_id) {
-            // Close the terminal
-            self.terminal_manager.close(terminal_id);
-            self.terminal_buffers.remove(&buffer_id);
-
-            // Clean up backing/rendering file
-            let backing_file = self.terminal_backing_files.remove(&terminal_id);
-            if let Some(ref path) = backing_file {
-                let _ = self.filesystem.remove_file(path);
-            }
-            // Clean up raw log file
-            if let Some(log_file) = self.terminal_log_files.remove(&terminal_id) {
-                if backing_file.as_ref() != Some(&log_file) {
-                    let _ = self.filesystem.remove_file(&log_file);
-                }
+        if let Some(terminal_id) = self.close_terminal_buffer(buffer_id) {
+            self.set_status_message(t!("terminal.closed", id = terminal_id.0).to_string());
+        } else {
+            self.set_status_message(t!("status.not_viewing_terminal").to_string());
+        }
+    }
+
+    /// Stop a terminal buffer's process and close the buffer
+    ///
+    /// Returns the terminal's ID, or None if the buffer is not a terminal.
+    pub(crate) fn close_terminal_buffer(&mut self, buffer_id: BufferId) -> Option<TerminalId> {
+        let terminal_id = self.terminal_buffers.remove(&buffer_id)?;
+
+        // Close the terminal
+        self.terminal_manager.close(terminal_id);
+        self.terminal_output_watches.remove(&terminal_id);
+        self.terminal_owners.remove(&terminal_id);
+
+        // Clean up backing/rendering file
+        let backing_file = self.terminal_backing_files.remove(&terminal_id);
+        if let Some(ref path) = backing_file {
+            let _ = self.local_filesystem.remove_file(path);
+        }
+        // Clean up raw log file
+        if let Some(log_file) = self.terminal_log_files.remove(&terminal_id) {
+            if backing_file.as_ref() != Some(&log_file) {
+                let _ = self.local_filesystem.remove_file(&log_file);
             }
+        }
 
-            // Exit terminal mode
+        // Exit terminal mode
+        if self.active_buffer() == buffer_id {
             self.terminal_mode = false;
             self.key_context = crate::input::keybindings::KeyContext::Normal;
+        }
 
-            // Close the buffer
-            let _ = self.close_buffer(buffer_id);
+        // Close the buffer
+        let _ = self.close_buffer(buffer_id);
 
-            self.set_status_message(t!("terminal.closed", id = terminal_id.0).to_string());
-        } else {
-            self.set_status_message(t!("status.not_viewing_terminal").to_string());
+        Some(terminal_id)
+    }
+
+    /// Run the plugin watches on a terminal's new output, firing
+    /// `terminal_output_matched` for each matching line in the plugin that
+    /// opened the terminal
+    pub(crate) fn check_terminal_output_watches(&mut self, terminal_id: TerminalId) {
+        let Some(watch) = self.terminal_output_watches.get_mut(&terminal_id) else {
+            return;
+        };
+        let Some(owner) = self.terminal_owners.get(&terminal_id).cloned() else {
+            return;
+        };
+        let Some(handle) = self.terminal_manager.get(terminal_id) else {
+            return;
+        };
+        let output = match handle.state.lock() {
+            Ok(mut state) => state.take_captured_output(),
+            Err(_) => return,
+        };
+        let text = crate::primitives::ansi::strip_ansi_codes(&String::from_utf8_lossy(&output));
+        let matches = watch.feed(&text);
+        let Some(buffer_id) = self.buffer_for_terminal(terminal_id) else {
+            return;
+        };
+        for (pattern, line) in matches {
+            self.plugin_manager.run_plugin_hook(
+                &owner,
+                "terminal_output_matched",
+                crate::services::plugins::hooks::HookArgs::TerminalOutputMatched {
+                    terminal_id,
+                    buffer_id,
+                    pattern,
+                    line,
+                },
+            );
         }
     }
 
+    /// Get the buffer showing a terminal
+    pub(crate) fn buffer_for_terminal(&self, terminal_id: TerminalId) -> Option<BufferId> {
+        self.terminal_buffers
+            .iter()
+            .find(|(_, &tid)| tid == terminal_id)
+            .map(|(&buffer_id, _)| buffer_id)
+    }
+
     /// Check if a buffer is a terminal buffer
     pub fn is_terminal_buffer(&self, buffer_id: BufferId) -> bool {
         self.terminal_buffers.contains_key(&buffer_id)
@@ -488,12 +554,13 @@ impl Editor {
                 if let Ok(mut state) = handle.state.lock() {
                     // Record the current file size as the history end point
                     // (before appending visible screen) so we can truncate back to it
-                    if let Ok(metadata) = self.filesystem.metadata(&backing_file) {
+                    if let Ok(metadata) = self.local_filesystem.metadata(&backing_file) {
                         state.set_backing_file_history_end(metadata.size);
                     }
 
                     // Open backing file in append mode to add visible screen
-                    if let Ok(mut file) = self.filesystem.open_file_for_append(&backing_file) {
+                    if let Ok(mut file) = self.local_filesystem.open_file_for_append(&backing_file)
+                    {
                         use std::io::BufWriter;
                         let mut writer = BufWriter::new(&mut *file);
                         if let Err(e) = state.append_visible_screen(&mut writer) {
@@ -515,7 +582,7 @@ impl Editor {
                 large_file_threshold,
                 &self.grammar_registry,
                 &self.config.languages,
-                std::sync::Arc::clone(&self.filesystem),
+                std::sync::Arc::clone(&self.local_filesystem),
             ) {
                 // Replace buffer state
                 if let Some(state) = self.buffers.get_mut(&buffer_id) {
@@ -588,8 +655,9 @@ impl Editor {
                             let truncate_pos = state.backing_file_history_end();
                             // Always truncate to remove appended visible screen
                             // (even if truncate_pos is 0, meaning no scrollback yet)
-                            if let Err(e) =
-                                self.filesystem.set_file_length(backing_path, truncate_pos)
+                            if let Err(e) = self
+                                .local_filesystem
+                                .set_file_length(backing_path, truncate_pos)
                             {
                                 tracing::warn!("Failed to truncate terminal backing file: {}", e);
                             }
@@ -749,6 +817,92 @@ impl Editor {
     }
 }
 
+/// Longest unterminated line kept for matching; longer lines keep their end
+const MAX_WATCHED_LINE_BYTES: usize = 4096;
+
+/// Regex patterns a plugin is watching for in a terminal's output
+#[derive(Default)]
+pub(crate) struct TerminalOutputWatch {
+    /// Patterns as registered, with their compiled form
+    patterns: Vec<(String, regex::Regex)>,
+    /// Output after the last newline, not yet a complete line
+    partial: String,
+    /// Indices of the patterns already reported for `partial`
+    partial_matched: Vec<usize>,
+    /// The output so far ended with a carriage return, so the next text
+    /// redraws the line unless it is a newline
+    carriage_return: bool,
+}
+
+impl TerminalOutputWatch {
+    /// Add a pattern to watch for
+    pub(crate) fn add_pattern(&mut self, pattern: String) -> Result<(), regex::Error> {
+        let regex = regex::Regex::new(&pattern)?;
+        self.patterns.push((pattern, regex));
+        Ok(())
+    }
+
+    /// Feed plain-text output and return `(pattern, line)` for each match
+    ///
+    /// The unterminated last line is matched too, so prompts that wait for
+    /// input are seen; a pattern is reported once per line either way. Only
+    /// lines that received new output are matched.
+    pub(crate) fn feed(&mut self, text: &str) -> Vec<(String, String)> {
+        let mut matches = Vec::new();
+        let mut rest = text;
+        while let Some(newline) = rest.find('\n') {
+            self.push(&rest[..newline]);
+            rest = &rest[newline + 1..];
+            self.carriage_return = false;
+            self.match_partial(&mut matches);
+            self.partial.clear();
+            self.partial_matched.clear();
+        }
+        self.push(rest);
+        if !rest.is_empty() && !self.partial.is_empty() {
+            self.match_partial(&mut matches);
+        }
+        matches
+    }
+
+    /// Add text without newlines to the current line
+    ///
+    /// A carriage return starts the line over, as progress output redraws
+    /// it, and the line is capped so such output cannot grow it unbounded.
+    fn push(&mut self, text: &str) {
+        for (index, part) in text.split('\r').enumerate() {
+            if index > 0 {
+                self.carriage_return = true;
+            }
+            if part.is_empty() {
+                continue;
+            }
+            if self.carriage_return {
+                self.carriage_return = false;
+                self.partial.clear();
+                self.partial_matched.clear();
+            }
+            self.partial.push_str(part);
+        }
+        if self.partial.len() > MAX_WATCHED_LINE_BYTES {
+            let mut cut = self.partial.len() - MAX_WATCHED_LINE_BYTES;
+            while !self.partial.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.partial.drain(..cut);
+        }
+    }
+
+    fn match_partial(&mut self, matches: &mut Vec<(String, String)>) {
+        for (index, (pattern, regex)) in self.patterns.iter().enumerate() {
+            if !self.partial_matched.contains(&index) && regex.is_match(&self.partial) {
+                self.partial_matched.push(index);
+                matches.push((pattern.clone(), self.partial.clone()));
+            }
+        }
+    }
+}
+
 /// Terminal rendering utilities
 pub mod render {
     use crate::services::terminal::TerminalCell;
@@ -930,3 +1084,53 @@ fn encode_x10_mouse(
 
     Some(vec![0x1b, b'[', b'M', cb, cx, cy])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_watch_matches_lines_once() {
+        let mut watch = TerminalOutputWatch::default();
+        watch
+            .add_pattern("^test result: (ok|FAILED)".to_string())
+            .unwrap();
+        watch.add_pattern(r">>> $".to_string()).unwrap();
+
+        // Line split across reads, with CRLF endings
+        assert!(watch.feed("running 2 tests\r\ntest result: o").is_empty());
+        let matches = watch.feed("k. 2 passed\r\n");
+        assert_eq!(
+            matches,
+            vec![(
+                "^test result: (ok|FAILED)".to_string(),
+                "test result: ok. 2 passed".to_string()
+            )]
+        );
+
+        // A prompt is reported before its line ends, and not again after
+        assert_eq!(watch.feed(">>> ").len(), 1);
+        assert!(watch.feed("print(1)\r\n").is_empty());
+    }
+
+    #[test]
+    fn test_output_watch_redraws_and_caps_lines() {
+        let mut watch = TerminalOutputWatch::default();
+        watch.add_pattern("^100%$".to_string()).unwrap();
+
+        // Progress output redraws one line with carriage returns
+        assert!(watch.feed("10%\r50%\r").is_empty());
+        assert_eq!(watch.feed("100%").len(), 1);
+        assert_eq!(watch.partial, "100%");
+
+        let long = "x".repeat(3 * MAX_WATCHED_LINE_BYTES);
+        watch.feed(&long);
+        assert_eq!(watch.partial.len(), MAX_WATCHED_LINE_BYTES);
+    }
+
+    #[test]
+    fn test_output_watch_rejects_invalid_pattern() {
+        let mut watch = TerminalOutputWatch::default();
+        assert!(watch.add_pattern("(unclosed".to_string()).is_err());
+    }
+}
@@ -63,6 +63,18 @@ pub(super) struct InteractiveReplaceState {
     pub replacements_made: usize,
 }
 
+/// What became of a save that did not fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveOutcome {
+    /// The buffer was written
+    Saved,
+    /// The remote host is offline; the save is written once it reconnects
+    Queued,
+    /// Nothing was written: a plugin cancelled the save, or a prompt has to
+    /// be answered first
+    NotSaved,
+}
+
 /// The kind of buffer (file-backed or virtual)
 #[derive(Debug, Clone, PartialEq)]
 pub enum BufferKind {
@@ -710,6 +722,8 @@ pub(crate) struct CachedLayout {
     pub view_line_mappings: HashMap<SplitId, Vec<ViewLineMapping>>,
     /// Settings modal layout for hit testing
     pub settings_layout: Option<crate::view::settings::SettingsLayout>,
+    /// Plugin form dialog layout for hit testing
+    pub form_dialog_layout: Option<crate::view::form_dialog::FormDialogLayout>,
     /// Status bar area (row, x, width)
     pub status_bar_area: Option<(u16, u16, u16)>,
     /// Status bar LSP indicator area (row, start_col, end_col)
@@ -722,6 +736,8 @@ pub(crate) struct CachedLayout {
     pub status_bar_language_area: Option<(u16, u16, u16)>,
     /// Status bar message area (row, start_col, end_col) - clickable to show status log
     pub status_bar_message_area: Option<(u16, u16, u16)>,
+    /// Status bar plugin item areas (plugin_name, id, (row, start_col, end_col))
+    pub status_bar_plugin_item_areas: Vec<(String, String, (u16, u16, u16))>,
     /// Search options layout for checkbox hit testing
     pub search_options_layout: Option<crate::view::ui::status_bar::SearchOptionsLayout>,
     /// Menu bar layout for hit testing
@@ -46,6 +46,8 @@ pub enum WarningActionId {
     DisableLsp(String),
     /// Copy text to clipboard
     CopyToClipboard(String),
+    /// Rebuild the plugin runtime and reload enabled plugins
+    RestartPluginRuntime,
     /// Custom action (for plugins)
     Custom(String),
 }
@@ -334,11 +336,79 @@ impl LspWarningDomain {
     }
 }
 
+/// Plugin warning domain for plugins disabled by the runtime watchdog
+#[derive(Debug, Default)]
+pub struct PluginWarningDomain {
+    /// Disabled plugins with the reason, in the order they were disabled
+    pub disabled: Vec<(String, String)>,
+}
+
+impl PluginWarningDomain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a plugin disabled for exceeding a limit
+    pub fn add_disabled(&mut self, name: String, reason: String) {
+        self.disabled.retain(|(existing, _)| *existing != name);
+        self.disabled.push((name, reason));
+    }
+
+    /// Clear the list (e.g. after the plugin runtime was restarted)
+    pub fn clear(&mut self) {
+        self.disabled.clear();
+    }
+}
+
+impl WarningDomain for PluginWarningDomain {
+    fn id(&self) -> &str {
+        "plugins"
+    }
+
+    fn label(&self) -> String {
+        // Disabling a plugin also logs a warning, so the general badge covers it
+        String::new()
+    }
+
+    fn level(&self) -> WarningLevel {
+        if self.disabled.is_empty() {
+            WarningLevel::None
+        } else {
+            WarningLevel::Error
+        }
+    }
+
+    fn popup_content(&self) -> WarningPopupContent {
+        let message = self
+            .disabled
+            .iter()
+            .map(|(name, reason)| t!("plugin.disabled", name = name, reason = reason).to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        WarningPopupContent {
+            title: t!("warning.plugin_title").to_string(),
+            message,
+            actions: vec![
+                WarningAction {
+                    label: t!("warning.restart_plugin_runtime").to_string(),
+                    action_id: WarningActionId::RestartPluginRuntime,
+                },
+                WarningAction {
+                    label: t!("warning.dismiss").to_string(),
+                    action_id: WarningActionId::Dismiss,
+                },
+            ],
+        }
+    }
+}
+
 /// Registry of all warning domains
 #[derive(Default)]
 pub struct WarningDomainRegistry {
     pub general: GeneralWarningDomain,
     pub lsp: LspWarningDomain,
+    pub plugins: PluginWarningDomain,
 }
 
 impl WarningDomainRegistry {
@@ -354,6 +424,10 @@ impl WarningDomainRegistry {
             domains.push(&self.lsp);
         }
 
+        if self.plugins.has_warnings() {
+            domains.push(&self.plugins);
+        }
+
         if self.general.has_warnings() {
             domains.push(&self.general);
         }
@@ -363,7 +437,10 @@ impl WarningDomainRegistry {
 
     /// Get the highest warning level across all domains
     pub fn highest_level(&self) -> WarningLevel {
-        if self.lsp.level() == WarningLevel::Error || self.general.level() == WarningLevel::Error {
+        if self.lsp.level() == WarningLevel::Error
+            || self.plugins.level() == WarningLevel::Error
+            || self.general.level() == WarningLevel::Error
+        {
             WarningLevel::Error
         } else if self.lsp.level() == WarningLevel::Warning
             || self.general.level() == WarningLevel::Warning
@@ -376,6 +453,6 @@ impl WarningDomainRegistry {
 
     /// Check if any domain has warnings
     pub fn has_any_warnings(&self) -> bool {
-        self.lsp.has_warnings() || self.general.has_warnings()
+        self.lsp.has_warnings() || self.plugins.has_warnings() || self.general.has_warnings()
     }
 }
@@ -403,6 +403,10 @@ pub struct Config {
     #[schemars(extend("x-standalone-category" = true, "x-no-add" = true))]
     pub plugins: HashMap<String, PluginConfig>,
 
+    /// Time budget and memory limit for plugin code
+    #[serde(default)]
+    pub plugin_runtime: PluginRuntimeConfig,
+
     /// Package manager settings for plugin/theme installation
     #[serde(default)]
     pub packages: PackagesConfig,
@@ -897,7 +901,7 @@ impl Default for PackagesConfig {
 }
 
 // Re-export PluginConfig from fresh-core for shared type usage
-pub use fresh_core::config::PluginConfig;
+pub use fresh_core::config::{PluginCapabilities, PluginConfig, PluginRuntimeConfig};
 
 impl Default for FileExplorerConfig {
     fn default() -> Self {
@@ -1331,6 +1335,7 @@ impl Default for Config {
             lsp: Self::default_lsp_config(),
             warnings: WarningsConfig::default(),
             plugins: HashMap::new(), // Populated when scanning for plugins
+            plugin_runtime: PluginRuntimeConfig::default(),
             packages: PackagesConfig::default(),
         }
     }
@@ -888,6 +888,11 @@ impl DirectoryContext {
         self.prompt_history_path("goto_line")
     }
 
+    /// Get the socket `fresh --server` listens on for `--remote` requests
+    pub fn server_socket_path(&self) -> std::path::PathBuf {
+        self.data_dir.join("server.sock")
+    }
+
     /// Get the terminals root directory
     pub fn terminals_dir(&self) -> std::path::PathBuf {
         self.data_dir.join("terminals")
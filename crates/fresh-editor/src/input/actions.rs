@@ -2279,6 +2279,10 @@ pub fn action_to_events(
         | Action::ShowStatusLog
         | Action::ShowLspStatus
         | Action::ClearWarnings
+        | Action::RestartPluginRuntime
+        | Action::PluginConsole
+        | Action::PluginConsoleSelectPlugin
+        | Action::PluginConsoleToggleAutoReload
         | Action::SmartHome
         | Action::ToggleComment
         | Action::SetBookmark(_)
@@ -2398,6 +2402,8 @@ pub fn action_to_events(
         | Action::SelectLocale
         | Action::Revert
         | Action::ToggleAutoRevert
+        | Action::ToggleFollowMode
+        | Action::SetFollowFilter
         | Action::FormatBuffer
         | Action::TrimTrailingWhitespace
         | Action::EnsureFinalNewline
@@ -2426,6 +2432,18 @@ pub fn action_to_events(
         | Action::ResetBufferSettings
         | Action::ShellCommand
         | Action::ShellCommandReplace
+        | Action::CompareFiles
+        | Action::CompareWithSaved
+        | Action::CompareWithClipboard
+        | Action::CompareNextHunk
+        | Action::ComparePrevHunk
+        | Action::CompareCopyHunkLeft
+        | Action::CompareCopyHunkRight
+        | Action::MergeNextConflict
+        | Action::MergePrevConflict
+        | Action::MergeAcceptOurs
+        | Action::MergeAcceptTheirs
+        | Action::MergeAcceptBoth
         | Action::CalibrateInput => return None,
 
         // Block/rectangular selection actions
@@ -106,6 +106,72 @@ impl ModeRegistry {
 
         registry.register(special_mode);
 
+        // Built-in side-by-side file comparison (see app/file_compare.rs)
+        let compare_mode = BufferMode::new("compare-view")
+            .with_read_only(true)
+            .with_binding(KeyCode::Char('q'), KeyModifiers::NONE, "close")
+            .with_binding(KeyCode::Char('n'), KeyModifiers::NONE, "compare_next_hunk")
+            .with_binding(KeyCode::Char(']'), KeyModifiers::NONE, "compare_next_hunk")
+            .with_binding(KeyCode::Char('p'), KeyModifiers::NONE, "compare_prev_hunk")
+            .with_binding(KeyCode::Char('['), KeyModifiers::NONE, "compare_prev_hunk")
+            // Terminals differ in whether they report Shift with '<' and '>'
+            .with_binding(
+                KeyCode::Char('<'),
+                KeyModifiers::NONE,
+                "compare_copy_hunk_left",
+            )
+            .with_binding(
+                KeyCode::Char('<'),
+                KeyModifiers::SHIFT,
+                "compare_copy_hunk_left",
+            )
+            .with_binding(
+                KeyCode::Char('>'),
+                KeyModifiers::NONE,
+                "compare_copy_hunk_right",
+            )
+            .with_binding(
+                KeyCode::Char('>'),
+                KeyModifiers::SHIFT,
+                "compare_copy_hunk_right",
+            );
+
+        registry.register(compare_mode);
+
+        // Built-in three-way merge (see app/merge_tool.rs)
+        let merge_mode = BufferMode::new("merge-view")
+            .with_read_only(true)
+            .with_binding(KeyCode::Char('q'), KeyModifiers::NONE, "close")
+            .with_binding(
+                KeyCode::Char('n'),
+                KeyModifiers::NONE,
+                "merge_next_conflict",
+            )
+            .with_binding(
+                KeyCode::Char(']'),
+                KeyModifiers::NONE,
+                "merge_next_conflict",
+            )
+            .with_binding(
+                KeyCode::Char('p'),
+                KeyModifiers::NONE,
+                "merge_prev_conflict",
+            )
+            .with_binding(
+                KeyCode::Char('['),
+                KeyModifiers::NONE,
+                "merge_prev_conflict",
+            )
+            .with_binding(KeyCode::Char('o'), KeyModifiers::NONE, "merge_accept_ours")
+            .with_binding(
+                KeyCode::Char('t'),
+                KeyModifiers::NONE,
+                "merge_accept_theirs",
+            )
+            .with_binding(KeyCode::Char('b'), KeyModifiers::NONE, "merge_accept_both");
+
+        registry.register(merge_mode);
+
         registry
     }
 
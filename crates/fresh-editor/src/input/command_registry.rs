@@ -103,6 +103,13 @@ impl CommandRegistry {
         commands.retain(|c| !c.name.starts_with(prefix));
     }
 
+    /// Unregister every plugin-registered command (e.g. before the plugin
+    /// runtime is restarted)
+    pub fn unregister_all_plugin_commands(&self) {
+        let mut commands = self.plugin_commands.write().unwrap();
+        commands.retain(|c| !matches!(c.source, super::commands::CommandSource::Plugin(_)));
+    }
+
     /// Unregister all commands registered by a specific plugin
     pub fn unregister_by_plugin(&self, plugin_name: &str) {
         let mut commands = self.plugin_commands.write().unwrap();
@@ -229,6 +229,22 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.toggle_follow_mode").to_string(),
+            description: t!("cmd.toggle_follow_mode_desc").to_string(),
+            action: Action::ToggleFollowMode,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.set_follow_filter").to_string(),
+            description: t!("cmd.set_follow_filter_desc").to_string(),
+            action: Action::SetFollowFilter,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.format_buffer").to_string(),
             description: t!("cmd.format_buffer_desc").to_string(),
@@ -1124,6 +1140,38 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.restart_plugin_runtime").to_string(),
+            description: t!("cmd.restart_plugin_runtime_desc").to_string(),
+            action: Action::RestartPluginRuntime,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.plugin_console").to_string(),
+            description: t!("cmd.plugin_console_desc").to_string(),
+            action: Action::PluginConsole,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.plugin_console_select_plugin").to_string(),
+            description: t!("cmd.plugin_console_select_plugin_desc").to_string(),
+            action: Action::PluginConsoleSelectPlugin,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.plugin_console_toggle_auto_reload").to_string(),
+            description: t!("cmd.plugin_console_toggle_auto_reload_desc").to_string(),
+            action: Action::PluginConsoleToggleAutoReload,
+            contexts: vec![],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // Config
         Command {
             name: t!("cmd.dump_config").to_string(),
@@ -1245,6 +1293,104 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        // File comparison
+        Command {
+            name: t!("cmd.compare_files").to_string(),
+            description: t!("cmd.compare_files_desc").to_string(),
+            action: Action::CompareFiles,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.compare_with_saved").to_string(),
+            description: t!("cmd.compare_with_saved_desc").to_string(),
+            action: Action::CompareWithSaved,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.compare_with_clipboard").to_string(),
+            description: t!("cmd.compare_with_clipboard_desc").to_string(),
+            action: Action::CompareWithClipboard,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.compare_next_hunk").to_string(),
+            description: t!("cmd.compare_next_hunk_desc").to_string(),
+            action: Action::CompareNextHunk,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.compare_prev_hunk").to_string(),
+            description: t!("cmd.compare_prev_hunk_desc").to_string(),
+            action: Action::ComparePrevHunk,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.compare_copy_hunk_left").to_string(),
+            description: t!("cmd.compare_copy_hunk_left_desc").to_string(),
+            action: Action::CompareCopyHunkLeft,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.compare_copy_hunk_right").to_string(),
+            description: t!("cmd.compare_copy_hunk_right_desc").to_string(),
+            action: Action::CompareCopyHunkRight,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        // Three-way merge
+        Command {
+            name: t!("cmd.merge_next_conflict").to_string(),
+            description: t!("cmd.merge_next_conflict_desc").to_string(),
+            action: Action::MergeNextConflict,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.merge_prev_conflict").to_string(),
+            description: t!("cmd.merge_prev_conflict_desc").to_string(),
+            action: Action::MergePrevConflict,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.merge_accept_ours").to_string(),
+            description: t!("cmd.merge_accept_ours_desc").to_string(),
+            action: Action::MergeAcceptOurs,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.merge_accept_theirs").to_string(),
+            description: t!("cmd.merge_accept_theirs_desc").to_string(),
+            action: Action::MergeAcceptTheirs,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.merge_accept_both").to_string(),
+            description: t!("cmd.merge_accept_both_desc").to_string(),
+            action: Action::MergeAcceptBoth,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
     ]
 }
 
@@ -307,6 +307,8 @@ pub enum Action {
     ForceQuit,
     Revert,
     ToggleAutoRevert,
+    ToggleFollowMode,
+    SetFollowFilter,
     FormatBuffer,
     TrimTrailingWhitespace,
     EnsureFinalNewline,
@@ -362,6 +364,14 @@ pub enum Action {
     ShowStatusLog,
     ShowLspStatus,
     ClearWarnings,
+    /// Rebuild the plugin runtime and reload enabled plugins
+    RestartPluginRuntime,
+    /// Open the plugin developer console and evaluate code in a plugin
+    PluginConsole,
+    /// Choose which plugin the developer console evaluates code in
+    PluginConsoleSelectPlugin,
+    /// Reload the console's plugin whenever its source file changes
+    PluginConsoleToggleAutoReload,
     CommandPalette, // TODO: Consider dropping this now that we have QuickOpen
     /// Quick Open - unified prompt with prefix-based provider routing
     QuickOpen,
@@ -546,6 +556,22 @@ pub enum Action {
     ShellCommand,        // Run shell command on buffer/selection, output to new buffer
     ShellCommandReplace, // Run shell command on buffer/selection, replace content
 
+    // File comparison
+    CompareFiles,         // Compare the current buffer with a file, side by side
+    CompareWithSaved,     // Compare the current buffer with its saved content
+    CompareWithClipboard, // Compare the current buffer with the clipboard
+    CompareNextHunk,      // Go to the next change in a comparison
+    ComparePrevHunk,      // Go to the previous change in a comparison
+    CompareCopyHunkLeft,  // Copy the change at the cursor to the left side
+    CompareCopyHunkRight, // Copy the change at the cursor to the right side
+
+    // Three-way merge
+    MergeNextConflict, // Go to the next conflict in a merge
+    MergePrevConflict, // Go to the previous conflict in a merge
+    MergeAcceptOurs,   // Resolve the conflict at the cursor with ours
+    MergeAcceptTheirs, // Resolve the conflict at the cursor with theirs
+    MergeAcceptBoth,   // Resolve the conflict at the cursor with ours, then theirs
+
     // Case conversion
     ToUpperCase, // Convert selection to uppercase
     ToLowerCase, // Convert selection to lowercase
@@ -657,6 +683,8 @@ impl Action {
             "force_quit" => Self::ForceQuit,
             "revert" => Self::Revert,
             "toggle_auto_revert" => Self::ToggleAutoRevert,
+            "toggle_follow_mode" => Self::ToggleFollowMode,
+            "set_follow_filter" => Self::SetFollowFilter,
             "format_buffer" => Self::FormatBuffer,
             "goto_line" => Self::GotoLine,
             "goto_matching_bracket" => Self::GoToMatchingBracket,
@@ -703,6 +731,10 @@ impl Action {
             "show_status_log" => Self::ShowStatusLog,
             "show_lsp_status" => Self::ShowLspStatus,
             "clear_warnings" => Self::ClearWarnings,
+            "restart_plugin_runtime" => Self::RestartPluginRuntime,
+            "plugin_console" => Self::PluginConsole,
+            "plugin_console_select_plugin" => Self::PluginConsoleSelectPlugin,
+            "plugin_console_toggle_auto_reload" => Self::PluginConsoleToggleAutoReload,
             "command_palette" => Self::CommandPalette,
             "quick_open" => Self::QuickOpen,
             "toggle_line_wrap" => Self::ToggleLineWrap,
@@ -849,6 +881,22 @@ impl Action {
             "shell_command" => Self::ShellCommand,
             "shell_command_replace" => Self::ShellCommandReplace,
 
+            // File comparison
+            "compare_files" => Self::CompareFiles,
+            "compare_with_saved" => Self::CompareWithSaved,
+            "compare_with_clipboard" => Self::CompareWithClipboard,
+            "compare_next_hunk" => Self::CompareNextHunk,
+            "compare_prev_hunk" => Self::ComparePrevHunk,
+            "compare_copy_hunk_left" => Self::CompareCopyHunkLeft,
+            "compare_copy_hunk_right" => Self::CompareCopyHunkRight,
+
+            // Three-way merge
+            "merge_next_conflict" => Self::MergeNextConflict,
+            "merge_prev_conflict" => Self::MergePrevConflict,
+            "merge_accept_ours" => Self::MergeAcceptOurs,
+            "merge_accept_theirs" => Self::MergeAcceptTheirs,
+            "merge_accept_both" => Self::MergeAcceptBoth,
+
             // Case conversion
             "to_upper_case" => Self::ToUpperCase,
             "to_lower_case" => Self::ToLowerCase,
@@ -1663,6 +1711,8 @@ impl KeybindingResolver {
             Action::ForceQuit => t!("action.force_quit"),
             Action::Revert => t!("action.revert"),
             Action::ToggleAutoRevert => t!("action.toggle_auto_revert"),
+            Action::ToggleFollowMode => t!("action.toggle_follow_mode"),
+            Action::SetFollowFilter => t!("action.set_follow_filter"),
             Action::FormatBuffer => t!("action.format_buffer"),
             Action::TrimTrailingWhitespace => t!("action.trim_trailing_whitespace"),
             Action::EnsureFinalNewline => t!("action.ensure_final_newline"),
@@ -1702,6 +1752,12 @@ impl KeybindingResolver {
             Action::ShowStatusLog => t!("action.show_status_log"),
             Action::ShowLspStatus => t!("action.show_lsp_status"),
             Action::ClearWarnings => t!("action.clear_warnings"),
+            Action::RestartPluginRuntime => t!("action.restart_plugin_runtime"),
+            Action::PluginConsole => t!("action.plugin_console"),
+            Action::PluginConsoleSelectPlugin => t!("action.plugin_console_select_plugin"),
+            Action::PluginConsoleToggleAutoReload => {
+                t!("action.plugin_console_toggle_auto_reload")
+            }
             Action::CommandPalette => t!("action.command_palette"),
             Action::QuickOpen => t!("action.quick_open"),
             Action::ToggleLineWrap => t!("action.toggle_line_wrap"),
@@ -1843,6 +1899,18 @@ impl KeybindingResolver {
             Action::SettingsDecrement => t!("action.settings_decrement"),
             Action::ShellCommand => t!("action.shell_command"),
             Action::ShellCommandReplace => t!("action.shell_command_replace"),
+            Action::CompareFiles => t!("action.compare_files"),
+            Action::CompareWithSaved => t!("action.compare_with_saved"),
+            Action::CompareWithClipboard => t!("action.compare_with_clipboard"),
+            Action::CompareNextHunk => t!("action.compare_next_hunk"),
+            Action::ComparePrevHunk => t!("action.compare_prev_hunk"),
+            Action::CompareCopyHunkLeft => t!("action.compare_copy_hunk_left"),
+            Action::CompareCopyHunkRight => t!("action.compare_copy_hunk_right"),
+            Action::MergeNextConflict => t!("action.merge_next_conflict"),
+            Action::MergePrevConflict => t!("action.merge_prev_conflict"),
+            Action::MergeAcceptOurs => t!("action.merge_accept_ours"),
+            Action::MergeAcceptTheirs => t!("action.merge_accept_theirs"),
+            Action::MergeAcceptBoth => t!("action.merge_accept_both"),
             Action::ToUpperCase => t!("action.to_uppercase"),
             Action::ToLowerCase => t!("action.to_lowercase"),
             Action::CalibrateInput => t!("action.calibrate_input"),
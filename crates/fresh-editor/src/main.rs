@@ -84,6 +84,35 @@ struct Args {
     /// Initialize a new package (plugin, theme, or language pack)
     #[arg(long, value_name = "TYPE")]
     init: Option<Option<String>>,
+
+    /// Apply a batch script to FILES without a terminal, save them and exit
+    #[arg(long, value_name = "SCRIPT")]
+    batch: Option<PathBuf>,
+
+    /// Listen for --remote requests, so later invocations open files in this editor
+    #[arg(long)]
+    server: bool,
+
+    /// Open FILES in the editor started with --server instead of a new one
+    #[arg(long)]
+    remote: bool,
+
+    /// Like --remote, but wait until the files are closed (for $EDITOR or GIT_EDITOR)
+    #[arg(long)]
+    wait: bool,
+
+    /// With --remote or --wait, open the files in a new split rather than as tabs
+    #[arg(long)]
+    split: bool,
+
+    /// Compare the two FILES side by side
+    #[arg(long)]
+    diff: bool,
+
+    /// Merge FILES given as BASE LOCAL REMOTE MERGED (for git mergetool);
+    /// exits with status 1 if MERGED still has conflicts
+    #[arg(long)]
+    merge: bool,
 }
 
 /// Parsed file location from CLI argument in file:line:col format
@@ -314,14 +343,20 @@ fn handle_first_run_setup(
         editor.open_stdin_buffer(&stream_state.temp_path, stream_state.thread_handle.take())?;
     }
 
-    for loc in file_locations {
-        if loc.path.is_dir() {
-            continue;
-        }
-        editor.open_file(&loc.path)?;
+    if let ([left, right], true) = (file_locations, args.diff) {
+        editor.compare_files(&left.path, &right.path)?;
+    } else if let ([base, local, remote, merged], true) = (file_locations, args.merge) {
+        editor.open_merge(&base.path, &local.path, &remote.path, &merged.path)?;
+    } else {
+        for loc in file_locations {
+            if loc.path.is_dir() {
+                continue;
+            }
+            editor.open_file(&loc.path)?;
 
-        if let Some(line) = loc.line {
-            editor.goto_line_col(line, loc.column);
+            if let Some(line) = loc.line {
+                editor.goto_line_col(line, loc.column);
+            }
         }
     }
 
@@ -512,9 +547,9 @@ fn parse_location(input: &str) -> ParsedLocation {
 
 /// Holds resources needed for remote editing (kept alive for duration of session)
 struct RemoteSession {
-    /// The SSH connection - dropping this closes the connection
-    _connection: remote::SshConnection,
-    /// Tokio runtime for async operations
+    /// Task owning the SSH connection and reconnecting it when the link drops
+    _supervisor: tokio::task::JoinHandle<()>,
+    /// Tokio runtime for async operations - dropping it closes the connection
     _runtime: tokio::runtime::Runtime,
 }
 
@@ -576,7 +611,7 @@ fn connect_remote(remote: &RemoteLocation) -> AnyhowResult<FilesystemResult> {
         filesystem,
         process_spawner,
         remote_session: Some(RemoteSession {
-            _connection: connection,
+            _supervisor: rt.spawn(connection.maintain()),
             _runtime: rt,
         }),
     })
@@ -686,7 +721,7 @@ fn initialize_app(args: &Args) -> AnyhowResult<SetupState> {
     };
 
     // Convert to FileLocation for downstream code
-    let file_locations: Vec<FileLocation> = parsed_locations
+    let mut file_locations: Vec<FileLocation> = parsed_locations
         .into_iter()
         .map(|loc| match loc {
             ParsedLocation::Local(fl) => fl,
@@ -706,6 +741,19 @@ fn initialize_app(args: &Args) -> AnyhowResult<SetupState> {
         remote_session,
     } = create_filesystem(&remote_info)?;
 
+    // Remote paths may be relative to the remote home directory or start with `~`.
+    // Resolve them on the remote host so buffers get absolute paths, which
+    // language servers need to build file URIs.
+    if remote_info.is_some() {
+        for loc in &mut file_locations {
+            if !loc.path.is_absolute() {
+                if let Ok(resolved) = filesystem.canonicalize(&loc.path) {
+                    loc.path = resolved;
+                }
+            }
+        }
+    }
+
     let mut working_dir = None;
     let mut show_file_explorer = false;
 
@@ -722,6 +770,16 @@ fn initialize_app(args: &Args) -> AnyhowResult<SetupState> {
         }
     }
 
+    // When opening remote files, work in the remote directory of the first file
+    // rather than the local current directory (which doesn't exist remotely)
+    if remote_info.is_some() && working_dir.is_none() {
+        working_dir = file_locations
+            .first()
+            .and_then(|loc| loc.path.parent())
+            .filter(|parent| parent.is_absolute())
+            .map(|parent| parent.to_path_buf());
+    }
+
     // Load config using the layered config system
     // For remote editing, use current local dir for config (remote doesn't have our config)
     let effective_working_dir = if remote_info.is_some() {
@@ -863,6 +921,118 @@ fn run_editor_iteration(
     })
 }
 
+/// Run a batch script over the files without a terminal
+///
+/// Prints what happened to each file and exits with status 1 if any failed.
+fn run_batch(args: &Args, script_path: &std::path::Path) -> AnyhowResult<()> {
+    use fresh::app::batch::{BatchOutcome, BatchScript};
+
+    let script = BatchScript::load(script_path)
+        .with_context(|| format!("Invalid batch script {}", script_path.display()))?;
+    if args.files.is_empty() {
+        anyhow::bail!("--batch needs files to edit");
+    }
+
+    let log_file = args
+        .log_file
+        .clone()
+        .unwrap_or_else(fresh::services::log_dirs::main_log_path);
+    let _tracing_handles = tracing_setup::init_global(&log_file);
+
+    let dir_context = DirectoryContext::from_system()?;
+    let working_dir = std::env::current_dir().context("Failed to get current directory")?;
+    let mut config = match &args.config {
+        Some(config_path) => config::Config::load_from_file(config_path).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to load config from {}: {}",
+                config_path.display(),
+                e
+            )
+        })?,
+        None => config::Config::load_with_layers(&dir_context, &working_dir),
+    };
+    // Nobody is there to be told about updates
+    config.check_for_updates = false;
+    fresh::i18n::init_with_config(args.locale.as_deref().or(config.locale.as_option()));
+
+    let mut editor = Editor::with_working_dir(
+        config,
+        80,
+        24,
+        Some(working_dir.clone()),
+        dir_context,
+        !args.no_plugins,
+        fresh::view::color_support::ColorCapability::TrueColor,
+        std::sync::Arc::new(StdFileSystem),
+    )
+    .context("Failed to create editor instance")?;
+    // Let plugins register their commands
+    editor.wait_for_plugin_work();
+
+    let mut failed = false;
+    for file in &args.files {
+        match editor.run_batch(&script, &working_dir.join(file)) {
+            BatchOutcome::Changed => println!("changed: {}", file),
+            BatchOutcome::Unchanged => println!("unchanged: {}", file),
+            BatchOutcome::Failed(e) => {
+                eprintln!("failed: {}: {}", file, e);
+                failed = true;
+            }
+        }
+    }
+    drop(editor);
+    if failed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Hand the files to an editor started with `--server`
+///
+/// Returns false when no editor is listening, so the caller starts one itself.
+#[cfg(unix)]
+fn open_in_running_editor(args: &Args) -> AnyhowResult<bool> {
+    use fresh::services::instance_server::{Client, OpenFile, OpenRequest, Reply};
+
+    let dir_context = DirectoryContext::from_system()?;
+    let working_dir = std::env::current_dir().context("Failed to get current directory")?;
+    let files = args
+        .files
+        .iter()
+        .map(|f| {
+            let location = parse_file_location(f);
+            OpenFile {
+                path: working_dir.join(location.path),
+                line: location.line,
+                column: location.column,
+            }
+        })
+        .collect();
+    let request = OpenRequest {
+        files,
+        split: args.split,
+        wait: args.wait,
+    };
+    let Some(mut client) = Client::send(&dir_context.server_socket_path(), &request)
+        .context("Failed to contact the running editor")?
+    else {
+        return Ok(false);
+    };
+    if let Reply::Failed { message } = client.next_reply()? {
+        anyhow::bail!("{}", message);
+    }
+    if args.wait {
+        // The only reply left is that the files were closed
+        client.next_reply()?;
+    }
+    Ok(true)
+}
+
+#[cfg(not(unix))]
+fn open_in_running_editor(_args: &Args) -> AnyhowResult<bool> {
+    anyhow::bail!("--remote and --wait are not yet supported on this platform")
+}
+
 /// Check a plugin by bundling it and printing the output
 #[cfg(feature = "plugins")]
 fn check_plugin_bundle(plugin_path: &std::path::Path) -> AnyhowResult<()> {
@@ -1527,6 +1697,23 @@ fn main() -> AnyhowResult<()> {
         return init_package_command(pkg_type.clone());
     }
 
+    // Handle --batch early (no terminal setup needed)
+    if let Some(script_path) = &args.batch {
+        return run_batch(&args, script_path);
+    }
+
+    if args.diff && args.files.len() != 2 {
+        anyhow::bail!("--diff needs exactly two files");
+    }
+    if args.merge && args.files.len() != 4 {
+        anyhow::bail!("--merge needs BASE LOCAL REMOTE MERGED");
+    }
+
+    // Hand the files to a running editor; with none running, become the editor
+    if (args.remote || args.wait) && open_in_running_editor(&args)? {
+        return Ok(());
+    }
+
     let SetupState {
         config,
         mut tracing_handles,
@@ -1557,6 +1744,10 @@ fn main() -> AnyhowResult<()> {
     // Track whether we should restore session on restart (for project switching)
     let mut restore_session_on_restart = false;
 
+    // The --server socket outlives each editor instance across restarts
+    #[cfg(unix)]
+    let mut instance_server = None;
+
     // Main editor loop - supports restarting with a new working directory
     // Returns (loop_result, last_update_result) tuple
     let (result, last_update_result) = loop {
@@ -1600,6 +1791,11 @@ fn main() -> AnyhowResult<()> {
                 session_enabled,
             )
             .context("Failed first run setup")?;
+
+            #[cfg(unix)]
+            if args.server {
+                editor.start_instance_server();
+            }
         } else {
             if restore_session_on_restart {
                 match editor.try_restore_session() {
@@ -1623,6 +1819,11 @@ fn main() -> AnyhowResult<()> {
             editor.set_status_message(fresh::i18n::switched_to_project_message(&path));
         }
 
+        #[cfg(unix)]
+        if let Some(server) = instance_server.take() {
+            editor.set_instance_server(server);
+        }
+
         if let Err(e) = editor.start_recovery_session() {
             tracing::warn!("Failed to start recovery session: {}", e);
         }
@@ -1641,6 +1842,10 @@ fn main() -> AnyhowResult<()> {
         let restart_dir = iteration.restart_dir;
         let loop_result = iteration.loop_result;
 
+        #[cfg(unix)]
+        {
+            instance_server = editor.take_instance_server();
+        }
         drop(editor);
 
         if let Some(new_dir) = restart_dir {
@@ -1684,7 +1889,22 @@ fn main() -> AnyhowResult<()> {
         }
     }
 
-    result.context("Editor loop returned an error")
+    result.context("Editor loop returned an error")?;
+
+    // Report to git mergetool whether the merge was completed
+    if let (true, Some(merged)) = (args.merge, file_locations.get(3)) {
+        let text = std::fs::read_to_string(&merged.path).unwrap_or_default();
+        let conflicts = fresh::model::merge::find_conflict_blocks(&text).len();
+        if conflicts > 0 {
+            eprintln!(
+                "{}: {} unresolved conflict(s)",
+                merged.path.display(),
+                conflicts
+            );
+            std::process::exit(1);
+        }
+    }
+    Ok(())
 }
 
 /// Main event loop
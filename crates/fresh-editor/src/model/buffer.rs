@@ -709,6 +709,13 @@ impl TextBuffer {
         self.modified = false;
     }
 
+    /// Get the content as of the last saved snapshot
+    /// Returns None if any of it is not loaded (lazy loading)
+    pub fn saved_content(&self) -> Option<Vec<u8>> {
+        let saved_bytes = self.tree_total_bytes(&self.saved_root);
+        self.extract_range_from_tree(&self.saved_root, 0, saved_bytes)
+    }
+
     /// Diff the current piece tree against the last saved snapshot.
     ///
     /// This compares actual byte content, not just tree structure. This means
@@ -1509,6 +1516,47 @@ impl TextBuffer {
         );
     }
 
+    /// Extend buffer with the bytes its file grew by, if the file was only
+    /// appended to (e.g. a log file being written).
+    ///
+    /// Like [`Self::extend_streaming`], the new bytes become an Unloaded chunk,
+    /// so they are only read once they are displayed. The file counts as
+    /// appended to if it grew and the first and last bytes of the current
+    /// content are unchanged on disk; comparing all of it would mean reading
+    /// the whole file again.
+    ///
+    /// Returns the appended byte range, or None (leaving the buffer untouched)
+    /// if the buffer has unsaved changes or the file changed in another way.
+    pub fn extend_appended(&mut self) -> Result<Option<Range<usize>>> {
+        const CHECK_BYTES: usize = 4096;
+
+        if self.modified {
+            return Ok(None);
+        }
+        let Some(path) = self.file_path.clone() else {
+            return Ok(None);
+        };
+        let old_size = self.total_bytes();
+        let new_size = self.fs.metadata(&path)?.size as usize;
+        if new_size <= old_size {
+            return Ok(None);
+        }
+
+        let check = old_size.min(CHECK_BYTES);
+        for offset in [0, old_size - check] {
+            let on_disk = self.fs.read_range(&path, offset as u64, check)?;
+            if self.get_text_range_mut(offset, check)? != on_disk {
+                return Ok(None);
+            }
+        }
+
+        self.extend_streaming(&path, new_size);
+        self.saved_file_size = Some(new_size);
+        self.mark_saved_snapshot();
+        self.bump_version();
+        Ok(Some(old_size..new_size))
+    }
+
     /// Check if the buffer has been modified since last save
     pub fn is_modified(&self) -> bool {
         self.modified
@@ -3221,6 +3269,32 @@ mod tests {
             assert_eq!(buffer.buffers[0].get_data(), None);
         }
 
+        #[test]
+        fn test_extend_appended_only_on_pure_append() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("app.log");
+            std::fs::write(&file_path, "one\ntwo\n").unwrap();
+            let mut buffer = TextBuffer::load_from_file(&file_path, 0, test_fs()).unwrap();
+
+            // Nothing was appended yet
+            assert_eq!(buffer.extend_appended().unwrap(), None);
+
+            std::fs::write(&file_path, "one\ntwo\nthree\n").unwrap();
+            assert_eq!(buffer.extend_appended().unwrap(), Some(8..14));
+            assert!(!buffer.is_modified());
+            // The appended bytes are loaded lazily from the file
+            assert!(!buffer.buffers.last().unwrap().is_loaded());
+            assert_eq!(
+                buffer.get_text_range_mut(0, 14).unwrap(),
+                b"one\ntwo\nthree\n"
+            );
+
+            // A rewritten file is not an append
+            std::fs::write(&file_path, "ONE\ntwo\nthree\nfour\n").unwrap();
+            assert_eq!(buffer.extend_appended().unwrap(), None);
+            assert_eq!(buffer.total_bytes(), 14);
+        }
+
         /// Test that reproduces issue #657: Search on large plain text files
         ///
         /// The bug: When a large file is opened with lazy loading, buffer.to_string()
@@ -5,6 +5,7 @@
 //! within a single tab.
 
 use crate::model::event::BufferId;
+use crate::model::merge::{MergeKind, MergeRegion};
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
@@ -354,6 +355,49 @@ impl LineAlignment {
         Self { rows }
     }
 
+    /// Create alignment for a three-way merge, with panes ours, base, theirs
+    ///
+    /// Conflicts are preceded by a hunk header row; changes that merge
+    /// cleanly are shown as additions without one.
+    pub fn from_merge(regions: &[MergeRegion]) -> Self {
+        let mut rows = Vec::new();
+        for region in regions {
+            let row_type = match region.kind {
+                MergeKind::Unchanged => RowType::Context,
+                MergeKind::Conflict => {
+                    rows.push(AlignedRow {
+                        pane_lines: vec![None, None, None],
+                        row_type: RowType::HunkHeader,
+                    });
+                    RowType::Modification
+                }
+                MergeKind::Ours | MergeKind::Theirs | MergeKind::Both => RowType::Addition,
+            };
+            let line_ref = |range: &Range<usize>, i: usize| {
+                (i < range.len()).then(|| SourceLineRef {
+                    line: range.start + i,
+                    byte_range: 0..0,
+                })
+            };
+            let max_lines = region
+                .ours
+                .len()
+                .max(region.base.len())
+                .max(region.theirs.len());
+            for i in 0..max_lines {
+                rows.push(AlignedRow {
+                    pane_lines: vec![
+                        line_ref(&region.ours, i),
+                        line_ref(&region.base, i),
+                        line_ref(&region.theirs, i),
+                    ],
+                    row_type,
+                });
+            }
+        }
+        Self { rows }
+    }
+
     /// Get the aligned row at the given display index
     pub fn get_row(&self, display_row: usize) -> Option<&AlignedRow> {
         self.rows.get(display_row)
@@ -518,6 +562,29 @@ mod tests {
         assert_eq!(alignment.rows[2].row_type, RowType::HunkHeader);
     }
 
+    #[test]
+    fn test_line_alignment_from_merge() {
+        let regions =
+            crate::model::merge::three_way_merge("a\nb\nc", "A\nb\nours\nmore", "a\nb\ntheirs");
+        let alignment = LineAlignment::from_merge(&regions);
+        let types: Vec<_> = alignment.rows.iter().map(|r| r.row_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                RowType::Addition,
+                RowType::Context,
+                RowType::HunkHeader,
+                RowType::Modification,
+                RowType::Modification,
+            ]
+        );
+        // The conflict's second row only has an "ours" line
+        let last = &alignment.rows[4];
+        assert_eq!(last.get_pane_line(0).map(|l| l.line), Some(3));
+        assert!(!last.has_content(1));
+        assert!(!last.has_content(2));
+    }
+
     #[test]
     fn test_composite_buffer_focus() {
         let sources = vec![
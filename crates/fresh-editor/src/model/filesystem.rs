@@ -446,6 +446,32 @@ pub trait FileSystem: Send + Sync {
         None
     }
 
+    /// Whether the remote host is currently reachable
+    ///
+    /// Always `true` for local filesystems. Remote filesystems report `false`
+    /// while the connection is down and being re-established.
+    fn is_remote_connected(&self) -> bool {
+        true
+    }
+
+    /// Whether this filesystem pushes change notifications through `watch`
+    ///
+    /// When `false`, callers detect external changes by polling metadata.
+    fn supports_watch(&self) -> bool {
+        false
+    }
+
+    /// Set the files and directories to report changes for
+    ///
+    /// Replaces the previously watched set. Changes are collected with
+    /// `take_changed_paths`.
+    fn watch(&self, _paths: &[PathBuf]) {}
+
+    /// Take the watched paths reported as changed since the last call
+    fn take_changed_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
     /// Get the home directory for this filesystem
     ///
     /// For local filesystems, returns the local home directory.
@@ -3,9 +3,21 @@
 //! This module provides a simple but robust diff algorithm that correctly handles
 //! insertions, deletions, and modifications. It uses a longest common subsequence (LCS)
 //! approach to identify which lines are unchanged, then marks the ranges that differ.
+//!
+//! The same LCS also produces the hunks and intra-line word changes shown by
+//! side-by-side file comparison.
 
+use crate::model::composite_buffer::DiffHunk;
 use std::ops::Range;
 
+/// Largest LCS table (lines or words of one side times the other) computed.
+/// A changed region bigger than this is reported as a single change.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// LCS table limit for word diffs computed while rendering. Lines with more
+/// changed words than this allows get one highlighted change instead.
+pub const MAX_INLINE_LCS_CELLS: usize = 10_000;
+
 /// Type of change detected for a line range
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChangeType {
@@ -91,7 +103,7 @@ struct LineMatch {
 
 /// Find the longest common subsequence of lines between saved and current.
 /// Returns a list of LineMatch with both saved and current indices.
-fn longest_common_subsequence(saved: &[&[u8]], current: &[&[u8]]) -> Vec<LineMatch> {
+fn longest_common_subsequence<T: PartialEq>(saved: &[T], current: &[T]) -> Vec<LineMatch> {
     let n = saved.len();
     let m = current.len();
 
@@ -299,6 +311,157 @@ pub fn merge_ranges(ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
     merged
 }
 
+/// Compare two texts line by line and return the hunks that differ, for a
+/// side-by-side view of `old` against `new`.
+///
+/// Lines are split on `\n` like [`diff_lines`]. A common prefix and suffix
+/// are skipped before the LCS, so large files with small changes stay cheap.
+pub fn diff_hunks(old: &[u8], new: &[u8]) -> Vec<DiffHunk> {
+    if old == new {
+        return vec![];
+    }
+    let old_lines: Vec<&[u8]> = old.split(|&b| b == b'\n').collect();
+    let new_lines: Vec<&[u8]> = new.split(|&b| b == b'\n').collect();
+
+    let (prefix, old_middle, new_middle) = trim_common(&old_lines, &new_lines);
+    changed_runs(old_middle, new_middle, MAX_LCS_CELLS)
+        .into_iter()
+        .map(|(old_range, new_range)| {
+            DiffHunk::new(
+                prefix + old_range.start,
+                old_range.len(),
+                prefix + new_range.start,
+                new_range.len(),
+            )
+        })
+        .collect()
+}
+
+/// Compare two versions of a line word by word.
+///
+/// Returns the changed character ranges of `old` and of `new`. Words are runs
+/// of alphanumeric characters (and `_`), runs of whitespace, and single
+/// punctuation characters.
+pub fn diff_words(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    diff_words_bounded(old, new, MAX_LCS_CELLS)
+}
+
+/// [`diff_words`] with a limit on the LCS table size. When the changed words
+/// of both sides need more than `max_cells`, everything between the common
+/// prefix and suffix is reported as one change.
+pub fn diff_words_bounded(
+    old: &str,
+    new: &str,
+    max_cells: usize,
+) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let old_chars: Vec<char> = old.chars().collect();
+    let new_chars: Vec<char> = new.chars().collect();
+    let old_words = split_words(&old_chars);
+    let new_words = split_words(&new_chars);
+    let old_tokens: Vec<&[char]> = old_words.iter().map(|r| &old_chars[r.clone()]).collect();
+    let new_tokens: Vec<&[char]> = new_words.iter().map(|r| &new_chars[r.clone()]).collect();
+
+    let (prefix, old_middle, new_middle) = trim_common(&old_tokens, &new_tokens);
+    let mut old_ranges = Vec::new();
+    let mut new_ranges = Vec::new();
+    for (old_run, new_run) in changed_runs(old_middle, new_middle, max_cells) {
+        if !old_run.is_empty() {
+            old_ranges.push(
+                old_words[prefix + old_run.start].start..old_words[prefix + old_run.end - 1].end,
+            );
+        }
+        if !new_run.is_empty() {
+            new_ranges.push(
+                new_words[prefix + new_run.start].start..new_words[prefix + new_run.end - 1].end,
+            );
+        }
+    }
+    (merge_ranges(old_ranges), merge_ranges(new_ranges))
+}
+
+/// Split characters into word, whitespace and punctuation tokens
+fn split_words(chars: &[char]) -> Vec<Range<usize>> {
+    #[derive(PartialEq)]
+    enum Class {
+        Word,
+        Space,
+        Other,
+    }
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            Class::Word
+        } else if c.is_whitespace() {
+            Class::Space
+        } else {
+            Class::Other
+        }
+    };
+
+    let mut words = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let first = class(chars[start]);
+        let mut end = start + 1;
+        if first != Class::Other {
+            while end < chars.len() && class(chars[end]) == first {
+                end += 1;
+            }
+        }
+        words.push(start..end);
+        start = end;
+    }
+    words
+}
+
+/// Skip the items `old` and `new` start and end with
+///
+/// Returns the length of the common prefix and the differing middles.
+fn trim_common<'a, T: PartialEq>(old: &'a [T], new: &'a [T]) -> (usize, &'a [T], &'a [T]) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let max_suffix = (old.len() - prefix).min(new.len() - prefix);
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    (
+        prefix,
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    )
+}
+
+/// Pair up the runs of items of `old` and `new` that are not in their LCS
+fn changed_runs<T: PartialEq>(
+    old: &[T],
+    new: &[T],
+    max_cells: usize,
+) -> Vec<(Range<usize>, Range<usize>)> {
+    let matches = if old.len().saturating_mul(new.len()) > max_cells {
+        vec![]
+    } else {
+        longest_common_subsequence(old, new)
+    };
+    let end = LineMatch {
+        saved_idx: old.len(),
+        current_idx: new.len(),
+    };
+
+    let mut runs = Vec::new();
+    let mut old_idx = 0;
+    let mut new_idx = 0;
+    for m in matches.iter().chain(std::iter::once(&end)) {
+        if m.saved_idx > old_idx || m.current_idx > new_idx {
+            runs.push((old_idx..m.saved_idx, new_idx..m.current_idx));
+        }
+        old_idx = m.saved_idx + 1;
+        new_idx = m.current_idx + 1;
+    }
+    runs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -486,6 +649,61 @@ mod tests {
         assert!(!diff.equal);
         assert_eq!(diff.changed_lines, vec![0..1]);
     }
+
+    #[test]
+    fn test_diff_hunks() {
+        assert!(diff_hunks(b"a\nb\n", b"a\nb\n").is_empty());
+
+        let old = b"a\nb\nc\nd\ne\n";
+        let new = b"a\nB\nc\nd\nnew\ne\n";
+        let hunks = diff_hunks(old, new);
+        let ranges: Vec<_> = hunks
+            .iter()
+            .map(|h| (h.old_start, h.old_count, h.new_start, h.new_count))
+            .collect();
+        // "b" became "B"; "new" was inserted before "e"
+        assert_eq!(ranges, vec![(1, 1, 1, 1), (4, 0, 4, 1)]);
+
+        let hunks = diff_hunks(b"a\nb\nc", b"c");
+        assert_eq!(
+            hunks
+                .iter()
+                .map(|h| (h.old_start, h.old_count, h.new_start, h.new_count))
+                .collect::<Vec<_>>(),
+            vec![(0, 2, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn test_diff_words() {
+        let (old, new) = diff_words("let x = foo(1);", "let y = foo(1, 2);");
+        assert_eq!(old, vec![4..5]);
+        assert_eq!(new, vec![4..5, 13..16]);
+
+        // Ranges are in characters, not bytes
+        let (old, new) = diff_words("héllo wörld", "héllo world");
+        assert_eq!(old, vec![6..11]);
+        assert_eq!(new, vec![6..11]);
+
+        assert_eq!(diff_words("same", "same"), (vec![], vec![]));
+        let (old, new) = diff_words("", "added");
+        assert!(old.is_empty());
+        assert_eq!(new, vec![0..5]);
+    }
+
+    #[test]
+    fn test_diff_words_bounded() {
+        // Within the limit the result matches the unbounded diff
+        assert_eq!(
+            diff_words_bounded("a b c d", "a x c y", 100),
+            diff_words("a b c d", "a x c y")
+        );
+
+        // Past the limit the middle between common prefix and suffix is one change
+        let (old, new) = diff_words_bounded("a b c d e", "a x c y e", 4);
+        assert_eq!(old, vec![2..7]);
+        assert_eq!(new, vec![2..7]);
+    }
 }
 
 #[cfg(test)]
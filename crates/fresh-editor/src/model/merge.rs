@@ -0,0 +1,356 @@
+//! Three-way line merge
+//!
+//! Merges two versions of a text ("ours" and "theirs") that were both derived
+//! from a common base, the way `git merge-file` does: the changes each side
+//! made relative to the base (from [`diff_hunks`]) are combined, and where
+//! both sides changed the same lines differently the region is a conflict.
+//!
+//! Conflicts are written to the merged text as `<<<<<<<` / `=======` /
+//! `>>>>>>>` marker blocks, which [`find_conflict_blocks`] finds again so that
+//! they can be resolved after the text has been edited.
+
+use crate::model::composite_buffer::DiffHunk;
+use crate::model::line_diff::diff_hunks;
+use std::ops::Range;
+
+/// How a region of a three-way merge is resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeKind {
+    /// No side changed these lines
+    Unchanged,
+    /// Only ours changed these lines
+    Ours,
+    /// Only theirs changed these lines
+    Theirs,
+    /// Both sides made the same change
+    Both,
+    /// Both sides changed these lines differently
+    Conflict,
+}
+
+/// A region of a three-way merge, as line ranges of each version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeRegion {
+    pub kind: MergeKind,
+    pub base: Range<usize>,
+    pub ours: Range<usize>,
+    pub theirs: Range<usize>,
+}
+
+/// A conflict marker block found in a text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictBlock {
+    /// Byte range of the whole block, including the newline after the
+    /// closing marker
+    pub range: Range<usize>,
+    /// Line of the opening marker
+    pub start_line: usize,
+    /// Lines between the opening marker and the base or separator marker,
+    /// each ending with a newline
+    pub ours: String,
+    /// Lines between the separator and the closing marker, each ending with
+    /// a newline
+    pub theirs: String,
+}
+
+/// Split a text into lines the way [`diff_hunks`] does
+pub fn split_lines(text: &str) -> Vec<&str> {
+    text.split('\n').collect()
+}
+
+/// Merge the changes `ours` and `theirs` made to `base`
+///
+/// Returns the regions covering all three texts in order. Changes that touch
+/// or overlap in the base are merged into one region.
+pub fn three_way_merge(base: &str, ours: &str, theirs: &str) -> Vec<MergeRegion> {
+    let base_lines = split_lines(base);
+    let ours_lines = split_lines(ours);
+    let theirs_lines = split_lines(theirs);
+    let ours_hunks = diff_hunks(base.as_bytes(), ours.as_bytes());
+    let theirs_hunks = diff_hunks(base.as_bytes(), theirs.as_bytes());
+
+    let mut regions = Vec::new();
+    // Line of each version the next region starts at
+    let (mut base_pos, mut ours_pos, mut theirs_pos) = (0, 0, 0);
+    let (mut o, mut t) = (0, 0);
+    while o < ours_hunks.len() || t < theirs_hunks.len() {
+        // Start the group with whichever hunk comes first in the base, then
+        // pull in every hunk of either side that touches it
+        let first = match (ours_hunks.get(o), theirs_hunks.get(t)) {
+            (Some(a), Some(b)) => a.old_start.min(b.old_start),
+            (Some(a), None) => a.old_start,
+            (None, Some(b)) => b.old_start,
+            (None, None) => unreachable!(),
+        };
+        let (group_start, mut group_end) = (first, first);
+        let (ours_from, theirs_from) = (o, t);
+        loop {
+            if let Some(h) = ours_hunks.get(o).filter(|h| h.old_start <= group_end) {
+                group_end = group_end.max(h.old_start + h.old_count);
+                o += 1;
+            } else if let Some(h) = theirs_hunks.get(t).filter(|h| h.old_start <= group_end) {
+                group_end = group_end.max(h.old_start + h.old_count);
+                t += 1;
+            } else {
+                break;
+            }
+        }
+
+        if group_start > base_pos {
+            let len = group_start - base_pos;
+            regions.push(MergeRegion {
+                kind: MergeKind::Unchanged,
+                base: base_pos..group_start,
+                ours: ours_pos..ours_pos + len,
+                theirs: theirs_pos..theirs_pos + len,
+            });
+            ours_pos += len;
+            theirs_pos += len;
+        }
+
+        let ours_end = side_end(&ours_hunks[ours_from..o], group_start, group_end, ours_pos);
+        let theirs_end = side_end(
+            &theirs_hunks[theirs_from..t],
+            group_start,
+            group_end,
+            theirs_pos,
+        );
+        let kind = match (o > ours_from, t > theirs_from) {
+            (true, false) => MergeKind::Ours,
+            (false, true) => MergeKind::Theirs,
+            _ if ours_lines[ours_pos..ours_end] == theirs_lines[theirs_pos..theirs_end] => {
+                MergeKind::Both
+            }
+            _ => MergeKind::Conflict,
+        };
+        regions.push(MergeRegion {
+            kind,
+            base: group_start..group_end,
+            ours: ours_pos..ours_end,
+            theirs: theirs_pos..theirs_end,
+        });
+        base_pos = group_end;
+        ours_pos = ours_end;
+        theirs_pos = theirs_end;
+    }
+
+    if base_pos < base_lines.len() {
+        regions.push(MergeRegion {
+            kind: MergeKind::Unchanged,
+            base: base_pos..base_lines.len(),
+            ours: ours_pos..ours_lines.len(),
+            theirs: theirs_pos..theirs_lines.len(),
+        });
+    }
+    regions
+}
+
+/// Where a side's version of the base lines `start..end` ends, given the
+/// side's hunks within them and where its version of them starts
+fn side_end(hunks: &[DiffHunk], start: usize, end: usize, side_start: usize) -> usize {
+    let growth: isize = hunks
+        .iter()
+        .map(|h| h.new_count as isize - h.old_count as isize)
+        .sum();
+    (side_start as isize + (end - start) as isize + growth) as usize
+}
+
+/// Build the merged text, with conflict marker blocks for the conflicts
+///
+/// Returns the text and the number of conflicts in it.
+pub fn merged_text(
+    regions: &[MergeRegion],
+    ours: &str,
+    theirs: &str,
+    ours_label: &str,
+    theirs_label: &str,
+) -> (String, usize) {
+    let ours_lines = split_lines(ours);
+    let theirs_lines = split_lines(theirs);
+    let mut lines: Vec<String> = Vec::new();
+    let mut conflicts = 0;
+    for region in regions {
+        match region.kind {
+            MergeKind::Unchanged | MergeKind::Ours | MergeKind::Both => {
+                lines.extend(
+                    ours_lines[region.ours.clone()]
+                        .iter()
+                        .map(|l| l.to_string()),
+                );
+            }
+            MergeKind::Theirs => {
+                lines.extend(
+                    theirs_lines[region.theirs.clone()]
+                        .iter()
+                        .map(|l| l.to_string()),
+                );
+            }
+            MergeKind::Conflict => {
+                conflicts += 1;
+                lines.push(format!("<<<<<<< {}", ours_label));
+                lines.extend(
+                    ours_lines[region.ours.clone()]
+                        .iter()
+                        .map(|l| l.to_string()),
+                );
+                lines.push("=======".to_string());
+                lines.extend(
+                    theirs_lines[region.theirs.clone()]
+                        .iter()
+                        .map(|l| l.to_string()),
+                );
+                lines.push(format!(">>>>>>> {}", theirs_label));
+            }
+        }
+    }
+    (lines.join("\n"), conflicts)
+}
+
+/// Find the conflict marker blocks of a text
+///
+/// Understands both the plain and the diff3 style (with a `|||||||` base
+/// section). Incomplete blocks are ignored.
+pub fn find_conflict_blocks(text: &str) -> Vec<ConflictBlock> {
+    enum Section {
+        Outside,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut blocks = Vec::new();
+    let mut section = Section::Outside;
+    let (mut start, mut start_line) = (0, 0);
+    let (mut ours, mut theirs) = (String::new(), String::new());
+    let mut offset = 0;
+    for (line_idx, line) in text.split_inclusive('\n').enumerate() {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let line_start = offset;
+        offset += line.len();
+
+        if content.starts_with("<<<<<<<") {
+            section = Section::Ours;
+            start = line_start;
+            start_line = line_idx;
+            ours.clear();
+            theirs.clear();
+            continue;
+        }
+        match section {
+            Section::Outside => {}
+            Section::Ours | Section::Base if content.starts_with("=======") => {
+                section = Section::Theirs;
+            }
+            Section::Ours if content.starts_with("|||||||") => section = Section::Base,
+            Section::Ours => push_line(&mut ours, line),
+            Section::Base => {}
+            Section::Theirs if content.starts_with(">>>>>>>") => {
+                blocks.push(ConflictBlock {
+                    range: start..offset,
+                    start_line,
+                    ours: std::mem::take(&mut ours),
+                    theirs: std::mem::take(&mut theirs),
+                });
+                section = Section::Outside;
+            }
+            Section::Theirs => push_line(&mut theirs, line),
+        }
+    }
+    blocks
+}
+
+/// Append a line, making sure it ends with a newline
+fn push_line(text: &mut String, line: &str) {
+    text.push_str(line);
+    if !line.ends_with('\n') {
+        text.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(regions: &[MergeRegion]) -> Vec<MergeKind> {
+        regions.iter().map(|r| r.kind).collect()
+    }
+
+    #[test]
+    fn test_three_way_merge_clean() {
+        let base = "a\nb\nc\nd\ne\n";
+        let ours = "a\nB\nc\nd\ne\n";
+        let theirs = "a\nb\nc\nd\nE\n";
+        let regions = three_way_merge(base, ours, theirs);
+        assert_eq!(
+            kinds(&regions),
+            vec![
+                MergeKind::Unchanged,
+                MergeKind::Ours,
+                MergeKind::Unchanged,
+                MergeKind::Theirs,
+                MergeKind::Unchanged,
+            ]
+        );
+        let (text, conflicts) = merged_text(&regions, ours, theirs, "ours", "theirs");
+        assert_eq!(text, "a\nB\nc\nd\nE\n");
+        assert_eq!(conflicts, 0);
+    }
+
+    #[test]
+    fn test_three_way_merge_insertions_shift_lines() {
+        let base = "a\nb\nc\n";
+        let ours = "x\ny\na\nb\nc\n";
+        let theirs = "a\nb\nC\n";
+        let regions = three_way_merge(base, ours, theirs);
+        let (text, conflicts) = merged_text(&regions, ours, theirs, "ours", "theirs");
+        assert_eq!(text, "x\ny\na\nb\nC\n");
+        assert_eq!(conflicts, 0);
+    }
+
+    #[test]
+    fn test_three_way_merge_conflict() {
+        let base = "a\nb\nc\n";
+        let ours = "a\nours\nc\n";
+        let theirs = "a\ntheirs\nc\n";
+        let regions = three_way_merge(base, ours, theirs);
+        assert_eq!(regions[1].kind, MergeKind::Conflict);
+        assert_eq!(
+            (
+                regions[1].base.clone(),
+                regions[1].ours.clone(),
+                regions[1].theirs.clone()
+            ),
+            (1..2, 1..2, 1..2)
+        );
+        let (text, conflicts) = merged_text(&regions, ours, theirs, "LOCAL", "REMOTE");
+        assert_eq!(
+            text,
+            "a\n<<<<<<< LOCAL\nours\n=======\ntheirs\n>>>>>>> REMOTE\nc\n"
+        );
+        assert_eq!(conflicts, 1);
+
+        // The same change on both sides is not a conflict
+        let regions = three_way_merge(base, ours, ours);
+        assert_eq!(regions[1].kind, MergeKind::Both);
+    }
+
+    #[test]
+    fn test_find_conflict_blocks() {
+        let text = "a\n<<<<<<< LOCAL\nours\n=======\ntheirs\n>>>>>>> REMOTE\nc\n\
+                    <<<<<<< HEAD\nx\n||||||| base\nb\n=======\n>>>>>>> other";
+        let blocks = find_conflict_blocks(text);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].start_line, 1);
+        assert_eq!(blocks[0].range, 2..51);
+        assert_eq!(blocks[0].ours, "ours\n");
+        assert_eq!(blocks[0].theirs, "theirs\n");
+        assert_eq!(blocks[1].start_line, 7);
+        assert_eq!(blocks[1].range.end, text.len());
+        assert_eq!(
+            (blocks[1].ours.as_str(), blocks[1].theirs.as_str()),
+            ("x\n", "")
+        );
+
+        assert!(find_conflict_blocks("<<<<<<< open\nno end\n").is_empty());
+    }
+}
@@ -13,5 +13,6 @@ pub mod filesystem;
 pub mod line_diff;
 pub mod marker;
 pub mod marker_tree;
+pub mod merge;
 pub mod piece_tree;
 pub mod piece_tree_diff;
@@ -6,7 +6,8 @@
 use crate::config::{
     AcceptSuggestionOnEnter, CursorStyle, FileBrowserConfig, FileExplorerConfig, FormatterConfig,
     HighlighterPreference, Keybinding, KeybindingMapName, KeymapConfig, LanguageConfig,
-    LineEndingOption, OnSaveAction, PluginConfig, TerminalConfig, ThemeName, WarningsConfig,
+    LineEndingOption, OnSaveAction, PluginCapabilities, PluginConfig, PluginRuntimeConfig,
+    TerminalConfig, ThemeName, WarningsConfig,
 };
 use crate::types::LspServerConfig;
 use serde::{Deserialize, Serialize};
@@ -88,6 +89,7 @@ pub struct PartialConfig {
     pub lsp: Option<HashMap<String, LspServerConfig>>,
     pub warnings: Option<PartialWarningsConfig>,
     pub plugins: Option<HashMap<String, PartialPluginConfig>>,
+    pub plugin_runtime: Option<PartialPluginRuntimeConfig>,
     pub packages: Option<PartialPackagesConfig>,
 }
 
@@ -104,6 +106,7 @@ impl Merge for PartialConfig {
         merge_partial(&mut self.file_browser, &other.file_browser);
         merge_partial(&mut self.terminal, &other.terminal);
         merge_partial(&mut self.warnings, &other.warnings);
+        merge_partial(&mut self.plugin_runtime, &other.plugin_runtime);
         merge_partial(&mut self.packages, &other.packages);
 
         // Lists: higher precedence replaces (per design doc)
@@ -304,6 +307,25 @@ impl Merge for PartialWarningsConfig {
     }
 }
 
+/// Partial plugin runtime limits configuration.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PartialPluginRuntimeConfig {
+    pub call_timeout_ms: Option<u64>,
+    pub memory_limit_mb: Option<u64>,
+    pub storage_quota_kb: Option<u64>,
+    pub hook_timeout_ms: Option<u64>,
+}
+
+impl Merge for PartialPluginRuntimeConfig {
+    fn merge_from(&mut self, other: &Self) {
+        self.call_timeout_ms.merge_from(&other.call_timeout_ms);
+        self.memory_limit_mb.merge_from(&other.memory_limit_mb);
+        self.storage_quota_kb.merge_from(&other.storage_quota_kb);
+        self.hook_timeout_ms.merge_from(&other.hook_timeout_ms);
+    }
+}
+
 /// Partial packages configuration for plugin/theme package management.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(default)]
@@ -325,12 +347,15 @@ pub struct PartialPluginConfig {
     pub enabled: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<std::path::PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub granted: Option<PluginCapabilities>,
 }
 
 impl Merge for PartialPluginConfig {
     fn merge_from(&mut self, other: &Self) {
         self.enabled.merge_from(&other.enabled);
         self.path.merge_from(&other.path);
+        self.granted.merge_from(&other.granted);
     }
 }
 
@@ -614,6 +639,28 @@ impl PartialWarningsConfig {
     }
 }
 
+impl From<&PluginRuntimeConfig> for PartialPluginRuntimeConfig {
+    fn from(cfg: &PluginRuntimeConfig) -> Self {
+        Self {
+            call_timeout_ms: Some(cfg.call_timeout_ms),
+            memory_limit_mb: Some(cfg.memory_limit_mb),
+            storage_quota_kb: Some(cfg.storage_quota_kb),
+            hook_timeout_ms: Some(cfg.hook_timeout_ms),
+        }
+    }
+}
+
+impl PartialPluginRuntimeConfig {
+    pub fn resolve(self, defaults: &PluginRuntimeConfig) -> PluginRuntimeConfig {
+        PluginRuntimeConfig {
+            call_timeout_ms: self.call_timeout_ms.unwrap_or(defaults.call_timeout_ms),
+            memory_limit_mb: self.memory_limit_mb.unwrap_or(defaults.memory_limit_mb),
+            storage_quota_kb: self.storage_quota_kb.unwrap_or(defaults.storage_quota_kb),
+            hook_timeout_ms: self.hook_timeout_ms.unwrap_or(defaults.hook_timeout_ms),
+        }
+    }
+}
+
 impl From<&crate::config::PackagesConfig> for PartialPackagesConfig {
     fn from(cfg: &crate::config::PackagesConfig) -> Self {
         Self {
@@ -638,6 +685,7 @@ impl From<&PluginConfig> for PartialPluginConfig {
         Self {
             enabled: Some(cfg.enabled),
             path: cfg.path.clone(),
+            granted: Some(cfg.granted.clone()),
         }
     }
 }
@@ -647,6 +695,7 @@ impl PartialPluginConfig {
         PluginConfig {
             enabled: self.enabled.unwrap_or(defaults.enabled),
             path: self.path.or_else(|| defaults.path.clone()),
+            granted: self.granted.unwrap_or_else(|| defaults.granted.clone()),
         }
     }
 }
@@ -728,13 +777,16 @@ impl From<&crate::config::Config> for PartialConfig {
                 let non_default_plugins: HashMap<String, PartialPluginConfig> = cfg
                     .plugins
                     .iter()
-                    .filter(|(_, v)| v.enabled != default_plugin.enabled)
+                    .filter(|(_, v)| {
+                        v.enabled != default_plugin.enabled || v.granted != default_plugin.granted
+                    })
                     .map(|(k, v)| {
                         (
                             k.clone(),
                             PartialPluginConfig {
                                 enabled: Some(v.enabled),
                                 path: None, // Don't save path - it's auto-discovered
+                                granted: (!v.granted.is_empty()).then(|| v.granted.clone()),
                             },
                         )
                     })
@@ -745,6 +797,7 @@ impl From<&crate::config::Config> for PartialConfig {
                     Some(non_default_plugins)
                 }
             },
+            plugin_runtime: Some(PartialPluginRuntimeConfig::from(&cfg.plugin_runtime)),
             packages: Some(PartialPackagesConfig::from(&cfg.packages)),
         }
     }
@@ -847,6 +900,10 @@ impl PartialConfig {
                 .map(|e| e.resolve(&defaults.warnings))
                 .unwrap_or_else(|| defaults.warnings.clone()),
             plugins,
+            plugin_runtime: self
+                .plugin_runtime
+                .map(|e| e.resolve(&defaults.plugin_runtime))
+                .unwrap_or_else(|| defaults.plugin_runtime.clone()),
             packages: self
                 .packages
                 .map(|e| e.resolve(&defaults.packages))
@@ -1178,6 +1235,7 @@ mod tests {
             PluginConfig {
                 enabled: true, // Default value
                 path: Some(std::path::PathBuf::from("/path/to/plugin.ts")),
+                ..Default::default()
             },
         );
 
@@ -1199,6 +1257,7 @@ mod tests {
             PluginConfig {
                 enabled: true,
                 path: Some(std::path::PathBuf::from("/path/to/enabled.ts")),
+                ..Default::default()
             },
         );
         config.plugins.insert(
@@ -1206,6 +1265,7 @@ mod tests {
             PluginConfig {
                 enabled: false, // Not default!
                 path: Some(std::path::PathBuf::from("/path/to/disabled.ts")),
+                ..Default::default()
             },
         );
 
@@ -1238,6 +1298,7 @@ mod tests {
             PluginConfig {
                 enabled: false,
                 path: Some(std::path::PathBuf::from("/some/path/plugin.ts")),
+                ..Default::default()
             },
         );
 
@@ -1260,6 +1321,7 @@ mod tests {
                 PartialPluginConfig {
                     enabled: Some(false),
                     path: None,
+                    granted: None,
                 },
             )])),
             ..Default::default()
@@ -1288,6 +1350,7 @@ mod tests {
                 PartialPluginConfig {
                     enabled: Some(false), // User disabled
                     path: None,
+                    granted: None,
                 },
             )])),
             ..Default::default()
@@ -1299,6 +1362,7 @@ mod tests {
                 PartialPluginConfig {
                     enabled: Some(true), // Lower layer has it enabled
                     path: None,
+                    granted: None,
                 },
             )])),
             ..Default::default()
@@ -1325,6 +1389,7 @@ mod tests {
             PluginConfig {
                 enabled: true,
                 path: Some(std::path::PathBuf::from("/a.ts")),
+                ..Default::default()
             },
         );
         config.plugins.insert(
@@ -1332,6 +1397,7 @@ mod tests {
             PluginConfig {
                 enabled: false,
                 path: Some(std::path::PathBuf::from("/b.ts")),
+                ..Default::default()
             },
         );
         config.plugins.insert(
@@ -1339,6 +1405,7 @@ mod tests {
             PluginConfig {
                 enabled: true,
                 path: Some(std::path::PathBuf::from("/c.ts")),
+                ..Default::default()
             },
         );
 
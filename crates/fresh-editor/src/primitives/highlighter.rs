@@ -19,6 +19,30 @@ pub use fresh_languages::{HighlightCategory, Language};
 use ratatui::style::Color;
 use std::ops::Range;
 
+/// Get the tree-sitter grammar for a language
+pub fn tree_sitter_language(language: &Language) -> fresh_languages::tree_sitter::Language {
+    match language {
+        Language::Rust => fresh_languages::tree_sitter_rust::LANGUAGE.into(),
+        Language::Python => fresh_languages::tree_sitter_python::LANGUAGE.into(),
+        Language::JavaScript => fresh_languages::tree_sitter_javascript::LANGUAGE.into(),
+        Language::TypeScript => fresh_languages::tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        Language::Go => fresh_languages::tree_sitter_go::LANGUAGE.into(),
+        Language::C => fresh_languages::tree_sitter_c::LANGUAGE.into(),
+        Language::Cpp => fresh_languages::tree_sitter_cpp::LANGUAGE.into(),
+        Language::Java => fresh_languages::tree_sitter_java::LANGUAGE.into(),
+        Language::Php => fresh_languages::tree_sitter_php::LANGUAGE_PHP.into(),
+        Language::Ruby => fresh_languages::tree_sitter_ruby::LANGUAGE.into(),
+        Language::Bash => fresh_languages::tree_sitter_bash::LANGUAGE.into(),
+        Language::Lua => fresh_languages::tree_sitter_lua::LANGUAGE.into(),
+        Language::Pascal => fresh_languages::tree_sitter_pascal::LANGUAGE.into(),
+        Language::Json => fresh_languages::tree_sitter_json::LANGUAGE.into(),
+        Language::HTML => fresh_languages::tree_sitter_html::LANGUAGE.into(),
+        Language::CSS => fresh_languages::tree_sitter_css::LANGUAGE.into(),
+        Language::CSharp => fresh_languages::tree_sitter_c_sharp::LANGUAGE.into(),
+        Language::Odin => fresh_languages::tree_sitter_odin::LANGUAGE.into(),
+    }
+}
+
 /// Maximum bytes to parse in a single operation (for viewport highlighting)
 const MAX_PARSE_BYTES: usize = LARGE_FILE_THRESHOLD_BYTES as usize; // 1MB
 
@@ -64,3 +64,5 @@ pub mod highlighter;
 pub mod indent;
 #[cfg(feature = "runtime")]
 pub mod reference_highlighter;
+#[cfg(feature = "runtime")]
+pub mod syntax_query;
@@ -22,7 +22,7 @@
 //! - Other languages fall back to identifier or text matching
 
 use crate::model::buffer::Buffer;
-use crate::primitives::highlighter::{HighlightSpan, Language};
+use crate::primitives::highlighter::{tree_sitter_language, HighlightSpan, Language};
 use crate::primitives::word_navigation::{find_word_end, find_word_start, is_word_char};
 use fresh_languages::tree_sitter::{Parser, Query, QueryCursor, StreamingIterator};
 use ratatui::style::Color;
@@ -203,28 +203,7 @@ impl ReferenceHighlighter {
     /// This enables syntax-aware identifier matching for the given language.
     /// If the language is not supported or parsing fails, falls back to text matching.
     pub fn set_language(&mut self, language: &Language) {
-        let ts_language = match language {
-            Language::Rust => fresh_languages::tree_sitter_rust::LANGUAGE.into(),
-            Language::Python => fresh_languages::tree_sitter_python::LANGUAGE.into(),
-            Language::JavaScript => fresh_languages::tree_sitter_javascript::LANGUAGE.into(),
-            Language::TypeScript => {
-                fresh_languages::tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()
-            }
-            Language::Go => fresh_languages::tree_sitter_go::LANGUAGE.into(),
-            Language::C => fresh_languages::tree_sitter_c::LANGUAGE.into(),
-            Language::Cpp => fresh_languages::tree_sitter_cpp::LANGUAGE.into(),
-            Language::Java => fresh_languages::tree_sitter_java::LANGUAGE.into(),
-            Language::Php => fresh_languages::tree_sitter_php::LANGUAGE_PHP.into(),
-            Language::Ruby => fresh_languages::tree_sitter_ruby::LANGUAGE.into(),
-            Language::Bash => fresh_languages::tree_sitter_bash::LANGUAGE.into(),
-            Language::Lua => fresh_languages::tree_sitter_lua::LANGUAGE.into(),
-            Language::Pascal => fresh_languages::tree_sitter_pascal::LANGUAGE.into(),
-            Language::Json => fresh_languages::tree_sitter_json::LANGUAGE.into(),
-            Language::HTML => fresh_languages::tree_sitter_html::LANGUAGE.into(),
-            Language::CSS => fresh_languages::tree_sitter_css::LANGUAGE.into(),
-            Language::CSharp => fresh_languages::tree_sitter_c_sharp::LANGUAGE.into(),
-            Language::Odin => fresh_languages::tree_sitter_odin::LANGUAGE.into(),
-        };
+        let ts_language = tree_sitter_language(language);
 
         // Create parser
         let mut parser = Parser::new();
@@ -0,0 +1,212 @@
+//! Structural queries over a buffer's syntax tree
+//!
+//! Backs the plugin syntax APIs: the chain of nodes under an offset, the
+//! innermost node of a given kind, and the captures of an ad-hoc tree-sitter
+//! query. The buffer is parsed on demand and its tree kept until the buffer
+//! changes.
+
+use crate::model::buffer::Buffer;
+use crate::primitives::highlighter::{tree_sitter_language, Language};
+use fresh_core::api::{SyntaxCapture, SyntaxNode};
+use fresh_languages::tree_sitter::{self, Node, Parser, Query, QueryCursor, StreamingIterator};
+use std::ops::Range;
+
+/// A parsed buffer, holding the source the tree's byte ranges refer to
+pub struct SyntaxTree {
+    tree: tree_sitter::Tree,
+    source: Vec<u8>,
+    language: Language,
+    /// Buffer version the tree was parsed from
+    version: u64,
+}
+
+impl SyntaxTree {
+    pub fn parse(buffer: &Buffer, language: &Language) -> Result<Self, String> {
+        let source = buffer
+            .get_all_text()
+            .ok_or_else(|| "Buffer is not fully loaded".to_string())?;
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_language(language))
+            .map_err(|e| format!("Failed to load {} grammar: {}", language, e))?;
+        let tree = parser
+            .parse(&source, None)
+            .ok_or_else(|| format!("Failed to parse buffer as {}", language))?;
+        Ok(Self {
+            tree,
+            source,
+            language: *language,
+            version: buffer.version(),
+        })
+    }
+}
+
+/// A buffer's last syntax tree, reused by queries until the buffer changes
+#[derive(Default)]
+pub struct SyntaxTreeCache {
+    tree: Option<SyntaxTree>,
+}
+
+impl SyntaxTreeCache {
+    /// Get the buffer's syntax tree, parsing it only if the buffer or its
+    /// language changed since the last call
+    pub fn get(&mut self, buffer: &Buffer, language: &Language) -> Result<&SyntaxTree, String> {
+        let stale = self
+            .tree
+            .as_ref()
+            .is_none_or(|tree| tree.version != buffer.version() || tree.language != *language);
+        if stale {
+            self.tree = None;
+            self.tree = Some(SyntaxTree::parse(buffer, language)?);
+        }
+        Ok(self.tree.as_ref().expect("tree was just parsed"))
+    }
+}
+
+fn to_syntax_node(node: Node) -> SyntaxNode {
+    SyntaxNode {
+        kind: node.kind().to_string(),
+        start: node.start_byte(),
+        end: node.end_byte(),
+    }
+}
+
+/// Named nodes containing `offset`, from the root down to the innermost
+fn ancestors_at(tree: &tree_sitter::Tree, offset: usize) -> Vec<Node<'_>> {
+    let root = tree.root_node();
+    let Some(mut node) = root.named_descendant_for_byte_range(offset, offset) else {
+        return vec![root];
+    };
+    let mut path = vec![node];
+    while let Some(parent) = node.parent() {
+        path.push(parent);
+        node = parent;
+    }
+    path.reverse();
+    path
+}
+
+/// Get the chain of named nodes under `offset`, outermost first
+pub fn node_path(tree: &SyntaxTree, offset: usize) -> Vec<SyntaxNode> {
+    ancestors_at(&tree.tree, offset)
+        .into_iter()
+        .map(to_syntax_node)
+        .collect()
+}
+
+/// Get the innermost node of `kind` that contains `offset`
+pub fn enclosing_node(tree: &SyntaxTree, offset: usize, kind: &str) -> Option<SyntaxNode> {
+    ancestors_at(&tree.tree, offset)
+        .into_iter()
+        .rev()
+        .find(|node| node.kind() == kind)
+        .map(to_syntax_node)
+}
+
+/// Run a tree-sitter query and return its captures in document order.
+///
+/// With `range`, only matches intersecting that byte range are returned.
+pub fn query_captures(
+    tree: &SyntaxTree,
+    query_source: &str,
+    range: Option<Range<usize>>,
+) -> Result<Vec<SyntaxCapture>, String> {
+    let query = Query::new(&tree_sitter_language(&tree.language), query_source)
+        .map_err(|e| format!("Invalid query: {}", e))?;
+
+    let mut cursor = QueryCursor::new();
+    if let Some(range) = range {
+        cursor.set_byte_range(range);
+    }
+    let names = query.capture_names();
+    let mut results = Vec::new();
+    let mut captures = cursor.captures(&query, tree.tree.root_node(), tree.source.as_slice());
+    while let Some((m, index)) = captures.next() {
+        let capture = m.captures[*index];
+        let node = to_syntax_node(capture.node);
+        results.push(SyntaxCapture {
+            name: names[capture.index as usize].to_string(),
+            kind: node.kind,
+            start: node.start,
+            end: node.end,
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str =
+        "fn main() {\n    let x = add(1, 2);\n}\n\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+
+    fn rust_tree() -> SyntaxTree {
+        SyntaxTree::parse(&Buffer::from_str_test(SOURCE), &Language::Rust).unwrap()
+    }
+
+    #[test]
+    fn test_node_path_ends_at_innermost_node() {
+        let tree = rust_tree();
+        let offset = SOURCE.find("add(1").unwrap();
+        let path = node_path(&tree, offset);
+
+        assert_eq!(path.first().unwrap().kind, "source_file");
+        let innermost = path.last().unwrap();
+        assert_eq!(innermost.kind, "identifier");
+        assert_eq!(innermost.start, offset);
+        assert_eq!(innermost.end, offset + 3);
+        assert!(path.iter().any(|n| n.kind == "call_expression"));
+    }
+
+    #[test]
+    fn test_enclosing_node_finds_nearest_kind() {
+        let tree = rust_tree();
+        let offset = SOURCE.find("a + b").unwrap();
+        let function = enclosing_node(&tree, offset, "function_item").unwrap();
+        assert_eq!(function.start, SOURCE.find("fn add").unwrap());
+        assert_eq!(function.end, SOURCE.len() - 1);
+
+        assert!(enclosing_node(&tree, offset, "struct_item").is_none());
+    }
+
+    #[test]
+    fn test_query_captures_in_range() {
+        let tree = rust_tree();
+        let query = "(function_item name: (identifier) @name)";
+
+        let all = query_captures(&tree, query, None).unwrap();
+        let names: Vec<_> = all.iter().map(|c| &SOURCE[c.start..c.end]).collect();
+        assert_eq!(names, vec!["main", "add"]);
+        assert!(all
+            .iter()
+            .all(|c| c.name == "name" && c.kind == "identifier"));
+
+        let second_half = SOURCE.find("fn add").unwrap()..SOURCE.len();
+        let ranged = query_captures(&tree, query, Some(second_half)).unwrap();
+        assert_eq!(ranged.len(), 1);
+        assert_eq!(&SOURCE[ranged[0].start..ranged[0].end], "add");
+    }
+
+    #[test]
+    fn test_cache_reparses_only_after_changes() {
+        let mut buffer = Buffer::from_str_test(SOURCE);
+        let mut cache = SyntaxTreeCache::default();
+        let version = cache.get(&buffer, &Language::Rust).unwrap().version;
+        assert_eq!(
+            cache.get(&buffer, &Language::Rust).unwrap().version,
+            version
+        );
+
+        buffer.insert(0, "struct S;\n");
+        let tree = cache.get(&buffer, &Language::Rust).unwrap();
+        assert_ne!(tree.version, version);
+        assert!(enclosing_node(tree, 0, "struct_item").is_some());
+    }
+
+    #[test]
+    fn test_invalid_query_is_an_error() {
+        let err = query_captures(&rust_tree(), "(not_a_node) @x", None).unwrap_err();
+        assert!(err.starts_with("Invalid query"));
+    }
+}
@@ -0,0 +1,325 @@
+//! Single-instance server: open files in an editor that is already running
+//!
+//! An editor started with `--server` listens on a per-user Unix socket.
+//! `fresh --remote file:line` hands its files to that editor instead of
+//! starting a new one, and `fresh --wait file` then blocks until the editor
+//! has closed them, so Fresh can be `$EDITOR` or `GIT_EDITOR` from inside its
+//! own terminal without nesting editors.
+//!
+//! The client sends one JSON line, an [`OpenRequest`]. The server answers with
+//! [`Reply::Opened`] (or [`Reply::Failed`]) and, for a client that waits,
+//! [`Reply::Closed`] once the buffers it opened are all closed.
+
+use crate::model::event::BufferId;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+/// How long a client may take to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A file to open, with an optional position (1-indexed)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenFile {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+/// What a `fresh --remote` client asks of the running editor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenRequest {
+    pub files: Vec<OpenFile>,
+    /// Open the files in a new split rather than as tabs of the active one
+    #[serde(default)]
+    pub split: bool,
+    /// Tell the client once all the opened buffers are closed
+    #[serde(default)]
+    pub wait: bool,
+}
+
+/// The server's answers to a client
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reply", rename_all = "snake_case")]
+pub enum Reply {
+    /// The files are open
+    Opened,
+    /// None of the files could be opened
+    Failed { message: String },
+    /// The buffers a waiting client opened were all closed
+    Closed,
+}
+
+/// A request received by the server, not answered yet
+pub struct PendingOpen {
+    pub request: OpenRequest,
+    stream: UnixStream,
+}
+
+/// A client waiting for its buffers to be closed
+struct Waiter {
+    buffers: Vec<BufferId>,
+    stream: UnixStream,
+}
+
+/// The listening side, owned by the editor
+pub struct InstanceServer {
+    path: PathBuf,
+    requests: mpsc::Receiver<PendingOpen>,
+    waiting: Vec<Waiter>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl InstanceServer {
+    /// Listen on `path`
+    ///
+    /// A socket file left behind by an editor that is gone is replaced; one
+    /// that an editor still answers on is an error.
+    pub fn bind(path: &Path) -> io::Result<Self> {
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("another editor is listening on {}", path.display()),
+                ));
+            }
+            std::fs::remove_file(path)?;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+
+        let (sender, requests) = mpsc::channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_stopped = Arc::clone(&stopped);
+        std::thread::Builder::new()
+            .name("instance-server".to_string())
+            .spawn(move || accept_loop(listener, sender, thread_stopped))?;
+
+        tracing::info!("Listening for remote-open requests on {}", path.display());
+        Ok(Self {
+            path: path.to_path_buf(),
+            requests,
+            waiting: Vec::new(),
+            stopped,
+        })
+    }
+
+    /// Take the requests received since the last call
+    pub fn take_requests(&mut self) -> Vec<PendingOpen> {
+        self.requests.try_iter().collect()
+    }
+
+    /// Answer a request with the buffers opened for it (none if all failed)
+    pub fn answer(&mut self, pending: PendingOpen, buffers: Vec<BufferId>, error: Option<String>) {
+        let PendingOpen {
+            request,
+            mut stream,
+        } = pending;
+        let reply = if buffers.is_empty() {
+            Reply::Failed {
+                message: error.unwrap_or_else(|| "No files to open".to_string()),
+            }
+        } else {
+            Reply::Opened
+        };
+        if send(&mut stream, &reply).is_err() {
+            return;
+        }
+        if request.wait && !buffers.is_empty() {
+            self.waiting.push(Waiter { buffers, stream });
+        }
+    }
+
+    /// Tell waiting clients whose buffers are all closed
+    pub fn release_closed(&mut self, is_open: impl Fn(BufferId) -> bool) {
+        self.waiting.retain_mut(|waiter| {
+            if waiter.buffers.iter().any(|id| is_open(*id)) {
+                return true;
+            }
+            let _ = send(&mut waiter.stream, &Reply::Closed);
+            false
+        });
+    }
+}
+
+impl Drop for InstanceServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag
+        let _ = UnixStream::connect(&self.path);
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn accept_loop(
+    listener: UnixListener,
+    sender: mpsc::Sender<PendingOpen>,
+    stopped: Arc<AtomicBool>,
+) {
+    for stream in listener.incoming() {
+        if stopped.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else {
+            continue;
+        };
+        match read_request(&stream) {
+            Ok(request) => {
+                if sender.send(PendingOpen { request, stream }).is_err() {
+                    break;
+                }
+            }
+            Err(e) => tracing::warn!("Ignoring bad remote-open request: {}", e),
+        }
+    }
+}
+
+fn read_request(stream: &UnixStream) -> io::Result<OpenRequest> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    stream.set_read_timeout(None)?;
+    serde_json::from_str(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn send(stream: &mut UnixStream, reply: &Reply) -> io::Result<()> {
+    let mut line = serde_json::to_string(reply).map_err(io::Error::other)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())
+}
+
+/// The client side: a request handed to a running editor
+pub struct Client {
+    reader: BufReader<UnixStream>,
+}
+
+impl Client {
+    /// Send a request to the editor listening on `path`
+    ///
+    /// Returns `None` when no editor is listening there.
+    pub fn send(path: &Path, request: &OpenRequest) -> io::Result<Option<Self>> {
+        let mut stream = match UnixStream::connect(path) {
+            Ok(stream) => stream,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+                ) =>
+            {
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        let mut line = serde_json::to_string(request).map_err(io::Error::other)?;
+        line.push('\n');
+        stream.write_all(line.as_bytes())?;
+        Ok(Some(Self {
+            reader: BufReader::new(stream),
+        }))
+    }
+
+    /// Wait for the editor's next reply
+    ///
+    /// An editor that exits counts as having closed the files.
+    pub fn next_reply(&mut self) -> io::Result<Reply> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(Reply::Closed);
+        }
+        serde_json::from_str(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait_for_request(server: &mut InstanceServer) -> PendingOpen {
+        for _ in 0..200 {
+            if let Some(pending) = server.take_requests().pop() {
+                return pending;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("no request received");
+    }
+
+    #[test]
+    fn test_open_and_wait() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.sock");
+        assert!(Client::send(
+            &path,
+            &OpenRequest {
+                files: vec![],
+                split: false,
+                wait: false,
+            }
+        )
+        .unwrap()
+        .is_none());
+
+        let mut server = InstanceServer::bind(&path).unwrap();
+        assert_eq!(
+            InstanceServer::bind(&path).err().map(|e| e.kind()),
+            Some(io::ErrorKind::AddrInUse)
+        );
+
+        let request = OpenRequest {
+            files: vec![OpenFile {
+                path: PathBuf::from("/tmp/COMMIT_EDITMSG"),
+                line: Some(3),
+                column: None,
+            }],
+            split: false,
+            wait: true,
+        };
+        let mut client = Client::send(&path, &request).unwrap().unwrap();
+        let pending = wait_for_request(&mut server);
+        assert_eq!(pending.request, request);
+
+        server.answer(pending, vec![BufferId(4)], None);
+        assert_eq!(client.next_reply().unwrap(), Reply::Opened);
+        server.release_closed(|id| id == BufferId(4));
+        server.release_closed(|_| false);
+        assert_eq!(client.next_reply().unwrap(), Reply::Closed);
+
+        drop(server);
+        assert!(!path.exists());
+        // A socket left behind by a dead editor is taken over
+        let stale = UnixListener::bind(&path).unwrap();
+        drop(stale);
+        assert!(InstanceServer::bind(&path).is_ok());
+    }
+
+    #[test]
+    fn test_failed_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.sock");
+        let mut server = InstanceServer::bind(&path).unwrap();
+        let request = OpenRequest {
+            files: vec![],
+            split: false,
+            wait: true,
+        };
+        let mut client = Client::send(&path, &request).unwrap().unwrap();
+        let pending = wait_for_request(&mut server);
+        server.answer(pending, vec![], Some("No such file".to_string()));
+        assert_eq!(
+            client.next_reply().unwrap(),
+            Reply::Failed {
+                message: "No such file".to_string()
+            }
+        );
+        // A failed request is not waited on
+        assert_eq!(client.next_reply().unwrap(), Reply::Closed);
+    }
+}
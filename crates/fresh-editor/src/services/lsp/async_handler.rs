@@ -2,7 +2,7 @@
 //!
 //! This module implements an asynchronous LSP client that:
 //! - Runs in a separate Tokio task
-//! - Uses tokio::process for async process I/O
+//! - Uses tokio::process for async process I/O (or the SSH agent for remote servers)
 //! - Sends notifications to main loop via AsyncBridge
 //! - Handles LSP notifications asynchronously (diagnostics, etc.)
 //!
@@ -15,7 +15,9 @@ use crate::services::async_bridge::{
     AsyncBridge, AsyncMessage, LspMessageType, LspProgressValue, LspSemanticTokensResponse,
     LspServerStatus,
 };
+use crate::services::lsp::remote::{RemoteLspTarget, UriTranslator};
 use crate::services::process_limits::ProcessLimits;
+use crate::services::remote::{PipeReader, PipeWriter, ProcessGuard};
 use lsp_types::{
     notification::{
         DidChangeTextDocument, DidOpenTextDocument, DidSaveTextDocument, Initialized, Notification,
@@ -39,7 +41,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{mpsc as std_mpsc, Arc, Mutex};
 use std::time::Instant;
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::process::Command;
 use tokio::sync::{mpsc, oneshot};
 
 /// Grace period after didOpen before sending didChange (in milliseconds)
@@ -465,10 +467,48 @@ enum LspCommand {
     Shutdown,
 }
 
+/// Framed writer for the server's stdin
+///
+/// Shared by the command loop and the stdout reader (which answers server
+/// requests), and applies remote URI translation to everything it sends.
+struct ServerWriter {
+    stdin: PipeWriter,
+    uris: Option<UriTranslator>,
+}
+
+impl ServerWriter {
+    /// Frame a serialized JSON-RPC message and write it to the server
+    async fn send(&mut self, json: String) -> Result<(), String> {
+        let json = match &self.uris {
+            Some(uris) => {
+                let mut value: Value = serde_json::from_str(&json)
+                    .map_err(|e| format!("Serialization error: {}", e))?;
+                uris.outgoing(&mut value);
+                value.to_string()
+            }
+            None => json,
+        };
+
+        let content = format!("Content-Length: {}\r\n\r\n{}", json.len(), json);
+
+        tracing::trace!("Writing LSP message to stdin ({} bytes)", content.len());
+
+        self.stdin
+            .write_all(content.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to stdin: {}", e))?;
+
+        self.stdin
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to flush stdin: {}", e))
+    }
+}
+
 /// Mutable state for LSP command processing
 struct LspState {
     /// Stdin for sending messages (shared with stdout reader for server responses)
-    stdin: Arc<tokio::sync::Mutex<ChildStdin>>,
+    stdin: Arc<tokio::sync::Mutex<ServerWriter>>,
 
     /// Next request ID
     next_id: i64,
@@ -584,20 +624,7 @@ impl LspState {
         let json =
             serde_json::to_string(message).map_err(|e| format!("Serialization error: {}", e))?;
 
-        let content = format!("Content-Length: {}\r\n\r\n{}", json.len(), json);
-
-        tracing::trace!("Writing LSP message to stdin ({} bytes)", content.len());
-
-        let mut stdin = self.stdin.lock().await;
-        stdin
-            .write_all(content.as_bytes())
-            .await
-            .map_err(|e| format!("Failed to write to stdin: {}", e))?;
-
-        stdin
-            .flush()
-            .await
-            .map_err(|e| format!("Failed to flush stdin: {}", e))?;
+        self.stdin.lock().await.send(json).await?;
 
         tracing::trace!("Successfully sent LSP message");
 
@@ -1898,14 +1925,17 @@ impl LspState {
 
 /// Async LSP task that handles all I/O
 struct LspTask {
-    /// Process handle - kept alive for lifetime management (kill_on_drop)
-    _process: Child,
+    /// Process handle - kept alive for lifetime management (killed on drop)
+    _process: ProcessGuard,
 
     /// Stdin for sending messages
-    stdin: ChildStdin,
+    stdin: PipeWriter,
 
     /// Stdout for receiving messages
-    stdout: BufReader<ChildStdout>,
+    stdout: BufReader<PipeReader>,
+
+    /// URI translation for servers running on a remote host
+    uris: Option<UriTranslator>,
 
     /// Next request ID
     next_id: i64,
@@ -1948,7 +1978,13 @@ impl LspTask {
         async_tx: std_mpsc::Sender<AsyncMessage>,
         process_limits: &ProcessLimits,
         stderr_log_path: std::path::PathBuf,
+        remote: Option<RemoteLspTarget>,
     ) -> Result<Self, String> {
+        if let Some(target) = remote {
+            return Self::spawn_remote(command, args, language, async_tx, stderr_log_path, target)
+                .await;
+        }
+
         tracing::info!("Spawning async LSP server: {} {:?}", command, args);
         tracing::info!("Process limits: {:?}", process_limits);
         tracing::info!("LSP stderr will be logged to: {:?}", stderr_log_path);
@@ -2000,17 +2036,74 @@ impl LspTask {
             .take()
             .ok_or_else(|| "Failed to get stdin".to_string())?;
 
-        let stdout = BufReader::new(
-            process
-                .stdout
-                .take()
-                .ok_or_else(|| "Failed to get stdout".to_string())?,
+        let stdout = process
+            .stdout
+            .take()
+            .ok_or_else(|| "Failed to get stdout".to_string())?;
+
+        Ok(Self {
+            _process: ProcessGuard::Local(process),
+            stdin: Box::new(stdin),
+            stdout: BufReader::new(Box::new(stdout) as PipeReader),
+            uris: None,
+            next_id: 0,
+            pending: HashMap::new(),
+            capabilities: None,
+            document_versions: HashMap::new(),
+            pending_opens: HashMap::new(),
+            initialized: false,
+            async_tx,
+            language,
+            server_command: command.to_string(),
+            stderr_log_path,
+        })
+    }
+
+    /// Create a new LSP task whose server runs on a remote host
+    ///
+    /// Process limits are not applied remotely. The server's stderr is copied
+    /// into the local stderr log so it can be inspected like a local server's.
+    async fn spawn_remote(
+        command: &str,
+        args: &[String],
+        language: String,
+        async_tx: std_mpsc::Sender<AsyncMessage>,
+        stderr_log_path: std::path::PathBuf,
+        target: RemoteLspTarget,
+    ) -> Result<Self, String> {
+        tracing::info!(
+            "Spawning remote LSP server: {} {:?} (cwd: {:?})",
+            command,
+            args,
+            target.cwd
         );
+        tracing::info!("LSP stderr will be logged to: {:?}", stderr_log_path);
+
+        let mut stderr_file = tokio::fs::File::create(&stderr_log_path)
+            .await
+            .map_err(|e| {
+                format!(
+                    "Failed to create LSP stderr log file {:?}: {}",
+                    stderr_log_path, e
+                )
+            })?;
+
+        let process = target
+            .spawner
+            .spawn_piped(command.to_string(), args.to_vec(), target.cwd.clone())
+            .await
+            .map_err(|e| format!("Failed to spawn remote LSP server '{}': {}", command, e))?;
+
+        let mut stderr = process.stderr;
+        tokio::spawn(async move {
+            let _ = tokio::io::copy(&mut stderr, &mut stderr_file).await;
+        });
 
         Ok(Self {
-            _process: process,
-            stdin,
-            stdout,
+            _process: process.guard,
+            stdin: process.stdin,
+            stdout: BufReader::new(process.stdout),
+            uris: target.uris,
             next_id: 0,
             pending: HashMap::new(),
             capabilities: None,
@@ -2064,19 +2157,20 @@ impl LspTask {
     #[allow(clippy::type_complexity)]
     #[allow(clippy::too_many_arguments)]
     fn spawn_stdout_reader(
-        mut stdout: BufReader<ChildStdout>,
+        mut stdout: BufReader<PipeReader>,
+        uris: Option<UriTranslator>,
         pending: Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
         async_tx: std_mpsc::Sender<AsyncMessage>,
         language: String,
         server_command: String,
-        stdin_writer: Arc<tokio::sync::Mutex<ChildStdin>>,
+        stdin_writer: Arc<tokio::sync::Mutex<ServerWriter>>,
         stderr_log_path: std::path::PathBuf,
         shutting_down: Arc<AtomicBool>,
     ) {
         tokio::spawn(async move {
             tracing::info!("LSP stdout reader task started for {}", language);
             loop {
-                match read_message_from_stdout(&mut stdout).await {
+                match read_message_from_stdout(&mut stdout, uris.as_ref()).await {
                     Ok(message) => {
                         tracing::trace!("Read message from LSP server: {:?}", message);
                         if let Err(e) = handle_message_dispatch(
@@ -2125,7 +2219,10 @@ impl LspTask {
         tracing::info!("LspTask::run() started for language: {}", self.language);
 
         // Create shared stdin writer so both command processing and stdout reader can write
-        let stdin_writer = Arc::new(tokio::sync::Mutex::new(self.stdin));
+        let stdin_writer = Arc::new(tokio::sync::Mutex::new(ServerWriter {
+            stdin: self.stdin,
+            uris: self.uris.clone(),
+        }));
 
         // Create state struct for command processing
         let mut state = LspState {
@@ -2150,6 +2247,7 @@ impl LspTask {
         // Spawn stdout reader task (shares stdin_writer for responding to server requests)
         Self::spawn_stdout_reader(
             self.stdout,
+            self.uris,
             pending.clone(),
             async_tx.clone(),
             language_clone.clone(),
@@ -2607,7 +2705,8 @@ impl LspTask {
 
 /// Standalone function to read a message from stdout (for reader task)
 async fn read_message_from_stdout(
-    stdout: &mut BufReader<ChildStdout>,
+    stdout: &mut BufReader<PipeReader>,
+    uris: Option<&UriTranslator>,
 ) -> Result<JsonRpcMessage, String> {
     // Read headers
     let mut content_length: Option<usize> = None;
@@ -2652,6 +2751,14 @@ async fn read_message_from_stdout(
 
     tracing::trace!("Received LSP message: {}", json);
 
+    if let Some(uris) = uris {
+        let mut value: Value = serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to deserialize message: {}", e))?;
+        uris.incoming(&mut value);
+        return serde_json::from_value(value)
+            .map_err(|e| format!("Failed to deserialize message: {}", e));
+    }
+
     serde_json::from_str(&json).map_err(|e| format!("Failed to deserialize message: {}", e))
 }
 
@@ -2663,7 +2770,7 @@ async fn handle_message_dispatch(
     async_tx: &std_mpsc::Sender<AsyncMessage>,
     language: &str,
     server_command: &str,
-    stdin_writer: &Arc<tokio::sync::Mutex<ChildStdin>>,
+    stdin_writer: &Arc<tokio::sync::Mutex<ServerWriter>>,
 ) -> Result<(), String> {
     match message {
         JsonRpcMessage::Response(response) => {
@@ -2789,16 +2896,10 @@ async fn handle_message_dispatch(
             // Write response directly to stdin (avoids deadlock when main loop is waiting for LSP response)
             let json = serde_json::to_string(&response)
                 .map_err(|e| format!("Failed to serialize response: {}", e))?;
-            let message = format!("Content-Length: {}\r\n\r\n{}", json.len(), json);
 
-            let mut stdin = stdin_writer.lock().await;
-            use tokio::io::AsyncWriteExt;
-            if let Err(e) = stdin.write_all(message.as_bytes()).await {
+            if let Err(e) = stdin_writer.lock().await.send(json).await {
                 tracing::error!("Failed to write server response: {}", e);
             }
-            if let Err(e) = stdin.flush().await {
-                tracing::error!("Failed to flush server response: {}", e);
-            }
             tracing::trace!("Sent response to server request id={}", response.id);
         }
     }
@@ -3052,6 +3153,27 @@ impl LspHandle {
         language: String,
         async_bridge: &AsyncBridge,
         process_limits: ProcessLimits,
+    ) -> Result<Self, String> {
+        Self::spawn_with_target(
+            runtime,
+            command,
+            args,
+            language,
+            async_bridge,
+            process_limits,
+            None,
+        )
+    }
+
+    /// Spawn a new LSP server in an async task, on the remote host if `remote` is set
+    pub fn spawn_with_target(
+        runtime: &tokio::runtime::Handle,
+        command: &str,
+        args: &[String],
+        language: String,
+        async_bridge: &AsyncBridge,
+        process_limits: ProcessLimits,
+        remote: Option<RemoteLspTarget>,
     ) -> Result<Self, String> {
         let (command_tx, command_rx) = mpsc::channel(100); // Buffer up to 100 commands
         let async_tx = async_bridge.sender();
@@ -3080,6 +3202,7 @@ impl LspHandle {
                 async_tx.clone(),
                 &process_limits,
                 stderr_log_path_clone.clone(),
+                remote,
             )
             .await
             {
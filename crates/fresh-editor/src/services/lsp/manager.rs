@@ -8,6 +8,7 @@
 
 use crate::services::async_bridge::AsyncBridge;
 use crate::services::lsp::async_handler::LspHandle;
+use crate::services::lsp::remote::RemoteLspTarget;
 use crate::types::LspServerConfig;
 use lsp_types::{SemanticTokensLegend, Uri};
 use std::collections::{HashMap, HashSet};
@@ -50,6 +51,9 @@ pub struct LspManager {
     /// Async bridge for communication
     async_bridge: Option<AsyncBridge>,
 
+    /// Remote host to run servers on (SSH editing); `None` runs them locally
+    remote: Option<RemoteLspTarget>,
+
     /// Restart attempt timestamps per language (for tracking restart frequency)
     restart_attempts: HashMap<String, Vec<Instant>>,
 
@@ -93,6 +97,7 @@ impl LspManager {
             per_language_root_uris: HashMap::new(),
             runtime: None,
             async_bridge: None,
+            remote: None,
             restart_attempts: HashMap::new(),
             restart_cooldown: HashSet::new(),
             pending_restarts: HashMap::new(),
@@ -245,6 +250,18 @@ impl LspManager {
         self.async_bridge = Some(async_bridge);
     }
 
+    /// Run language servers on a remote host instead of locally
+    ///
+    /// Applies to servers spawned (or restarted) after this call.
+    pub fn set_remote_target(&mut self, remote: Option<RemoteLspTarget>) {
+        self.remote = remote;
+    }
+
+    /// Check whether servers are launched on a remote host
+    pub fn is_remote(&self) -> bool {
+        self.remote.is_some()
+    }
+
     /// Set configuration for a language
     pub fn set_language_config(&mut self, language: String, config: LspServerConfig) {
         self.config.insert(language, config);
@@ -377,13 +394,14 @@ impl LspManager {
         // Spawn new handle
         tracing::info!("Spawning async LSP server for language: {}", language);
 
-        match LspHandle::spawn(
+        match LspHandle::spawn_with_target(
             runtime,
             command,
             &config.args,
             language.to_string(),
             async_bridge,
             config.process_limits.clone(),
+            self.remote.clone(),
         ) {
             Ok(handle) => {
                 // Initialize the handle (non-blocking)
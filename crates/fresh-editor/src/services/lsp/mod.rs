@@ -77,6 +77,9 @@
 //! - **`diagnostics`**: Converts LSP diagnostics to editor overlays (colored
 //!   underlines for errors, warnings, etc.).
 //!
+//! - **`remote`**: Launch target and URI translation for language servers that
+//!   run on the remote host during SSH editing.
+//!
 //! # Message Flow
 //!
 //! ## Outgoing Requests (Editor → Server)
@@ -117,6 +120,7 @@
 pub mod async_handler;
 pub mod diagnostics;
 pub mod manager;
+pub mod remote;
 pub mod semantic_tokens;
 
 // Re-export for public API (used by tests)
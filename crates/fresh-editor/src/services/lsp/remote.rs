@@ -0,0 +1,259 @@
+//! Language servers running on a remote host
+//!
+//! During SSH editing, language servers are spawned on the remote side through
+//! the agent (see [`crate::services::remote::ProcessSpawner::spawn_piped`]) so
+//! they see the remote project. The editor refers to files by the paths the
+//! user opened them with, while servers usually report canonical paths
+//! (symlinks resolved), so `file://` URIs are translated between the two
+//! spellings of the workspace root in both directions.
+
+use crate::services::remote::ProcessSpawner;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Where and how to launch language servers for a remote session
+#[derive(Clone)]
+pub struct RemoteLspTarget {
+    /// Spawner that starts processes on the remote host
+    pub spawner: Arc<dyn ProcessSpawner>,
+    /// Working directory for the server process on the remote host
+    pub cwd: Option<String>,
+    /// URI translation between editor paths and remote paths
+    pub uris: Option<UriTranslator>,
+}
+
+/// Object keys whose string values are document or folder URIs
+///
+/// Covers `TextDocumentIdentifier`, `Location`, `LocationLink`,
+/// `WorkspaceFolder`, `InitializeParams`, `ConfigurationItem` and the
+/// resource operations of a `WorkspaceEdit`.
+const URI_KEYS: &[&str] = &[
+    "uri",
+    "targetUri",
+    "rootUri",
+    "scopeUri",
+    "oldUri",
+    "newUri",
+];
+
+/// Object keys whose values are maps keyed by URI (`WorkspaceEdit.changes`,
+/// `relatedDocuments` of diagnostic reports)
+const URI_MAP_KEYS: &[&str] = &["changes", "relatedDocuments"];
+
+/// Rewrites `file://` URIs under one root into another root
+///
+/// `local` is the workspace root as the editor spells it, `remote` is the
+/// same directory as the language server sees it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UriTranslator {
+    /// Editor-side root URI, without trailing slash
+    local: String,
+    /// Server-side root URI, without trailing slash
+    remote: String,
+}
+
+impl UriTranslator {
+    /// Create a translator between two absolute root directories
+    ///
+    /// Returns `None` if either path cannot be expressed as a file URI or the
+    /// roots are identical (nothing to translate).
+    pub fn new(local_root: &Path, remote_root: &Path) -> Option<Self> {
+        let local = Self::root_uri(local_root)?;
+        let remote = Self::root_uri(remote_root)?;
+        if local == remote {
+            return None;
+        }
+        Some(Self { local, remote })
+    }
+
+    fn root_uri(path: &Path) -> Option<String> {
+        let url = url::Url::from_file_path(path).ok()?;
+        Some(url.as_str().trim_end_matches('/').to_string())
+    }
+
+    /// Translate an editor-side URI to the server-side spelling
+    pub fn to_remote(&self, uri: &str) -> Option<String> {
+        Self::rebase(uri, &self.local, &self.remote)
+    }
+
+    /// Translate a server-side URI to the editor-side spelling
+    pub fn to_local(&self, uri: &str) -> Option<String> {
+        Self::rebase(uri, &self.remote, &self.local)
+    }
+
+    fn rebase(uri: &str, from: &str, to: &str) -> Option<String> {
+        let rest = uri.strip_prefix(from)?;
+        // Only match on a path component boundary
+        if rest.is_empty() || rest.starts_with('/') {
+            Some(format!("{}{}", to, rest))
+        } else {
+            None
+        }
+    }
+
+    /// Rewrite the URIs of an outgoing JSON-RPC message
+    pub fn outgoing(&self, message: &mut Value) {
+        Self::walk(message, &|s| self.to_remote(s));
+    }
+
+    /// Rewrite the URIs of an incoming JSON-RPC message
+    pub fn incoming(&self, message: &mut Value) {
+        Self::walk(message, &|s| self.to_local(s));
+    }
+
+    /// Rewrite the values of URI-bearing keys (and the keys of URI maps)
+    ///
+    /// Other strings, such as document text and edits, are never touched even
+    /// if they contain a URI.
+    fn walk(value: &mut Value, map: &dyn Fn(&str) -> Option<String>) {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    Self::walk(item, map);
+                }
+            }
+            Value::Object(object) => {
+                for (key, item) in object.iter_mut() {
+                    match item {
+                        Value::String(s) if URI_KEYS.contains(&key.as_str()) => {
+                            if let Some(mapped) = map(s) {
+                                *s = mapped;
+                            }
+                        }
+                        Value::Object(uri_map) if URI_MAP_KEYS.contains(&key.as_str()) => {
+                            *uri_map = std::mem::take(uri_map)
+                                .into_iter()
+                                .map(|(uri, mut entry)| {
+                                    Self::walk(&mut entry, map);
+                                    (map(&uri).unwrap_or(uri), entry)
+                                })
+                                .collect();
+                        }
+                        _ => Self::walk(item, map),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn translator() -> UriTranslator {
+        UriTranslator::new(
+            &PathBuf::from("/home/alice/proj"),
+            &PathBuf::from("/data/home/alice/proj"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_identical_roots_need_no_translation() {
+        let root = PathBuf::from("/home/alice/proj");
+        assert!(UriTranslator::new(&root, &root).is_none());
+    }
+
+    #[test]
+    fn test_translates_both_directions() {
+        let t = translator();
+        assert_eq!(
+            t.to_remote("file:///home/alice/proj/src/main.rs")
+                .as_deref(),
+            Some("file:///data/home/alice/proj/src/main.rs")
+        );
+        assert_eq!(
+            t.to_local("file:///data/home/alice/proj/src/main.rs")
+                .as_deref(),
+            Some("file:///home/alice/proj/src/main.rs")
+        );
+        assert_eq!(
+            t.to_remote("file:///home/alice/proj").as_deref(),
+            Some("file:///data/home/alice/proj")
+        );
+    }
+
+    #[test]
+    fn test_respects_path_component_boundaries() {
+        let t = translator();
+        assert_eq!(t.to_remote("file:///home/alice/project2/a.rs"), None);
+        assert_eq!(t.to_remote("file:///etc/hosts"), None);
+    }
+
+    #[test]
+    fn test_rewrites_nested_message() {
+        let t = translator();
+        let mut msg = serde_json::json!({
+            "uri": "file:///data/home/alice/proj/a.rs",
+            "items": [{"location": {"uri": "file:///data/home/alice/proj/b.rs"}}],
+            "text": "file:///data/home/alice/proj is mentioned in text",
+        });
+        t.incoming(&mut msg);
+        assert_eq!(msg["uri"], "file:///home/alice/proj/a.rs");
+        assert_eq!(
+            msg["items"][0]["location"]["uri"],
+            "file:///home/alice/proj/b.rs"
+        );
+        // Strings outside URI keys are left alone
+        assert_eq!(
+            msg["text"],
+            "file:///data/home/alice/proj is mentioned in text"
+        );
+    }
+
+    #[test]
+    fn test_leaves_document_text_alone() {
+        let t = translator();
+        let mut msg = serde_json::json!({
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": {"uri": "file:///home/alice/proj/a.rs", "version": 2},
+                "contentChanges": [{"text": "file:///home/alice/proj/x.rs"}],
+            },
+        });
+        t.outgoing(&mut msg);
+        assert_eq!(
+            msg["params"]["textDocument"]["uri"],
+            "file:///data/home/alice/proj/a.rs"
+        );
+        assert_eq!(
+            msg["params"]["contentChanges"][0]["text"],
+            "file:///home/alice/proj/x.rs"
+        );
+    }
+
+    #[test]
+    fn test_rewrites_workspace_edit_keys() {
+        let t = translator();
+        let mut msg = serde_json::json!({
+            "edit": {
+                "changes": {
+                    "file:///data/home/alice/proj/a.rs": [
+                        {"newText": "file:///data/home/alice/proj/b.rs"}
+                    ],
+                },
+                "documentChanges": [
+                    {"kind": "rename", "oldUri": "file:///data/home/alice/proj/c.rs",
+                     "newUri": "file:///data/home/alice/proj/d.rs"}
+                ],
+            },
+        });
+        t.incoming(&mut msg);
+        let changes = msg["edit"]["changes"].as_object().unwrap();
+        assert_eq!(
+            changes.keys().collect::<Vec<_>>(),
+            vec!["file:///home/alice/proj/a.rs"]
+        );
+        assert_eq!(
+            changes["file:///home/alice/proj/a.rs"][0]["newText"],
+            "file:///data/home/alice/proj/b.rs"
+        );
+        let rename = &msg["edit"]["documentChanges"][0];
+        assert_eq!(rename["oldUri"], "file:///home/alice/proj/c.rs");
+        assert_eq!(rename["newUri"], "file:///home/alice/proj/d.rs");
+    }
+}
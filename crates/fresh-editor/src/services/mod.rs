@@ -8,6 +8,8 @@ pub mod clipboard;
 pub mod fs;
 #[cfg(target_os = "linux")]
 pub mod gpm;
+#[cfg(unix)]
+pub mod instance_server;
 pub mod log_dirs;
 pub mod lsp;
 pub mod plugins;
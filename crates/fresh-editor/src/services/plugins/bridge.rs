@@ -99,4 +99,8 @@ impl PluginServiceBridge for EditorServiceBridge {
     fn config_dir(&self) -> PathBuf {
         self.dir_context.config_dir.clone()
     }
+
+    fn data_dir(&self) -> PathBuf {
+        self.dir_context.data_dir.clone()
+    }
 }
@@ -7,7 +7,7 @@
 
 use crate::config_io::DirectoryContext;
 use crate::input::command_registry::CommandRegistry;
-use fresh_core::config::PluginConfig;
+use fresh_core::config::{PluginCapabilities, PluginConfig, PluginRuntimeConfig};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::{Arc, RwLock};
@@ -15,7 +15,7 @@ use std::sync::{Arc, RwLock};
 #[cfg(feature = "plugins")]
 use super::bridge::EditorServiceBridge;
 #[cfg(feature = "plugins")]
-use fresh_plugin_runtime::PluginThreadHandle;
+use fresh_plugin_runtime::{PluginLimits, PluginThreadHandle};
 
 /// Unified plugin manager that abstracts over the plugin system.
 ///
@@ -24,6 +24,9 @@ use fresh_plugin_runtime::PluginThreadHandle;
 pub struct PluginManager {
     #[cfg(feature = "plugins")]
     inner: Option<PluginThreadHandle>,
+    /// What the plugin thread was spawned with, kept for `restart`
+    #[cfg(feature = "plugins")]
+    spawn_args: Option<(Arc<EditorServiceBridge>, PluginLimits)>,
     #[cfg(not(feature = "plugins"))]
     _phantom: std::marker::PhantomData<()>,
 }
@@ -37,6 +40,7 @@ impl PluginManager {
         enable: bool,
         command_registry: Arc<RwLock<CommandRegistry>>,
         dir_context: DirectoryContext,
+        runtime_config: &PluginRuntimeConfig,
     ) -> Self {
         #[cfg(feature = "plugins")]
         {
@@ -45,28 +49,27 @@ impl PluginManager {
                     command_registry: command_registry.clone(),
                     dir_context,
                 });
-                match PluginThreadHandle::spawn(services) {
-                    Ok(handle) => {
-                        return Self {
-                            inner: Some(handle),
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to spawn TypeScript plugin thread: {}", e);
-                        #[cfg(debug_assertions)]
-                        panic!("TypeScript plugin thread creation failed: {}", e);
-                    }
-                }
+                let limits = PluginLimits::from(runtime_config);
+                let mut manager = Self {
+                    inner: None,
+                    spawn_args: Some((services, limits)),
+                };
+                manager.spawn();
+                return manager;
             } else {
                 tracing::info!("Plugins disabled via --no-plugins flag");
             }
-            Self { inner: None }
+            Self {
+                inner: None,
+                spawn_args: None,
+            }
         }
 
         #[cfg(not(feature = "plugins"))]
         {
             let _ = command_registry; // Suppress unused warning
             let _ = dir_context; // Suppress unused warning
+            let _ = runtime_config; // Suppress unused warning
             if enable {
                 tracing::warn!("Plugins requested but compiled without plugin support");
             }
@@ -76,6 +79,40 @@ impl PluginManager {
         }
     }
 
+    /// Spawn the plugin thread from the saved spawn arguments.
+    #[cfg(feature = "plugins")]
+    fn spawn(&mut self) {
+        let Some((services, limits)) = self.spawn_args.clone() else {
+            return;
+        };
+        match PluginThreadHandle::spawn(services, limits) {
+            Ok(handle) => self.inner = Some(handle),
+            Err(e) => {
+                tracing::error!("Failed to spawn TypeScript plugin thread: {}", e);
+                #[cfg(debug_assertions)]
+                panic!("TypeScript plugin thread creation failed: {}", e);
+            }
+        }
+    }
+
+    /// Shut down the plugin thread and start a fresh one with no plugins loaded.
+    ///
+    /// Does nothing when plugins were not enabled. Returns whether a new
+    /// plugin thread is running.
+    pub fn restart(&mut self) -> bool {
+        #[cfg(feature = "plugins")]
+        {
+            // Dropping the handle shuts the old thread down
+            self.inner = None;
+            self.spawn();
+            self.inner.is_some()
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            false
+        }
+    }
+
     /// Check if the plugin system is active (has a running plugin thread).
     pub fn is_active(&self) -> bool {
         #[cfg(feature = "plugins")]
@@ -200,6 +237,115 @@ impl PluginManager {
         }
     }
 
+    /// Run a hook for one plugin's handlers only (fire-and-forget).
+    pub fn run_plugin_hook(
+        &self,
+        plugin_name: &str,
+        hook_name: &str,
+        args: super::hooks::HookArgs,
+    ) {
+        #[cfg(feature = "plugins")]
+        {
+            if let Some(ref manager) = self.inner {
+                manager.run_plugin_hook(plugin_name, hook_name, args);
+            }
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            let _ = (plugin_name, hook_name, args);
+        }
+    }
+
+    /// Check whether plugins handle a cancellable hook (cheap; no round trip
+    /// to the plugin thread).
+    pub fn handles_cancellable_hook(&self, hook_name: &str) -> bool {
+        #[cfg(feature = "plugins")]
+        {
+            self.inner
+                .as_ref()
+                .map(|m| m.handles_cancellable_hook(hook_name))
+                .unwrap_or(false)
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            let _ = hook_name;
+            false
+        }
+    }
+
+    /// Run a cancellable hook and wait, briefly, for the plugins' decision.
+    pub fn run_cancellable_hook(
+        &self,
+        hook_name: &str,
+        args: super::hooks::HookArgs,
+    ) -> super::hooks::HookOutcome {
+        #[cfg(feature = "plugins")]
+        {
+            self.inner
+                .as_ref()
+                .map(|m| m.run_cancellable_hook(hook_name, args))
+                .unwrap_or_default()
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            let _ = (hook_name, args);
+            super::hooks::HookOutcome::default()
+        }
+    }
+
+    /// Run several cancellable hooks in one round trip and wait, briefly,
+    /// for the plugins' decisions. The outcomes end at the first cancelled
+    /// hook, and are empty when the plugins did not answer in time.
+    pub fn run_cancellable_hooks(
+        &self,
+        hooks: Vec<(String, super::hooks::HookArgs)>,
+    ) -> Vec<super::hooks::HookOutcome> {
+        #[cfg(feature = "plugins")]
+        {
+            self.inner
+                .as_ref()
+                .map(|m| m.run_cancellable_hooks(hooks))
+                .unwrap_or_default()
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            let _ = hooks;
+            Vec::new()
+        }
+    }
+
+    /// Record the capabilities the user granted to a plugin package.
+    pub fn set_capability_grant(&self, name: &str, granted: PluginCapabilities) {
+        #[cfg(feature = "plugins")]
+        {
+            if let Some(ref manager) = self.inner {
+                manager.set_capability_grant(name, granted);
+            }
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            let _ = (name, granted);
+        }
+    }
+
+    /// Ask the plugins providing a language feature for their results.
+    pub fn request_language_feature(
+        &self,
+        request_id: u64,
+        request: fresh_core::api::LanguageFeatureRequest,
+    ) {
+        #[cfg(feature = "plugins")]
+        {
+            if let Some(ref manager) = self.inner {
+                manager.request_language_feature(request_id, request);
+            }
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            let _ = (request_id, request);
+        }
+    }
+
     /// Deliver a response to a pending async plugin operation.
     pub fn deliver_response(&self, response: super::api::PluginResponse) {
         #[cfg(feature = "plugins")]
@@ -267,6 +413,26 @@ impl PluginManager {
             .reload_plugin(name)
     }
 
+    /// Evaluate code in a plugin's context, returning a printable result.
+    #[cfg(feature = "plugins")]
+    pub fn evaluate_in_plugin(&self, name: &str, code: &str) -> anyhow::Result<String> {
+        self.inner
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Plugin system not active"))?
+            .evaluate_in_plugin(name, code)
+    }
+
+    /// List registered hook handlers with their call timings.
+    #[cfg(feature = "plugins")]
+    pub fn list_hook_handlers(
+        &self,
+    ) -> Vec<fresh_plugin_runtime::backend::quickjs_backend::HookHandlerInfo> {
+        self.inner
+            .as_ref()
+            .map(|m| m.list_hook_handlers())
+            .unwrap_or_default()
+    }
+
     /// Check if any handlers are registered for a hook.
     pub fn has_hook_handlers(&self, hook_name: &str) -> bool {
         #[cfg(feature = "plugins")]
@@ -23,3 +23,7 @@ pub use manager::PluginManager;
 // Re-export thread module for oneshot channels used by plugin action execution
 #[cfg(feature = "plugins")]
 pub use fresh_plugin_runtime::thread;
+
+// Re-export process plumbing for interactive plugin processes
+#[cfg(feature = "plugins")]
+pub use fresh_plugin_runtime::process;
@@ -9,23 +9,81 @@ import shutil
 import subprocess
 import threading
 import select
+import queue
+import signal
+import struct
+import time
+
+try:
+    import pty
+    import fcntl
+    import termios
+except ImportError:
+    pty = None
+
+try:
+    import ctypes
+
+    _libc = ctypes.CDLL(None, use_errno=True)
+    inotify_init1 = _libc.inotify_init1
+    inotify_add_watch = _libc.inotify_add_watch
+    inotify_add_watch.argtypes = [ctypes.c_int, ctypes.c_char_p, ctypes.c_uint32]
+    inotify = True
+except (ImportError, OSError, AttributeError):
+    inotify = False
 
 CHUNK = 65536
-VERSION = 1
+VERSION = 2
+# Optional protocol features, advertised in the ready message (v2+)
+CAPS = ["stdin", "signal", "watch"] + (["pty"] if pty else [])
+SIGNALS = {
+    "INT": signal.SIGINT,
+    "TERM": signal.SIGTERM,
+    "KILL": getattr(signal, "SIGKILL", signal.SIGTERM),
+    "HUP": getattr(signal, "SIGHUP", signal.SIGTERM),
+    "QUIT": getattr(signal, "SIGQUIT", signal.SIGTERM),
+}
 
 # Active background processes: id -> Popen
 procs = {}
+# Stdin write queues for processes started with piped stdin or a PTY: id -> Queue
+stdin_queues = {}
+# PTY master fds for processes started with "pty": id -> fd
+ptys = {}
 # Request IDs marked for cancellation
 cancelled = set()
 # Lock for thread-safe access
 lock = threading.Lock()
+# inotify flags (linux/inotify.h)
+IN_MODIFY = 0x2
+IN_ATTRIB = 0x4
+IN_CLOSE_WRITE = 0x8
+IN_MOVED_FROM = 0x40
+IN_MOVED_TO = 0x80
+IN_CREATE = 0x100
+IN_DELETE = 0x200
+IN_DELETE_SELF = 0x400
+IN_MOVE_SELF = 0x800
+IN_CLOEXEC = 0o2000000
+IN_NONBLOCK = 0o4000
+# Events that add, remove or rename a directory entry
+IN_ENTRY_EVENTS = IN_MOVED_FROM | IN_MOVED_TO | IN_CREATE | IN_DELETE
+IN_WATCH_MASK = (
+    IN_MODIFY | IN_ATTRIB | IN_CLOSE_WRITE | IN_ENTRY_EVENTS | IN_DELETE_SELF | IN_MOVE_SELF
+)
+# Delay before reporting inotify events, so bursts (write + rename) coalesce
+WATCH_DEBOUNCE = 0.05
+# Lock serializing writes to stdout (output threads send concurrently)
+out_lock = threading.Lock()
 
 
 def send(id, **kw):
     """Send a JSON message to stdout."""
     msg = {"id": id, **kw}
-    sys.stdout.write(json.dumps(msg, separators=(",", ":")) + "\n")
-    sys.stdout.flush()
+    line = json.dumps(msg, separators=(",", ":")) + "\n"
+    with out_lock:
+        sys.stdout.write(line)
+        sys.stdout.flush()
 
 
 def b64(data):
@@ -268,19 +326,275 @@ def cmd_info(id, p):
     })
 
 
+# === Watching ===
+
+
+def watch_snapshot(path):
+    """Fingerprint of a path for the polling watcher (None if missing)."""
+    try:
+        st = os.stat(path)
+    except OSError:
+        return None
+    return (st.st_mtime_ns, st.st_size, st.st_ino)
+
+
+def watch_inotify(id, paths):
+    """Report changes using inotify. Returns False if inotify is unusable."""
+    fd = inotify_init1(IN_NONBLOCK | IN_CLOEXEC)
+    if fd < 0:
+        return False
+
+    # Files are watched through their parent directory, so saves that
+    # replace the file (write to temp + rename) are still noticed
+    dirs = {}
+    try:
+        for path in paths:
+            target = path if os.path.isdir(path) else os.path.dirname(path)
+            if target in dirs.values():
+                continue
+            wd = inotify_add_watch(fd, os.fsencode(target), IN_WATCH_MASK)
+            if wd >= 0:
+                dirs[wd] = target
+
+        while id not in cancelled:
+            readable, _, _ = select.select([fd], [], [], 0.2)
+            if not readable:
+                continue
+            time.sleep(WATCH_DEBOUNCE)
+
+            data = b""
+            while True:
+                try:
+                    chunk = os.read(fd, CHUNK)
+                except BlockingIOError:
+                    break
+                if not chunk:
+                    break
+                data += chunk
+
+            changed = set()
+            offset = 0
+            while offset + 16 <= len(data):
+                wd, mask, _cookie, length = struct.unpack_from("iIII", data, offset)
+                name = data[offset + 16 : offset + 16 + length].rstrip(b"\0")
+                offset += 16 + length
+
+                directory = dirs.get(wd)
+                if directory is None:
+                    continue
+                if directory in paths and mask & (IN_ENTRY_EVENTS | IN_DELETE_SELF | IN_MOVE_SELF):
+                    changed.add(paths[directory])
+                if name:
+                    child = os.path.join(directory, os.fsdecode(name))
+                    if child in paths:
+                        changed.add(paths[child])
+
+            if changed:
+                send(id, d={"changed": sorted(changed)})
+
+        send(id, e="cancelled")
+    finally:
+        os.close(fd)
+    return True
+
+
+def watch_poll(id, paths, interval):
+    """Report changes by comparing mtime/size/inode every `interval` seconds."""
+    snapshots = {path: watch_snapshot(path) for path in paths}
+    next_poll = time.monotonic() + interval
+
+    while id not in cancelled:
+        time.sleep(0.1)
+        if time.monotonic() < next_poll:
+            continue
+        next_poll = time.monotonic() + interval
+
+        changed = []
+        for path in paths:
+            snapshot = watch_snapshot(path)
+            if snapshot != snapshots[path]:
+                snapshots[path] = snapshot
+                changed.append(paths[path])
+        if changed:
+            send(id, d={"changed": sorted(changed)})
+
+    send(id, e="cancelled")
+
+
+def cmd_watch(id, p):
+    """Stream change notifications for files and directories until cancelled.
+
+    Directories report entries being added, removed or renamed; files report
+    content changes, replacement and deletion. Uses inotify when available,
+    otherwise polls every `interval` milliseconds.
+    """
+    # Canonical path -> path as requested, so events use the caller's spelling
+    paths = {validate_path(path): path for path in p["paths"]}
+    interval = p.get("interval", 1000) / 1000.0
+
+    def run():
+        try:
+            if not (inotify and watch_inotify(id, paths)):
+                watch_poll(id, paths, interval)
+        except Exception as e:
+            send(id, e=str(e))
+        finally:
+            cancelled.discard(id)
+
+    threading.Thread(target=run, daemon=True).start()
+
+
 # === Process Operations ===
 
 
+def start_feeder(id, write, close):
+    """Start a thread writing queued stdin chunks without blocking dispatch."""
+    q = queue.Queue()
+    with lock:
+        stdin_queues[id] = q
+
+    def feed_stdin():
+        try:
+            while True:
+                chunk = q.get()
+                if chunk is None:
+                    break
+                write(chunk)
+        except (BrokenPipeError, OSError, ValueError):
+            pass
+        finally:
+            try:
+                close()
+            except (BrokenPipeError, OSError):
+                pass
+
+    threading.Thread(target=feed_stdin, daemon=True).start()
+
+
+def finish_proc(id):
+    """Forget a finished process and stop its stdin feeder."""
+    with lock:
+        procs.pop(id, None)
+        cancelled.discard(id)
+        q = stdin_queues.pop(id, None)
+        master = ptys.pop(id, None)
+    if q is not None:
+        q.put(None)
+    if master is not None:
+        try:
+            os.close(master)
+        except OSError:
+            pass
+
+
+def set_winsize(fd, rows, cols):
+    """Set the window size of a PTY."""
+    fcntl.ioctl(fd, termios.TIOCSWINSZ, struct.pack("HHHH", rows, cols, 0, 0))
+
+
+def exec_pty(id, p, cmd, args, cwd):
+    """Execute a process attached to a new PTY, streaming its output as "out"."""
+    if pty is None:
+        send(id, e="pty not supported on this host")
+        return
+
+    size = p["pty"]
+    master, slave = pty.openpty()
+    set_winsize(master, size.get("rows", 24), size.get("cols", 80))
+
+    env = dict(os.environ)
+    env.update(p.get("env") or {})
+    env.setdefault("TERM", "xterm-256color")
+
+    def make_controlling_tty():
+        # New session (start_new_session) with the PTY as controlling terminal
+        fcntl.ioctl(0, termios.TIOCSCTTY, 0)
+
+    try:
+        proc = subprocess.Popen(
+            [cmd] + args,
+            cwd=cwd,
+            env=env,
+            stdin=slave,
+            stdout=slave,
+            stderr=slave,
+            start_new_session=True,
+            preexec_fn=make_controlling_tty,
+        )
+    except FileNotFoundError:
+        os.close(master)
+        send(id, e=f"command not found: {cmd}")
+        return
+    except PermissionError:
+        os.close(master)
+        send(id, e=f"permission denied: {cmd}")
+        return
+    finally:
+        os.close(slave)
+
+    with lock:
+        procs[id] = proc
+        ptys[id] = master
+
+    def write_all(chunk):
+        while chunk:
+            n = os.write(master, chunk)
+            chunk = chunk[n:]
+
+    # The master fd is closed by finish_proc, not by the feeder
+    start_feeder(id, write_all, lambda: None)
+
+    def stream_pty():
+        """Stream PTY output until the process exits."""
+        try:
+            while True:
+                if id in cancelled:
+                    os.killpg(proc.pid, signal.SIGHUP)
+                    send(id, e="cancelled")
+                    return
+                readable, _, _ = select.select([master], [], [], 0.05)
+                if readable:
+                    try:
+                        data = os.read(master, 4096)
+                    except OSError:
+                        # EIO once every slave fd is closed
+                        data = b""
+                    if not data:
+                        break
+                    send(id, d={"out": b64(data)})
+                elif proc.poll() is not None:
+                    break
+
+            send(id, r={"code": proc.wait()})
+        except Exception as e:
+            send(id, e=str(e))
+        finally:
+            finish_proc(id)
+
+    threading.Thread(target=stream_pty, daemon=True).start()
+
+
 def cmd_exec(id, p):
-    """Execute a process with streaming output."""
+    """Execute a process with streaming output.
+
+    With "stdin": true the process gets a pipe for stdin that is fed by
+    subsequent "stdin" requests carrying this request's id. With
+    "pty": {"rows", "cols"} it runs on a new PTY instead of pipes.
+    """
     cwd = validate_path(p["cwd"]) if p.get("cwd") else None
     cmd = p["cmd"]
     args = p.get("args", [])
+    piped = bool(p.get("stdin"))
+
+    if p.get("pty"):
+        exec_pty(id, p, cmd, args, cwd)
+        return
 
     try:
         proc = subprocess.Popen(
             [cmd] + args,
             cwd=cwd,
+            stdin=subprocess.PIPE if piped else subprocess.DEVNULL,
             stdout=subprocess.PIPE,
             stderr=subprocess.PIPE,
         )
@@ -294,6 +608,17 @@ def cmd_exec(id, p):
     with lock:
         procs[id] = proc
 
+    if piped:
+        pipe = proc.stdin
+        # The feeder thread owns the pipe from here on
+        proc.stdin = None
+
+        def write_pipe(chunk):
+            pipe.write(chunk)
+            pipe.flush()
+
+        start_feeder(id, write_pipe, pipe.close)
+
     def stream_output():
         """Stream process output in a background thread."""
         try:
@@ -313,7 +638,9 @@ def cmd_exec(id, p):
                     [proc.stdout, proc.stderr], [], [], 0.05
                 )
                 for fd in readable:
-                    data = fd.read(4096)
+                    # os.read returns what is available instead of
+                    # blocking until the buffer is full
+                    data = os.read(fd.fileno(), 4096)
                     if data:
                         key = "out" if fd == proc.stdout else "err"
                         send(id, d={key: b64(data)})
@@ -329,20 +656,88 @@ def cmd_exec(id, p):
         except Exception as e:
             send(id, e=str(e))
         finally:
-            with lock:
-                procs.pop(id, None)
-                cancelled.discard(id)
+            finish_proc(id)
 
     threading.Thread(target=stream_output, daemon=True).start()
 
 
+def cmd_stdin(id, p):
+    """Write to (or close) the stdin of a process started with "stdin" or "pty"."""
+    target_id = p["id"]
+    with lock:
+        q = stdin_queues.get(target_id)
+        is_pty = target_id in ptys
+
+    if q is None:
+        send(id, e="process not found")
+        return
+
+    data = p.get("data")
+    if data:
+        q.put(unb64(data))
+    if p.get("eof"):
+        if is_pty:
+            # A PTY has no separate stdin to close; send the EOF character
+            q.put(b"\x04")
+        else:
+            with lock:
+                stdin_queues.pop(target_id, None)
+            q.put(None)
+    send(id, r={})
+
+
+def cmd_resize(id, p):
+    """Change the window size of a PTY process."""
+    target_id = p["id"]
+    with lock:
+        master = ptys.get(target_id)
+
+    if master is None:
+        send(id, e="process not found")
+        return
+
+    # The kernel delivers SIGWINCH to the foreground process group
+    set_winsize(master, p["rows"], p["cols"])
+    send(id, r={})
+
+
+def cmd_signal(id, p):
+    """Deliver a signal to a process (its whole process group for PTYs)."""
+    target_id = p["id"]
+    sig = SIGNALS.get(p.get("sig"))
+    if sig is None:
+        send(id, e=f"unknown signal: {p.get('sig')}")
+        return
+
+    with lock:
+        proc = procs.get(target_id)
+        is_pty = target_id in ptys
+
+    if proc is None:
+        send(id, e="process not found")
+        return
+
+    if is_pty:
+        os.killpg(proc.pid, sig)
+    else:
+        proc.send_signal(sig)
+    send(id, r={})
+
+
 def cmd_kill(id, p):
     """Kill a background process."""
     target_id = p["id"]
     with lock:
         proc = procs.get(target_id)
+        is_pty = target_id in ptys
 
     if proc:
+        if is_pty:
+            # Interactive shells ignore SIGTERM; hang up the session instead
+            try:
+                os.killpg(proc.pid, SIGNALS["HUP"])
+            except OSError:
+                pass
         proc.terminate()
         try:
             proc.wait(timeout=2)
@@ -383,7 +778,11 @@ METHODS = {
     "chmod": cmd_chmod,
     "exists": cmd_exists,
     "info": cmd_info,
+    "watch": cmd_watch,
     "exec": cmd_exec,
+    "stdin": cmd_stdin,
+    "resize": cmd_resize,
+    "signal": cmd_signal,
     "kill": cmd_kill,
     "cancel": cmd_cancel,
 }
@@ -423,8 +822,8 @@ def handle_request(line):
 
 def main():
     """Main entry point."""
-    # Send ready message
-    send(0, ok=True, v=VERSION)
+    # Send ready message (v2+ also lists optional capabilities)
+    send(0, ok=True, v=VERSION, caps=CAPS)
 
     # Process requests from stdin
     for line in sys.stdin:
@@ -6,7 +6,7 @@ use crate::services::remote::protocol::{AgentRequest, AgentResponse};
 use std::collections::HashMap;
 use std::io;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
 use tokio::sync::{mpsc, oneshot};
 
@@ -34,24 +34,33 @@ pub enum ChannelError {
 
 /// Pending request state
 struct PendingRequest {
-    /// Channel for streaming data
-    data_tx: mpsc::Sender<serde_json::Value>,
+    /// Channel for streaming data (unbounded so process output is never dropped)
+    data_tx: mpsc::UnboundedSender<serde_json::Value>,
     /// Channel for final result
     result_tx: oneshot::Sender<Result<serde_json::Value, String>>,
 }
 
 /// Communication channel with the remote agent
+///
+/// The channel outlives its transport: after the SSH link drops, a new agent
+/// can be attached with [`AgentChannel::reconnect`] and every holder of the
+/// channel (filesystem, process spawner) keeps working.
 pub struct AgentChannel {
-    /// Sender to the write task
-    write_tx: mpsc::Sender<String>,
+    /// Sender to the write task of the current transport
+    write_tx: Mutex<mpsc::Sender<String>>,
     /// Pending requests awaiting responses
     pending: Arc<Mutex<HashMap<u64, PendingRequest>>>,
     /// Next request ID
     next_id: AtomicU64,
     /// Whether the channel is connected
     connected: Arc<std::sync::atomic::AtomicBool>,
+    /// Transport generation, bumped on every reconnect so I/O tasks of a dead
+    /// transport cannot mark the new one disconnected
+    generation: Arc<AtomicU64>,
     /// Runtime handle for blocking operations
     runtime_handle: tokio::runtime::Handle,
+    /// Optional protocol features advertised by the agent
+    capabilities: RwLock<Vec<String>>,
 }
 
 impl AgentChannel {
@@ -59,74 +68,147 @@ impl AgentChannel {
     ///
     /// Must be called from within a Tokio runtime context.
     pub fn new(
-        mut reader: tokio::io::BufReader<tokio::process::ChildStdout>,
-        mut writer: tokio::process::ChildStdin,
+        reader: tokio::io::BufReader<tokio::process::ChildStdout>,
+        writer: tokio::process::ChildStdin,
     ) -> Self {
         let pending: Arc<Mutex<HashMap<u64, PendingRequest>>> =
             Arc::new(Mutex::new(HashMap::new()));
         let connected = Arc::new(std::sync::atomic::AtomicBool::new(true));
+        let generation = Arc::new(AtomicU64::new(0));
         // Capture the runtime handle for later use in blocking operations
         let runtime_handle = tokio::runtime::Handle::current();
 
+        let write_tx = Self::start_transport(
+            reader,
+            writer,
+            pending.clone(),
+            connected.clone(),
+            generation.clone(),
+        );
+
+        Self {
+            write_tx: Mutex::new(write_tx),
+            pending,
+            next_id: AtomicU64::new(1),
+            connected,
+            generation,
+            runtime_handle,
+            capabilities: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Spawn the read and write tasks for one transport
+    ///
+    /// Returns the sender feeding the write task.
+    fn start_transport(
+        mut reader: tokio::io::BufReader<tokio::process::ChildStdout>,
+        mut writer: tokio::process::ChildStdin,
+        pending: Arc<Mutex<HashMap<u64, PendingRequest>>>,
+        connected: Arc<std::sync::atomic::AtomicBool>,
+        generation: Arc<AtomicU64>,
+    ) -> mpsc::Sender<String> {
+        let transport = generation.load(Ordering::SeqCst);
+        let is_current = {
+            let generation = generation.clone();
+            move || generation.load(Ordering::SeqCst) == transport
+        };
+
         // Channel for outgoing requests
         let (write_tx, mut write_rx) = mpsc::channel::<String>(64);
 
         // Spawn write task
         let connected_write = connected.clone();
+        let is_current_write = is_current.clone();
         tokio::spawn(async move {
             while let Some(msg) = write_rx.recv().await {
-                if writer.write_all(msg.as_bytes()).await.is_err() {
-                    connected_write.store(false, Ordering::SeqCst);
-                    break;
-                }
-                if writer.flush().await.is_err() {
-                    connected_write.store(false, Ordering::SeqCst);
+                if writer.write_all(msg.as_bytes()).await.is_err() || writer.flush().await.is_err()
+                {
+                    if is_current_write() {
+                        connected_write.store(false, Ordering::SeqCst);
+                    }
                     break;
                 }
             }
         });
 
         // Spawn read task
-        let pending_read = pending.clone();
-        let connected_read = connected.clone();
         tokio::spawn(async move {
             let mut line = String::new();
             loop {
                 line.clear();
                 match reader.read_line(&mut line).await {
-                    Ok(0) => {
-                        // EOF
-                        connected_read.store(false, Ordering::SeqCst);
-                        break;
-                    }
+                    // EOF or error: the transport is gone
+                    Ok(0) | Err(_) => break,
                     Ok(_) => {
                         if let Ok(resp) = serde_json::from_str::<AgentResponse>(&line) {
-                            Self::handle_response(&pending_read, resp);
+                            Self::handle_response(&pending, resp);
                         }
                     }
-                    Err(_) => {
-                        connected_read.store(false, Ordering::SeqCst);
-                        break;
-                    }
                 }
             }
 
-            // Clean up pending requests on disconnect
-            let mut pending = pending_read.lock().unwrap();
-            for (_, req) in pending.drain() {
-                let _ = req.result_tx.send(Err("connection closed".to_string()));
+            // Clean up pending requests on disconnect (a reconnect already
+            // failed the ones belonging to this transport)
+            if is_current() {
+                connected.store(false, Ordering::SeqCst);
+                Self::fail_pending(&pending);
             }
         });
 
-        Self {
-            write_tx,
-            pending,
-            next_id: AtomicU64::new(1),
-            connected,
-            runtime_handle,
+        write_tx
+    }
+
+    /// Fail every pending request with "connection closed"
+    fn fail_pending(pending: &Arc<Mutex<HashMap<u64, PendingRequest>>>) {
+        let mut pending = pending.lock().unwrap();
+        for (_, req) in pending.drain() {
+            let _ = req.result_tx.send(Err("connection closed".to_string()));
         }
     }
 
+    /// Attach a freshly started agent after the previous transport dropped
+    ///
+    /// Requests still pending on the old transport fail with "connection
+    /// closed"; new requests go to the new agent. Must be called from within
+    /// a Tokio runtime context.
+    pub fn reconnect(
+        &self,
+        reader: tokio::io::BufReader<tokio::process::ChildStdout>,
+        writer: tokio::process::ChildStdin,
+        capabilities: Vec<String>,
+    ) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        Self::fail_pending(&self.pending);
+
+        let write_tx = Self::start_transport(
+            reader,
+            writer,
+            self.pending.clone(),
+            self.connected.clone(),
+            self.generation.clone(),
+        );
+        *self.write_tx.lock().unwrap() = write_tx;
+        *self.capabilities.write().unwrap() = capabilities;
+        self.connected.store(true, Ordering::SeqCst);
+    }
+
+    /// Record the capabilities the agent advertised in its ready message
+    pub fn with_capabilities(self, capabilities: Vec<String>) -> Self {
+        *self.capabilities.write().unwrap() = capabilities;
+        self
+    }
+
+    /// Check whether the agent supports an optional protocol feature
+    ///
+    /// See the `CAP_*` constants in [`crate::services::remote::protocol`].
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities
+            .read()
+            .unwrap()
+            .iter()
+            .any(|c| c == capability)
+    }
+
     /// Handle an incoming response
     fn handle_response(pending: &Arc<Mutex<HashMap<u64, PendingRequest>>>, resp: AgentResponse) {
         let mut pending = pending.lock().unwrap();
@@ -134,7 +216,7 @@ impl AgentChannel {
         if let Some(req) = pending.get(&resp.id) {
             if let Some(data) = resp.data {
                 // Streaming data - send to channel (ignore if receiver dropped)
-                let _ = req.data_tx.try_send(data);
+                let _ = req.data_tx.send(data);
             }
 
             if let Some(result) = resp.result {
@@ -181,7 +263,27 @@ impl AgentChannel {
         params: serde_json::Value,
     ) -> Result<
         (
-            mpsc::Receiver<serde_json::Value>,
+            mpsc::UnboundedReceiver<serde_json::Value>,
+            oneshot::Receiver<Result<serde_json::Value, String>>,
+        ),
+        ChannelError,
+    > {
+        let (_, data_rx, result_rx) = self.request_streaming_with_id(method, params).await?;
+        Ok((data_rx, result_rx))
+    }
+
+    /// Send a request that may stream data, also returning its request id
+    ///
+    /// The id is needed to address the request later, e.g. to feed stdin of
+    /// a process started with `exec` or to kill it.
+    pub async fn request_streaming_with_id(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<
+        (
+            u64,
+            mpsc::UnboundedReceiver<serde_json::Value>,
             oneshot::Receiver<Result<serde_json::Value, String>>,
         ),
         ChannelError,
@@ -193,7 +295,7 @@ impl AgentChannel {
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
 
         // Create channels for response
-        let (data_tx, data_rx) = mpsc::channel(64);
+        let (data_tx, data_rx) = mpsc::unbounded_channel();
         let (result_tx, result_rx) = oneshot::channel();
 
         // Register pending request
@@ -204,12 +306,18 @@ impl AgentChannel {
 
         // Build and send request
         let req = AgentRequest::new(id, method, params);
-        self.write_tx
+        let write_tx = self.write_tx.lock().unwrap().clone();
+        write_tx
             .send(req.to_json_line())
             .await
             .map_err(|_| ChannelError::ChannelClosed)?;
 
-        Ok((data_rx, result_rx))
+        Ok((id, data_rx, result_rx))
+    }
+
+    /// Runtime handle the channel's I/O tasks run on
+    pub fn runtime_handle(&self) -> &tokio::runtime::Handle {
+        &self.runtime_handle
     }
 
     /// Send a request synchronously (blocking)
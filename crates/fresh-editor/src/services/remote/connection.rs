@@ -3,10 +3,11 @@
 //! Handles spawning SSH process and bootstrapping the Python agent.
 
 use crate::services::remote::channel::AgentChannel;
-use crate::services::remote::protocol::AgentResponse;
+use crate::services::remote::protocol::{AgentResponse, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
 use crate::services::remote::AGENT_SOURCE;
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
 
@@ -84,16 +85,110 @@ pub struct SshConnection {
     params: ConnectionParams,
 }
 
+/// Agent transport produced by a successful bootstrap
+struct AgentStart {
+    process: Child,
+    reader: BufReader<tokio::process::ChildStdout>,
+    writer: tokio::process::ChildStdin,
+    capabilities: Vec<String>,
+}
+
+/// First delay between reconnection attempts
+pub const RECONNECT_INITIAL_DELAY: Duration = Duration::from_secs(1);
+
+/// Upper bound for the delay between reconnection attempts
+pub const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Delay before reconnection attempt `attempt` (0-based), doubling up to the cap
+pub fn reconnect_delay(attempt: u32) -> Duration {
+    RECONNECT_INITIAL_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(RECONNECT_MAX_DELAY)
+}
+
 impl SshConnection {
     /// Establish a new SSH connection and bootstrap the agent
     pub async fn connect(params: ConnectionParams) -> Result<Self, SshError> {
+        let start = Self::start_agent(&params, true).await?;
+
+        // Create channel (takes ownership of stdin for writing)
+        let channel = std::sync::Arc::new(
+            AgentChannel::new(start.reader, start.writer).with_capabilities(start.capabilities),
+        );
+
+        Ok(Self {
+            process: start.process,
+            channel,
+            params,
+        })
+    }
+
+    /// Re-establish a dropped connection, attaching the new agent to the
+    /// existing channel
+    ///
+    /// Runs SSH non-interactively: the editor owns the terminal by now, so
+    /// only key or agent based authentication can succeed.
+    pub async fn reconnect(&mut self) -> Result<(), SshError> {
+        let start = Self::start_agent(&self.params, false).await?;
+        let _ = self.process.start_kill();
+        self.process = start.process;
+        self.channel
+            .reconnect(start.reader, start.writer, start.capabilities);
+        Ok(())
+    }
+
+    /// Keep the connection alive, reconnecting with exponential backoff
+    ///
+    /// Never returns; run it as a background task for the whole session.
+    pub async fn maintain(mut self) {
+        let mut attempt = 0;
+        loop {
+            if self.channel.is_connected() {
+                attempt = 0;
+                tokio::time::sleep(RECONNECT_INITIAL_DELAY).await;
+                continue;
+            }
+
+            let delay = reconnect_delay(attempt);
+            tracing::info!(
+                "Connection to {} lost, reconnecting in {:?}",
+                self.connection_string(),
+                delay
+            );
+            tokio::time::sleep(delay).await;
+            match self.reconnect().await {
+                Ok(()) => {
+                    tracing::info!("Reconnected to {}", self.connection_string());
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Reconnection attempt {} to {} failed: {}",
+                        attempt + 1,
+                        self.connection_string(),
+                        e
+                    );
+                    attempt = attempt.saturating_add(1);
+                }
+            }
+        }
+    }
+
+    /// Spawn SSH, bootstrap the agent and wait for its ready message
+    async fn start_agent(
+        params: &ConnectionParams,
+        interactive: bool,
+    ) -> Result<AgentStart, SshError> {
         let mut cmd = Command::new("ssh");
 
         // Don't check host key strictly for ease of use
         cmd.arg("-o").arg("StrictHostKeyChecking=accept-new");
-        // Allow password prompts - SSH will use the terminal for this
-        // Note: We inherit stderr so SSH can prompt for password if needed
-
+        // Detect dead links (suspended laptop, VPN drop) instead of hanging
+        cmd.arg("-o").arg("ServerAliveInterval=15");
+        cmd.arg("-o").arg("ServerAliveCountMax=3");
+        if !interactive {
+            cmd.arg("-o").arg("BatchMode=yes");
+            cmd.arg("-o").arg("ConnectTimeout=10");
+        }
         if let Some(port) = params.port {
             cmd.arg("-p").arg(port.to_string());
         }
@@ -121,8 +216,13 @@ impl SshConnection {
 
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
-        // Inherit stderr so SSH can prompt for password on the terminal
-        cmd.stderr(Stdio::inherit());
+        if interactive {
+            // Inherit stderr so SSH can prompt for password on the terminal
+            cmd.stderr(Stdio::inherit());
+        } else {
+            // The editor UI owns the terminal
+            cmd.stderr(Stdio::null());
+        }
 
         let mut child = cmd.spawn()?;
 
@@ -135,7 +235,7 @@ impl SshConnection {
             .stdout
             .take()
             .ok_or_else(|| SshError::AgentStartFailed("failed to get stdout".to_string()))?;
-        // Note: stderr is inherited so SSH can prompt for password on the terminal
+        // Note: when interactive, stderr is inherited so SSH can prompt for a password
 
         // Send the agent code (exact byte count)
         stdin.write_all(AGENT_SOURCE.as_bytes()).await?;
@@ -172,22 +272,20 @@ impl SshConnection {
             ));
         }
 
-        // Check protocol version
+        // Check protocol version (older agents are accepted without capabilities)
         let version = ready.version.unwrap_or(0);
-        if version != crate::services::remote::protocol::PROTOCOL_VERSION {
+        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version) {
             return Err(SshError::VersionMismatch {
-                expected: crate::services::remote::protocol::PROTOCOL_VERSION,
+                expected: PROTOCOL_VERSION,
                 got: version,
             });
         }
 
-        // Create channel (takes ownership of stdin for writing)
-        let channel = std::sync::Arc::new(AgentChannel::new(reader, stdin));
-
-        Ok(Self {
+        Ok(AgentStart {
             process: child,
-            channel,
-            params,
+            reader,
+            writer: stdin,
+            capabilities: ready.caps.unwrap_or_default(),
         })
     }
 
@@ -225,6 +323,21 @@ impl Drop for SshConnection {
 /// Not intended for production use.
 #[doc(hidden)]
 pub async fn spawn_local_agent() -> Result<std::sync::Arc<AgentChannel>, SshError> {
+    let start = start_local_agent().await?;
+    Ok(std::sync::Arc::new(
+        AgentChannel::new(start.reader, start.writer).with_capabilities(start.capabilities),
+    ))
+}
+
+/// Attach a new local agent to a channel whose agent died (testing only)
+#[doc(hidden)]
+pub async fn reconnect_local_agent(channel: &AgentChannel) -> Result<(), SshError> {
+    let start = start_local_agent().await?;
+    channel.reconnect(start.reader, start.writer, start.capabilities);
+    Ok(())
+}
+
+async fn start_local_agent() -> Result<AgentStart, SshError> {
     use tokio::process::Command as TokioCommand;
 
     let mut child = TokioCommand::new("python3")
@@ -260,7 +373,12 @@ pub async fn spawn_local_agent() -> Result<std::sync::Arc<AgentChannel>, SshErro
         ));
     }
 
-    Ok(std::sync::Arc::new(AgentChannel::new(reader, stdin)))
+    Ok(AgentStart {
+        process: child,
+        reader,
+        writer: stdin,
+        capabilities: ready.caps.unwrap_or_default(),
+    })
 }
 
 #[cfg(test)]
@@ -302,4 +420,13 @@ mod tests {
         };
         assert_eq!(params.to_string(), "bob@server.local:2222");
     }
+
+    #[test]
+    fn test_reconnect_delay_backoff() {
+        assert_eq!(reconnect_delay(0), RECONNECT_INITIAL_DELAY);
+        assert_eq!(reconnect_delay(1), RECONNECT_INITIAL_DELAY * 2);
+        assert_eq!(reconnect_delay(3), RECONNECT_INITIAL_DELAY * 8);
+        assert_eq!(reconnect_delay(10), RECONNECT_MAX_DELAY);
+        assert_eq!(reconnect_delay(u32::MAX), RECONNECT_MAX_DELAY);
+    }
 }
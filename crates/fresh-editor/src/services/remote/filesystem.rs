@@ -7,12 +7,13 @@ use crate::model::filesystem::{
 };
 use crate::services::remote::channel::{AgentChannel, ChannelError};
 use crate::services::remote::protocol::{
-    decode_base64, ls_params, read_params, stat_params, sudo_write_params, write_params,
-    RemoteDirEntry, RemoteMetadata,
+    decode_base64, ls_params, read_params, stat_params, sudo_write_params, watch_params,
+    write_params, RemoteDirEntry, RemoteMetadata, CAP_WATCH,
 };
 use std::io::{self, Cursor, Read, Seek, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, UNIX_EPOCH};
 
 /// Remote filesystem that communicates with the Python agent
@@ -20,6 +21,23 @@ pub struct RemoteFileSystem {
     channel: Arc<AgentChannel>,
     /// Display string for the connection
     connection_string: String,
+    /// The agent-side watch covering open files and expanded directories
+    watch: Mutex<WatchState>,
+    /// Paths reported changed by the watch, until taken
+    changed_paths: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+/// State of the streaming `watch` request
+#[derive(Default)]
+struct WatchState {
+    /// Paths the current watch covers
+    paths: Vec<PathBuf>,
+    /// Request id of the current watch, if one was started
+    request_id: Option<u64>,
+    /// Cleared when the watch stream ends (cancelled or connection lost)
+    active: Arc<AtomicBool>,
+    /// Set when a watch ended unexpectedly and changes may have been missed
+    resync: bool,
 }
 
 impl RemoteFileSystem {
@@ -28,6 +46,8 @@ impl RemoteFileSystem {
         Self {
             channel,
             connection_string,
+            watch: Mutex::new(WatchState::default()),
+            changed_paths: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -389,6 +409,82 @@ impl FileSystem for RemoteFileSystem {
         Some(&self.connection_string)
     }
 
+    fn is_remote_connected(&self) -> bool {
+        self.channel.is_connected()
+    }
+
+    fn supports_watch(&self) -> bool {
+        self.channel.has_capability(CAP_WATCH)
+    }
+
+    fn watch(&self, paths: &[PathBuf]) {
+        if !self.supports_watch() {
+            return;
+        }
+
+        let mut state = self.watch.lock().unwrap();
+        let active = state.active.load(Ordering::SeqCst);
+        if active && state.paths == paths {
+            return;
+        }
+
+        if let Some(id) = state.request_id.take() {
+            if active {
+                let channel = self.channel.clone();
+                self.channel.runtime_handle().spawn(async move {
+                    let _ = channel.cancel(id).await;
+                });
+            } else {
+                // The stream died under us (e.g. the connection dropped)
+                state.resync = true;
+            }
+        }
+        state.paths = paths.to_vec();
+        if paths.is_empty() {
+            return;
+        }
+
+        let path_strs: Vec<String> = paths
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+        let (id, mut data_rx, _result_rx) = match self.channel.runtime_handle().block_on(
+            self.channel
+                .request_streaming_with_id("watch", watch_params(&path_strs)),
+        ) {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::debug!("Failed to start remote watch: {}", e);
+                return;
+            }
+        };
+
+        let active = Arc::new(AtomicBool::new(true));
+        state.request_id = Some(id);
+        state.active = active.clone();
+
+        let changed_paths = self.changed_paths.clone();
+        if std::mem::take(&mut state.resync) {
+            // Anything could have changed while nothing was watching
+            changed_paths.lock().unwrap().extend_from_slice(paths);
+        }
+        self.channel.runtime_handle().spawn(async move {
+            while let Some(data) = data_rx.recv().await {
+                if let Some(changed) = data.get("changed").and_then(|v| v.as_array()) {
+                    changed_paths
+                        .lock()
+                        .unwrap()
+                        .extend(changed.iter().filter_map(|p| p.as_str()).map(PathBuf::from));
+                }
+            }
+            active.store(false, Ordering::SeqCst);
+        });
+    }
+
+    fn take_changed_paths(&self) -> Vec<PathBuf> {
+        std::mem::take(&mut *self.changed_paths.lock().unwrap())
+    }
+
     fn home_dir(&self) -> io::Result<PathBuf> {
         let result = self
             .channel
@@ -12,15 +12,16 @@ mod spawner;
 pub use channel::AgentChannel;
 /// Re-export for integration tests - spawns a local agent without SSH
 #[doc(hidden)]
-pub use connection::spawn_local_agent;
+pub use connection::{reconnect_local_agent, spawn_local_agent};
 pub use connection::{ConnectionParams, SshConnection};
 pub use filesystem::RemoteFileSystem;
 pub use protocol::{
     decode_base64, encode_base64, ls_params, read_params, stat_params, sudo_write_params,
-    write_params, AgentRequest, AgentResponse,
+    write_params, AgentRequest, AgentResponse, CAP_PTY, CAP_SIGNAL, CAP_STDIN, CAP_WATCH,
 };
 pub use spawner::{
-    LocalProcessSpawner, ProcessSpawner, RemoteProcessSpawner, SpawnError, SpawnResult,
+    LocalProcessSpawner, PipeReader, PipeWriter, PipedProcess, ProcessGuard, ProcessSignal,
+    ProcessSpawner, RemoteProcessSpawner, SpawnError, SpawnResult,
 };
 
 /// The Python agent source code, embedded at compile time.
@@ -7,7 +7,22 @@ use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use serde::{Deserialize, Serialize};
 
 /// Protocol version
-pub const PROTOCOL_VERSION: u32 = 1;
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest agent protocol version still accepted
+///
+/// Version 1 agents do not advertise capabilities, so only the base
+/// file and exec methods are used with them.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Capability: `exec` with `"stdin": true` and the `stdin` method
+pub const CAP_STDIN: &str = "stdin";
+/// Capability: `exec` with `"pty"` and the `resize` method
+pub const CAP_PTY: &str = "pty";
+/// Capability: the `signal` method
+pub const CAP_SIGNAL: &str = "signal";
+/// Capability: the `watch` method
+pub const CAP_WATCH: &str = "watch";
 
 /// Request sent to the agent
 #[derive(Debug, Clone, Serialize)]
@@ -50,6 +65,8 @@ pub struct AgentResponse {
     pub ok: Option<bool>,
     #[serde(rename = "v")]
     pub version: Option<u32>,
+    /// Optional features supported by the agent (ready message, v2+)
+    pub caps: Option<Vec<String>>,
 }
 
 impl AgentResponse {
@@ -187,6 +204,14 @@ pub fn ls_params(path: &str) -> serde_json::Value {
     serde_json::json!({"path": path})
 }
 
+/// Build params for watch request
+///
+/// The agent streams `{"changed": [path, ...]}` data until the request is
+/// cancelled. Directories report entries being added, removed or renamed.
+pub fn watch_params(paths: &[String]) -> serde_json::Value {
+    serde_json::json!({"paths": paths})
+}
+
 /// Build params for exec request
 ///
 /// NOTE: Used by RemoteProcessSpawner, appears unused until spawner integration.
@@ -201,6 +226,60 @@ pub fn exec_params(cmd: &str, args: &[String], cwd: Option<&str>) -> serde_json:
     params
 }
 
+/// Build params for an exec request whose stdin stays open
+///
+/// Input is then delivered with `stdin` requests referencing this request's id.
+pub fn exec_piped_params(cmd: &str, args: &[String], cwd: Option<&str>) -> serde_json::Value {
+    let mut params = exec_params(cmd, args, cwd);
+    params["stdin"] = serde_json::json!(true);
+    params
+}
+
+/// Build params for stdin request (write to, and optionally close, a piped process)
+pub fn stdin_params(request_id: u64, data: &[u8], eof: bool) -> serde_json::Value {
+    let mut params = serde_json::json!({"id": request_id});
+    if !data.is_empty() {
+        params["data"] = serde_json::json!(encode_base64(data));
+    }
+    if eof {
+        params["eof"] = serde_json::json!(true);
+    }
+    params
+}
+
+/// Build params for an exec request running on a new PTY of the given size
+///
+/// The process output (stdout and stderr merged by the terminal) arrives as
+/// `out` data; input is delivered with `stdin` requests.
+pub fn exec_pty_params(
+    cmd: &str,
+    args: &[String],
+    cwd: Option<&str>,
+    rows: u16,
+    cols: u16,
+) -> serde_json::Value {
+    let mut params = exec_params(cmd, args, cwd);
+    params["pty"] = serde_json::json!({"rows": rows, "cols": cols});
+    params
+}
+
+/// Build params for resize request (change the window size of a PTY process)
+pub fn resize_params(request_id: u64, rows: u16, cols: u16) -> serde_json::Value {
+    serde_json::json!({"id": request_id, "rows": rows, "cols": cols})
+}
+
+/// Build params for signal request
+///
+/// `signal` is one of `INT`, `TERM`, `KILL`, `HUP` or `QUIT`.
+pub fn signal_params(request_id: u64, signal: &str) -> serde_json::Value {
+    serde_json::json!({"id": request_id, "sig": signal})
+}
+
+/// Build params for kill request
+pub fn kill_params(request_id: u64) -> serde_json::Value {
+    serde_json::json!({"id": request_id})
+}
+
 /// Build params for cancel request
 pub fn cancel_params(request_id: u64) -> serde_json::Value {
     serde_json::json!({"id": request_id})
@@ -225,6 +304,15 @@ mod tests {
         let resp: AgentResponse = serde_json::from_str(ready).unwrap();
         assert!(resp.is_ready());
         assert_eq!(resp.version, Some(1));
+        assert_eq!(resp.caps, None);
+
+        let ready_v2 = r#"{"id":0,"ok":true,"v":2,"caps":["stdin","pty"]}"#;
+        let resp: AgentResponse = serde_json::from_str(ready_v2).unwrap();
+        assert!(resp.is_ready());
+        assert_eq!(
+            resp.caps,
+            Some(vec!["stdin".to_string(), "pty".to_string()])
+        );
 
         let data = r#"{"id":1,"d":{"data":"SGVsbG8="}}"#;
         let resp: AgentResponse = serde_json::from_str(data).unwrap();
@@ -1,15 +1,25 @@
 //! Remote process spawner
 //!
-//! Executes processes on the remote host via the SSH agent.
-//!
-//! NOTE: This module is currently unused but provides infrastructure for
-//! plugin process execution on remote hosts. It will be integrated with
-//! the Editor's SpawnProcess handler to allow plugins like live_grep,
-//! git_grep, and fuzzy finder to work transparently on remote files.
+//! Executes processes on the remote host via the SSH agent, either to
+//! completion (plugin `SpawnProcess`), as long-running piped processes
+//! (language servers) or attached to a remote PTY (terminals).
 
 use crate::services::remote::channel::{AgentChannel, ChannelError};
-use crate::services::remote::protocol::{decode_base64, exec_params};
+use crate::services::remote::protocol::{
+    decode_base64, exec_params, exec_piped_params, exec_pty_params, kill_params, resize_params,
+    signal_params, stdin_params, CAP_PTY, CAP_SIGNAL, CAP_STDIN,
+};
 use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Writable end of a process pipe (local or remote)
+pub type PipeWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Readable end of a process pipe (local or remote)
+pub type PipeReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Buffer size of the in-memory pipes backing remote process streams
+const REMOTE_PIPE_CAPACITY: usize = 64 * 1024;
 
 /// Result of spawning a process
 ///
@@ -35,13 +45,182 @@ pub enum SpawnError {
 
     #[error("Decode error: {0}")]
     Decode(String),
+
+    #[error("Not supported: {0}")]
+    Unsupported(String),
+}
+
+/// Signal that can be delivered to a running process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSignal {
+    Interrupt,
+    Terminate,
+    Kill,
+    Hangup,
+    Quit,
+}
+
+impl ProcessSignal {
+    /// Name used by the agent `signal` method
+    pub fn agent_name(self) -> &'static str {
+        match self {
+            ProcessSignal::Interrupt => "INT",
+            ProcessSignal::Terminate => "TERM",
+            ProcessSignal::Kill => "KILL",
+            ProcessSignal::Hangup => "HUP",
+            ProcessSignal::Quit => "QUIT",
+        }
+    }
+}
+
+/// A running process with full-duplex stdin/stdout and a stderr stream
+///
+/// Dropping the process kills it (local: `kill_on_drop`, remote: `kill` request).
+/// Callers must keep draining `stderr`: for remote processes both output streams
+/// are fed by the same pump, so a full stderr pipe stalls stdout too.
+pub struct PipedProcess {
+    pub stdin: PipeWriter,
+    pub stdout: PipeReader,
+    pub stderr: PipeReader,
+    /// Keeps the process alive; killing it when dropped
+    pub guard: ProcessGuard,
+}
+
+/// Lifetime guard for a [`PipedProcess`]
+pub enum ProcessGuard {
+    /// Local child process (spawned with `kill_on_drop`)
+    Local(tokio::process::Child),
+    /// Process running on the remote agent, addressed by its exec request id
+    Remote {
+        channel: Arc<AgentChannel>,
+        request_id: u64,
+        /// Exit code, delivered once the agent reports the process finished
+        exit: Option<tokio::sync::oneshot::Receiver<i32>>,
+    },
+}
+
+impl ProcessGuard {
+    /// Wait for the process to exit and return its exit code
+    ///
+    /// The code is -1 when the process was killed by a signal or failed to
+    /// start on the remote host.
+    pub async fn wait(&mut self) -> Result<i32, SpawnError> {
+        match self {
+            ProcessGuard::Local(child) => child
+                .wait()
+                .await
+                .map(|status| status.code().unwrap_or(-1))
+                .map_err(|e| SpawnError::Process(e.to_string())),
+            ProcessGuard::Remote { exit, .. } => {
+                let exit = exit
+                    .take()
+                    .ok_or_else(|| SpawnError::Process("process already waited on".to_string()))?;
+                exit.await
+                    .map_err(|_| SpawnError::Channel(ChannelError::ChannelClosed))
+            }
+        }
+    }
+
+    /// Deliver a signal to the process
+    ///
+    /// For remote PTY processes the whole process group receives it, like a
+    /// keyboard-generated signal would.
+    pub async fn signal(&mut self, signal: ProcessSignal) -> Result<(), SpawnError> {
+        match self {
+            ProcessGuard::Local(child) => {
+                #[cfg(unix)]
+                {
+                    use nix::sys::signal::{kill, Signal};
+                    let pid = child
+                        .id()
+                        .ok_or_else(|| SpawnError::Process("process has exited".to_string()))?;
+                    let sig = match signal {
+                        ProcessSignal::Interrupt => Signal::SIGINT,
+                        ProcessSignal::Terminate => Signal::SIGTERM,
+                        ProcessSignal::Kill => Signal::SIGKILL,
+                        ProcessSignal::Hangup => Signal::SIGHUP,
+                        ProcessSignal::Quit => Signal::SIGQUIT,
+                    };
+                    kill(nix::unistd::Pid::from_raw(pid as i32), sig)
+                        .map_err(|e| SpawnError::Process(e.to_string()))
+                }
+                #[cfg(not(unix))]
+                {
+                    match signal {
+                        ProcessSignal::Kill | ProcessSignal::Terminate => child
+                            .start_kill()
+                            .map_err(|e| SpawnError::Process(e.to_string())),
+                        _ => Err(SpawnError::Unsupported(format!(
+                            "signal {} on this platform",
+                            signal.agent_name()
+                        ))),
+                    }
+                }
+            }
+            ProcessGuard::Remote {
+                channel,
+                request_id,
+                ..
+            } => {
+                if !channel.has_capability(CAP_SIGNAL) {
+                    return Err(SpawnError::Unsupported(
+                        "remote agent cannot deliver signals".to_string(),
+                    ));
+                }
+                channel
+                    .request("signal", signal_params(*request_id, signal.agent_name()))
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Change the window size of a process started with
+    /// [`ProcessSpawner::spawn_pty`]
+    pub async fn resize(&self, rows: u16, cols: u16) -> Result<(), SpawnError> {
+        match self {
+            ProcessGuard::Local(_) => Err(SpawnError::Unsupported(
+                "process is not attached to a PTY".to_string(),
+            )),
+            ProcessGuard::Remote {
+                channel,
+                request_id,
+                ..
+            } => {
+                channel
+                    .request("resize", resize_params(*request_id, rows, cols))
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Drop for ProcessGuard {
+    fn drop(&mut self) {
+        if let ProcessGuard::Remote {
+            channel,
+            request_id,
+            ..
+        } = self
+        {
+            if !channel.is_connected() {
+                return;
+            }
+            let channel = channel.clone();
+            let request_id = *request_id;
+            // The process may already have exited; "process not found" is fine
+            channel.runtime_handle().clone().spawn(async move {
+                let _ = channel.request("kill", kill_params(request_id)).await;
+            });
+        }
+    }
 }
 
 /// Trait for spawning processes (local or remote)
 ///
-/// NOTE: Unused until process spawner is integrated with Editor.
-/// This abstraction allows plugins to spawn processes transparently
-/// on either local or remote filesystems.
+/// This abstraction allows plugins and language servers to spawn processes
+/// transparently on either local or remote filesystems.
 #[async_trait::async_trait]
 pub trait ProcessSpawner: Send + Sync {
     /// Spawn a process and wait for completion
@@ -51,6 +230,58 @@ pub trait ProcessSpawner: Send + Sync {
         args: Vec<String>,
         cwd: Option<String>,
     ) -> Result<SpawnResult, SpawnError>;
+
+    /// Spawn a long-running process with piped stdin, stdout and stderr
+    async fn spawn_piped(
+        &self,
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+    ) -> Result<PipedProcess, SpawnError>;
+
+    /// Spawn a long-running piped process with extra environment variables
+    ///
+    /// The default sets the variables through `env`, for spawners (like the
+    /// remote agent) that cannot pass an environment themselves.
+    async fn spawn_piped_with_env(
+        &self,
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        env: Vec<(String, String)>,
+    ) -> Result<PipedProcess, SpawnError> {
+        if env.is_empty() {
+            return self.spawn_piped(command, args, cwd).await;
+        }
+        let env_args = env
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .chain(std::iter::once(command))
+            .chain(args)
+            .collect();
+        self.spawn_piped("env".to_string(), env_args, cwd).await
+    }
+
+    /// Spawn a process on a new PTY with the given window size
+    ///
+    /// The terminal merges the process's stdout and stderr, so all output
+    /// arrives on `stdout` and `stderr` only carries spawn failures. Closing
+    /// `stdin` sends the terminal EOF character.
+    ///
+    /// Only the remote spawner implements this; local terminals open their
+    /// PTY directly (see `services::terminal`).
+    async fn spawn_pty(
+        &self,
+        _command: String,
+        _args: Vec<String>,
+        _cwd: Option<String>,
+        _rows: u16,
+        _cols: u16,
+    ) -> Result<PipedProcess, SpawnError> {
+        Err(SpawnError::Unsupported(
+            "PTY processes are only spawned remotely".to_string(),
+        ))
+    }
 }
 
 /// Local process spawner using tokio
@@ -85,6 +316,52 @@ impl ProcessSpawner for LocalProcessSpawner {
             exit_code: output.status.code().unwrap_or(-1),
         })
     }
+
+    async fn spawn_piped(
+        &self,
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+    ) -> Result<PipedProcess, SpawnError> {
+        self.spawn_piped_with_env(command, args, cwd, Vec::new())
+            .await
+    }
+
+    async fn spawn_piped_with_env(
+        &self,
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        env: Vec<(String, String)>,
+    ) -> Result<PipedProcess, SpawnError> {
+        let mut cmd = tokio::process::Command::new(&command);
+        cmd.args(&args)
+            .envs(env)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+
+        if let Some(ref dir) = cwd {
+            cmd.current_dir(dir);
+        }
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| SpawnError::Process(e.to_string()))?;
+
+        let take_err = |what: &str| SpawnError::Process(format!("failed to get {}", what));
+        let stdin = child.stdin.take().ok_or_else(|| take_err("stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| take_err("stdout"))?;
+        let stderr = child.stderr.take().ok_or_else(|| take_err("stderr"))?;
+
+        Ok(PipedProcess {
+            stdin: Box::new(stdin),
+            stdout: Box::new(stdout),
+            stderr: Box::new(stderr),
+            guard: ProcessGuard::Local(child),
+        })
+    }
 }
 
 /// Remote process spawner via SSH agent
@@ -97,6 +374,89 @@ impl RemoteProcessSpawner {
     pub fn new(channel: Arc<AgentChannel>) -> Self {
         Self { channel }
     }
+
+    /// Start an `exec` request and connect its streams to in-memory pipes
+    async fn start_duplex(&self, params: serde_json::Value) -> Result<PipedProcess, SpawnError> {
+        let (request_id, mut data_rx, result_rx) = self
+            .channel
+            .request_streaming_with_id("exec", params)
+            .await?;
+
+        // Output pump: agent `d` messages -> in-memory stdout/stderr pipes
+        let (mut stdout_tx, stdout_rx) = tokio::io::duplex(REMOTE_PIPE_CAPACITY);
+        let (mut stderr_tx, stderr_rx) = tokio::io::duplex(REMOTE_PIPE_CAPACITY);
+        let (exit_tx, exit_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            while let Some(data) = data_rx.recv().await {
+                if let Some(out) = data.get("out").and_then(|v| v.as_str()) {
+                    if let Ok(decoded) = decode_base64(out) {
+                        let _ = stdout_tx.write_all(&decoded).await;
+                    }
+                }
+                if let Some(err) = data.get("err").and_then(|v| v.as_str()) {
+                    if let Ok(decoded) = decode_base64(err) {
+                        let _ = stderr_tx.write_all(&decoded).await;
+                    }
+                }
+            }
+            // Surface spawn failures (e.g. "command not found") on stderr
+            let exit_code = match result_rx.await {
+                Ok(Ok(result)) => {
+                    tracing::debug!("Remote process {} exited: {}", request_id, result);
+                    result
+                        .get("code")
+                        .and_then(|v| v.as_i64())
+                        .map_or(-1, |c| c as i32)
+                }
+                Ok(Err(e)) => {
+                    let _ = stderr_tx
+                        .write_all(format!("fresh: remote process failed: {}\n", e).as_bytes())
+                        .await;
+                    -1
+                }
+                Err(_) => -1,
+            };
+            let _ = exit_tx.send(exit_code);
+            // Dropping the writers signals EOF to the readers
+        });
+
+        // Input pump: in-memory stdin pipe -> agent `stdin` requests
+        let (stdin_tx, mut stdin_rx) = tokio::io::duplex(REMOTE_PIPE_CAPACITY);
+        let channel = self.channel.clone();
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; REMOTE_PIPE_CAPACITY];
+            loop {
+                match stdin_rx.read(&mut buf).await {
+                    Ok(0) | Err(_) => {
+                        let _ = channel
+                            .request("stdin", stdin_params(request_id, &[], true))
+                            .await;
+                        break;
+                    }
+                    Ok(n) => {
+                        if channel
+                            .request("stdin", stdin_params(request_id, &buf[..n], false))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(PipedProcess {
+            stdin: Box::new(stdin_tx),
+            stdout: Box::new(stdout_rx),
+            stderr: Box::new(stderr_rx),
+            guard: ProcessGuard::Remote {
+                channel: self.channel.clone(),
+                request_id,
+                exit: Some(exit_rx),
+            },
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -133,7 +493,7 @@ impl ProcessSpawner for RemoteProcessSpawner {
         let result = result_rx
             .await
             .map_err(|_| SpawnError::Channel(ChannelError::ChannelClosed))?
-            .map_err(|e| SpawnError::Process(e))?;
+            .map_err(SpawnError::Process)?;
 
         let exit_code = result
             .get("code")
@@ -147,6 +507,38 @@ impl ProcessSpawner for RemoteProcessSpawner {
             exit_code,
         })
     }
+
+    async fn spawn_piped(
+        &self,
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+    ) -> Result<PipedProcess, SpawnError> {
+        if !self.channel.has_capability(CAP_STDIN) {
+            return Err(SpawnError::Unsupported(
+                "remote agent cannot write to process stdin".to_string(),
+            ));
+        }
+        let params = exec_piped_params(&command, &args, cwd.as_deref());
+        self.start_duplex(params).await
+    }
+
+    async fn spawn_pty(
+        &self,
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        rows: u16,
+        cols: u16,
+    ) -> Result<PipedProcess, SpawnError> {
+        if !self.channel.has_capability(CAP_PTY) {
+            return Err(SpawnError::Unsupported(
+                "remote agent cannot allocate a PTY".to_string(),
+            ));
+        }
+        let params = exec_pty_params(&command, &args, cwd.as_deref(), rows, cols);
+        self.start_duplex(params).await
+    }
 }
 
 #[cfg(test)]
@@ -164,4 +556,41 @@ mod tests {
         assert_eq!(result.exit_code, 0);
         assert!(result.stdout.trim() == "hello");
     }
+
+    #[tokio::test]
+    async fn test_local_spawner_piped() {
+        let spawner = LocalProcessSpawner;
+        let mut process = spawner
+            .spawn_piped("cat".to_string(), vec![], None)
+            .await
+            .unwrap();
+
+        process.stdin.write_all(b"ping\n").await.unwrap();
+        process.stdin.flush().await.unwrap();
+        drop(process.stdin);
+
+        let mut output = String::new();
+        process.stdout.read_to_string(&mut output).await.unwrap();
+        assert_eq!(output, "ping\n");
+        assert_eq!(process.guard.wait().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_local_spawner_piped_with_env() {
+        let spawner = LocalProcessSpawner;
+        let mut process = spawner
+            .spawn_piped_with_env(
+                "sh".to_string(),
+                vec!["-c".to_string(), "echo $GREETING; exit 3".to_string()],
+                None,
+                vec![("GREETING".to_string(), "hi".to_string())],
+            )
+            .await
+            .unwrap();
+
+        let mut output = String::new();
+        process.stdout.read_to_string(&mut output).await.unwrap();
+        assert_eq!(output, "hi\n");
+        assert_eq!(process.guard.wait().await.unwrap(), 3);
+    }
 }
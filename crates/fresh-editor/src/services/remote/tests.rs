@@ -9,7 +9,7 @@ use super::AGENT_SOURCE;
 
 #[test]
 fn test_protocol_version() {
-    assert_eq!(PROTOCOL_VERSION, 1);
+    assert_eq!(PROTOCOL_VERSION, 2);
 }
 
 #[test]
@@ -34,6 +34,19 @@ fn test_response_parsing_ready() {
     assert!(!resp.is_data());
 }
 
+#[test]
+fn test_response_parsing_ready_capabilities() {
+    let json = r#"{"id":0,"ok":true,"v":2,"caps":["stdin","pty","signal"]}"#;
+    let resp: AgentResponse = serde_json::from_str(json).unwrap();
+
+    assert!(resp.is_ready());
+    assert_eq!(resp.version, Some(2));
+    let caps = resp.caps.unwrap();
+    assert!(caps.iter().any(|c| c == CAP_STDIN));
+    assert!(caps.iter().any(|c| c == CAP_PTY));
+    assert!(caps.iter().any(|c| c == CAP_SIGNAL));
+}
+
 #[test]
 fn test_response_parsing_data() {
     let json = r#"{"id":1,"d":{"data":"SGVsbG8="}}"#;
@@ -132,6 +145,61 @@ fn test_exec_params() {
     assert!(params.get("cwd").is_none());
 }
 
+#[test]
+fn test_exec_piped_params() {
+    let params = exec_piped_params("rust-analyzer", &[], Some("/home/user/proj"));
+    assert_eq!(params["cmd"], "rust-analyzer");
+    assert_eq!(params["cwd"], "/home/user/proj");
+    assert_eq!(params["stdin"], true);
+
+    // Plain exec keeps stdin closed
+    let params = exec_params("ls", &[], None);
+    assert!(params.get("stdin").is_none());
+}
+
+#[test]
+fn test_stdin_params() {
+    let params = stdin_params(7, b"Hello", false);
+    assert_eq!(params["id"], 7);
+    assert_eq!(params["data"], encode_base64(b"Hello"));
+    assert!(params.get("eof").is_none());
+
+    let params = stdin_params(7, b"", true);
+    assert!(params.get("data").is_none());
+    assert_eq!(params["eof"], true);
+}
+
+#[test]
+fn test_exec_pty_params() {
+    let params = exec_pty_params("bash", &["-l".to_string()], Some("/srv"), 24, 80);
+    assert_eq!(params["cmd"], "bash");
+    assert_eq!(params["args"][0], "-l");
+    assert_eq!(params["cwd"], "/srv");
+    assert_eq!(params["pty"]["rows"], 24);
+    assert_eq!(params["pty"]["cols"], 80);
+}
+
+#[test]
+fn test_resize_params() {
+    let params = resize_params(3, 50, 132);
+    assert_eq!(params["id"], 3);
+    assert_eq!(params["rows"], 50);
+    assert_eq!(params["cols"], 132);
+}
+
+#[test]
+fn test_signal_params() {
+    let params = signal_params(9, "INT");
+    assert_eq!(params["id"], 9);
+    assert_eq!(params["sig"], "INT");
+}
+
+#[test]
+fn test_watch_params() {
+    let params = watch_params(&["/a.txt".to_string(), "/dir".to_string()]);
+    assert_eq!(params["paths"], serde_json::json!(["/a.txt", "/dir"]));
+}
+
 #[test]
 fn test_cancel_params() {
     let params = cancel_params(42);
@@ -315,7 +383,177 @@ mod agent_integration {
         let resp: AgentResponse = serde_json::from_str(&line).unwrap();
 
         assert!(resp.is_ready());
-        assert_eq!(resp.version, Some(1));
+        assert_eq!(resp.version, Some(PROTOCOL_VERSION));
+        let caps = resp.caps.unwrap();
+        assert!(caps.iter().any(|c| c == CAP_STDIN));
+        assert!(caps.iter().any(|c| c == CAP_SIGNAL));
+        assert!(caps.iter().any(|c| c == CAP_WATCH));
+    }
+
+    /// Read responses for request 1 until it finishes, returning its output
+    /// and either the exit code or the error
+    fn collect_exec(
+        stdout: &mut BufReader<std::process::ChildStdout>,
+    ) -> (Vec<u8>, Result<i64, String>) {
+        let mut output = Vec::new();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if stdout.read_line(&mut line).unwrap() == 0 {
+                panic!("agent closed stdout before exec finished");
+            }
+            let resp: AgentResponse = serde_json::from_str(&line).unwrap();
+            if resp.id != 1 {
+                assert!(resp.error.is_none(), "request failed: {:?}", resp.error);
+                continue;
+            }
+            if let Some(data) = resp.data {
+                if let Some(out) = data.get("out").and_then(|v| v.as_str()) {
+                    output.extend(decode_base64(out).unwrap());
+                }
+            }
+            if let Some(result) = resp.result {
+                return (output, Ok(result["code"].as_i64().unwrap()));
+            }
+            if let Some(error) = resp.error {
+                return (output, Err(error));
+            }
+        }
+    }
+
+    #[test]
+    fn test_agent_exec_pty_resize() {
+        let Some((mut stdin, mut stdout)) = spawn_agent() else {
+            eprintln!("Skipping test: Python3 not available");
+            return;
+        };
+
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        let ready: AgentResponse = serde_json::from_str(&line).unwrap();
+        if !ready.caps.unwrap_or_default().iter().any(|c| c == CAP_PTY) {
+            eprintln!("Skipping test: agent has no PTY support");
+            return;
+        }
+
+        // Report the size, wait for a line, then report the size again
+        let script = "stty size; read x; echo got:$x; stty size".to_string();
+        let exec = AgentRequest::new(
+            1,
+            "exec",
+            exec_pty_params("sh", &["-c".to_string(), script], None, 30, 100),
+        );
+        stdin.write_all(exec.to_json_line().as_bytes()).unwrap();
+        stdin.flush().unwrap();
+
+        // Wait for the initial size before resizing
+        let mut initial = Vec::new();
+        while !String::from_utf8_lossy(&initial).contains("30 100") {
+            line.clear();
+            stdout.read_line(&mut line).unwrap();
+            let resp: AgentResponse = serde_json::from_str(&line).unwrap();
+            assert!(resp.error.is_none(), "exec failed: {:?}", resp.error);
+            if let Some(out) = resp.data.as_ref().and_then(|d| d.get("out")) {
+                initial.extend(decode_base64(out.as_str().unwrap()).unwrap());
+            }
+        }
+
+        let resize = AgentRequest::new(2, "resize", resize_params(1, 40, 120));
+        let input = AgentRequest::new(3, "stdin", stdin_params(1, b"hi\n", false));
+        for req in [resize, input] {
+            stdin.write_all(req.to_json_line().as_bytes()).unwrap();
+        }
+        stdin.flush().unwrap();
+
+        let (output, code) = collect_exec(&mut stdout);
+        let output = String::from_utf8_lossy(&output);
+        assert_eq!(code, Ok(0));
+        assert!(output.contains("got:hi"), "output: {:?}", output);
+        assert!(output.contains("40 120"), "output: {:?}", output);
+    }
+
+    #[test]
+    fn test_agent_signal() {
+        let Some((mut stdin, mut stdout)) = spawn_agent() else {
+            eprintln!("Skipping test: Python3 not available");
+            return;
+        };
+
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+
+        let exec = AgentRequest::new(1, "exec", exec_params("sleep", &["30".to_string()], None));
+        let signal = AgentRequest::new(2, "signal", signal_params(1, "TERM"));
+        for req in [exec, signal] {
+            stdin.write_all(req.to_json_line().as_bytes()).unwrap();
+        }
+        stdin.flush().unwrap();
+
+        // Python reports death by signal N as a negative exit code
+        let (_, code) = collect_exec(&mut stdout);
+        assert_eq!(code, Ok(-15));
+    }
+
+    /// Read the next batch of changed paths reported for request 1
+    fn next_watch_event(stdout: &mut BufReader<std::process::ChildStdout>) -> Vec<String> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if stdout.read_line(&mut line).unwrap() == 0 {
+                panic!("agent closed stdout while watching");
+            }
+            let resp: AgentResponse = serde_json::from_str(&line).unwrap();
+            assert!(resp.error.is_none(), "watch failed: {:?}", resp.error);
+            if let Some(changed) = resp.data.as_ref().and_then(|d| d.get("changed")) {
+                return serde_json::from_value(changed.clone()).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_agent_watch() {
+        let Some((mut stdin, mut stdout)) = spawn_agent() else {
+            eprintln!("Skipping test: Python3 not available");
+            return;
+        };
+
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().to_string_lossy().to_string();
+        let file = temp_dir.path().join("watched.txt");
+        std::fs::write(&file, "one").unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        let req = AgentRequest::new(1, "watch", watch_params(&[file.clone(), dir.clone()]));
+        stdin.write_all(req.to_json_line().as_bytes()).unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+
+        // Modifying a file reports the file, not its directory
+        std::fs::write(&file, "two, longer").unwrap();
+        assert_eq!(next_watch_event(&mut stdout), vec![file.clone()]);
+
+        // Adding an entry reports the directory
+        std::fs::write(temp_dir.path().join("new.txt"), "").unwrap();
+        assert_eq!(next_watch_event(&mut stdout), vec![dir.clone()]);
+
+        let cancel = AgentRequest::new(2, "cancel", cancel_params(1));
+        stdin.write_all(cancel.to_json_line().as_bytes()).unwrap();
+        stdin.flush().unwrap();
+
+        let mut finished = false;
+        for _ in 0..2 {
+            line.clear();
+            stdout.read_line(&mut line).unwrap();
+            let resp: AgentResponse = serde_json::from_str(&line).unwrap();
+            if resp.id == 1 {
+                assert_eq!(resp.error.as_deref(), Some("cancelled"));
+                finished = true;
+            }
+        }
+        assert!(finished, "watch should end after cancel");
     }
 
     #[test]
@@ -423,6 +661,49 @@ mod agent_integration {
         assert!(!path.contains('~'));
     }
 
+    #[test]
+    fn test_agent_exec_piped_stdin() {
+        let Some((mut stdin, mut stdout)) = spawn_agent() else {
+            eprintln!("Skipping test: Python3 not available");
+            return;
+        };
+
+        // Read ready message
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+
+        // Start `cat` with piped stdin, then feed it a line and close stdin
+        let exec = AgentRequest::new(1, "exec", exec_piped_params("cat", &[], None));
+        let input = AgentRequest::new(2, "stdin", stdin_params(1, b"round trip\n", true));
+        stdin.write_all(exec.to_json_line().as_bytes()).unwrap();
+        stdin.write_all(input.to_json_line().as_bytes()).unwrap();
+        stdin.flush().unwrap();
+
+        let mut output = Vec::new();
+        let exit_code = loop {
+            line.clear();
+            if stdout.read_line(&mut line).unwrap() == 0 {
+                panic!("agent closed stdout before exec finished");
+            }
+            let resp: AgentResponse = serde_json::from_str(&line).unwrap();
+            if resp.id != 1 {
+                continue;
+            }
+            if let Some(data) = resp.data {
+                if let Some(out) = data.get("out").and_then(|v| v.as_str()) {
+                    output.extend(decode_base64(out).unwrap());
+                }
+            }
+            if let Some(result) = resp.result {
+                break result["code"].as_i64().unwrap();
+            }
+            assert!(resp.error.is_none(), "exec failed: {:?}", resp.error);
+        };
+
+        assert_eq!(exit_code, 0);
+        assert_eq!(output, b"round trip\n");
+    }
+
     #[test]
     fn test_agent_unknown_method() {
         let Some((mut stdin, mut stdout)) = spawn_agent() else {
@@ -17,9 +17,17 @@
 //! it calls `process_output()` to update the terminal grid, then `flush_new_scrollback()`
 //! to append any new scrollback lines to the backing file. This ensures scrollback is
 //! written incrementally as lines scroll off screen, avoiding O(n) work on mode switches.
+//!
+//! ## Remote Terminals
+//!
+//! During SSH editing the PTY lives on the remote host (allocated by the agent,
+//! see [`ProcessSpawner::spawn_pty`]). The same read loop consumes its output, so
+//! backing files, session logs and redraw notifications behave exactly as for
+//! local terminals.
 
 use super::term::TerminalState;
 use crate::services::async_bridge::AsyncBridge;
+use crate::services::remote::{PipeReader, PipedProcess, ProcessSpawner};
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
 use std::collections::HashMap;
 use std::io::{Read, Write};
@@ -27,9 +35,73 @@ use std::sync::atomic::AtomicBool;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 pub use fresh_core::TerminalId;
 
+/// Starts the remote user's login shell (the agent runs commands without one)
+const REMOTE_SHELL_SCRIPT: &str = "exec \"${SHELL:-/bin/sh}\" -l";
+
+/// Shell name recorded for remote terminals
+const REMOTE_SHELL_NAME: &str = "$SHELL";
+
+/// Where terminals are spawned during a remote session
+#[derive(Clone)]
+pub struct RemoteTerminalTarget {
+    /// Spawner that allocates PTYs on the remote host
+    pub spawner: Arc<dyn ProcessSpawner>,
+    /// Runtime driving the remote process streams from the terminal threads
+    pub runtime: tokio::runtime::Handle,
+}
+
+/// What a new terminal runs
+///
+/// The default launches the user's shell; plugins can run a specific
+/// program with extra environment variables instead.
+#[derive(Debug, Clone, Default)]
+pub struct TerminalLaunch {
+    /// Program to run (default: the user's shell)
+    pub program: Option<String>,
+    /// Arguments for `program`
+    pub args: Vec<String>,
+    /// Extra environment variables
+    pub env: Vec<(String, String)>,
+}
+
+/// Blocking `Read` over an async pipe, for the terminal reader thread
+struct BlockingPipeReader {
+    pipe: PipeReader,
+    runtime: tokio::runtime::Handle,
+}
+
+impl Read for BlockingPipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.runtime.block_on(self.pipe.read(buf))
+    }
+}
+
+/// Create the emulator state for a new terminal
+///
+/// If the backing file already has content (session restore), its length is
+/// recorded so `enter_terminal_mode` doesn't truncate existing history to 0.
+fn new_terminal_state(
+    cols: u16,
+    rows: u16,
+    backing_path: Option<&std::path::PathBuf>,
+) -> Arc<Mutex<TerminalState>> {
+    let state = Arc::new(Mutex::new(TerminalState::new(cols, rows)));
+    if let Some(p) = backing_path {
+        if let Ok(metadata) = std::fs::metadata(p) {
+            if metadata.len() > 0 {
+                if let Ok(mut s) = state.lock() {
+                    s.set_backing_file_history_end(metadata.len());
+                }
+            }
+        }
+    }
+    state
+}
+
 /// Messages sent to terminal I/O thread
 enum TerminalCommand {
     /// Write data to PTY
@@ -110,6 +182,8 @@ pub struct TerminalManager {
     next_id: usize,
     /// Async bridge for sending notifications to main loop
     async_bridge: Option<AsyncBridge>,
+    /// Remote host to spawn terminals on (SSH editing)
+    remote: Option<RemoteTerminalTarget>,
 }
 
 impl TerminalManager {
@@ -119,6 +193,7 @@ impl TerminalManager {
             terminals: HashMap::new(),
             next_id: 0,
             async_bridge: None,
+            remote: None,
         }
     }
 
@@ -132,6 +207,18 @@ impl TerminalManager {
         TerminalId(self.next_id)
     }
 
+    /// Run new terminals on a remote host instead of a local PTY
+    ///
+    /// Set during SSH editing so terminals open next to the remote project.
+    pub fn set_remote_target(&mut self, target: Option<RemoteTerminalTarget>) {
+        self.remote = target;
+    }
+
+    /// Whether new terminals are spawned on a remote host
+    pub fn is_remote(&self) -> bool {
+        self.remote.is_some()
+    }
+
     /// Spawn a new terminal session
     ///
     /// # Arguments
@@ -141,6 +228,10 @@ impl TerminalManager {
     /// * `log_path` - Optional path for raw PTY log (for session restore)
     /// * `backing_path` - Optional path for rendered scrollback (incremental streaming)
     ///
+    /// During a remote session (see [`Self::set_remote_target`]) the shell runs
+    /// on the remote host and `cwd` is a remote path; the log and backing files
+    /// are always local.
+    ///
     /// # Returns
     /// The terminal ID if successful
     pub fn spawn(
@@ -150,250 +241,395 @@ impl TerminalManager {
         cwd: Option<std::path::PathBuf>,
         log_path: Option<std::path::PathBuf>,
         backing_path: Option<std::path::PathBuf>,
+    ) -> Result<TerminalId, String> {
+        self.spawn_with(
+            cols,
+            rows,
+            cwd,
+            &TerminalLaunch::default(),
+            log_path,
+            backing_path,
+        )
+    }
+
+    /// Spawn a new terminal session running `launch` instead of the shell
+    ///
+    /// Arguments are as for [`Self::spawn`].
+    pub fn spawn_with(
+        &mut self,
+        cols: u16,
+        rows: u16,
+        cwd: Option<std::path::PathBuf>,
+        launch: &TerminalLaunch,
+        log_path: Option<std::path::PathBuf>,
+        backing_path: Option<std::path::PathBuf>,
     ) -> Result<TerminalId, String> {
         let id = TerminalId(self.next_id);
         self.next_id += 1;
 
-        // Try to spawn a real PTY-backed terminal first.
-        let handle_result: Result<TerminalHandle, String> = (|| {
-            // Create PTY
-            let pty_system = native_pty_system();
-            let pty_pair = pty_system
-                .openpty(PtySize {
-                    rows,
-                    cols,
-                    pixel_width: 0,
-                    pixel_height: 0,
-                })
-                .map_err(|e| format!("Failed to open PTY: {}", e))?;
-
-            // Detect shell
-            let shell = detect_shell();
-            tracing::info!("Spawning terminal with shell: {}", shell);
-
-            // Build command
-            let mut cmd = CommandBuilder::new(&shell);
-            if let Some(ref dir) = cwd {
-                cmd.cwd(dir);
-            }
+        let handle = match self.remote.clone() {
+            Some(target) => self.spawn_remote(
+                id,
+                &target,
+                (cols, rows),
+                cwd,
+                launch,
+                log_path.as_ref(),
+                backing_path.as_ref(),
+            )?,
+            None => self.spawn_local(
+                id,
+                (cols, rows),
+                cwd,
+                launch,
+                log_path.as_ref(),
+                backing_path.as_ref(),
+            )?,
+        };
 
-            // Spawn the shell process
-            let mut child = pty_pair
-                .slave
-                .spawn_command(cmd)
-                .map_err(|e| format!("Failed to spawn shell: {}", e))?;
-
-            // Create terminal state
-            let state = Arc::new(Mutex::new(TerminalState::new(cols, rows)));
-
-            // Initialize backing_file_history_end if backing file already exists (session restore)
-            // This ensures enter_terminal_mode doesn't truncate existing history to 0
-            if let Some(ref p) = backing_path {
-                if let Ok(metadata) = std::fs::metadata(p) {
-                    if metadata.len() > 0 {
-                        if let Ok(mut s) = state.lock() {
-                            s.set_backing_file_history_end(metadata.len());
-                        }
-                    }
-                }
-            }
+        self.terminals.insert(id, handle);
+        tracing::info!("Created terminal {:?} ({}x{})", id, cols, rows);
+
+        Ok(id)
+    }
 
-            // Create communication channel
-            let (command_tx, command_rx) = mpsc::channel::<TerminalCommand>();
+    /// Spawn a terminal on a local PTY
+    fn spawn_local(
+        &self,
+        id: TerminalId,
+        (cols, rows): (u16, u16),
+        cwd: Option<std::path::PathBuf>,
+        launch: &TerminalLaunch,
+        log_path: Option<&std::path::PathBuf>,
+        backing_path: Option<&std::path::PathBuf>,
+    ) -> Result<TerminalHandle, String> {
+        // Create PTY
+        let pty_system = native_pty_system();
+        let pty_pair = pty_system
+            .openpty(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| format!("Failed to open PTY: {}", e))?;
 
-            // Alive flag
-            let alive = Arc::new(AtomicBool::new(true));
-            let alive_clone = alive.clone();
+        // Detect shell, unless a program was requested
+        let shell = launch.program.clone().unwrap_or_else(detect_shell);
+        tracing::info!("Spawning terminal with shell: {}", shell);
 
-            // Get master for I/O
-            let mut master = pty_pair
-                .master
-                .take_writer()
-                .map_err(|e| format!("Failed to get PTY writer: {}", e))?;
+        // Build command
+        let mut cmd = CommandBuilder::new(&shell);
+        cmd.args(&launch.args);
+        for (key, value) in &launch.env {
+            cmd.env(key, value);
+        }
+        if let Some(ref dir) = cwd {
+            cmd.cwd(dir);
+        }
 
-            let mut reader = pty_pair
-                .master
-                .try_clone_reader()
-                .map_err(|e| format!("Failed to get PTY reader: {}", e))?;
+        // Spawn the shell process
+        let mut child = pty_pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|e| format!("Failed to spawn shell: {}", e))?;
+
+        // Create terminal state
+        let state = new_terminal_state(cols, rows, backing_path);
+
+        // Create communication channel
+        let (command_tx, command_rx) = mpsc::channel::<TerminalCommand>();
+
+        // Alive flag
+        let alive = Arc::new(AtomicBool::new(true));
+
+        // Get master for I/O
+        let mut master = pty_pair
+            .master
+            .take_writer()
+            .map_err(|e| format!("Failed to get PTY writer: {}", e))?;
+
+        let reader = pty_pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| format!("Failed to get PTY reader: {}", e))?;
+
+        self.spawn_reader_thread(
+            id,
+            reader,
+            state.clone(),
+            alive.clone(),
+            log_path,
+            backing_path,
+        );
+
+        // Spawn writer thread
+        let pty_size_ref = pty_pair.master;
+        thread::spawn(move || {
+            loop {
+                match command_rx.recv() {
+                    Ok(TerminalCommand::Write(data)) => {
+                        if let Err(e) = master.write_all(&data) {
+                            tracing::error!("Terminal write error: {}", e);
+                            break;
+                        }
+                        let _ = master.flush();
+                    }
+                    Ok(TerminalCommand::Resize { cols, rows }) => {
+                        if let Err(e) = pty_size_ref.resize(PtySize {
+                            rows,
+                            cols,
+                            pixel_width: 0,
+                            pixel_height: 0,
+                        }) {
+                            tracing::warn!("Failed to resize PTY: {}", e);
+                        }
+                    }
+                    Ok(TerminalCommand::Shutdown) | Err(_) => {
+                        break;
+                    }
+                }
+            }
+            // Clean up child process
+            let _ = child.kill();
+            let _ = child.wait();
+        });
+
+        // Create handle
+        Ok(TerminalHandle {
+            state,
+            command_tx,
+            alive,
+            cols,
+            rows,
+            cwd,
+            shell,
+        })
+    }
 
-            // Clone state for reader thread
-            let state_clone = state.clone();
-            let async_bridge = self.async_bridge.clone();
+    /// Spawn a terminal on a PTY allocated by the remote agent
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_remote(
+        &self,
+        id: TerminalId,
+        target: &RemoteTerminalTarget,
+        (cols, rows): (u16, u16),
+        cwd: Option<std::path::PathBuf>,
+        launch: &TerminalLaunch,
+        log_path: Option<&std::path::PathBuf>,
+        backing_path: Option<&std::path::PathBuf>,
+    ) -> Result<TerminalHandle, String> {
+        let (program, args) = remote_command_line(launch);
+        tracing::info!("Spawning remote terminal running {}", program);
+
+        let runtime = target.runtime.clone();
+        let process = runtime
+            .block_on(target.spawner.spawn_pty(
+                program,
+                args,
+                cwd.as_ref().map(|dir| dir.to_string_lossy().into_owned()),
+                rows,
+                cols,
+            ))
+            .map_err(|e| format!("Failed to spawn remote shell: {}", e))?;
+        let PipedProcess {
+            mut stdin,
+            stdout,
+            stderr,
+            guard,
+        } = process;
+
+        let state = new_terminal_state(cols, rows, backing_path);
+        let (command_tx, command_rx) = mpsc::channel::<TerminalCommand>();
+        let alive = Arc::new(AtomicBool::new(true));
+
+        // Spawn failures are reported on stderr after the output ends
+        let reader = BlockingPipeReader {
+            pipe: Box::new(stdout.chain(stderr)),
+            runtime: runtime.clone(),
+        };
+        self.spawn_reader_thread(
+            id,
+            Box::new(reader),
+            state.clone(),
+            alive.clone(),
+            log_path,
+            backing_path,
+        );
+
+        // Spawn writer thread; dropping the guard hangs up the remote session
+        thread::spawn(move || {
+            loop {
+                match command_rx.recv() {
+                    Ok(TerminalCommand::Write(data)) => {
+                        let written = runtime.block_on(async {
+                            stdin.write_all(&data).await?;
+                            stdin.flush().await
+                        });
+                        if let Err(e) = written {
+                            tracing::error!("Remote terminal write error: {}", e);
+                            break;
+                        }
+                    }
+                    Ok(TerminalCommand::Resize { cols, rows }) => {
+                        if let Err(e) = runtime.block_on(guard.resize(rows, cols)) {
+                            tracing::warn!("Failed to resize remote PTY: {}", e);
+                        }
+                    }
+                    Ok(TerminalCommand::Shutdown) | Err(_) => {
+                        break;
+                    }
+                }
+            }
+            drop(guard);
+        });
+
+        Ok(TerminalHandle {
+            state,
+            command_tx,
+            alive,
+            cols,
+            rows,
+            cwd,
+            shell: launch
+                .program
+                .clone()
+                .unwrap_or_else(|| REMOTE_SHELL_NAME.to_string()),
+        })
+    }
 
-            // Optional raw log writer for full-session capture (for live terminal resume)
-            let mut log_writer = log_path
-                .as_ref()
-                .and_then(|p| {
+    /// Spawn the thread that feeds PTY output into the terminal emulator
+    ///
+    /// Scrollback is streamed to the backing file as it scrolls off screen and
+    /// raw output is appended to the log; the main loop is notified on output
+    /// and when the process exits.
+    fn spawn_reader_thread(
+        &self,
+        terminal_id: TerminalId,
+        mut reader: Box<dyn Read + Send>,
+        state: Arc<Mutex<TerminalState>>,
+        alive: Arc<AtomicBool>,
+        log_path: Option<&std::path::PathBuf>,
+        backing_path: Option<&std::path::PathBuf>,
+    ) {
+        let async_bridge = self.async_bridge.clone();
+
+        // Optional raw log writer for full-session capture (for live terminal resume)
+        let mut log_writer = log_path
+            .and_then(|p| {
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(p)
+                    .ok()
+            })
+            .map(std::io::BufWriter::new);
+
+        // Backing file writer for incremental scrollback streaming
+        // During session restore, the backing file may already contain scrollback content.
+        // We open for append to continue streaming new scrollback after the existing content.
+        // For new terminals, append mode also works (creates file if needed).
+        let mut backing_writer = backing_path
+            .and_then(|p| {
+                // Check if backing file exists and has content (session restore case)
+                let existing_has_content =
+                    p.exists() && std::fs::metadata(p).map(|m| m.len() > 0).unwrap_or(false);
+
+                if existing_has_content {
+                    // Session restore: open for append to continue streaming new scrollback
+                    // The existing content is preserved and loaded into buffer separately.
+                    // Note: enter_terminal_mode will truncate when user re-enters terminal.
                     std::fs::OpenOptions::new()
                         .create(true)
                         .append(true)
                         .open(p)
                         .ok()
-                })
-                .map(std::io::BufWriter::new);
-
-            // Backing file writer for incremental scrollback streaming
-            // During session restore, the backing file may already contain scrollback content.
-            // We open for append to continue streaming new scrollback after the existing content.
-            // For new terminals, append mode also works (creates file if needed).
-            let mut backing_writer = backing_path
-                .as_ref()
-                .and_then(|p| {
-                    // Check if backing file exists and has content (session restore case)
-                    let existing_has_content =
-                        p.exists() && std::fs::metadata(p).map(|m| m.len() > 0).unwrap_or(false);
-
-                    if existing_has_content {
-                        // Session restore: open for append to continue streaming new scrollback
-                        // The existing content is preserved and loaded into buffer separately.
-                        // Note: enter_terminal_mode will truncate when user re-enters terminal.
-                        std::fs::OpenOptions::new()
-                            .create(true)
-                            .append(true)
-                            .open(p)
-                            .ok()
-                    } else {
-                        // New terminal: start fresh with truncate
-                        std::fs::OpenOptions::new()
-                            .create(true)
-                            .write(true)
-                            .truncate(true)
-                            .open(p)
-                            .ok()
+                } else {
+                    // New terminal: start fresh with truncate
+                    std::fs::OpenOptions::new()
+                        .create(true)
+                        .write(true)
+                        .truncate(true)
+                        .open(p)
+                        .ok()
+                }
+            })
+            .map(std::io::BufWriter::new);
+
+        thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => {
+                        // EOF - process exited
+                        tracing::info!("Terminal {:?} EOF", terminal_id);
+                        break;
                     }
-                })
-                .map(std::io::BufWriter::new);
-
-            // Spawn reader thread
-            let terminal_id = id;
-            thread::spawn(move || {
-                let mut buf = [0u8; 4096];
-                loop {
-                    match reader.read(&mut buf) {
-                        Ok(0) => {
-                            // EOF - process exited
-                            tracing::info!("Terminal {:?} EOF", terminal_id);
-                            break;
-                        }
-                        Ok(n) => {
-                            // Process output through terminal emulator and stream scrollback
-                            if let Ok(mut state) = state_clone.lock() {
-                                state.process_output(&buf[..n]);
-
-                                // Incrementally stream new scrollback lines to backing file
-                                if let Some(ref mut writer) = backing_writer {
-                                    match state.flush_new_scrollback(writer) {
-                                        Ok(lines_written) => {
-                                            if lines_written > 0 {
-                                                // Update the history end offset
-                                                if let Ok(pos) = writer.get_ref().metadata() {
-                                                    state.set_backing_file_history_end(pos.len());
-                                                }
-                                                let _ = writer.flush();
+                    Ok(n) => {
+                        // Process output through terminal emulator and stream scrollback
+                        if let Ok(mut state) = state.lock() {
+                            state.process_output(&buf[..n]);
+
+                            // Incrementally stream new scrollback lines to backing file
+                            if let Some(ref mut writer) = backing_writer {
+                                match state.flush_new_scrollback(writer) {
+                                    Ok(lines_written) => {
+                                        if lines_written > 0 {
+                                            // Update the history end offset
+                                            if let Ok(pos) = writer.get_ref().metadata() {
+                                                state.set_backing_file_history_end(pos.len());
                                             }
-                                        }
-                                        Err(e) => {
-                                            tracing::warn!(
-                                                "Terminal backing file write error: {}",
-                                                e
-                                            );
-                                            backing_writer = None;
+                                            let _ = writer.flush();
                                         }
                                     }
+                                    Err(e) => {
+                                        tracing::warn!("Terminal backing file write error: {}", e);
+                                        backing_writer = None;
+                                    }
                                 }
                             }
+                        }
 
-                            // Append raw bytes to log if available (for session restore replay)
-                            if let Some(w) = log_writer.as_mut() {
-                                if let Err(e) = w.write_all(&buf[..n]) {
-                                    tracing::warn!("Terminal log write error: {}", e);
-                                    log_writer = None; // stop logging on error
-                                } else if let Err(e) = w.flush() {
-                                    tracing::warn!("Terminal log flush error: {}", e);
-                                    log_writer = None;
-                                }
-                            }
-
-                            // Notify main loop to redraw
-                            if let Some(ref bridge) = async_bridge {
-                                let _ = bridge.sender().send(
-                                    crate::services::async_bridge::AsyncMessage::TerminalOutput {
-                                        terminal_id,
-                                    },
-                                );
+                        // Append raw bytes to log if available (for session restore replay)
+                        if let Some(w) = log_writer.as_mut() {
+                            if let Err(e) = w.write_all(&buf[..n]) {
+                                tracing::warn!("Terminal log write error: {}", e);
+                                log_writer = None; // stop logging on error
+                            } else if let Err(e) = w.flush() {
+                                tracing::warn!("Terminal log flush error: {}", e);
+                                log_writer = None;
                             }
                         }
-                        Err(e) => {
-                            tracing::error!("Terminal read error: {}", e);
-                            break;
+
+                        // Notify main loop to redraw
+                        if let Some(ref bridge) = async_bridge {
+                            let _ = bridge.sender().send(
+                                crate::services::async_bridge::AsyncMessage::TerminalOutput {
+                                    terminal_id,
+                                },
+                            );
                         }
                     }
-                }
-                alive_clone.store(false, std::sync::atomic::Ordering::Relaxed);
-                if let Some(mut w) = log_writer {
-                    let _ = w.flush();
-                }
-                if let Some(mut w) = backing_writer {
-                    let _ = w.flush();
-                }
-                // Notify that terminal exited
-                if let Some(ref bridge) = async_bridge {
-                    let _ = bridge.sender().send(
-                        crate::services::async_bridge::AsyncMessage::TerminalExited { terminal_id },
-                    );
-                }
-            });
-
-            // Spawn writer thread
-            let pty_size_ref = pty_pair.master;
-            thread::spawn(move || {
-                loop {
-                    match command_rx.recv() {
-                        Ok(TerminalCommand::Write(data)) => {
-                            if let Err(e) = master.write_all(&data) {
-                                tracing::error!("Terminal write error: {}", e);
-                                break;
-                            }
-                            let _ = master.flush();
-                        }
-                        Ok(TerminalCommand::Resize { cols, rows }) => {
-                            if let Err(e) = pty_size_ref.resize(PtySize {
-                                rows,
-                                cols,
-                                pixel_width: 0,
-                                pixel_height: 0,
-                            }) {
-                                tracing::warn!("Failed to resize PTY: {}", e);
-                            }
-                        }
-                        Ok(TerminalCommand::Shutdown) | Err(_) => {
-                            break;
-                        }
+                    Err(e) => {
+                        tracing::error!("Terminal read error: {}", e);
+                        break;
                     }
                 }
-                // Clean up child process
-                let _ = child.kill();
-                let _ = child.wait();
-            });
-
-            // Create handle
-            Ok(TerminalHandle {
-                state,
-                command_tx,
-                alive,
-                cols,
-                rows,
-                cwd: cwd.clone(),
-                shell,
-            })
-        })();
-
-        let handle = handle_result?;
-
-        self.terminals.insert(id, handle);
-        tracing::info!("Created terminal {:?} ({}x{})", id, cols, rows);
-
-        Ok(id)
+            }
+            alive.store(false, std::sync::atomic::Ordering::Relaxed);
+            if let Some(mut w) = log_writer {
+                let _ = w.flush();
+            }
+            if let Some(mut w) = backing_writer {
+                let _ = w.flush();
+            }
+            // Notify that terminal exited
+            if let Some(ref bridge) = async_bridge {
+                let _ = bridge.sender().send(
+                    crate::services::async_bridge::AsyncMessage::TerminalExited { terminal_id },
+                );
+            }
+        });
     }
 
     /// Get a terminal handle by ID
@@ -462,6 +698,32 @@ impl Drop for TerminalManager {
     }
 }
 
+/// Program and arguments that run `launch` on the remote host
+///
+/// The agent has no way to pass environment variables, so they are set
+/// through `env`.
+fn remote_command_line(launch: &TerminalLaunch) -> (String, Vec<String>) {
+    let mut argv: Vec<String> = match &launch.program {
+        Some(program) => std::iter::once(program.clone())
+            .chain(launch.args.iter().cloned())
+            .collect(),
+        None => vec![
+            "/bin/sh".to_string(),
+            "-c".to_string(),
+            REMOTE_SHELL_SCRIPT.to_string(),
+        ],
+    };
+    if !launch.env.is_empty() {
+        let assignments = launch.env.iter().map(|(k, v)| format!("{}={}", k, v));
+        argv = std::iter::once("env".to_string())
+            .chain(assignments)
+            .chain(argv)
+            .collect();
+    }
+    let program = argv.remove(0);
+    (program, argv)
+}
+
 /// Detect the user's shell
 pub fn detect_shell() -> String {
     // Try $SHELL environment variable first
@@ -492,6 +754,22 @@ mod tests {
         assert_eq!(format!("{}", id), "Terminal-42");
     }
 
+    #[test]
+    fn test_remote_command_line() {
+        let shell = remote_command_line(&TerminalLaunch::default());
+        assert_eq!(shell.0, "/bin/sh");
+        assert_eq!(shell.1, vec!["-c", REMOTE_SHELL_SCRIPT]);
+
+        let launch = TerminalLaunch {
+            program: Some("cargo".to_string()),
+            args: vec!["test".to_string()],
+            env: vec![("RUST_LOG".to_string(), "debug".to_string())],
+        };
+        let (program, args) = remote_command_line(&launch);
+        assert_eq!(program, "env");
+        assert_eq!(args, vec!["RUST_LOG=debug", "cargo", "test"]);
+    }
+
     #[test]
     fn test_detect_shell() {
         let shell = detect_shell();
@@ -51,5 +51,7 @@ mod manager;
 pub mod pty;
 pub mod term;
 
-pub use manager::{detect_shell, TerminalId, TerminalManager};
+pub use manager::{
+    detect_shell, RemoteTerminalTarget, TerminalId, TerminalLaunch, TerminalManager,
+};
 pub use term::{TerminalCell, TerminalState};
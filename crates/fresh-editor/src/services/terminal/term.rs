@@ -38,6 +38,9 @@ use std::io::{self, Write};
 // Keep a generous scrollback so sync-to-buffer can include deep history.
 const SCROLLBACK_LINES: usize = 200_000;
 
+/// Most raw output kept for `take_captured_output` between calls
+const MAX_CAPTURED_OUTPUT: usize = 64 * 1024;
+
 /// Event listener that does nothing (we handle events ourselves)
 struct NullListener;
 
@@ -65,6 +68,8 @@ pub struct TerminalState {
     synced_history_lines: usize,
     /// Byte offset in backing file where scrollback ends (for truncation)
     backing_file_history_end: u64,
+    /// Raw output not yet taken by `take_captured_output`, when capturing
+    captured_output: Option<Vec<u8>>,
 }
 
 impl TerminalState {
@@ -86,6 +91,7 @@ impl TerminalState {
             terminal_title: String::new(),
             synced_history_lines: 0,
             backing_file_history_end: 0,
+            captured_output: None,
         }
     }
 
@@ -93,6 +99,29 @@ impl TerminalState {
     pub fn process_output(&mut self, data: &[u8]) {
         self.parser.advance(&mut self.term, data);
         self.dirty = true;
+        if let Some(captured) = self.captured_output.as_mut() {
+            captured.extend_from_slice(data);
+            // Nobody is draining it; keep only the most recent output
+            if captured.len() > MAX_CAPTURED_OUTPUT {
+                captured.drain(..captured.len() - MAX_CAPTURED_OUTPUT);
+            }
+        }
+    }
+
+    /// Start keeping a copy of raw PTY output for `take_captured_output`
+    ///
+    /// Output that is not taken is capped at the most recent 64 KiB.
+    pub fn capture_output(&mut self) {
+        self.captured_output.get_or_insert_with(Vec::new);
+    }
+
+    /// Take the raw output received since the last call (empty unless
+    /// `capture_output` was called)
+    pub fn take_captured_output(&mut self) -> Vec<u8> {
+        self.captured_output
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
     }
 
     /// Resize the terminal
@@ -207,7 +236,6 @@ impl TerminalState {
     /// For mode switching, use the incremental streaming architecture instead:
     /// - `flush_new_scrollback()` during PTY reads
     /// - `append_visible_screen()` on mode exit
-    #[allow(dead_code)]
     pub fn full_content_string(&self) -> String {
         use alacritty_terminal::grid::Dimensions;
         use alacritty_terminal::index::{Column, Line};
@@ -643,6 +671,18 @@ mod tests {
         assert!(content.contains("Hello, World!"));
     }
 
+    #[test]
+    fn test_captured_output() {
+        let mut state = TerminalState::new(80, 24);
+        state.process_output(b"before\r\n");
+        assert!(state.take_captured_output().is_empty());
+
+        state.capture_output();
+        state.process_output(b"\x1b[32mok\x1b[0m\r\n");
+        assert_eq!(state.take_captured_output(), b"\x1b[32mok\x1b[0m\r\n");
+        assert!(state.take_captured_output().is_empty());
+    }
+
     #[test]
     fn test_terminal_resize() {
         let mut state = TerminalState::new(80, 24);
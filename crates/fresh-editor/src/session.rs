@@ -29,6 +29,8 @@ use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+pub use fresh_core::path_encoding::{decode_filename_to_path, encode_path_for_filename};
+
 use crate::input::input_history::get_data_dir;
 
 /// Current session file format version
@@ -450,92 +452,6 @@ pub fn get_sessions_dir() -> io::Result<PathBuf> {
     Ok(get_data_dir()?.join("sessions"))
 }
 
-/// Encode a path into a filesystem-safe filename using percent encoding
-///
-/// Keeps alphanumeric chars, `-`, `.`, `_` as-is.
-/// Replaces `/` with `_` for readability.
-/// Percent-encodes other special characters as %XX.
-///
-/// Example: `/home/user/my project` -> `home_user_my%20project`
-pub fn encode_path_for_filename(path: &Path) -> String {
-    let path_str = path.to_string_lossy();
-    let mut result = String::with_capacity(path_str.len() * 2);
-
-    for c in path_str.chars() {
-        match c {
-            // Path separators become underscores for readability
-            '/' | '\\' => result.push('_'),
-            // Safe chars pass through
-            c if c.is_ascii_alphanumeric() => result.push(c),
-            '-' | '.' => result.push(c),
-            // Underscore needs special handling to avoid collision with /
-            '_' => result.push_str("%5F"),
-            // Everything else gets percent-encoded
-            c => {
-                for byte in c.to_string().as_bytes() {
-                    result.push_str(&format!("%{:02X}", byte));
-                }
-            }
-        }
-    }
-
-    // Remove leading underscores (from leading /)
-    let result = result.trim_start_matches('_').to_string();
-
-    // Collapse multiple underscores
-    let mut final_result = String::with_capacity(result.len());
-    let mut last_was_underscore = false;
-    for c in result.chars() {
-        if c == '_' {
-            if !last_was_underscore {
-                final_result.push(c);
-            }
-            last_was_underscore = true;
-        } else {
-            final_result.push(c);
-            last_was_underscore = false;
-        }
-    }
-
-    if final_result.is_empty() {
-        final_result = "root".to_string();
-    }
-
-    final_result
-}
-
-/// Decode a filename back to the original path (for debugging/tooling)
-#[allow(dead_code)]
-pub fn decode_filename_to_path(encoded: &str) -> Option<PathBuf> {
-    if encoded == "root" {
-        return Some(PathBuf::from("/"));
-    }
-
-    let mut result = String::with_capacity(encoded.len() + 1);
-    // Re-add leading slash that was stripped during encoding
-    result.push('/');
-
-    let mut chars = encoded.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '%' {
-            // Read two hex digits
-            let hex: String = chars.by_ref().take(2).collect();
-            if hex.len() == 2 {
-                if let Ok(byte) = u8::from_str_radix(&hex, 16) {
-                    result.push(byte as char);
-                }
-            }
-        } else if c == '_' {
-            result.push('/');
-        } else {
-            result.push(c);
-        }
-    }
-
-    Some(PathBuf::from(result))
-}
-
 /// Get the session file path for a working directory
 pub fn get_session_path(working_dir: &Path) -> io::Result<PathBuf> {
     let canonical = working_dir
@@ -773,29 +689,6 @@ mod tests {
         assert!(filename.starts_with("home_user_project"));
     }
 
-    #[test]
-    fn test_percent_encoding_edge_cases() {
-        // Path with dashes (should pass through)
-        let encoded = encode_path_for_filename(Path::new("/home/user/my-project"));
-        assert_eq!(encoded, "home_user_my-project");
-
-        // Path with spaces (percent-encoded)
-        let encoded = encode_path_for_filename(Path::new("/home/user/my project"));
-        assert_eq!(encoded, "home_user_my%20project");
-        let decoded = decode_filename_to_path(&encoded).unwrap();
-        assert_eq!(decoded, PathBuf::from("/home/user/my project"));
-
-        // Path with underscores (percent-encoded to avoid collision with /)
-        let encoded = encode_path_for_filename(Path::new("/home/user/my_project"));
-        assert_eq!(encoded, "home_user_my%5Fproject");
-        let decoded = decode_filename_to_path(&encoded).unwrap();
-        assert_eq!(decoded, PathBuf::from("/home/user/my_project"));
-
-        // Root path
-        let encoded = encode_path_for_filename(Path::new("/"));
-        assert_eq!(encoded, "root");
-    }
-
     #[test]
     fn test_session_serialization() {
         let session = Session::new(PathBuf::from("/home/user/test"));
@@ -14,6 +14,7 @@ use crate::primitives::highlight_engine::HighlightEngine;
 use crate::primitives::highlighter::Language;
 use crate::primitives::indent::IndentCalculator;
 use crate::primitives::reference_highlighter::ReferenceHighlighter;
+use crate::primitives::syntax_query::SyntaxTreeCache;
 use crate::primitives::text_property::TextPropertyManager;
 use crate::view::bracket_highlight_overlay::BracketHighlightOverlay;
 use crate::view::margin::{MarginAnnotation, MarginContent, MarginManager, MarginPosition};
@@ -112,6 +113,9 @@ pub struct EditorState {
     /// Semantic highlighter for word occurrence highlighting
     pub reference_highlighter: ReferenceHighlighter,
 
+    /// Syntax tree for plugin syntax queries, kept until the buffer changes
+    pub syntax_tree: SyntaxTreeCache,
+
     /// View mode for this buffer (Source or Compose)
     pub view_mode: ViewMode,
 
@@ -175,6 +179,7 @@ impl EditorState {
             use_tabs: false,
             tab_size: 4, // Default tab size
             reference_highlighter: ReferenceHighlighter::new(),
+            syntax_tree: SyntaxTreeCache::default(),
             view_mode: ViewMode::Source,
             debug_highlight_mode: false,
             compose_width: None,
@@ -268,6 +273,7 @@ impl EditorState {
             use_tabs: false,
             tab_size: 4,
             reference_highlighter,
+            syntax_tree: SyntaxTreeCache::default(),
             view_mode: ViewMode::Source,
             debug_highlight_mode: false,
             compose_width: None,
@@ -336,6 +342,7 @@ impl EditorState {
             use_tabs: false,
             tab_size: 4,
             reference_highlighter,
+            syntax_tree: SyntaxTreeCache::default(),
             view_mode: ViewMode::Source,
             debug_highlight_mode: false,
             compose_width: None,
@@ -5,6 +5,14 @@
 use crate::model::cursor::Cursors;
 use crate::model::event::BufferId;
 use ratatui::layout::Rect;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Most modified line pairs whose inline word diff is kept between frames
+const MAX_CACHED_INLINE_DIFFS: usize = 1024;
+
+/// Changed character ranges of the old and the new side of a modified line
+pub type InlineDiff = (Vec<Range<usize>>, Vec<Range<usize>>);
 
 /// View state for a composite buffer in a split
 #[derive(Debug, Clone)]
@@ -46,6 +54,10 @@ pub struct CompositeViewState {
 
     /// Selection anchor column (where selection started)
     pub selection_anchor_column: usize,
+
+    /// Inline word diffs of modified rows, keyed by the (old, new) line text,
+    /// so they are not recomputed on every frame
+    inline_diffs: HashMap<(String, String), InlineDiff>,
 }
 
 impl CompositeViewState {
@@ -64,7 +76,28 @@ impl CompositeViewState {
             visual_mode: false,
             selection_anchor_row: 0,
             selection_anchor_column: 0,
+            inline_diffs: HashMap::new(),
+        }
+    }
+
+    /// Get the inline word diff of a modified line pair, computing it with
+    /// `compute` the first time the pair is seen
+    pub fn inline_diff(
+        &mut self,
+        old: &str,
+        new: &str,
+        compute: impl FnOnce(&str, &str) -> InlineDiff,
+    ) -> InlineDiff {
+        let key = (old.to_string(), new.to_string());
+        if let Some(diff) = self.inline_diffs.get(&key) {
+            return diff.clone();
+        }
+        if self.inline_diffs.len() >= MAX_CACHED_INLINE_DIFFS {
+            self.inline_diffs.clear();
         }
+        let diff = compute(old, new);
+        self.inline_diffs.insert(key, diff.clone());
+        diff
     }
 
     /// Start visual selection at current cursor position
@@ -0,0 +1,206 @@
+//! Plugin form dialog rendering
+//!
+//! Renders a plugin form as a centered modal: one row of controls per field
+//! with aligned labels, followed by the button row.
+
+use crate::app::form_dialog::{FormControl, FormDialog};
+use crate::view::controls::{
+    render_button_row, render_dropdown_aligned, render_number_input_aligned,
+    render_text_input_aligned, render_text_list, render_toggle_aligned, ButtonColors, ButtonLayout,
+    DropdownColors, DropdownLayout, FocusState, NumberInputColors, NumberInputLayout,
+    TextInputColors, TextInputLayout, TextListColors, TextListLayout, ToggleColors, ToggleLayout,
+};
+use crate::view::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    widgets::{Block, Borders, Clear},
+    Frame,
+};
+
+/// Width of the dialog in characters
+const DIALOG_WIDTH: u16 = 64;
+/// Space between buttons
+const BUTTON_GAP: u16 = 2;
+
+/// Hit areas of a rendered form field
+#[derive(Debug, Clone)]
+pub enum FormFieldLayout {
+    Text(TextInputLayout),
+    Number(NumberInputLayout),
+    Toggle(ToggleLayout),
+    Dropdown(DropdownLayout),
+    TextList(TextListLayout),
+}
+
+impl FormFieldLayout {
+    /// Check if a point is on the field, including an open dropdown's options
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        match self {
+            Self::Text(layout) => layout.contains(x, y),
+            Self::Number(layout) => layout.contains(x, y),
+            Self::Toggle(layout) => layout.contains(x, y),
+            Self::Dropdown(layout) => layout.contains(x, y) || layout.option_at(x, y).is_some(),
+            Self::TextList(layout) => {
+                let area = layout.full_area;
+                x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height
+            }
+        }
+    }
+}
+
+/// Layout of a rendered form dialog, for mouse hit testing
+#[derive(Debug, Clone, Default)]
+pub struct FormDialogLayout {
+    /// The whole dialog, border included
+    pub dialog_area: Rect,
+    /// One entry per field, in field order
+    pub fields: Vec<FormFieldLayout>,
+    /// One entry per visible button, in button order
+    pub buttons: Vec<ButtonLayout>,
+}
+
+/// Render a plugin form dialog centered in `area`
+pub fn render_form_dialog(
+    frame: &mut Frame,
+    area: Rect,
+    dialog: &FormDialog,
+    theme: &Theme,
+) -> FormDialogLayout {
+    let fields_height: u16 = dialog.fields.iter().map(|f| f.control.height()).sum();
+    // Border, padding row above the fields, blank row and the button row below them
+    let dialog_height = (fields_height + 5).min(area.height.saturating_sub(2));
+    let dialog_width = DIALOG_WIDTH.min(area.width.saturating_sub(4));
+
+    let dialog_area = Rect {
+        x: area.x + (area.width.saturating_sub(dialog_width)) / 2,
+        y: area.y + (area.height.saturating_sub(dialog_height)) / 2,
+        width: dialog_width,
+        height: dialog_height,
+    };
+
+    frame.render_widget(Clear, dialog_area);
+
+    let block = Block::default()
+        .title(format!(" {} ", dialog.title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.editor_fg))
+        .style(Style::default().bg(theme.editor_bg).fg(theme.editor_fg));
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+
+    let mut layout = FormDialogLayout {
+        dialog_area,
+        ..Default::default()
+    };
+    if inner.height < 2 || inner.width < 10 {
+        return layout;
+    }
+
+    // Align all single-row controls on the longest label
+    let label_width = dialog
+        .fields
+        .iter()
+        .filter(|f| !matches!(f.control, FormControl::TextList(_)))
+        .map(|f| field_label(&f.control).chars().count() as u16)
+        .max()
+        .unwrap_or(0);
+    let content = Rect::new(
+        inner.x + 1,
+        inner.y,
+        inner.width.saturating_sub(2),
+        inner.height,
+    );
+    // Labels are followed by ": "
+    let field_width = content.width.saturating_sub(label_width + 2);
+    // The last row is kept for the buttons
+    let fields_bottom = content.y + content.height.saturating_sub(2);
+
+    let mut y = content.y + 1;
+    for field in &dialog.fields {
+        let height = field.control.height().min(fields_bottom.saturating_sub(y));
+        let row = Rect::new(content.x, y, content.width, height);
+        y += height;
+
+        let field_layout = match &field.control {
+            FormControl::Text(state) => {
+                let text_layout = render_text_input_aligned(
+                    frame,
+                    row,
+                    state,
+                    &TextInputColors::from_theme(theme),
+                    field_width,
+                    Some(label_width),
+                );
+                if state.focus == FocusState::Focused {
+                    if let Some(position) = text_layout.cursor_pos {
+                        frame.set_cursor_position(position);
+                    }
+                }
+                FormFieldLayout::Text(text_layout)
+            }
+            FormControl::Number(state) => FormFieldLayout::Number(render_number_input_aligned(
+                frame,
+                row,
+                state,
+                &NumberInputColors::from_theme(theme),
+                Some(label_width),
+            )),
+            FormControl::Toggle(state) => FormFieldLayout::Toggle(render_toggle_aligned(
+                frame,
+                row,
+                state,
+                &ToggleColors::from_theme(theme),
+                Some(label_width),
+            )),
+            FormControl::Dropdown(state) => FormFieldLayout::Dropdown(render_dropdown_aligned(
+                frame,
+                row,
+                state,
+                &DropdownColors::from_theme(theme),
+                Some(label_width),
+            )),
+            FormControl::TextList(state) => FormFieldLayout::TextList(render_text_list(
+                frame,
+                row,
+                state,
+                &TextListColors::from_theme(theme),
+                content.width.saturating_sub(6),
+            )),
+        };
+        layout.fields.push(field_layout);
+    }
+
+    // Buttons, centered on the last row
+    let button_colors = ButtonColors::from_theme(theme);
+    let buttons: Vec<_> = dialog
+        .buttons
+        .iter()
+        .map(|(_, state)| (state, &button_colors))
+        .collect();
+    let buttons_width: u16 = dialog
+        .buttons
+        .iter()
+        .map(|(_, state)| state.label.chars().count() as u16 + 4)
+        .sum::<u16>()
+        + BUTTON_GAP * dialog.buttons.len().saturating_sub(1) as u16;
+    let button_row = Rect::new(
+        content.x + content.width.saturating_sub(buttons_width) / 2,
+        content.y + content.height - 1,
+        buttons_width.min(content.width),
+        1,
+    );
+    layout.buttons = render_button_row(frame, button_row, &buttons, BUTTON_GAP);
+
+    layout
+}
+
+fn field_label(control: &FormControl) -> &str {
+    match control {
+        FormControl::Text(state) => &state.label,
+        FormControl::Number(state) => &state.label,
+        FormControl::Toggle(state) => &state.label,
+        FormControl::Dropdown(state) => &state.label,
+        FormControl::TextList(state) => &state.label,
+    }
+}
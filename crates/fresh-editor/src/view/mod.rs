@@ -47,6 +47,8 @@ pub mod file_browser_input;
 #[cfg(feature = "runtime")]
 pub mod file_tree;
 #[cfg(feature = "runtime")]
+pub mod form_dialog;
+#[cfg(feature = "runtime")]
 pub mod markdown;
 #[cfg(feature = "runtime")]
 pub mod popup;
@@ -38,6 +38,10 @@ pub enum PromptType {
     SetBackgroundFile,
     /// Set background blend ratio (0-1)
     SetBackgroundBlend,
+    /// Choose a file to compare the current buffer with
+    CompareWithFile,
+    /// Set the filter of a followed buffer (empty clears it)
+    SetFollowFilter,
     /// Plugin-controlled prompt with custom type identifier
     /// The string identifier is used to filter hooks in plugin code
     Plugin { custom_type: String },
@@ -104,6 +108,12 @@ pub enum PromptType {
         /// False if renaming an existing file (should keep focus in file explorer)
         is_new_file: bool,
     },
+    /// Ask the user to grant a plugin package the capabilities it declares
+    ConfirmPluginCapabilities {
+        name: String,
+        path: std::path::PathBuf,
+        requested: crate::config::PluginCapabilities,
+    },
     /// Confirm deleting a file or directory in the file explorer
     ConfirmDeleteFile {
         path: std::path::PathBuf,
@@ -118,6 +128,10 @@ pub enum PromptType {
     /// Async prompt from plugin (for editor.prompt() API)
     /// The result is returned via callback resolution
     AsyncPrompt,
+    /// Choose the plugin the developer console evaluates code in
+    PluginConsoleSelect,
+    /// Code to evaluate in the developer console's plugin
+    PluginConsoleEval,
 }
 
 /// Prompt state for the minibuffer
@@ -252,7 +252,11 @@ impl Theme {
     /// This makes the hardware cursor visible on any background.
     pub fn set_terminal_cursor_color(&self) {
         use super::types::color_to_rgb;
-        use std::io::Write;
+        use std::io::{IsTerminal, Write};
+        // Keep escape codes out of piped output (e.g. `fresh --batch`)
+        if !std::io::stdout().is_terminal() {
+            return;
+        }
         if let Some((r, g, b)) = color_to_rgb(self.cursor) {
             // OSC 12 sets cursor color: \x1b]12;#RRGGBB\x07
             let _ = write!(
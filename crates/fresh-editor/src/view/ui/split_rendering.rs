@@ -7,6 +7,7 @@ use crate::app::BufferMetadata;
 use crate::model::buffer::Buffer;
 use crate::model::cursor::SelectionMode;
 use crate::model::event::{BufferId, EventLog, SplitDirection};
+use crate::model::line_diff::{diff_words_bounded, MAX_INLINE_LCS_CELLS};
 use crate::primitives::ansi::AnsiParser;
 use crate::primitives::ansi_background::AnsiBackground;
 use crate::primitives::display_width::char_width;
@@ -33,50 +34,6 @@ use std::ops::Range;
 /// memory usage reasonable (~80KB per ViewLine instead of hundreds of MB).
 const MAX_SAFE_LINE_WIDTH: usize = 10_000;
 
-/// Compute character-level diff between two strings, returning ranges of changed characters.
-/// Returns a tuple of (old_changed_ranges, new_changed_ranges) where each range indicates
-/// character indices that differ between the strings.
-fn compute_inline_diff(old_text: &str, new_text: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
-    let old_chars: Vec<char> = old_text.chars().collect();
-    let new_chars: Vec<char> = new_text.chars().collect();
-
-    let mut old_ranges = Vec::new();
-    let mut new_ranges = Vec::new();
-
-    // Find common prefix
-    let prefix_len = old_chars
-        .iter()
-        .zip(new_chars.iter())
-        .take_while(|(a, b)| a == b)
-        .count();
-
-    // Find common suffix (from the non-prefix part)
-    let old_remaining = old_chars.len() - prefix_len;
-    let new_remaining = new_chars.len() - prefix_len;
-    let suffix_len = old_chars
-        .iter()
-        .rev()
-        .zip(new_chars.iter().rev())
-        .take(old_remaining.min(new_remaining))
-        .take_while(|(a, b)| a == b)
-        .count();
-
-    // The changed range is between prefix and suffix
-    let old_start = prefix_len;
-    let old_end = old_chars.len().saturating_sub(suffix_len);
-    let new_start = prefix_len;
-    let new_end = new_chars.len().saturating_sub(suffix_len);
-
-    if old_start < old_end {
-        old_ranges.push(old_start..old_end);
-    }
-    if new_start < new_end {
-        new_ranges.push(new_start..new_end);
-    }
-
-    (old_ranges, new_ranges)
-}
-
 fn push_span_with_map(
     spans: &mut Vec<Span<'static>>,
     map: &mut Vec<Option<usize>>,
@@ -1324,11 +1281,14 @@ impl SplitRenderer {
                     }
                 }
 
-                // Compute inline diff between panes (typically old vs new)
+                // Compute word diff between panes (typically old vs new)
                 if line_contents.len() >= 2 {
                     if let (Some(old_text), Some(new_text)) = (&line_contents[0], &line_contents[1])
                     {
-                        let (old_ranges, new_ranges) = compute_inline_diff(old_text, new_text);
+                        let (old_ranges, new_ranges) =
+                            view_state.inline_diff(old_text, new_text, |old, new| {
+                                diff_words_bounded(old, new, MAX_INLINE_LCS_CELLS)
+                            });
                         vec![old_ranges, new_ranges]
                     } else {
                         vec![Vec::new(); composite.sources.len()]
@@ -2,6 +2,7 @@
 
 use std::path::Path;
 
+use crate::app::status_bar_items::{StatusBarAlignment, StatusBarItem, StatusBarItemRegistry};
 use crate::app::WarningLevel;
 use crate::primitives::display_width::{char_width, str_width};
 use crate::state::EditorState;
@@ -26,6 +27,8 @@ pub struct StatusBarLayout {
     pub language_indicator: Option<(u16, u16, u16)>,
     /// Status message area (row, start_col, end_col) - clickable to show full history
     pub message_area: Option<(u16, u16, u16)>,
+    /// Plugin item areas (plugin_name, id, (row, start_col, end_col)) for fully shown items
+    pub plugin_items: Vec<(String, String, (u16, u16, u16))>,
 }
 
 /// Status bar hover state for styling clickable indicators
@@ -238,6 +241,40 @@ pub fn truncate_path(path: &Path, max_len: usize) -> TruncatedPath {
     }
 }
 
+/// Truncate consecutive text segments to `max_width` columns as one line
+///
+/// Returns the visible part of each segment (empty once the width runs out).
+/// When anything is cut, "..." is appended to the segment where the cut happened.
+fn truncate_segments(segments: &[&str], max_width: usize) -> Vec<String> {
+    let total_width: usize = segments.iter().map(|text| str_width(text)).sum();
+    if total_width <= max_width {
+        return segments.iter().map(|text| text.to_string()).collect();
+    }
+
+    let mut remaining = max_width.saturating_sub(3); // -3 for "..."
+    let mut cut = false;
+    let mut displayed: Vec<String> = Vec::with_capacity(segments.len());
+    for text in segments {
+        let mut kept = String::new();
+        if !cut {
+            for ch in text.chars() {
+                let w = char_width(ch);
+                if w > remaining {
+                    cut = true;
+                    break;
+                }
+                remaining -= w;
+                kept.push(ch);
+            }
+            if cut {
+                kept.push_str("...");
+            }
+        }
+        displayed.push(kept);
+    }
+    displayed
+}
+
 /// Renders the status bar and prompt/minibuffer
 pub struct StatusBarRenderer;
 
@@ -257,6 +294,7 @@ impl StatusBarRenderer {
     /// * `warning_level` - LSP warning level (for coloring LSP indicator)
     /// * `general_warning_count` - Number of general warnings (for badge display)
     /// * `remote_connection` - Optional remote connection info (e.g., "user@host")
+    /// * `plugin_items` - Status bar items contributed by plugins
     ///
     /// # Returns
     /// Layout information with positions of clickable indicators
@@ -277,6 +315,7 @@ impl StatusBarRenderer {
         general_warning_count: usize,
         hover: StatusBarHover,
         remote_connection: Option<&str>,
+        plugin_items: &StatusBarItemRegistry,
     ) -> StatusBarLayout {
         Self::render_status(
             frame,
@@ -294,6 +333,7 @@ impl StatusBarRenderer {
             general_warning_count,
             hover,
             remote_connection,
+            plugin_items,
         )
     }
 
@@ -459,6 +499,21 @@ impl StatusBarRenderer {
         }
     }
 
+    /// Style for a plugin item from its theme keys, defaulting to the status bar colours
+    fn item_style(item: &StatusBarItem, theme: &crate::view::theme::Theme) -> Style {
+        let fg = item
+            .fg
+            .as_deref()
+            .and_then(|key| theme.resolve_theme_key(key))
+            .unwrap_or(theme.status_bar_fg);
+        let bg = item
+            .bg
+            .as_deref()
+            .and_then(|key| theme.resolve_theme_key(key))
+            .unwrap_or(theme.status_bar_bg);
+        Style::default().fg(fg).bg(bg)
+    }
+
     /// Render the normal status bar
     #[allow(clippy::too_many_arguments)]
     fn render_status(
@@ -477,6 +532,7 @@ impl StatusBarRenderer {
         general_warning_count: usize,
         hover: StatusBarHover,
         remote_connection: Option<&str>,
+        plugin_items: &StatusBarItemRegistry,
     ) -> StatusBarLayout {
         // Initialize layout tracking
         let mut layout = StatusBarLayout::default();
@@ -604,14 +660,29 @@ impl StatusBarRenderer {
             format!("{remote_prefix}{filename}{modified}{diagnostics_summary}")
         };
 
+        // Plugin items: left ones go between the position info and the messages,
+        // right ones before the built-in indicators
+        let left_items: Vec<(&StatusBarItem, String)> = plugin_items
+            .items(StatusBarAlignment::Left)
+            .into_iter()
+            .map(|item| (item, format!(" {} ", item.text)))
+            .collect();
+        let mut right_items: Vec<(&StatusBarItem, String)> = plugin_items
+            .items(StatusBarAlignment::Right)
+            .into_iter()
+            .map(|item| (item, format!(" {} ", item.text)))
+            .collect();
+        let left_items_text: String = left_items.iter().map(|(_, text)| text.as_str()).collect();
+
         // Track where the message starts for click detection
         let base_and_chord_width = str_width(&base_status) + str_width(&chord_display);
+        let message_start = base_and_chord_width + str_width(&left_items_text);
         let message_width = str_width(&message_suffix);
 
-        let left_status = format!("{base_status}{chord_display}{message_suffix}");
+        let left_status = format!("{base_status}{chord_display}{left_items_text}{message_suffix}");
 
         // Build right-side indicators (these stay fixed on the right)
-        // Order: [Line ending] [Language] [LSP indicator] [warning badge] [update] [Palette]
+        // Order: [Plugin items] [Line ending] [Language] [LSP indicator] [warning badge] [update] [Palette]
         // Note: Remote indicator is now on the left side, before the filename
 
         // Line ending indicator (clickable to change format)
@@ -658,12 +729,24 @@ impl StatusBarRenderer {
         // Right side: [Line ending] [Language] [LSP indicator] [warning badge] [update] [Palette]
         let available_width = area.width as usize;
         let cmd_palette_width = str_width(&padded_cmd_palette);
-        let right_side_width = line_ending_width
+        let mut right_side_width = line_ending_width
             + language_width
             + lsp_indicator_width
             + warning_badge_width
             + update_width
-            + cmd_palette_width;
+            + cmd_palette_width
+            + right_items
+                .iter()
+                .map(|(_, text)| str_width(text))
+                .sum::<usize>();
+
+        // Drop the lowest priority right-side plugin items that don't fit
+        while right_side_width + 1 > available_width {
+            match right_items.pop() {
+                Some((_, text)) => right_side_width -= str_width(&text),
+                None => break,
+            }
+        }
 
         // Only show command palette indicator if there's enough space (at least 15 chars for minimal display)
         let spans = if available_width >= 15 {
@@ -675,40 +758,31 @@ impl StatusBarRenderer {
             };
 
             let mut spans = vec![];
+            let default_style = Style::default()
+                .fg(theme.status_bar_fg)
+                .bg(theme.status_bar_bg);
+
+            // Left side segments: position info, plugin items, messages
+            let mut left_segments: Vec<(String, Style, Option<&StatusBarItem>)> =
+                vec![(format!("{base_status}{chord_display}"), default_style, None)];
+            for (item, text) in &left_items {
+                left_segments.push((text.clone(), Self::item_style(item, theme), Some(item)));
+            }
+            left_segments.push((message_suffix.clone(), default_style, None));
 
             // Truncate left status if it's too long (use visual width, not char count)
-            let left_visual_width = str_width(&left_status);
-            let displayed_left = if left_visual_width > left_max_width {
-                let truncate_at = left_max_width.saturating_sub(3); // -3 for "..."
-                if truncate_at > 0 {
-                    // Take characters up to visual width limit
-                    let mut width = 0;
-                    let truncated: String = left_status
-                        .chars()
-                        .take_while(|ch| {
-                            let w = char_width(*ch);
-                            if width + w <= truncate_at {
-                                width += w;
-                                true
-                            } else {
-                                false
-                            }
-                        })
-                        .collect();
-                    format!("{}...", truncated)
-                } else {
-                    String::from("...")
-                }
-            } else {
-                left_status.clone()
-            };
-
-            let displayed_left_len = str_width(&displayed_left);
+            let segment_texts: Vec<&str> = left_segments
+                .iter()
+                .map(|(text, _, _)| text.as_str())
+                .collect();
+            let displayed_segments = truncate_segments(&segment_texts, left_max_width);
+            let displayed_left_len: usize =
+                displayed_segments.iter().map(|text| str_width(text)).sum();
 
             // Track message area for click detection (if there's a message)
             if message_width > 0 {
-                // The message starts after base_and_chord, but might be truncated
-                let msg_start = base_and_chord_width.min(displayed_left_len);
+                // The message starts after base, chord and plugin items, but might be truncated
+                let msg_start = message_start.min(displayed_left_len);
                 let msg_end = displayed_left_len;
                 if msg_end > msg_start {
                     layout.message_area =
@@ -716,12 +790,24 @@ impl StatusBarRenderer {
                 }
             }
 
-            spans.push(Span::styled(
-                displayed_left.clone(),
-                Style::default()
-                    .fg(theme.status_bar_fg)
-                    .bg(theme.status_bar_bg),
-            ));
+            let mut left_col = area.x;
+            for ((text, style, item), displayed) in left_segments.iter().zip(displayed_segments) {
+                if displayed.is_empty() {
+                    continue;
+                }
+                let width = str_width(&displayed) as u16;
+                if let Some(item) = item {
+                    if displayed == *text {
+                        layout.plugin_items.push((
+                            item.plugin_name.clone(),
+                            item.id.clone(),
+                            (area.y, left_col, left_col + width),
+                        ));
+                    }
+                }
+                spans.push(Span::styled(displayed, *style));
+                left_col += width;
+            }
 
             // Add spacing to push right side indicators to the right
             if displayed_left_len + right_side_width < available_width {
@@ -748,6 +834,18 @@ impl StatusBarRenderer {
                 current_col = area.x + (available_width - right_side_width) as u16;
             }
 
+            // Add plugin items ahead of the built-in indicators
+            for (item, text) in &right_items {
+                let width = str_width(text) as u16;
+                layout.plugin_items.push((
+                    item.plugin_name.clone(),
+                    item.id.clone(),
+                    (area.y, current_col, current_col + width),
+                ));
+                spans.push(Span::styled(text.clone(), Self::item_style(item, theme)));
+                current_col += width;
+            }
+
             // Add line ending indicator (clickable to change format)
             {
                 let is_hovering = hover == StatusBarHover::LineEndingIndicator;
@@ -1267,4 +1365,18 @@ mod tests {
 
         assert_eq!(truncated.to_string_plain(), "/home/user/project");
     }
+
+    #[test]
+    fn test_truncate_segments_fits() {
+        let displayed = truncate_segments(&["file.rs | Ln 1", " main ", " | saved"], 40);
+        assert_eq!(displayed, vec!["file.rs | Ln 1", " main ", " | saved"]);
+    }
+
+    #[test]
+    fn test_truncate_segments_cuts_inside_segment() {
+        let displayed = truncate_segments(&["file.rs", " main ", " | saved"], 12);
+        assert_eq!(displayed, vec!["file.rs", " m...", ""]);
+        let width: usize = displayed.iter().map(|text| str_width(text)).sum();
+        assert_eq!(width, 12);
+    }
 }
@@ -0,0 +1,83 @@
+//! E2E tests for headless batch editing (`fresh --batch`)
+
+use crate::common::harness::{copy_plugin_lib, EditorTestHarness};
+use fresh::app::batch::{BatchOutcome, BatchScript};
+use fresh::config::Config;
+use std::fs;
+use tempfile::TempDir;
+
+const STAMP_PLUGIN: &str = r#"
+const editor = getEditor();
+
+globalThis.batch_stamp = function() {
+    editor.insertText(editor.getActiveBufferId(), 0, "// stamped\n");
+};
+editor.registerCommand("Stamp File", "Add a header line", "batch_stamp", null);
+"#;
+
+/// Actions, replacements, saved macros and plugin commands are applied to
+/// each file, and only changed files are written
+#[test]
+fn test_batch_script_edits_and_saves_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    fs::write(plugins_dir.join("stamp.ts"), STAMP_PLUGIN).unwrap();
+
+    let first = project_root.join("first.txt");
+    let second = project_root.join("second.txt");
+    fs::write(&first, "let a = foo\nlet b = foo\n").unwrap();
+    fs::write(&second, "let c = foo\n").unwrap();
+    fs::write(
+        project_root.join("semicolon.json"),
+        "// Macro 'a' (2 actions)\n\n[\"MoveLineEnd\", {\"InsertChar\": \";\"}]",
+    )
+    .unwrap();
+    let script = project_root.join("script.json");
+    fs::write(
+        &script,
+        r#"{"steps": [
+            {"replace": "foo", "with": "bar"},
+            {"action": "move_document_start"},
+            {"macro": "semicolon.json"},
+            {"action": "move_down"},
+            {"macro": ["MoveLineEnd", {"InsertChar": ";"}]},
+            {"command": "Stamp File"}
+        ]}"#,
+    )
+    .unwrap();
+    let script = BatchScript::load(&script).unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(80, 24, Config::default(), project_root)
+            .unwrap();
+    let editor = harness.editor_mut();
+    // Let the plugin register its command
+    editor.wait_for_plugin_work();
+
+    assert_eq!(editor.run_batch(&script, &first), BatchOutcome::Changed);
+    assert_eq!(
+        fs::read_to_string(&first).unwrap(),
+        "// stamped\nlet a = bar;\nlet b = bar;\n"
+    );
+    assert_eq!(editor.run_batch(&script, &second), BatchOutcome::Changed);
+    assert_eq!(
+        fs::read_to_string(&second).unwrap(),
+        "// stamped\nlet c = bar;\n;"
+    );
+
+    let unknown =
+        BatchScript::parse(r#"{"steps": [{"command": "Nope"}]}"#, temp_dir.path()).unwrap();
+    assert_eq!(
+        editor.run_batch(&unknown, &first),
+        BatchOutcome::Failed("unknown command 'Nope'".to_string())
+    );
+    let nothing = BatchScript::parse(
+        r#"{"steps": [{"replace": "zzz", "with": "q"}]}"#,
+        temp_dir.path(),
+    )
+    .unwrap();
+    assert_eq!(editor.run_batch(&nothing, &first), BatchOutcome::Unchanged);
+}
@@ -0,0 +1,90 @@
+//! E2E tests for the native side-by-side file comparison
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use std::fs;
+use tempfile::TempDir;
+
+/// Hunks can be walked with n and copied across with < and >, editing the
+/// compared files
+#[test]
+fn test_compare_files_copies_hunks() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().to_path_buf();
+    let left = project_root.join("left.txt");
+    let right = project_root.join("right.txt");
+    fs::write(&left, "one\ntwo\nthree\nfour\n").unwrap();
+    fs::write(&right, "one\n2\nthree\nfour\nfive\n").unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 30, Config::default(), project_root)
+            .unwrap();
+    harness.editor_mut().compare_files(&left, &right).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("left.txt");
+    harness.assert_screen_contains("right.txt");
+    harness.assert_screen_contains("2 change(s)");
+
+    // Take the left side of the first change
+    harness
+        .send_key(KeyCode::Char('n'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('>'), KeyModifiers::NONE)
+        .unwrap();
+    // Take the right side of the remaining one
+    harness
+        .send_key(KeyCode::Char('n'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('<'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('n'), KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("No more changes");
+
+    let editor = harness.editor_mut();
+    let left_id = editor.open_file(&left).unwrap();
+    let right_id = editor.open_file(&right).unwrap();
+    let expected = "one\ntwo\nthree\nfour\nfive\n";
+    assert_eq!(editor.get_buffer_content(left_id).unwrap(), expected);
+    assert_eq!(editor.get_buffer_content(right_id).unwrap(), expected);
+}
+
+/// Unsaved edits can be reverted hunk by hunk from the saved copy, which
+/// itself is read-only
+#[test]
+fn test_compare_with_saved_reverts_hunk() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("notes.txt");
+    fs::write(&file, "alpha\nbeta\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(120, 30).unwrap();
+    harness.open_file(&file).unwrap();
+    harness.type_text("new ").unwrap();
+    harness.assert_buffer_content("new alpha\nbeta\n");
+
+    harness.editor_mut().compare_with_saved();
+    harness.render().unwrap();
+    harness.assert_screen_contains("notes.txt (saved)");
+    harness.assert_screen_contains("1 change(s)");
+
+    harness
+        .send_key(KeyCode::Char('n'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('<'), KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("read-only");
+    harness
+        .send_key(KeyCode::Char('>'), KeyModifiers::NONE)
+        .unwrap();
+
+    // Closing the comparison returns to the file
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_buffer_content("alpha\nbeta\n");
+}
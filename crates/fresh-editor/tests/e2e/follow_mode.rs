@@ -0,0 +1,177 @@
+//! E2E tests for follow mode on growing files
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// Delay before changing a file so that its mtime differs (see auto_revert.rs)
+const FILE_CHANGE_DELAY: Duration = Duration::from_millis(2100);
+
+/// Append lines to a file the way a logger does
+fn append_and_sync(path: &Path, content: &str) {
+    let mut file = OpenOptions::new().append(true).open(path).unwrap();
+    file.write_all(content.as_bytes()).unwrap();
+    file.sync_all().unwrap();
+}
+
+fn log_lines(range: std::ops::RangeInclusive<usize>) -> String {
+    range.map(|i| format!("log line {}\n", i)).collect()
+}
+
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+}
+
+/// Appends extend the buffer in place and the view stays at the end
+#[test]
+fn test_follow_mode_keeps_view_at_end() {
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    let file_path = harness.project_dir().unwrap().join("service.log");
+    fs::write(&file_path, log_lines(1..=40)).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    run_command(&mut harness, "Toggle Follow Mode");
+    harness.assert_screen_contains("Following service.log");
+    harness.assert_screen_contains("Ln 41");
+    harness.assert_screen_contains("log line 40");
+
+    harness.sleep(FILE_CHANGE_DELAY);
+    append_and_sync(&file_path, &log_lines(41..=60));
+    harness
+        .wait_until(|h| h.screen_to_string().contains("log line 60"))
+        .expect("Follow mode should show the appended lines");
+    harness.assert_screen_not_contains("log line 30");
+    assert_eq!(harness.cursor_position(), log_lines(1..=60).len());
+    assert!(!harness.editor().active_state().buffer.is_modified());
+
+    // Moving up unpins the view
+    harness
+        .send_key(KeyCode::PageUp, KeyModifiers::NONE)
+        .unwrap();
+    let cursor = harness.cursor_position();
+    harness.sleep(FILE_CHANGE_DELAY);
+    append_and_sync(&file_path, &log_lines(61..=62));
+    harness
+        .wait_until(|h| h.editor().active_state().buffer.len() == log_lines(1..=62).len())
+        .expect("Follow mode should extend the buffer");
+    harness.render().unwrap();
+    assert_eq!(harness.cursor_position(), cursor);
+    harness.assert_screen_not_contains("log line 62");
+}
+
+/// Lines matching the filter are highlighted as they arrive
+#[test]
+fn test_follow_mode_filter_highlights_matching_lines() {
+    let mut harness = EditorTestHarness::with_temp_project(120, 24).unwrap();
+    let file_path = harness.project_dir().unwrap().join("service.log");
+    fs::write(&file_path, "INFO started\nERROR early\n").unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    run_command(&mut harness, "Toggle Follow Mode");
+    run_command(&mut harness, "Follow: Set Filter");
+    harness.type_text("ERROR").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("Highlighting lines matching ERROR");
+
+    harness.sleep(FILE_CHANGE_DELAY);
+    append_and_sync(&file_path, "INFO request\nERROR timeout\n");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("ERROR timeout"))
+        .expect("Follow mode should show the appended lines");
+
+    let state = harness.editor().active_state();
+    let mut highlighted: Vec<_> = state
+        .overlays
+        .all()
+        .iter()
+        .filter(|o| o.namespace.as_ref().map(|ns| ns.as_str()) == Some("follow-filter"))
+        .map(|o| o.range(&state.marker_list))
+        .collect();
+    highlighted.sort_by_key(|r| r.start);
+    assert_eq!(highlighted, vec![13..24, 38..51]);
+}
+
+/// A truncated or rotated file is reloaded and followed from its new end,
+/// even with auto-revert off
+#[test]
+fn test_follow_mode_reloads_truncated_file() {
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    let file_path = harness.project_dir().unwrap().join("service.log");
+    fs::write(&file_path, log_lines(1..=40)).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.editor_mut().toggle_auto_revert();
+
+    run_command(&mut harness, "Toggle Follow Mode");
+    harness.assert_screen_contains("log line 40");
+
+    // Truncated in place
+    harness.sleep(FILE_CHANGE_DELAY);
+    fs::write(&file_path, "").unwrap();
+    append_and_sync(&file_path, "rotated 1\n");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("rotated 1"))
+        .expect("Follow mode should reload the truncated file");
+    harness.assert_screen_not_contains("log line 40");
+    assert_eq!(harness.cursor_position(), "rotated 1\n".len());
+
+    // Replaced by a new file bigger than the old one
+    harness.sleep(FILE_CHANGE_DELAY);
+    let new_path = file_path.with_extension("new");
+    fs::write(&new_path, log_lines(100..=150)).unwrap();
+    fs::rename(&new_path, &file_path).unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("log line 150"))
+        .expect("Follow mode should reload the replaced file");
+    harness.assert_screen_not_contains("rotated 1");
+    assert_eq!(harness.cursor_position(), log_lines(100..=150).len());
+    assert!(!harness.editor().active_state().buffer.is_modified());
+}
+
+/// A line written in two appends is matched as a whole
+#[test]
+fn test_follow_mode_filter_matches_lines_completed_by_append() {
+    let mut harness = EditorTestHarness::with_temp_project(120, 24).unwrap();
+    let file_path = harness.project_dir().unwrap().join("service.log");
+    fs::write(&file_path, "INFO started\n").unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    run_command(&mut harness, "Toggle Follow Mode");
+    run_command(&mut harness, "Follow: Set Filter");
+    harness.type_text("ERROR.*timeout").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness.sleep(FILE_CHANGE_DELAY);
+    append_and_sync(&file_path, "ERROR request");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("ERROR request"))
+        .expect("Follow mode should show the appended text");
+    harness.sleep(FILE_CHANGE_DELAY);
+    append_and_sync(&file_path, " timeout\nINFO done\n");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("INFO done"))
+        .expect("Follow mode should show the appended lines");
+
+    let state = harness.editor().active_state();
+    let highlighted: Vec<_> = state
+        .overlays
+        .all()
+        .iter()
+        .filter(|o| o.namespace.as_ref().map(|ns| ns.as_str()) == Some("follow-filter"))
+        .map(|o| o.range(&state.marker_list))
+        .collect();
+    assert_eq!(highlighted, vec![13..34]);
+}
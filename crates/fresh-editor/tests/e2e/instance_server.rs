@@ -0,0 +1,56 @@
+//! E2E tests for opening files in a running editor with `--remote`/`--wait`
+
+use crate::common::harness::EditorTestHarness;
+use fresh::services::instance_server::{Client, InstanceServer, OpenFile, OpenRequest, Reply};
+use std::sync::mpsc;
+use tempfile::TempDir;
+
+/// A waiting client gets its file opened at the requested line, and is
+/// released once the buffer is closed
+#[test]
+fn test_remote_open_and_wait_for_close() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket = temp_dir.path().join("server.sock");
+    let file = temp_dir.path().join("COMMIT_EDITMSG");
+    std::fs::write(&file, "first\nsecond\nthird\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness
+        .editor_mut()
+        .set_instance_server(InstanceServer::bind(&socket).unwrap());
+
+    let (replies, received) = mpsc::channel();
+    let request = OpenRequest {
+        files: vec![OpenFile {
+            path: file.clone(),
+            line: Some(2),
+            column: None,
+        }],
+        split: false,
+        wait: true,
+    };
+    std::thread::spawn(move || {
+        let mut client = Client::send(&socket, &request).unwrap().unwrap();
+        for _ in 0..2 {
+            replies.send(client.next_reply().unwrap()).unwrap();
+        }
+    });
+
+    harness
+        .wait_until(|h| h.screen_to_string().contains("COMMIT_EDITMSG"))
+        .unwrap();
+    assert_eq!(received.recv().unwrap(), Reply::Opened);
+    harness.assert_buffer_content("first\nsecond\nthird\n");
+    assert_eq!(harness.cursor_position(), "first\n".len());
+
+    let buffer = harness.editor().active_buffer();
+    harness.editor_mut().close_buffer(buffer).unwrap();
+    let mut closed = None;
+    harness
+        .wait_until(|_| {
+            closed = received.try_recv().ok();
+            closed.is_some()
+        })
+        .unwrap();
+    assert_eq!(closed, Some(Reply::Closed));
+}
@@ -0,0 +1,95 @@
+//! E2E tests for the three-way merge tool (`fresh --merge`)
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Write BASE, LOCAL, REMOTE and MERGED files the way git mergetool does,
+/// with one clean change on each side and one conflict
+fn write_merge_files(dir: &Path) -> [PathBuf; 4] {
+    let paths = ["base.txt", "local.txt", "remote.txt", "merged.txt"].map(|name| dir.join(name));
+    fs::write(&paths[0], "one\ntwo\nthree\nfour\nfive\n").unwrap();
+    fs::write(&paths[1], "ONE\ntwo\nthree\nfour\nours\n").unwrap();
+    fs::write(&paths[2], "one\ntwo\nTHREE\nfour\ntheirs\n").unwrap();
+    fs::write(&paths[3], "git's merge\n").unwrap();
+    paths
+}
+
+/// Clean changes are merged automatically and the conflict is resolved from
+/// the MERGED file with a command
+#[test]
+fn test_merge_resolves_conflict_from_result() {
+    let temp_dir = TempDir::new().unwrap();
+    let [base, local, remote, merged] = write_merge_files(temp_dir.path());
+
+    let mut harness = EditorTestHarness::new(120, 30).unwrap();
+    harness
+        .editor_mut()
+        .open_merge(&base, &local, &remote, &merged)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Ours (LOCAL)");
+    harness.assert_screen_contains("Base");
+    harness.assert_screen_contains("Theirs (REMOTE)");
+    harness.assert_screen_contains("Conflict 1 of 1");
+    harness.assert_buffer_content(
+        "ONE\ntwo\nTHREE\nfour\n<<<<<<< LOCAL\nours\n=======\ntheirs\n>>>>>>> REMOTE\n",
+    );
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text("Merge: Accept Theirs").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_buffer_content("ONE\ntwo\nTHREE\nfour\ntheirs\n");
+    harness.assert_screen_contains("All conflicts resolved");
+
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(&merged).unwrap(),
+        "ONE\ntwo\nTHREE\nfour\ntheirs\n"
+    );
+}
+
+/// Conflicts can be resolved from the three versions with single keys
+#[test]
+fn test_merge_resolves_conflict_from_composite() {
+    let temp_dir = TempDir::new().unwrap();
+    let [base, local, remote, merged] = write_merge_files(temp_dir.path());
+
+    let mut harness = EditorTestHarness::new(120, 30).unwrap();
+    let merge_id = harness
+        .editor_mut()
+        .open_merge(&base, &local, &remote, &merged)
+        .unwrap();
+    harness.render().unwrap();
+
+    // Focus the three versions above the MERGED file
+    harness.mouse_click(10, 4).unwrap();
+    assert_eq!(harness.editor().active_buffer(), merge_id);
+    harness
+        .send_key(KeyCode::Char('n'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('b'), KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("All conflicts resolved");
+    harness
+        .send_key(KeyCode::Char('b'), KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("Conflict already resolved");
+
+    let editor = harness.editor_mut();
+    let merged_id = editor.open_file(&merged).unwrap();
+    assert_eq!(
+        editor.get_buffer_content(merged_id).unwrap(),
+        "ONE\ntwo\nTHREE\nfour\nours\ntheirs\n"
+    );
+}
@@ -2,6 +2,7 @@ pub mod ansi_cursor;
 pub mod auto_indent;
 pub mod auto_revert;
 pub mod basic;
+pub mod batch;
 pub mod binary_file;
 pub mod buffer_lifecycle;
 pub mod buffer_settings_commands;
@@ -13,9 +14,13 @@ pub mod document_model;
 pub mod emacs_actions;
 pub mod explorer_menu;
 pub mod file_browser;
+pub mod file_compare;
 pub mod file_explorer;
 pub mod file_permissions;
+pub mod follow_mode;
 pub mod indent_dedent;
+#[cfg(unix)]
+pub mod instance_server;
 pub mod language_features_e2e;
 pub mod large_file_mode;
 pub mod lifecycle;
@@ -29,6 +34,7 @@ pub mod margin;
 pub mod markdown_compose;
 pub mod menu_bar;
 pub mod merge_conflict;
+pub mod merge_tool;
 pub mod mouse;
 pub mod movement;
 pub mod multi_file_opening;
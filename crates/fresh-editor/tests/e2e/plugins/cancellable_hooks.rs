@@ -0,0 +1,89 @@
+//! E2E tests for hooks that let plugins cancel or rewrite edits and saves
+
+use crate::common::harness::{copy_plugin_lib, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use std::fs;
+use tempfile::TempDir;
+
+const GUARD_PLUGIN: &str = r#"
+const editor = getEditor();
+
+globalThis.guard_before_insert = function(data: { text: string }) {
+    if (data.text === "!") return false;
+    if (data.text === "-") return { text: "–" };
+};
+editor.on("before_insert", "guard_before_insert");
+
+globalThis.guard_before_save = function(data: { content: string }) {
+    if (data.content.includes("<<<<<<<")) return false;
+    return { content: data.content.replace("TODO", "DONE") };
+};
+editor.on("before_file_save", "guard_before_save");
+"#;
+
+#[test]
+fn test_plugins_cancel_and_rewrite_edits_and_saves() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    fs::write(plugins_dir.join("guard.ts"), GUARD_PLUGIN).unwrap();
+    let file = project_root.join("notes.txt");
+    fs::write(&file, "").unwrap();
+
+    let mut config = Config::default();
+    // Generous, so a slow test machine does not make the editor go ahead
+    config.plugin_runtime.hook_timeout_ms = 5000;
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, config, project_root).unwrap();
+    harness
+        .wait_until(|h| {
+            h.editor()
+                .plugin_manager()
+                .handles_cancellable_hook("before_file_save")
+        })
+        .unwrap();
+    harness.open_file(&file).unwrap();
+
+    harness.type_text("a-b!").unwrap();
+    harness.assert_buffer_content("a\u{2013}b");
+
+    harness.type_text(" TODO").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(fs::read_to_string(&file).unwrap(), "a\u{2013}b DONE");
+    harness.assert_buffer_content("a\u{2013}b DONE");
+
+    harness.type_text(" <<<<<<<").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(fs::read_to_string(&file).unwrap(), "a\u{2013}b DONE");
+    harness.assert_screen_contains("Save cancelled by plugin 'guard'");
+
+    // Saving under another name asks the plugins too
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Save File As").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_screen_contains("Save as:").unwrap();
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::CONTROL)
+        .unwrap();
+    let copy = file.with_file_name("copy.txt");
+    harness.type_text(copy.to_str().unwrap()).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    assert!(!copy.exists());
+    harness.assert_screen_contains("Save cancelled by plugin 'guard'");
+}
@@ -0,0 +1,121 @@
+//! E2E tests for plugin form dialogs
+//!
+//! Plugins show a modal form, get a hook for every field edit and one more
+//! when a button is pressed or the form is dismissed.
+
+use crate::common::harness::{copy_plugin_lib, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use tempfile::TempDir;
+
+const FORM_PLUGIN: &str = r#"
+const editor = getEditor();
+
+editor.showForm({
+    id: "new-component",
+    title: "New Component",
+    fields: [
+        { id: "name", label: "Name", kind: "text", placeholder: "Button" },
+        { id: "tests", label: "Add tests", kind: "toggle", value: true },
+    ],
+    buttons: [{ id: "create", label: "Create" }, { id: "skip", label: "Skip" }],
+});
+
+globalThis.on_form_field_changed = function(args: { form_id: string; field: string; value: unknown }): void {
+    editor.setStatus(`changed ${args.form_id}.${args.field}=${args.value}`);
+};
+
+globalThis.on_form_result = function(args: { form_id: string; button: string; values: Record<string, unknown> }): void {
+    editor.setStatus(`result ${args.button} name=${args.values.name} tests=${args.values.tests}`);
+};
+
+editor.on("form_field_changed", "on_form_field_changed");
+editor.on("form_result", "on_form_result");
+"#;
+
+fn setup() -> (TempDir, EditorTestHarness) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    fs::write(plugins_dir.join("form.ts"), FORM_PLUGIN).unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("New Component"))
+        .unwrap();
+    (temp_dir, harness)
+}
+
+fn status(harness: &EditorTestHarness) -> Option<String> {
+    harness.editor().get_status_message().cloned()
+}
+
+#[test]
+fn test_plugin_form_edit_and_submit() {
+    let (_temp_dir, mut harness) = setup();
+    harness.assert_screen_contains("Add tests");
+    harness.assert_screen_contains("Create");
+
+    harness.type_text("Card").unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("changed new-component.name=Card"))
+        .unwrap();
+
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness
+        .send_key(KeyCode::Char(' '), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("changed new-component.tests=false"))
+        .unwrap();
+
+    // Enter in the text field presses the first button
+    harness
+        .send_key(KeyCode::BackTab, KeyModifiers::SHIFT)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("result create name=Card tests=false"))
+        .unwrap();
+    harness.assert_screen_not_contains("New Component");
+}
+
+#[test]
+fn test_plugin_form_buttons_and_dismiss() {
+    let (_temp_dir, mut harness) = setup();
+
+    // Keys don't reach the buffer while the form is open
+    harness.type_text("x").unwrap();
+    let buffer = harness.get_buffer_content().unwrap_or_default();
+    assert!(!buffer.contains('x'));
+
+    let screen = harness.screen_to_string();
+    let (row, line) = screen
+        .lines()
+        .enumerate()
+        .find(|(_, line)| line.contains("Skip"))
+        .unwrap();
+    let col = line[..line.find("Skip").unwrap()].chars().count() as u16;
+    harness.mouse_click(col + 1, row as u16).unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("result skip name=x tests=true"))
+        .unwrap();
+    harness.assert_screen_not_contains("New Component");
+}
+
+#[test]
+fn test_plugin_form_escape_dismisses() {
+    let (_temp_dir, mut harness) = setup();
+
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("result dismissed name= tests=true"))
+        .unwrap();
+    harness.assert_screen_not_contains("New Component");
+}
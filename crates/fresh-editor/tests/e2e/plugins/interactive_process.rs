@@ -0,0 +1,98 @@
+//! E2E tests for interactive plugin processes
+//!
+//! Plugins write to a process's stdin through its handle and get the
+//! output back through the process output hooks.
+
+use crate::common::harness::{copy_plugin_lib, EditorTestHarness};
+use std::fs;
+use tempfile::TempDir;
+
+const INTERACTIVE_PLUGIN: &str = r#"
+const editor = getEditor();
+
+let output = "";
+let errors = "";
+const proc = editor.spawnInteractiveProcess("sh", ["-c", 'read line; echo "$PREFIX$line"; echo oops >&2'], {
+    env: { PREFIX: "got:" },
+});
+
+globalThis.on_stdout = function(args: { process_id: number; data: string }): void {
+    if (args.process_id !== proc.processId) return;
+    output += args.data;
+};
+
+globalThis.on_stderr = function(args: { process_id: number; data: string }): void {
+    if (args.process_id !== proc.processId) return;
+    errors += args.data;
+};
+
+editor.on("onProcessStdout", "on_stdout");
+editor.on("onProcessStderr", "on_stderr");
+
+proc.write("hello\n");
+proc.then((result) => {
+    editor.setStatus(`exit=${result.exit_code} stdout=${output.trim()} stderr=${errors.trim()}`);
+});
+"#;
+
+const CHUNKS_PLUGIN: &str = r#"
+const editor = getEditor();
+
+let output = "";
+const proc = editor.spawnInteractiveProcess("cat", [], { output: "chunks" });
+
+globalThis.on_stdout = function(args: { process_id: number; data: string }): void {
+    if (args.process_id !== proc.processId) return;
+    output += args.data;
+    // A prompt without a trailing newline still arrives
+    if (output === "> ") {
+        proc.closeStdin();
+    }
+};
+
+editor.on("onProcessStdout", "on_stdout");
+
+proc.write("> ");
+proc.then((result) => {
+    editor.setStatus(`closed exit=${result.exit_code} output=${output}`);
+});
+"#;
+
+fn setup(plugin: &str) -> (TempDir, EditorTestHarness) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    fs::write(plugins_dir.join("interactive.ts"), plugin).unwrap();
+
+    let harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    (temp_dir, harness)
+}
+
+fn status(harness: &EditorTestHarness) -> Option<String> {
+    harness.editor().get_status_message().cloned()
+}
+
+#[test]
+fn test_interactive_process_stdin_env_and_output() {
+    let (_temp_dir, mut harness) = setup(INTERACTIVE_PLUGIN);
+    harness
+        .wait_until(|h| status(h).is_some_and(|s| s.starts_with("exit=")))
+        .unwrap();
+    assert_eq!(
+        status(&harness).as_deref(),
+        Some("exit=0 stdout=got:hello stderr=oops")
+    );
+}
+
+#[test]
+fn test_interactive_process_chunks_and_close_stdin() {
+    let (_temp_dir, mut harness) = setup(CHUNKS_PLUGIN);
+    harness
+        .wait_until(|h| status(h).is_some_and(|s| s.starts_with("closed")))
+        .unwrap();
+    assert_eq!(status(&harness).as_deref(), Some("closed exit=0 output=> "));
+}
@@ -0,0 +1,99 @@
+//! E2E tests for plugin language feature providers
+//!
+//! Plugins register completion/hover/diagnostics providers for a language;
+//! their results show up in the same popups and overlays as LSP results,
+//! even when no language server is running.
+
+use crate::common::harness::{copy_plugin_lib, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+const PROVIDER_PLUGIN: &str = r#"
+const editor = getEditor();
+
+globalThis.dslComplete = function(req) {
+    return [
+        { label: "timeout_ms", detail: "number", kind: "variable" },
+        { label: "retries", detail: "number" },
+    ];
+};
+globalThis.dslHover = async function(req) {
+    return "**timeout_ms**: line " + (req.line + 1);
+};
+globalThis.dslDiagnostics = function(req) {
+    return [{
+        message: "unknown key 'bogus'",
+        severity: 2,
+        range: { start: { line: 1, character: 0 }, end: { line: 1, character: 5 } },
+    }];
+};
+
+editor.registerLanguageProvider("completion", "python", "dslComplete");
+editor.registerLanguageProvider("hover", "python", "dslHover");
+editor.registerLanguageProvider("diagnostics", "python", "dslDiagnostics");
+"#;
+
+/// Project with the provider plugin and a file of the provided language
+/// (no language server is configured to start for it)
+fn setup() -> (TempDir, PathBuf, EditorTestHarness) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    fs::write(plugins_dir.join("dsl_provider.ts"), PROVIDER_PLUGIN).unwrap();
+
+    let file = project_root.join("settings.py");
+    fs::write(&file, "timeout_ms = 10\nbogus = 1\n").unwrap();
+
+    let harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    (temp_dir, file, harness)
+}
+
+#[test]
+fn test_plugin_completion_provider_without_lsp() {
+    let (_temp_dir, file, mut harness) = setup();
+    harness.open_file(&file).unwrap();
+
+    harness
+        .send_key(KeyCode::Char(' '), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("retries"))
+        .unwrap();
+    harness.assert_screen_contains("timeout_ms");
+}
+
+#[test]
+fn test_plugin_hover_provider_without_lsp() {
+    let (_temp_dir, file, mut harness) = setup();
+    harness.open_file(&file).unwrap();
+
+    harness
+        .send_key(KeyCode::Char('k'), KeyModifiers::ALT)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("line 1"))
+        .unwrap();
+}
+
+#[test]
+fn test_plugin_diagnostics_shown_as_overlays() {
+    let (_temp_dir, file, mut harness) = setup();
+    harness.open_file(&file).unwrap();
+
+    harness
+        .wait_until(|h| {
+            h.editor()
+                .active_state()
+                .overlays
+                .all()
+                .iter()
+                .any(|overlay| overlay.message.as_deref() == Some("unknown key 'bogus'"))
+        })
+        .unwrap();
+}
@@ -2,12 +2,21 @@
 //! These tests are only compiled when the "plugins" feature is enabled.
 
 pub mod audit_mode;
+pub mod cancellable_hooks;
 pub mod diff_cursor;
 pub mod find_file;
+pub mod forms;
 pub mod git;
 pub mod gutter;
+pub mod interactive_process;
 pub mod language_pack;
+pub mod language_providers;
 pub mod lsp_find_references;
 pub mod package_manager;
+pub mod path_watches;
 pub mod plugin;
+pub mod plugin_api;
+pub mod plugin_console;
+pub mod status_bar_items;
+pub mod terminals;
 pub mod theme_editor;
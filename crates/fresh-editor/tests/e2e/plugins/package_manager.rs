@@ -1051,3 +1051,130 @@ globalThis.uninstall_test_hello = function() { editor.setStatus("Hello from unin
         screen
     );
 }
+
+/// Write a plugin package that declares the clipboard capability and
+/// registers a command, and return the editor's config dir context
+fn setup_clipboard_package(repo: &GitTestRepo) -> fresh::config_io::DirectoryContext {
+    let plugins_dir = repo.path.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+
+    let dir_context = fresh::config_io::DirectoryContext::for_testing(&repo.path.join(".config"));
+    let package_dir = dir_context
+        .config_dir
+        .join("plugins")
+        .join("packages")
+        .join("clip-plugin");
+    fs::create_dir_all(&package_dir).unwrap();
+    fs::write(
+        package_dir.join("clip_plugin.ts"),
+        r#"
+const editor = getEditor();
+editor.registerCommand("Clip Plugin: Copy", "Copy a greeting", "clip_plugin_copy", null);
+globalThis.clip_plugin_copy = function() { editor.setClipboard("hello"); };
+"#,
+    )
+    .unwrap();
+    fs::write(
+        package_dir.join("package.json"),
+        r#"{
+    "name": "clip-plugin",
+    "version": "1.0.0",
+    "type": "plugin",
+    "fresh": {
+        "entry": "clip_plugin.ts",
+        "capabilities": { "clipboard": true }
+    }
+}"#,
+    )
+    .unwrap();
+    dir_context
+}
+
+/// Test that a package declaring capabilities asks for consent before it loads,
+/// and that the grant is saved to the user config.
+#[test]
+fn test_package_capabilities_granted_on_first_run() {
+    init_tracing_from_env();
+
+    let repo = GitTestRepo::new();
+    repo.setup_typical_project();
+    let dir_context = setup_clipboard_package(&repo);
+    let config_path = dir_context.config_dir.join("config.json");
+
+    let original_dir = repo.change_to_repo_dir();
+    let _guard = DirGuard::new(original_dir);
+
+    let mut harness = EditorTestHarness::with_shared_dir_context(
+        120,
+        30,
+        Default::default(),
+        repo.path.clone(),
+        dir_context,
+    )
+    .unwrap();
+
+    harness
+        .wait_until(|h| {
+            h.screen_to_string()
+                .contains("Allow plugin 'clip_plugin' to set the clipboard?")
+        })
+        .unwrap();
+    harness.type_text("y").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    let granted = &harness.editor().config().plugins["clip_plugin"];
+    assert!(granted.enabled);
+    assert!(granted.granted.clipboard);
+    let saved = fs::read_to_string(&config_path).unwrap();
+    assert!(saved.contains("\"clipboard\": true"), "config: {}", saved);
+
+    // The plugin was loaded once granted
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Clip Plugin").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Clip Plugin: Copy"))
+        .unwrap();
+}
+
+/// Test that refusing a package's capabilities keeps it unloaded and disables it.
+#[test]
+fn test_package_capabilities_denied() {
+    init_tracing_from_env();
+
+    let repo = GitTestRepo::new();
+    repo.setup_typical_project();
+    let dir_context = setup_clipboard_package(&repo);
+
+    let original_dir = repo.change_to_repo_dir();
+    let _guard = DirGuard::new(original_dir);
+
+    let mut harness = EditorTestHarness::with_shared_dir_context(
+        120,
+        30,
+        Default::default(),
+        repo.path.clone(),
+        dir_context,
+    )
+    .unwrap();
+
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Allow plugin 'clip_plugin'"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("was not granted its capabilities");
+    assert!(!harness.editor().config().plugins["clip_plugin"].enabled);
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Clip Plugin").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("Clip Plugin: Copy");
+}
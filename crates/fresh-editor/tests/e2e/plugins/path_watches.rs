@@ -0,0 +1,121 @@
+//! E2E tests for plugin file-system watches
+//!
+//! Plugins watch a directory and get debounced change events through the
+//! `path_changed` hook until they close the watch.
+
+use crate::common::harness::{copy_plugin_lib, EditorTestHarness};
+use std::fs;
+use tempfile::TempDir;
+
+const WATCH_PLUGIN: &str = r#"
+const editor = getEditor();
+
+const watch = editor.watchPath("reports", { recursive: true, glob: "*.xml" });
+
+globalThis.on_path_changed = function(args: {
+    watch_id: number;
+    events: { path: string; kind: string }[];
+}): void {
+    if (args.watch_id !== watch.watchId) return;
+    const names = args.events.map((e) => `${e.kind}:${e.path.split("/").pop()}`);
+    editor.setStatus(`changed ${names.join(",")}`);
+    if (names.includes("create:stop.xml")) {
+        watch.close();
+    }
+};
+
+editor.on("path_changed", "on_path_changed");
+editor.setStatus("watching");
+"#;
+
+fn status(harness: &EditorTestHarness) -> Option<String> {
+    harness.editor().get_status_message().cloned()
+}
+
+#[test]
+fn test_plugin_path_watch_reports_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    let reports = project_root.join("reports");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    fs::create_dir_all(reports.join("unit")).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    fs::write(plugins_dir.join("watch.ts"), WATCH_PLUGIN).unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("watching"))
+        .unwrap();
+
+    // The first snapshot is taken in the background, so touch a file until
+    // the watch reports it
+    let ready = reports.join("ready.xml");
+    let mut writes = 0;
+    harness
+        .wait_until(|h| {
+            if status(h).is_some_and(|s| s.contains("ready.xml")) {
+                return true;
+            }
+            writes += 1;
+            fs::write(&ready, "x".repeat(writes)).unwrap();
+            false
+        })
+        .unwrap();
+
+    fs::write(reports.join("unit/a.xml"), "<ok/>").unwrap();
+    fs::write(reports.join("unit/notes.txt"), "ignored").unwrap();
+    harness
+        .wait_until(|h| status(h).is_some_and(|s| s.contains("create:a.xml")))
+        .unwrap();
+
+    fs::remove_file(reports.join("unit/a.xml")).unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("changed delete:a.xml"))
+        .unwrap();
+
+    // Closing the watch stops the events
+    fs::write(reports.join("stop.xml"), "").unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("changed create:stop.xml"))
+        .unwrap();
+    fs::write(reports.join("after.xml"), "").unwrap();
+    for _ in 0..10 {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        harness.editor_mut().process_async_messages();
+        harness.render().unwrap();
+    }
+    assert_eq!(status(&harness).as_deref(), Some("changed create:stop.xml"));
+}
+
+#[test]
+fn test_plugin_path_watch_reports_start_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    fs::write(
+        plugins_dir.join("bad_watch.ts"),
+        r#"
+const editor = getEditor();
+const watch = editor.watchPath(".", { glob: "[" });
+globalThis.on_path_changed = function(args: { watch_id: number; error: string | null }): void {
+    if (args.watch_id === watch.watchId && args.error) {
+        editor.setStatus("watch failed");
+    }
+};
+editor.on("path_changed", "on_path_changed");
+"#,
+    )
+    .unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("watch failed"))
+        .unwrap();
+}
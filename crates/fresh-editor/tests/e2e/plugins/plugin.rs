@@ -1282,3 +1282,205 @@ editor.debug("Theme overlay test plugin loaded");
     let screen = harness.screen_to_string();
     println!("Final screen:\n{}", screen);
 }
+
+/// Test that applyEdits applies all edits as one undo step and rejects
+/// edits computed against an older buffer version
+#[test]
+fn test_plugin_apply_edits_transaction() {
+    init_tracing_from_env();
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+
+    let test_plugin = r#"
+const editor = getEditor();
+
+editor.registerCommand("Test: Apply Edits", "Rewrite two ranges", "test_apply_edits", null);
+
+globalThis.test_apply_edits = async function(): Promise<void> {
+    const bufferId = editor.getActiveBufferId();
+    const version = editor.getBufferInfo(bufferId)!.version;
+    const edits = [
+        { start: 0, end: 3, text: "let" },
+        { start: 9, end: 9, text: ";" },
+    ];
+    await editor.applyEdits(bufferId, version, edits, "Plugin rewrite");
+    try {
+        // Same offsets, but computed against the version we just replaced
+        await editor.applyEdits(bufferId, version, edits);
+        editor.setStatus("second edit applied");
+    } catch (e) {
+        const err = e as StaleVersionError;
+        editor.setStatus(`${err.name}: now at ${err.currentVersion === version ? "same" : "newer"} version`);
+    }
+};
+"#;
+    fs::write(plugins_dir.join("apply_edits.ts"), test_plugin).unwrap();
+
+    let fixture = TestFixture::new("test.js", "var x = 1\n").unwrap();
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(80, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&fixture.path).unwrap();
+    harness.render().unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Test: Apply Edits").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Rewrite two ranges"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness
+        .wait_until(|h| {
+            h.editor().get_status_message().map(String::as_str)
+                == Some("StaleVersionError: now at newer version")
+        })
+        .unwrap();
+    harness.assert_buffer_content("let x = 1;\n");
+
+    // Both edits are undone together
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content("var x = 1\n");
+}
+
+/// Test that plugins can inspect the syntax tree: the node path under an
+/// offset, the enclosing node of a kind, and the captures of a query
+#[test]
+fn test_plugin_syntax_tree_queries() {
+    init_tracing_from_env();
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+
+    let test_plugin = r#"
+const editor = getEditor();
+
+editor.registerCommand("Test: Syntax Tree", "Inspect the syntax tree", "test_syntax_tree", null);
+
+globalThis.test_syntax_tree = async function(): Promise<void> {
+    const bufferId = editor.getActiveBufferId();
+    const path = await editor.getSyntaxNodePath(bufferId, 28);
+    const fn = await editor.getEnclosingSyntaxNode(bufferId, 28, "function_item");
+    const calls = await editor.querySyntax(bufferId, "(call_expression function: (identifier) @callee)");
+    const callees = calls.map((c) => `${c.name}@${c.start}`).join(",");
+    editor.setStatus(`${path[path.length - 1].kind} in ${fn!.start}-${fn!.end}; calls: ${callees}`);
+};
+"#;
+    fs::write(plugins_dir.join("syntax_tree.ts"), test_plugin).unwrap();
+
+    let fixture =
+        TestFixture::new("test.rs", "fn main() {\n    let total = add(1, 2);\n}\n").unwrap();
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(80, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&fixture.path).unwrap();
+    harness.render().unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Test: Syntax Tree").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Inspect the syntax tree"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness
+        .wait_until(|h| {
+            h.editor().get_status_message().map(String::as_str)
+                == Some("identifier in 0-40; calls: callee@28")
+        })
+        .unwrap();
+}
+
+/// Test that plugins can replace the cursors with several selections,
+/// including block selections, and observe the change through the
+/// selection_changed hook
+#[test]
+fn test_plugin_set_selections() {
+    init_tracing_from_env();
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+
+    let test_plugin = r#"
+const editor = getEditor();
+
+editor.registerCommand("Test: Select Words", "Select two words", "test_select_words", null);
+
+globalThis.test_select_words = function(): void {
+    editor.setSelections([
+        { anchor: 0, position: 5 },
+        { anchor: 12, position: 22, mode: "block" },
+    ]);
+};
+
+globalThis.on_selection_changed = function(args: { selections: SelectionSpec[] }): void {
+    const primary = args.selections[args.selections.length - 1];
+    editor.setStatus(`selections: ${args.selections.length}, primary ${primary.mode} ${primary.anchor}-${primary.position}`);
+};
+editor.on("selection_changed", "on_selection_changed");
+"#;
+    fs::write(plugins_dir.join("select_words.ts"), test_plugin).unwrap();
+
+    let fixture = TestFixture::new("test.txt", "alpha beta\ngamma delta\n").unwrap();
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(80, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&fixture.path).unwrap();
+    harness.render().unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Test: Select Words").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Select two words"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness
+        .wait_until(|h| {
+            h.editor().get_status_message().map(String::as_str)
+                == Some("selections: 2, primary block 12-22")
+        })
+        .unwrap();
+
+    let cursors = &harness.editor().active_state().cursors;
+    assert_eq!(cursors.count(), 2);
+    assert_eq!(cursors.primary().selection_range(), Some(12..22));
+    assert!(cursors.primary().has_block_selection());
+    let mut ranges = cursors.selections();
+    ranges.sort_by_key(|range| range.start);
+    assert_eq!(ranges, vec![0..5, 12..22]);
+
+    // Escape drops back to one cursor, which is a selection change too
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness
+        .wait_until(|h| {
+            h.editor()
+                .get_status_message()
+                .is_some_and(|status| status.starts_with("selections: 1,"))
+        })
+        .unwrap();
+}
@@ -0,0 +1,81 @@
+//! E2E tests for cross-plugin APIs and events
+//!
+//! One plugin exports an API and publishes its own events; another calls the
+//! API and subscribes to the events.
+
+use crate::common::harness::{copy_plugin_lib, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use tempfile::TempDir;
+
+const PROVIDER_PLUGIN: &str = r#"
+const editor = getEditor();
+
+let ticks = 0;
+
+editor.exportApi({
+    add(a: number, b: number): number {
+        return a + b;
+    },
+    async describe(): Promise<{ ticks: number }> {
+        return { ticks };
+    },
+});
+
+globalThis.provider_tick = function(): void {
+    ticks += 1;
+    editor.emitEvent("provider:tick", { ticks });
+};
+
+editor.registerCommand("Provider: Tick", "Publish a tick event", "provider_tick", null);
+"#;
+
+const CONSUMER_PLUGIN: &str = r#"
+const editor = getEditor();
+
+globalThis.consumer_on_tick = function(data: { ticks: number }): void {
+    editor.getPluginApi("provider").then(async (api) => {
+        const state = await api.describe() as { ticks: number };
+        editor.setStatus(`tick ${data.ticks}, provider has ${state.ticks}`);
+    });
+};
+editor.on("provider:tick", "consumer_on_tick");
+
+editor.getPluginApi("provider").then(async (api) => {
+    editor.setStatus(`sum ${await api.add(2, 3)}`);
+});
+"#;
+
+fn status(harness: &EditorTestHarness) -> Option<String> {
+    harness.editor().get_status_message().cloned()
+}
+
+#[test]
+fn test_plugins_call_exported_api_and_share_events() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    fs::write(plugins_dir.join("provider.ts"), PROVIDER_PLUGIN).unwrap();
+    fs::write(plugins_dir.join("consumer.ts"), CONSUMER_PLUGIN).unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("sum 5"))
+        .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text("Provider: Tick").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("tick 1, provider has 1"))
+        .unwrap();
+}
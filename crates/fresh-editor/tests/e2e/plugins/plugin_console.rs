@@ -0,0 +1,165 @@
+//! E2E tests for the plugin developer console
+//!
+//! The console evaluates code in a plugin's context, lists what each plugin
+//! registered and reloads a plugin when its source changes.
+
+use crate::common::harness::{copy_plugin_lib, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+const GREETER_PLUGIN: &str = r#"
+const editor = getEditor();
+
+globalThis.greeting = "hello";
+
+globalThis.greeter_say_hello = function(): void {
+    editor.setStatus(globalThis.greeting);
+};
+globalThis.greeter_on_save = function(): void {};
+
+editor.registerCommand("Greeter: Say Hello", "Show the greeting", "greeter_say_hello", null);
+editor.on("after_file_save", "greeter_on_save");
+editor.setStatus("greeter loaded v1");
+"#;
+
+fn setup() -> (TempDir, PathBuf, EditorTestHarness) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    let plugin_path = plugins_dir.join("greeter.ts");
+    fs::write(&plugin_path, GREETER_PLUGIN).unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 30, Default::default(), project_root)
+            .unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("greeter loaded v1"))
+        .unwrap();
+    (temp_dir, plugin_path, harness)
+}
+
+fn status(harness: &EditorTestHarness) -> Option<String> {
+    harness.editor().get_status_message().cloned()
+}
+
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_prompt().unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+}
+
+/// Open the console and choose the greeter plugin
+fn open_console(harness: &mut EditorTestHarness) {
+    run_command(harness, "Plugin Console");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Evaluate in plugin:"))
+        .unwrap();
+    harness.type_text("greeter").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("greeter> "))
+        .unwrap();
+}
+
+#[test]
+fn test_plugin_console_evaluates_in_plugin() {
+    let (_temp_dir, _plugin_path, mut harness) = setup();
+    open_console(&mut harness);
+
+    let content = harness.get_buffer_content().unwrap();
+    assert!(content.contains("evaluating in: greeter"), "{}", content);
+    assert!(
+        content.contains("commands: Greeter: Say Hello"),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains("hook after_file_save -> greeter_on_save (never called)"),
+        "{}",
+        content
+    );
+
+    harness.type_text("greeting + \" world\"").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| {
+            h.get_buffer_content()
+                .is_some_and(|c| c.contains("    \"hello world\""))
+        })
+        .unwrap();
+
+    // Errors are shown, and commands sent by snippets are listed
+    harness
+        .type_text("editor.setStatus(\"from console\")")
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("from console"))
+        .unwrap();
+    harness.type_text("missingName").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| {
+            h.get_buffer_content().is_some_and(|c| {
+                c.contains("    ! ") && c.contains("missingName") && c.contains("SetStatus")
+            })
+        })
+        .unwrap();
+
+    // An empty line leaves the REPL
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+}
+
+#[test]
+fn test_plugin_console_auto_reload() {
+    let (_temp_dir, plugin_path, mut harness) = setup();
+    open_console(&mut harness);
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+
+    run_command(&mut harness, "Plugin Console: Toggle Auto-Reload");
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("Auto-reload enabled for 'greeter'"))
+        .unwrap();
+    assert!(harness
+        .get_buffer_content()
+        .unwrap()
+        .contains("[auto-reload]"));
+
+    // Make sure the new file gets a different modification time
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    fs::write(
+        &plugin_path,
+        GREETER_PLUGIN.replace("greeter loaded v1", "greeter loaded v2"),
+    )
+    .unwrap();
+    harness
+        .wait_until(|h| {
+            h.get_buffer_content()
+                .is_some_and(|c| c.contains("[reloaded greeter]"))
+        })
+        .unwrap();
+    harness
+        .wait_until(|h| status(h).as_deref() == Some("greeter loaded v2"))
+        .unwrap();
+}
@@ -0,0 +1,104 @@
+//! E2E tests for plugin status bar items
+//!
+//! Plugins keep named items in the status bar, update their text, and get a
+//! hook when the user clicks them.
+
+use crate::common::harness::{copy_plugin_lib, EditorTestHarness};
+use std::fs;
+use tempfile::TempDir;
+
+const STATUS_ITEMS_PLUGIN: &str = r#"
+const editor = getEditor();
+
+editor.setStatusBarItem("branch", { text: "[branch main]", alignment: "left" });
+editor.setStatusBarItem("summary", { text: "tests: 3 ok", fg: "ui.status_error_indicator_fg", priority: 10 });
+editor.setStatusBarItem("hidden", { text: "" });
+
+globalThis.on_item_clicked = function(args: { plugin: string; id: string }): void {
+    editor.setStatusBarItemText(args.id, "tests: running");
+    editor.removeStatusBarItem("branch");
+    editor.setStatus(`clicked ${args.plugin}/${args.id}`);
+};
+
+editor.on("status_bar_item_clicked", "on_item_clicked");
+"#;
+
+/// Another plugin with a click handler, which must not see clicks on items
+/// it does not own
+const OTHER_ITEMS_PLUGIN: &str = r#"
+const editor = getEditor();
+
+editor.setStatusBarItem("other", { text: "[other idle]", alignment: "left" });
+
+globalThis.on_other_clicked = function(args: { plugin: string; id: string }): void {
+    editor.setStatusBarItemText("other", `[other saw ${args.id}]`);
+};
+
+editor.on("status_bar_item_clicked", "on_other_clicked");
+"#;
+
+fn setup() -> (TempDir, EditorTestHarness) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    fs::write(plugins_dir.join("status_items.ts"), STATUS_ITEMS_PLUGIN).unwrap();
+    fs::write(plugins_dir.join("other_items.ts"), OTHER_ITEMS_PLUGIN).unwrap();
+
+    let harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    (temp_dir, harness)
+}
+
+/// Screen position (col, row) of the first occurrence of `text`
+fn find_on_screen(harness: &EditorTestHarness, text: &str) -> Option<(u16, u16)> {
+    (0..harness.terminal_height()).find_map(|row| {
+        let line = harness.get_screen_row(row);
+        line.find(text)
+            .map(|byte_col| (line[..byte_col].chars().count() as u16, row as u16))
+    })
+}
+
+#[test]
+fn test_plugin_status_bar_items_shown_and_clickable() {
+    let (_temp_dir, mut harness) = setup();
+
+    harness
+        .wait_until(|h| {
+            let screen = h.screen_to_string();
+            screen.contains("[branch main]") && screen.contains("tests: 3 ok")
+        })
+        .unwrap();
+
+    // Left item comes before the right item, on the same status bar row
+    let (left_col, left_row) = find_on_screen(&harness, "[branch main]").unwrap();
+    let (right_col, right_row) = find_on_screen(&harness, "tests: 3 ok").unwrap();
+    assert_eq!(left_row, right_row);
+    assert!(left_col < right_col);
+
+    harness.mouse_click(right_col + 1, right_row).unwrap();
+    harness
+        .wait_until(|h| {
+            h.editor().get_status_message().map(String::as_str)
+                == Some("clicked status_items/summary")
+        })
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("tests: running"))
+        .unwrap();
+    harness.assert_screen_not_contains("[branch main]");
+
+    // Only the owning plugin is told about the click
+    harness.assert_screen_contains("[other idle]");
+    let (other_col, other_row) = find_on_screen(&harness, "[other idle]").unwrap();
+    harness.mouse_click(other_col + 1, other_row).unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("[other saw other]"))
+        .unwrap();
+    assert_eq!(
+        harness.editor().get_status_message().map(String::as_str),
+        Some("clicked status_items/summary")
+    );
+}
@@ -0,0 +1,142 @@
+//! E2E tests for the plugin terminal API
+//!
+//! Plugins open terminals running their own command, drive them with input,
+//! react to output patterns and process exit, and close them.
+//!
+//! NOTE: These tests need a working PTY and skip themselves otherwise.
+
+use crate::common::harness::{copy_plugin_lib, EditorTestHarness};
+use crossterm::event::{KeyCode, KeyModifiers};
+use portable_pty::{native_pty_system, PtySize};
+use std::fs;
+use tempfile::TempDir;
+
+const TERMINAL_PLUGIN: &str = r#"
+const editor = getEditor();
+
+editor.registerCommand("Test: Run REPL", "Drive a process in a terminal", "test_run_repl", null);
+editor.registerCommand("Test: Open And Close", "Open then close a terminal", "test_open_close", null);
+
+globalThis.test_run_repl = async function(): Promise<void> {
+    const term = await editor.createTerminal({
+        command: "sh",
+        args: ["-c", "echo READY-$GREETING; read x; echo got $x; read y"],
+        env: { GREETING: "hi" },
+        direction: "vertical",
+    });
+    editor.watchTerminalOutput(term.terminalId, "^READY-");
+    editor.watchTerminalOutput(term.terminalId, "^got ");
+};
+
+globalThis.on_terminal_output = async function(args: { terminal_id: number; pattern: string; line: string }): Promise<void> {
+    if (args.pattern === "^READY-") {
+        editor.sendTerminalInput(args.terminal_id, "hello\r");
+        return;
+    }
+    const text = await editor.getTerminalScrollback(args.terminal_id);
+    globalThis.seen = `${args.line}, scrollback ${text.includes("READY-hi") ? "ok" : "missing"}`;
+    editor.sendTerminalInput(args.terminal_id, "bye\r");
+};
+
+globalThis.on_terminal_exited = function(args: { terminal_id: number }): void {
+    editor.setStatus(`exited after: ${globalThis.seen}`);
+};
+
+globalThis.test_open_close = async function(): Promise<void> {
+    const term = await editor.createTerminal({ command: "sleep", args: ["30"], focus: false });
+    editor.closeTerminal(term.terminalId);
+    editor.setStatus(`closed terminal ${term.terminalId}`);
+};
+
+editor.on("terminal_output_matched", "on_terminal_output");
+editor.on("terminal_exited", "on_terminal_exited");
+"#;
+
+fn pty_available() -> bool {
+    let available = native_pty_system()
+        .openpty(PtySize {
+            rows: 1,
+            cols: 1,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .is_ok();
+    if !available {
+        eprintln!("Skipping terminal test: PTY not available in this environment");
+    }
+    available
+}
+
+fn setup() -> (TempDir, EditorTestHarness) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    copy_plugin_lib(&plugins_dir);
+    fs::write(plugins_dir.join("terminal_driver.ts"), TERMINAL_PLUGIN).unwrap();
+
+    let harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, Default::default(), project_root)
+            .unwrap();
+    (temp_dir, harness)
+}
+
+fn run_command(harness: &mut EditorTestHarness, name: &str, description: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains(description))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+}
+
+#[test]
+fn test_plugin_drives_terminal_process() {
+    if !pty_available() {
+        return;
+    }
+    let (_temp_dir, mut harness) = setup();
+    harness.render().unwrap();
+
+    run_command(
+        &mut harness,
+        "Test: Run REPL",
+        "Drive a process in a terminal",
+    );
+
+    harness
+        .wait_until(|h| {
+            h.editor().get_status_message().map(String::as_str)
+                == Some("exited after: got hello, scrollback ok")
+        })
+        .unwrap();
+    harness.assert_screen_contains("*Terminal 0*");
+}
+
+#[test]
+fn test_plugin_closes_terminal() {
+    if !pty_available() {
+        return;
+    }
+    let (_temp_dir, mut harness) = setup();
+    harness.render().unwrap();
+
+    run_command(
+        &mut harness,
+        "Test: Open And Close",
+        "Open then close a terminal",
+    );
+
+    harness
+        .wait_until(|h| {
+            h.editor().get_status_message().map(String::as_str) == Some("closed terminal 0")
+        })
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("*Terminal 0*");
+    assert_eq!(harness.editor().terminal_manager().count(), 0);
+}
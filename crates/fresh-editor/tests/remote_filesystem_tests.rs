@@ -9,7 +9,10 @@
 //! - RemoteFileSystem for file operations
 
 use fresh::model::filesystem::FileSystem;
-use fresh::services::remote::{spawn_local_agent, RemoteFileSystem};
+use fresh::services::remote::{
+    reconnect_local_agent, spawn_local_agent, ProcessSignal, ProcessSpawner, RemoteFileSystem,
+    RemoteProcessSpawner, CAP_PTY,
+};
 
 /// Creates a RemoteFileSystem using production code
 fn create_test_filesystem() -> Option<(RemoteFileSystem, tempfile::TempDir, tokio::runtime::Runtime)>
@@ -260,3 +263,220 @@ fn test_read_range_on_large_file() {
         "Read range content should match"
     );
 }
+
+#[test]
+fn test_remote_piped_process_roundtrip() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let Some(rt) = tokio::runtime::Runtime::new().ok() else {
+        eprintln!("Skipping test: could not create runtime");
+        return;
+    };
+    let Some(channel) = rt.block_on(spawn_local_agent()).ok() else {
+        eprintln!("Skipping test: could not spawn agent");
+        return;
+    };
+    let spawner = RemoteProcessSpawner::new(channel);
+
+    // Full-duplex round trip through the agent, as used by remote language servers
+    let output = rt.block_on(async {
+        let mut process = spawner
+            .spawn_piped("cat".to_string(), vec![], None)
+            .await
+            .unwrap();
+
+        process
+            .stdin
+            .write_all(b"Content-Length: 2\r\n\r\n{}")
+            .await
+            .unwrap();
+        process.stdin.flush().await.unwrap();
+        drop(process.stdin);
+
+        let mut output = String::new();
+        process.stdout.read_to_string(&mut output).await.unwrap();
+        output
+    });
+
+    assert_eq!(output, "Content-Length: 2\r\n\r\n{}");
+}
+
+#[test]
+fn test_remote_pty_process_signal() {
+    use tokio::io::AsyncReadExt;
+
+    let Some(rt) = tokio::runtime::Runtime::new().ok() else {
+        eprintln!("Skipping test: could not create runtime");
+        return;
+    };
+    let Some(channel) = rt.block_on(spawn_local_agent()).ok() else {
+        eprintln!("Skipping test: could not spawn agent");
+        return;
+    };
+    if !channel.has_capability(CAP_PTY) {
+        eprintln!("Skipping test: agent has no PTY support");
+        return;
+    }
+    let spawner = RemoteProcessSpawner::new(channel);
+
+    // Interrupting the foreground process of a remote terminal ends it
+    let output = rt.block_on(async {
+        let mut process = spawner
+            .spawn_pty(
+                "sh".to_string(),
+                vec!["-c".to_string(), "stty size; exec sleep 30".to_string()],
+                None,
+                24,
+                80,
+            )
+            .await
+            .unwrap();
+
+        let mut output = Vec::new();
+        let mut buf = [0u8; 256];
+        while !String::from_utf8_lossy(&output).contains("24 80") {
+            let n = process.stdout.read(&mut buf).await.unwrap();
+            assert!(n > 0, "process exited early");
+            output.extend_from_slice(&buf[..n]);
+        }
+
+        process
+            .guard
+            .signal(ProcessSignal::Interrupt)
+            .await
+            .unwrap();
+        // Output reaches EOF once the process is gone
+        process.stdout.read_to_end(&mut output).await.unwrap();
+        String::from_utf8_lossy(&output).to_string()
+    });
+
+    assert!(output.contains("24 80"), "output: {:?}", output);
+}
+
+#[test]
+fn test_remote_terminal_session() {
+    use fresh::services::terminal::{RemoteTerminalTarget, TerminalManager};
+    use std::sync::Arc;
+
+    let Some(rt) = tokio::runtime::Runtime::new().ok() else {
+        eprintln!("Skipping test: could not create runtime");
+        return;
+    };
+    let Some(channel) = rt.block_on(spawn_local_agent()).ok() else {
+        eprintln!("Skipping test: could not spawn agent");
+        return;
+    };
+    if !channel.has_capability(CAP_PTY) {
+        eprintln!("Skipping test: agent has no PTY support");
+        return;
+    }
+    let temp_dir = tempfile::tempdir().unwrap();
+    let backing_path = temp_dir.path().join("terminal.txt");
+
+    let mut manager = TerminalManager::new();
+    manager.set_remote_target(Some(RemoteTerminalTarget {
+        spawner: Arc::new(RemoteProcessSpawner::new(channel)),
+        runtime: rt.handle().clone(),
+    }));
+    let id = manager
+        .spawn(
+            80,
+            24,
+            Some(temp_dir.path().to_path_buf()),
+            None,
+            Some(backing_path.clone()),
+        )
+        .unwrap();
+
+    // The shell starts in the requested remote directory
+    let handle = manager.get_mut(id).unwrap();
+    handle.resize(100, 30);
+    handle.write(b"pwd; stty size; echo done-$((40+2))\n");
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let content = loop {
+        let content = handle.state.lock().unwrap().content_string();
+        if content.contains("done-42") || std::time::Instant::now() > deadline {
+            break content;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    };
+
+    assert!(content.contains("done-42"), "content: {}", content);
+    assert!(content.contains("30 100"), "content: {}", content);
+    let dir_name = temp_dir.path().file_name().unwrap().to_string_lossy();
+    assert!(content.contains(dir_name.as_ref()), "content: {}", content);
+    // Scrollback goes to the local backing file
+    assert!(backing_path.exists());
+
+    manager.close(id);
+}
+
+#[test]
+fn test_reconnect_after_agent_dies() {
+    let Some(rt) = tokio::runtime::Runtime::new().ok() else {
+        eprintln!("Skipping test: could not create runtime");
+        return;
+    };
+    let Some(channel) = rt.block_on(spawn_local_agent()).ok() else {
+        eprintln!("Skipping test: could not spawn agent");
+        return;
+    };
+    let temp_dir = tempfile::tempdir().unwrap();
+    let fs = RemoteFileSystem::new(channel.clone(), "test@localhost".to_string());
+    let spawner = RemoteProcessSpawner::new(channel.clone());
+
+    // Simulate the link dropping by killing the agent from underneath
+    let _ = rt.block_on(spawner.spawn(
+        "sh".to_string(),
+        vec!["-c".to_string(), "kill -9 $PPID".to_string()],
+        None,
+    ));
+    for _ in 0..100 {
+        if !fs.is_remote_connected() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert!(!fs.is_remote_connected(), "Channel should notice the drop");
+
+    let test_path = temp_dir.path().join("offline.txt");
+    assert!(fs.write_file(&test_path, b"lost").is_err());
+
+    // Reconnecting swaps in a fresh agent; existing handles keep working
+    rt.block_on(reconnect_local_agent(&channel)).unwrap();
+    assert!(fs.is_remote_connected());
+
+    fs.write_file(&test_path, b"after reconnect").unwrap();
+    assert_eq!(fs.read_file(&test_path).unwrap(), b"after reconnect");
+}
+
+#[test]
+fn test_remote_watch_reports_changes() {
+    let Some((fs, temp_dir, _rt)) = create_test_filesystem() else {
+        eprintln!("Skipping test: could not create test filesystem");
+        return;
+    };
+    assert!(fs.supports_watch());
+
+    let file = temp_dir.path().join("watched.txt");
+    std::fs::write(&file, "before").unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    fs.watch(&[file.clone(), dir.clone()]);
+    std::thread::sleep(std::time::Duration::from_millis(300));
+
+    // Changed by another tool on the "remote" host
+    std::fs::write(&file, "after, from elsewhere").unwrap();
+    std::fs::write(temp_dir.path().join("new.txt"), "").unwrap();
+
+    let mut changed = Vec::new();
+    for _ in 0..100 {
+        changed.extend(fs.take_changed_paths());
+        if changed.contains(&file) && changed.contains(&dir) {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert!(changed.contains(&file), "changed: {:?}", changed);
+    assert!(changed.contains(&dir), "changed: {:?}", changed);
+}
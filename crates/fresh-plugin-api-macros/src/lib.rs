@@ -624,6 +624,38 @@ interface ProcessHandle<T> extends PromiseLike<T> {
   kill(): Promise<boolean>;
 }
 
+/** Handle for a process started with `spawnInteractiveProcess` */
+interface InteractiveProcessHandle extends ProcessHandle<BackgroundProcessResult> {
+  /** Process ID, as reported by the process output hooks */
+  readonly processId: number;
+  /** Write text to the process's stdin. Returns false if the editor is gone */
+  write(data: string): boolean;
+  /** Close the process's stdin so it sees end of input */
+  closeStdin(): boolean;
+}
+
+/** Subscription returned by `watchPath`; changes arrive through the `path_changed` hook */
+interface PathWatch {
+  /** Watch ID, as reported in `path_changed` events */
+  readonly watchId: number;
+  /** Stop watching. Returns false if the editor is gone */
+  close(): boolean;
+}
+
+/** API another plugin exported with `exportApi`; every method returns a promise of its result */
+interface PluginApi {
+  [method: string]: (...args: unknown[]) => Promise<unknown>;
+}
+
+/** Rejection of `applyEdits` when the buffer changed since `expectedVersion` */
+interface StaleVersionError extends Error {
+  name: "StaleVersionError";
+  bufferId: number;
+  expectedVersion: number;
+  /** The buffer's version now; re-read the text and retry with it */
+  currentVersion: number;
+}
+
 /** Buffer identifier */
 type BufferId = number;
 
@@ -657,6 +689,9 @@ const BUILTIN_TS_TYPES: &[&str] = &[
     "Array",
     "Promise",
     "ProcessHandle",
+    "InteractiveProcessHandle",
+    "PathWatch",
+    "PluginApi",
     "PromiseLike",
     "BufferId",
     "SplitId", // Defined in preamble
@@ -86,27 +86,33 @@
 //!
 //! This validates TypeScript syntax and writes `plugins/lib/fresh.d.ts`.
 
+use crate::bus::{BusMessage, PluginBus};
+use crate::capabilities::{CapabilityScope, ScopeRoots};
+use crate::storage::{PluginStorage, StorageScope};
 use anyhow::{anyhow, Result};
 use fresh_core::api::{
     ActionSpec, BufferInfo, CompositeHunk, CreateCompositeBufferOptions, EditorStateSnapshot,
-    JsCallbackId, LanguagePackConfig, LspServerPackConfig, OverlayOptions, PluginCommand,
-    PluginResponse,
+    JsCallbackId, LanguageFeature, LanguageFeatureRequest, LanguagePackConfig, LspServerPackConfig,
+    OverlayOptions, PluginCommand, PluginResponse,
 };
 use fresh_core::command::Command;
+use fresh_core::config::{PluginCapabilities, PluginRuntimeConfig};
+use fresh_core::hooks::{replaceable_hook_fields, HookOutcome};
 use fresh_core::overlay::OverlayNamespace;
 use fresh_core::text_property::TextPropertyEntry;
-use fresh_core::{BufferId, SplitId};
+use fresh_core::{BufferId, SplitId, TerminalId};
 use fresh_parser_js::{
     bundle_module, has_es_imports, has_es_module_syntax, strip_imports_and_exports,
     transpile_typescript,
 };
 use fresh_plugin_api_macros::{plugin_api, plugin_api_impl};
 use rquickjs::{Context, Function, Object, Runtime, Value};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::{mpsc, Arc, RwLock};
+use std::time::{Duration, Instant};
 
 /// Convert a QuickJS Value to serde_json::Value
 fn js_to_json(ctx: &rquickjs::Ctx<'_>, val: Value<'_>) -> serde_json::Value {
@@ -464,6 +470,166 @@ pub struct PluginHandler {
     pub handler_name: String,
 }
 
+/// A registered hook handler and how long its calls took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookHandlerInfo {
+    pub hook_name: String,
+    pub plugin_name: String,
+    pub handler_name: String,
+    /// Number of times the handler ran
+    pub calls: u64,
+    /// Time spent in the handler over all calls
+    pub total_time: Duration,
+    /// Longest single call
+    pub max_time: Duration,
+}
+
+/// Call statistics of one hook handler
+#[derive(Debug, Clone, Copy, Default)]
+struct HookTiming {
+    calls: u64,
+    total_time: Duration,
+    max_time: Duration,
+}
+
+/// Turns a value into the text the developer console shows for it
+const DESCRIBE_VALUE_JS: &str = r#"
+(function(v) {
+    if (v === undefined) return "undefined";
+    if (typeof v === "function") return "[Function " + (v.name || "anonymous") + "]";
+    if (typeof v === "string") return JSON.stringify(v);
+    if (typeof v !== "object" || v === null || v instanceof Error) return String(v);
+    try {
+        const json = JSON.stringify(v, null, 2);
+        return json === undefined ? String(v) : json;
+    } catch (e) {
+        return String(v);
+    }
+})
+"#;
+
+/// A plugin handler providing a language feature for one language
+#[derive(Debug, Clone)]
+pub struct LanguageProvider {
+    pub plugin_name: String,
+    pub feature: LanguageFeature,
+    pub language: String,
+    pub handler_name: String,
+}
+
+/// Resource limits enforced on plugin code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PluginLimits {
+    /// Longest a single call into plugin code (loading it, a hook handler, an
+    /// action, a callback) may run before it is interrupted
+    pub call_timeout: Option<Duration>,
+    /// Heap limit in bytes, shared by all plugins
+    pub memory_limit: Option<usize>,
+    /// Size limit in bytes for each plugin's stored data in one scope
+    pub storage_quota: Option<usize>,
+    /// Longest the editor waits for the handlers of a cancellable hook; with
+    /// none, the editor does not run those hooks
+    pub hook_timeout: Option<Duration>,
+}
+
+impl From<&PluginRuntimeConfig> for PluginLimits {
+    fn from(config: &PluginRuntimeConfig) -> Self {
+        Self {
+            call_timeout: (config.call_timeout_ms > 0)
+                .then(|| Duration::from_millis(config.call_timeout_ms)),
+            memory_limit: (config.memory_limit_mb > 0)
+                .then(|| config.memory_limit_mb as usize * 1024 * 1024),
+            storage_quota: (config.storage_quota_kb > 0)
+                .then(|| config.storage_quota_kb as usize * 1024),
+            hook_timeout: (config.hook_timeout_ms > 0)
+                .then(|| Duration::from_millis(config.hook_timeout_ms)),
+        }
+    }
+}
+
+impl Default for PluginLimits {
+    fn default() -> Self {
+        Self::from(&PluginRuntimeConfig::default())
+    }
+}
+
+/// A call into a plugin's code: the plugin, and when the call must finish
+type WatchedCall = (String, Option<Instant>);
+
+/// Tracks which plugin's code is running so runaway calls can be interrupted
+///
+/// QuickJS polls the runtime's interrupt handler while executing; the handler
+/// asks the watchdog whether the current call has run out of time.
+#[derive(Default)]
+struct Watchdog {
+    /// Time budget for each call
+    call_timeout: Cell<Option<Duration>>,
+    /// Plugin whose code is running, and when its call must finish
+    current: RefCell<Option<WatchedCall>>,
+    /// Plugins that exceeded a limit, with the reason, awaiting disabling
+    tripped: RefCell<Vec<(String, String)>>,
+}
+
+impl Watchdog {
+    /// Mark the start of a call into `plugin_name`'s code
+    ///
+    /// Returns the call it is nested in (e.g. a hook run from an action), to
+    /// be handed back to `end`.
+    fn begin(&self, plugin_name: &str) -> Option<WatchedCall> {
+        let deadline = self
+            .call_timeout
+            .get()
+            .map(|timeout| Instant::now() + timeout);
+        self.current
+            .replace(Some((plugin_name.to_string(), deadline)))
+    }
+
+    /// Mark the end of the current call, resuming the watch of the call it
+    /// was nested in
+    fn end(&self, outer: Option<WatchedCall>) {
+        *self.current.borrow_mut() = outer;
+    }
+
+    fn is_tripped(&self, plugin_name: &str) -> bool {
+        self.tripped
+            .borrow()
+            .iter()
+            .any(|(name, _)| name == plugin_name)
+    }
+
+    fn trip(&self, plugin_name: &str, reason: String) {
+        if !self.is_tripped(plugin_name) {
+            tracing::warn!("Plugin '{}' {}", plugin_name, reason);
+            self.tripped
+                .borrow_mut()
+                .push((plugin_name.to_string(), reason));
+        }
+    }
+
+    /// Interrupt handler: true aborts the running JS with an uncatchable error
+    fn should_interrupt(&self) -> bool {
+        let current = self.current.borrow();
+        let Some((plugin_name, deadline)) = current.as_ref() else {
+            return false;
+        };
+        // Code of an already-tripped plugin is cut short immediately
+        if self.is_tripped(plugin_name) {
+            return true;
+        }
+        match deadline {
+            Some(deadline) if Instant::now() >= *deadline => {
+                let timeout = self.call_timeout.get().unwrap_or_default();
+                self.trip(
+                    plugin_name,
+                    format!("exceeded its {} ms time budget", timeout.as_millis()),
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 /// JavaScript-exposed Editor API using rquickjs class system
 /// This allows proper lifetime handling for methods returning JS values
 #[derive(rquickjs::class::Trace, rquickjs::JsLifetime)]
@@ -483,9 +649,100 @@ pub struct JsEditorApi {
     callback_contexts: Rc<RefCell<HashMap<u64, String>>>,
     #[qjs(skip_trace)]
     services: Arc<dyn fresh_core::services::PluginServiceBridge>,
+    #[qjs(skip_trace)]
+    capabilities: Rc<RefCell<HashMap<String, CapabilityScope>>>,
+    #[qjs(skip_trace)]
+    storage: Rc<RefCell<PluginStorage>>,
+    #[qjs(skip_trace)]
+    language_providers: Rc<RefCell<Vec<LanguageProvider>>>,
+    #[qjs(skip_trace)]
+    bus: Rc<RefCell<PluginBus>>,
     pub plugin_name: String,
 }
 
+impl JsEditorApi {
+    /// Check a guarded API call against the plugin's capabilities
+    ///
+    /// Unrestricted plugins always pass; for restricted ones a denied call
+    /// throws, with `action` completing "plugin 'x' may not ...".
+    fn require(
+        &self,
+        ctx: &rquickjs::Ctx<'_>,
+        allowed: impl FnOnce(&CapabilityScope) -> bool,
+        action: impl FnOnce() -> String,
+    ) -> rquickjs::Result<()> {
+        match self.capabilities.borrow().get(&self.plugin_name) {
+            Some(scope) if !allowed(scope) => {
+                let message = format!(
+                    "Permission denied: plugin '{}' may not {}",
+                    self.plugin_name,
+                    action()
+                );
+                tracing::warn!("{}", message);
+                Err(rquickjs::Exception::throw_message(ctx, &message))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn require_read(&self, ctx: &rquickjs::Ctx<'_>, path: &str) -> rquickjs::Result<()> {
+        self.require(
+            ctx,
+            |scope| scope.can_read(Path::new(path)),
+            || format!("read '{}'", path),
+        )
+    }
+
+    fn require_write(&self, ctx: &rquickjs::Ctx<'_>, path: &str) -> rquickjs::Result<()> {
+        self.require(
+            ctx,
+            |scope| scope.can_write(Path::new(path)),
+            || format!("write '{}'", path),
+        )
+    }
+
+    fn require_spawn(&self, ctx: &rquickjs::Ctx<'_>, command: &str) -> rquickjs::Result<()> {
+        self.require(
+            ctx,
+            |scope| scope.can_spawn(command),
+            || format!("run '{}'", command),
+        )
+    }
+
+    fn storage_scope(
+        &self,
+        ctx: &rquickjs::Ctx<'_>,
+        scope: rquickjs::function::Opt<String>,
+    ) -> rquickjs::Result<StorageScope> {
+        StorageScope::parse(scope.0.as_deref())
+            .map_err(|e| rquickjs::Exception::throw_message(ctx, &e.to_string()))
+    }
+
+    fn storage_workspace(&self) -> PathBuf {
+        self.state_snapshot
+            .read()
+            .map(|s| s.working_dir.clone())
+            .unwrap_or_default()
+    }
+
+    /// Only unrestricted plugins may manage other plugins or run editor
+    /// actions, whose effects no capability covers
+    fn require_unrestricted(&self, ctx: &rquickjs::Ctx<'_>, action: &str) -> rquickjs::Result<()> {
+        self.require(ctx, |_| false, || action.to_string())
+    }
+
+    /// Allocate a request id for an async call, owned by this plugin
+    fn next_callback_id(&self) -> u64 {
+        let mut id_ref = self.next_request_id.borrow_mut();
+        let id = *id_ref;
+        *id_ref += 1;
+        self.callback_contexts
+            .borrow_mut()
+            .insert(id, self.plugin_name.clone());
+        id
+    }
+}
+
 #[plugin_api_impl]
 #[rquickjs::methods(rename_all = "camelCase")]
 impl JsEditorApi {
@@ -545,18 +802,71 @@ impl JsEditorApi {
             .send(PluginCommand::SetStatus { message: msg });
     }
 
+    /// Add or replace a persistent status bar item
+    ///
+    /// `id` is scoped to the calling plugin. Clicking the item fires the
+    /// `status_bar_item_clicked` hook of this plugin only, with `{ plugin, id }`.
+    pub fn set_status_bar_item(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        id: String,
+        options: fresh_core::api::StatusBarItemOptions,
+    ) -> rquickjs::Result<bool> {
+        if let Some(alignment) = options.alignment.as_deref() {
+            if alignment != "left" && alignment != "right" {
+                return Err(rquickjs::Exception::throw_message(
+                    &ctx,
+                    &format!("Unknown status bar alignment '{}'", alignment),
+                ));
+            }
+        }
+        Ok(self
+            .command_sender
+            .send(PluginCommand::SetStatusBarItem {
+                plugin_name: self.plugin_name.clone(),
+                id,
+                options,
+            })
+            .is_ok())
+    }
+
+    /// Change the text of a status bar item, keeping its colours and position
+    pub fn set_status_bar_item_text(&self, id: String, text: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::SetStatusBarItemText {
+                plugin_name: self.plugin_name.clone(),
+                id,
+                text,
+            })
+            .is_ok()
+    }
+
+    /// Remove a status bar item
+    pub fn remove_status_bar_item(&self, id: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::RemoveStatusBarItem {
+                plugin_name: self.plugin_name.clone(),
+                id,
+            })
+            .is_ok()
+    }
+
     // === Clipboard ===
 
-    pub fn copy_to_clipboard(&self, text: String) {
-        let _ = self
-            .command_sender
-            .send(PluginCommand::SetClipboard { text });
+    pub fn copy_to_clipboard(&self, ctx: rquickjs::Ctx<'_>, text: String) -> rquickjs::Result<()> {
+        self.set_clipboard(ctx, text)
     }
 
-    pub fn set_clipboard(&self, text: String) {
+    pub fn set_clipboard(&self, ctx: rquickjs::Ctx<'_>, text: String) -> rquickjs::Result<()> {
+        self.require(
+            &ctx,
+            |scope| scope.can_set_clipboard(),
+            || "set the clipboard".to_string(),
+        )?;
         let _ = self
             .command_sender
             .send(PluginCommand::SetClipboard { text });
+        Ok(())
     }
 
     // === Command Registration ===
@@ -628,10 +938,16 @@ impl JsEditorApi {
     }
 
     /// Execute a built-in action
-    pub fn execute_action(&self, action_name: String) -> bool {
-        self.command_sender
+    pub fn execute_action(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        action_name: String,
+    ) -> rquickjs::Result<bool> {
+        self.require_unrestricted(&ctx, "run editor actions")?;
+        Ok(self
+            .command_sender
             .send(PluginCommand::ExecuteAction { action_name })
-            .is_ok()
+            .is_ok())
     }
 
     // === Translation ===
@@ -719,13 +1035,20 @@ impl JsEditorApi {
 
     /// Save a buffer to a specific file path
     /// Used by :w filename to save unnamed buffers or save-as
-    pub fn save_buffer_to_path(&self, buffer_id: u32, path: String) -> bool {
-        self.command_sender
+    pub fn save_buffer_to_path(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        buffer_id: u32,
+        path: String,
+    ) -> rquickjs::Result<bool> {
+        self.require_write(&ctx, &path)?;
+        Ok(self
+            .command_sender
             .send(PluginCommand::SaveBufferToPath {
                 buffer_id: BufferId(buffer_id as usize),
                 path: std::path::PathBuf::from(path),
             })
-            .is_ok()
+            .is_ok())
     }
 
     /// Get buffer info by ID
@@ -886,6 +1209,42 @@ impl JsEditorApi {
             .is_ok()
     }
 
+    /// Replace several ranges of a buffer as a single undo step (async)
+    ///
+    /// Offsets refer to the buffer text at `expectedVersion` (see
+    /// `BufferInfo.version`) and ranges must not overlap. Resolves with the
+    /// buffer's new version; if the buffer changed since `expectedVersion`
+    /// nothing is applied and the promise rejects with a `StaleVersionError`.
+    #[plugin_api(async_promise, js_name = "applyEdits", ts_return = "number")]
+    #[qjs(rename = "_applyEditsStart")]
+    pub fn apply_edits_start(
+        &self,
+        _ctx: rquickjs::Ctx<'_>,
+        buffer_id: u32,
+        expected_version: u64,
+        edits: Vec<fresh_core::api::BufferEdit>,
+        label: rquickjs::function::Opt<String>,
+    ) -> u64 {
+        let id = {
+            let mut id_ref = self.next_request_id.borrow_mut();
+            let id = *id_ref;
+            *id_ref += 1;
+            // Record context for this callback
+            self.callback_contexts
+                .borrow_mut()
+                .insert(id, self.plugin_name.clone());
+            id
+        };
+        let _ = self.command_sender.send(PluginCommand::ApplyEdits {
+            buffer_id: BufferId(buffer_id as usize),
+            expected_version,
+            edits,
+            label: label.0,
+            callback_id: JsCallbackId::new(id),
+        });
+        id
+    }
+
     /// Insert text at cursor position in active buffer
     pub fn insert_at_cursor(&self, text: String) -> bool {
         self.command_sender
@@ -896,26 +1255,43 @@ impl JsEditorApi {
     // === File Operations ===
 
     /// Open a file, optionally at a specific line/column
-    pub fn open_file(&self, path: String, line: Option<u32>, column: Option<u32>) -> bool {
-        self.command_sender
+    pub fn open_file(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        path: String,
+        line: Option<u32>,
+        column: Option<u32>,
+    ) -> rquickjs::Result<bool> {
+        self.require_read(&ctx, &path)?;
+        Ok(self
+            .command_sender
             .send(PluginCommand::OpenFileAtLocation {
                 path: PathBuf::from(path),
                 line: line.map(|l| l as usize),
                 column: column.map(|c| c as usize),
             })
-            .is_ok()
+            .is_ok())
     }
 
     /// Open a file in a specific split
-    pub fn open_file_in_split(&self, split_id: u32, path: String, line: u32, column: u32) -> bool {
-        self.command_sender
+    pub fn open_file_in_split(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        split_id: u32,
+        path: String,
+        line: u32,
+        column: u32,
+    ) -> rquickjs::Result<bool> {
+        self.require_read(&ctx, &path)?;
+        Ok(self
+            .command_sender
             .send(PluginCommand::OpenFileInSplit {
                 split_id: split_id as usize,
                 path: PathBuf::from(path),
                 line: Some(line as usize),
                 column: Some(column as usize),
             })
-            .is_ok()
+            .is_ok())
     }
 
     /// Show a buffer in the current split
@@ -960,8 +1336,17 @@ impl JsEditorApi {
     // === Environment ===
 
     /// Get an environment variable
-    pub fn get_env(&self, name: String) -> Option<String> {
-        std::env::var(&name).ok()
+    pub fn get_env(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        name: String,
+    ) -> rquickjs::Result<Option<String>> {
+        self.require(
+            &ctx,
+            |scope| scope.can_read_env(&name),
+            || format!("read environment variable '{}'", name),
+        )?;
+        Ok(std::env::var(&name).ok())
     }
 
     /// Get current working directory
@@ -972,6 +1357,96 @@ impl JsEditorApi {
             .unwrap_or_else(|_| ".".to_string())
     }
 
+    // === Storage ===
+
+    /// Get a value this plugin stored, or `undefined` if the key is not set.
+    /// `scope` is "global" (the default) or "workspace" (the current working directory)
+    pub fn storage_get<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        key: String,
+        scope: rquickjs::function::Opt<String>,
+    ) -> rquickjs::Result<Value<'js>> {
+        let scope = self.storage_scope(&ctx, scope)?;
+        let value = self.storage.borrow_mut().get(
+            &self.plugin_name,
+            scope,
+            &self.storage_workspace(),
+            &key,
+        );
+        match value {
+            Some(value) => rquickjs_serde::to_value(ctx, &value)
+                .map_err(|e| rquickjs::Error::new_from_js_message("serialize", "", &e.to_string())),
+            None => Ok(Value::new_undefined(ctx)),
+        }
+    }
+
+    /// Store a JSON-serializable value for this plugin, persisted across runs.
+    /// Throws if the write would take the scope past the storage quota
+    pub fn storage_set<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        key: String,
+        value: Value<'js>,
+        scope: rquickjs::function::Opt<String>,
+    ) -> rquickjs::Result<()> {
+        let scope = self.storage_scope(&ctx, scope)?;
+        let value = js_to_json(&ctx, value);
+        self.storage
+            .borrow_mut()
+            .set(
+                &self.plugin_name,
+                scope,
+                &self.storage_workspace(),
+                &key,
+                value,
+            )
+            .map_err(|e| rquickjs::Exception::throw_message(&ctx, &e.to_string()))
+    }
+
+    /// Remove a stored key; returns true if it was set
+    pub fn storage_delete(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        key: String,
+        scope: rquickjs::function::Opt<String>,
+    ) -> rquickjs::Result<bool> {
+        let scope = self.storage_scope(&ctx, scope)?;
+        self.storage
+            .borrow_mut()
+            .delete(&self.plugin_name, scope, &self.storage_workspace(), &key)
+            .map_err(|e| rquickjs::Exception::throw_message(&ctx, &e.to_string()))
+    }
+
+    /// List the keys this plugin has stored in a scope
+    pub fn storage_keys(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        scope: rquickjs::function::Opt<String>,
+    ) -> rquickjs::Result<Vec<String>> {
+        let scope = self.storage_scope(&ctx, scope)?;
+        Ok(self
+            .storage
+            .borrow_mut()
+            .keys(&self.plugin_name, scope, &self.storage_workspace()))
+    }
+
+    /// Delete everything a plugin has stored (used when uninstalling it)
+    pub fn clear_plugin_storage(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        name: String,
+    ) -> rquickjs::Result<bool> {
+        self.require_unrestricted(&ctx, "manage plugins")?;
+        match self.storage.borrow_mut().remove_plugin(&name) {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                tracing::warn!("{}", e);
+                Ok(false)
+            }
+        }
+    }
+
     // === Path Operations ===
 
     /// Join path components (variadic - accepts multiple string arguments)
@@ -1055,18 +1530,30 @@ impl JsEditorApi {
     // === File System ===
 
     /// Check if file exists
-    pub fn file_exists(&self, path: String) -> bool {
-        Path::new(&path).exists()
+    pub fn file_exists(&self, ctx: rquickjs::Ctx<'_>, path: String) -> rquickjs::Result<bool> {
+        self.require_read(&ctx, &path)?;
+        Ok(Path::new(&path).exists())
     }
 
     /// Read file contents
-    pub fn read_file(&self, path: String) -> Option<String> {
-        std::fs::read_to_string(&path).ok()
+    pub fn read_file(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        path: String,
+    ) -> rquickjs::Result<Option<String>> {
+        self.require_read(&ctx, &path)?;
+        Ok(std::fs::read_to_string(&path).ok())
     }
 
     /// Write file contents
-    pub fn write_file(&self, path: String, content: String) -> bool {
-        std::fs::write(&path, content).is_ok()
+    pub fn write_file(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        path: String,
+        content: String,
+    ) -> rquickjs::Result<bool> {
+        self.require_write(&ctx, &path)?;
+        Ok(std::fs::write(&path, content).is_ok())
     }
 
     /// Read directory contents (returns array of {name, is_file, is_dir})
@@ -1078,6 +1565,7 @@ impl JsEditorApi {
     ) -> rquickjs::Result<Value<'js>> {
         use fresh_core::api::DirEntry;
 
+        self.require_read(&ctx, &path)?;
         let entries: Vec<DirEntry> = match std::fs::read_dir(&path) {
             Ok(entries) => entries
                 .filter_map(|e| e.ok())
@@ -1100,46 +1588,204 @@ impl JsEditorApi {
             .map_err(|e| rquickjs::Error::new_from_js_message("serialize", "", &e.to_string()))
     }
 
-    // === Config ===
-
-    /// Get current config as JS object
-    pub fn get_config<'js>(&self, ctx: rquickjs::Ctx<'js>) -> rquickjs::Result<Value<'js>> {
-        let config: serde_json::Value = self
-            .state_snapshot
-            .read()
-            .map(|s| s.config.clone())
-            .unwrap_or_else(|_| serde_json::json!({}));
-
-        rquickjs_serde::to_value(ctx, &config)
-            .map_err(|e| rquickjs::Error::new_from_js_message("serialize", "", &e.to_string()))
+    /// Watch a file or directory for changes
+    ///
+    /// Debounced batches of `{path, kind}` events ("create", "modify" or
+    /// "delete") arrive through this plugin's `path_changed` handlers as
+    /// `{plugin, watch_id, events, error}`. If the watch cannot start (e.g. an
+    /// invalid glob), a single event carries the `error`. Relative paths are
+    /// resolved against the working directory. The watch ends when the
+    /// plugin is unloaded.
+    #[plugin_api(js_name = "watchPath", ts_return = "PathWatch")]
+    #[qjs(rename = "_watchPathStart")]
+    pub fn watch_path_start(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        path: String,
+        opts: rquickjs::function::Opt<fresh_core::api::WatchPathOptions>,
+    ) -> rquickjs::Result<u64> {
+        let path = self.storage_workspace().join(path);
+        let path = path.to_string_lossy().into_owned();
+        self.require_read(&ctx, &path)?;
+        let watch_id = {
+            let mut id_ref = self.next_request_id.borrow_mut();
+            let id = *id_ref;
+            *id_ref += 1;
+            id
+        };
+        let _ = self.command_sender.send(PluginCommand::WatchPath {
+            plugin_name: self.plugin_name.clone(),
+            watch_id,
+            path,
+            options: opts.0.unwrap_or_default(),
+        });
+        Ok(watch_id)
     }
 
-    /// Get user config as JS object
-    pub fn get_user_config<'js>(&self, ctx: rquickjs::Ctx<'js>) -> rquickjs::Result<Value<'js>> {
-        let config: serde_json::Value = self
-            .state_snapshot
-            .read()
-            .map(|s| s.user_config.clone())
-            .unwrap_or_else(|_| serde_json::json!({}));
-
-        rquickjs_serde::to_value(ctx, &config)
-            .map_err(|e| rquickjs::Error::new_from_js_message("serialize", "", &e.to_string()))
+    /// Stop a watch started with `watchPath`
+    pub fn unwatch_path(&self, watch_id: u64) -> bool {
+        self.command_sender
+            .send(PluginCommand::UnwatchPath {
+                plugin_name: self.plugin_name.clone(),
+                watch_id,
+            })
+            .is_ok()
     }
 
-    /// Reload configuration from file
-    pub fn reload_config(&self) {
-        let _ = self.command_sender.send(PluginCommand::ReloadConfig);
-    }
+    // === Cross-plugin APIs and events ===
 
-    /// Reload theme registry from disk
-    /// Call this after installing theme packages or saving new themes
-    pub fn reload_themes(&self) {
-        let _ = self.command_sender.send(PluginCommand::ReloadThemes);
+    /// Export an API object for other plugins, under this plugin's name
+    ///
+    /// Other plugins get it with `getPluginApi`. Its functions run in this
+    /// plugin, with this plugin's capabilities; arguments and results are
+    /// copied as JSON. Exporting again replaces the previous API.
+    pub fn export_api<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        api: rquickjs::Object<'js>,
+    ) -> rquickjs::Result<()> {
+        let mut methods = Vec::new();
+        for key in api.keys::<String>() {
+            let key = key?;
+            if api.get::<_, Value>(&key)?.is_function() {
+                methods.push(key);
+            }
+        }
+        ctx.globals().set("_exportedApi", api)?;
+        self.bus.borrow_mut().export(&self.plugin_name, methods);
+        Ok(())
     }
 
-    /// Register a TextMate grammar file for a language
-    /// The grammar will be pending until reload_grammars() is called
-    pub fn register_grammar(
+    /// Get the API another plugin exported with `exportApi`
+    ///
+    /// Waits until the plugin has exported it; fails if the plugin is not
+    /// loaded once the editor has started, or when it is unloaded. Every
+    /// method of the returned object returns a promise of its result.
+    #[plugin_api(async_promise, js_name = "getPluginApi", ts_return = "PluginApi")]
+    #[qjs(rename = "_getPluginApiStart")]
+    pub fn get_plugin_api_start(&self, _ctx: rquickjs::Ctx<'_>, name: String) -> u64 {
+        let id = {
+            let mut id_ref = self.next_request_id.borrow_mut();
+            let id = *id_ref;
+            *id_ref += 1;
+            self.callback_contexts
+                .borrow_mut()
+                .insert(id, self.plugin_name.clone());
+            id
+        };
+        self.bus.borrow_mut().request_api(&name, id);
+        id
+    }
+
+    /// Call a method of another plugin's exported API (used by `getPluginApi`)
+    #[qjs(rename = "_callPluginApiStart")]
+    pub fn call_plugin_api_start<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        name: String,
+        method: String,
+        args: Value<'js>,
+    ) -> u64 {
+        let id = {
+            let mut id_ref = self.next_request_id.borrow_mut();
+            let id = *id_ref;
+            *id_ref += 1;
+            self.callback_contexts
+                .borrow_mut()
+                .insert(id, self.plugin_name.clone());
+            id
+        };
+        let args = js_to_json(&ctx, args);
+        self.bus.borrow_mut().call(id, &name, &method, args);
+        id
+    }
+
+    /// Answer a call to this plugin's exported API
+    #[qjs(rename = "_replyPluginApi")]
+    pub fn reply_plugin_api<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        callback_id: u64,
+        result: Value<'js>,
+        error: Option<String>,
+    ) {
+        let result = match error {
+            Some(error) => Err(error),
+            None => Ok(js_to_json(&ctx, result)),
+        };
+        self.bus
+            .borrow_mut()
+            .finish_call(&self.plugin_name, callback_id, result);
+    }
+
+    /// Publish a plugin-defined event to the handlers registered with `on`
+    ///
+    /// The name must be namespaced, like "git:status_changed", so it cannot
+    /// be mistaken for an editor hook. Handlers get `data` (copied as JSON),
+    /// after the current call into plugin code returns.
+    pub fn emit_event<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        name: String,
+        data: rquickjs::function::Opt<Value<'js>>,
+    ) -> rquickjs::Result<()> {
+        if !crate::bus::is_plugin_event_name(&name) {
+            return Err(rquickjs::Exception::throw_message(
+                &ctx,
+                &format!(
+                    "Event name '{}' must be namespaced, like 'my_plugin:changed'",
+                    name
+                ),
+            ));
+        }
+        let data = data
+            .0
+            .map(|data| js_to_json(&ctx, data))
+            .unwrap_or(serde_json::Value::Null);
+        self.bus.borrow_mut().emit(&name, data);
+        Ok(())
+    }
+
+    // === Config ===
+
+    /// Get current config as JS object
+    pub fn get_config<'js>(&self, ctx: rquickjs::Ctx<'js>) -> rquickjs::Result<Value<'js>> {
+        let config: serde_json::Value = self
+            .state_snapshot
+            .read()
+            .map(|s| s.config.clone())
+            .unwrap_or_else(|_| serde_json::json!({}));
+
+        rquickjs_serde::to_value(ctx, &config)
+            .map_err(|e| rquickjs::Error::new_from_js_message("serialize", "", &e.to_string()))
+    }
+
+    /// Get user config as JS object
+    pub fn get_user_config<'js>(&self, ctx: rquickjs::Ctx<'js>) -> rquickjs::Result<Value<'js>> {
+        let config: serde_json::Value = self
+            .state_snapshot
+            .read()
+            .map(|s| s.user_config.clone())
+            .unwrap_or_else(|_| serde_json::json!({}));
+
+        rquickjs_serde::to_value(ctx, &config)
+            .map_err(|e| rquickjs::Error::new_from_js_message("serialize", "", &e.to_string()))
+    }
+
+    /// Reload configuration from file
+    pub fn reload_config(&self) {
+        let _ = self.command_sender.send(PluginCommand::ReloadConfig);
+    }
+
+    /// Reload theme registry from disk
+    /// Call this after installing theme packages or saving new themes
+    pub fn reload_themes(&self) {
+        let _ = self.command_sender.send(PluginCommand::ReloadThemes);
+    }
+
+    /// Register a TextMate grammar file for a language
+    /// The grammar will be pending until reload_grammars() is called
+    pub fn register_grammar(
         &self,
         language: String,
         grammar_path: String,
@@ -1155,16 +1801,111 @@ impl JsEditorApi {
     }
 
     /// Register language configuration (comment prefix, indentation, formatter)
-    pub fn register_language_config(&self, language: String, config: LanguagePackConfig) -> bool {
-        self.command_sender
+    pub fn register_language_config(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        language: String,
+        config: LanguagePackConfig,
+    ) -> rquickjs::Result<bool> {
+        if let Some(formatter) = &config.formatter {
+            self.require_spawn(&ctx, &formatter.command)?;
+        }
+        Ok(self
+            .command_sender
             .send(PluginCommand::RegisterLanguageConfig { language, config })
-            .is_ok()
+            .is_ok())
     }
 
     /// Register an LSP server for a language
-    pub fn register_lsp_server(&self, language: String, config: LspServerPackConfig) -> bool {
-        self.command_sender
+    pub fn register_lsp_server(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        language: String,
+        config: LspServerPackConfig,
+    ) -> rquickjs::Result<bool> {
+        self.require_spawn(&ctx, &config.command)?;
+        Ok(self
+            .command_sender
             .send(PluginCommand::RegisterLspServer { language, config })
+            .is_ok())
+    }
+
+    /// Provide a language feature for a language, merged with LSP results
+    ///
+    /// `feature` is "completion", "hover", "definition", "codeAction" or
+    /// "diagnostics". The handler receives a `LanguageFeatureRequest` and
+    /// returns (or resolves to) the results. Registering again for the same
+    /// feature and language replaces the previous handler.
+    pub fn register_language_provider(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        feature: String,
+        language: String,
+        handler_name: String,
+    ) -> rquickjs::Result<bool> {
+        let Some(feature) = LanguageFeature::parse(&feature) else {
+            return Err(rquickjs::Exception::throw_message(
+                &ctx,
+                &format!("Unknown language feature '{}'", feature),
+            ));
+        };
+        let mut providers = self.language_providers.borrow_mut();
+        providers.retain(|p| {
+            !(p.plugin_name == self.plugin_name && p.feature == feature && p.language == language)
+        });
+        providers.push(LanguageProvider {
+            plugin_name: self.plugin_name.clone(),
+            feature,
+            language: language.clone(),
+            handler_name,
+        });
+        Ok(self
+            .command_sender
+            .send(PluginCommand::RegisterLanguageProvider {
+                plugin_name: self.plugin_name.clone(),
+                feature,
+                language,
+            })
+            .is_ok())
+    }
+
+    /// Stop providing a language feature for a language
+    pub fn unregister_language_provider(&self, feature: String, language: String) -> bool {
+        let Some(feature) = LanguageFeature::parse(&feature) else {
+            return false;
+        };
+        let mut providers = self.language_providers.borrow_mut();
+        let before = providers.len();
+        providers.retain(|p| {
+            !(p.plugin_name == self.plugin_name && p.feature == feature && p.language == language)
+        });
+        if providers.len() == before {
+            return false;
+        }
+        let _ = self
+            .command_sender
+            .send(PluginCommand::UnregisterLanguageProvider {
+                plugin_name: self.plugin_name.clone(),
+                feature,
+                language,
+            });
+        true
+    }
+
+    /// Send a provider handler's results back to the editor
+    #[qjs(rename = "_languageProviderResult")]
+    pub fn language_provider_result<'js>(
+        &self,
+        ctx: rquickjs::Ctx<'js>,
+        request_id: u64,
+        result: Value<'js>,
+    ) -> bool {
+        self.command_sender
+            .send(PluginCommand::LanguageProviderResult {
+                plugin_name: self.plugin_name.clone(),
+                request_id,
+                result: js_to_json(&ctx, result),
+            })
             .is_ok()
     }
 
@@ -1240,6 +1981,7 @@ impl JsEditorApi {
         ctx: rquickjs::Ctx<'js>,
         path: String,
     ) -> rquickjs::Result<Value<'js>> {
+        self.require_read(&ctx, &path)?;
         let metadata = std::fs::metadata(&path).ok();
         let stat = metadata.map(|m| {
             serde_json::json!({
@@ -1384,6 +2126,83 @@ impl JsEditorApi {
         Ok(id)
     }
 
+    // === Syntax Tree ===
+
+    /// Get the syntax nodes under a byte offset, outermost first (async)
+    ///
+    /// Rejects if the buffer's language has no tree-sitter grammar.
+    #[plugin_api(
+        async_promise,
+        js_name = "getSyntaxNodePath",
+        ts_return = "SyntaxNode[]"
+    )]
+    #[qjs(rename = "_getSyntaxNodePathStart")]
+    pub fn get_syntax_node_path_start(&self, buffer_id: u32, offset: u32) -> u64 {
+        let id = self.next_callback_id();
+        let _ = self.command_sender.send(PluginCommand::GetSyntaxNodePath {
+            buffer_id: BufferId(buffer_id as usize),
+            offset: offset as usize,
+            callback_id: JsCallbackId::new(id),
+        });
+        id
+    }
+
+    /// Get the innermost syntax node of a kind containing a byte offset (async)
+    ///
+    /// Resolves with null if no enclosing node has that kind.
+    #[plugin_api(
+        async_promise,
+        js_name = "getEnclosingSyntaxNode",
+        ts_return = "SyntaxNode | null"
+    )]
+    #[qjs(rename = "_getEnclosingSyntaxNodeStart")]
+    pub fn get_enclosing_syntax_node_start(
+        &self,
+        buffer_id: u32,
+        offset: u32,
+        kind: String,
+    ) -> u64 {
+        let id = self.next_callback_id();
+        let _ = self
+            .command_sender
+            .send(PluginCommand::GetEnclosingSyntaxNode {
+                buffer_id: BufferId(buffer_id as usize),
+                offset: offset as usize,
+                kind,
+                callback_id: JsCallbackId::new(id),
+            });
+        id
+    }
+
+    /// Run a tree-sitter query against a buffer (async)
+    ///
+    /// Pass `start` and `end` to only return matches within that byte range.
+    /// Rejects if the query does not compile for the buffer's language.
+    #[plugin_api(async_promise, js_name = "querySyntax", ts_return = "SyntaxCapture[]")]
+    #[qjs(rename = "_querySyntaxStart")]
+    pub fn query_syntax_start(
+        &self,
+        buffer_id: u32,
+        query: String,
+        start: rquickjs::function::Opt<u32>,
+        end: rquickjs::function::Opt<u32>,
+    ) -> u64 {
+        let id = self.next_callback_id();
+        let range = match (start.0, end.0) {
+            (None, None) => None,
+            (start, end) => {
+                Some(start.unwrap_or(0) as usize..end.map_or(usize::MAX, |e| e as usize))
+            }
+        };
+        let _ = self.command_sender.send(PluginCommand::QuerySyntax {
+            buffer_id: BufferId(buffer_id as usize),
+            query,
+            range,
+            callback_id: JsCallbackId::new(id),
+        });
+        id
+    }
+
     // === Overlays ===
 
     /// Add an overlay with styling options
@@ -1939,6 +2758,20 @@ impl JsEditorApi {
             .is_ok()
     }
 
+    /// Replace all cursors in the active buffer with the given selections
+    ///
+    /// Each entry becomes one cursor at `position`, selecting back to
+    /// `anchor` if given; `mode: "block"` makes it a rectangular selection.
+    /// The last entry becomes the primary cursor. An empty array is ignored.
+    pub fn set_selections(&self, selections: Vec<fresh_core::api::SelectionSpec>) -> bool {
+        if selections.is_empty() {
+            return false;
+        }
+        self.command_sender
+            .send(PluginCommand::SetSelections { selections })
+            .is_ok()
+    }
+
     // === Line Indicators ===
 
     /// Set a line indicator in the gutter
@@ -2038,10 +2871,16 @@ impl JsEditorApi {
     /// Execute multiple actions in sequence
     ///
     /// Takes typed ActionSpec array - serde validates field names at runtime
-    pub fn execute_actions(&self, actions: Vec<ActionSpec>) -> bool {
-        self.command_sender
+    pub fn execute_actions(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        actions: Vec<ActionSpec>,
+    ) -> rquickjs::Result<bool> {
+        self.require_unrestricted(&ctx, "run editor actions")?;
+        Ok(self
+            .command_sender
             .send(PluginCommand::ExecuteActions { actions })
-            .is_ok()
+            .is_ok())
     }
 
     /// Show an action popup
@@ -2058,6 +2897,31 @@ impl JsEditorApi {
             .is_ok()
     }
 
+    /// Show a modal form dialog
+    ///
+    /// Field edits fire the `form_field_changed` hook and pressing a button
+    /// (or dismissing the dialog with Escape) fires `form_result`.
+    pub fn show_form(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        options: fresh_core::api::FormOptions,
+    ) -> rquickjs::Result<bool> {
+        if let Err(msg) = options.validate() {
+            return Err(rquickjs::Exception::throw_message(&ctx, &msg));
+        }
+        Ok(self
+            .command_sender
+            .send(PluginCommand::ShowForm { options })
+            .is_ok())
+    }
+
+    /// Close a form dialog without firing `form_result`
+    pub fn close_form(&self, form_id: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::CloseForm { form_id })
+            .is_ok()
+    }
+
     /// Disable LSP for a specific language
     pub fn disable_lsp_for_language(&self, language: String) -> bool {
         self.command_sender
@@ -2327,11 +3191,12 @@ impl JsEditorApi {
     #[qjs(rename = "_spawnProcessStart")]
     pub fn spawn_process_start(
         &self,
-        _ctx: rquickjs::Ctx<'_>,
+        ctx: rquickjs::Ctx<'_>,
         command: String,
         args: Vec<String>,
         cwd: rquickjs::function::Opt<String>,
-    ) -> u64 {
+    ) -> rquickjs::Result<u64> {
+        self.require_spawn(&ctx, &command)?;
         let id = {
             let mut id_ref = self.next_request_id.borrow_mut();
             let id = *id_ref;
@@ -2362,7 +3227,7 @@ impl JsEditorApi {
             args,
             cwd: effective_cwd,
         });
-        id
+        Ok(id)
     }
 
     /// Wait for a process to complete and get its result (async)
@@ -2479,11 +3344,12 @@ impl JsEditorApi {
     #[qjs(rename = "_spawnBackgroundProcessStart")]
     pub fn spawn_background_process_start(
         &self,
-        _ctx: rquickjs::Ctx<'_>,
+        ctx: rquickjs::Ctx<'_>,
         command: String,
         args: Vec<String>,
         cwd: rquickjs::function::Opt<String>,
-    ) -> u64 {
+    ) -> rquickjs::Result<u64> {
+        self.require_spawn(&ctx, &command)?;
         let id = {
             let mut id_ref = self.next_request_id.borrow_mut();
             let id = *id_ref;
@@ -2505,7 +3371,7 @@ impl JsEditorApi {
                 cwd: cwd.0,
                 callback_id: JsCallbackId::new(id),
             });
-        id
+        Ok(id)
     }
 
     /// Kill a background process
@@ -2515,28 +3381,36 @@ impl JsEditorApi {
             .is_ok()
     }
 
-    // === Misc ===
-
-    /// Force refresh of line display
-    pub fn refresh_lines(&self, buffer_id: u32) -> bool {
-        self.command_sender
-            .send(PluginCommand::RefreshLines {
-                buffer_id: BufferId(buffer_id as usize),
-            })
-            .is_ok()
-    }
-
-    /// Get the current locale
-    pub fn get_current_locale(&self) -> String {
-        self.services.current_locale()
-    }
-
-    // === Plugin Management ===
-
-    /// Load a plugin from a file path (async)
-    #[plugin_api(async_promise, js_name = "loadPlugin", ts_return = "boolean")]
-    #[qjs(rename = "_loadPluginStart")]
-    pub fn load_plugin_start(&self, _ctx: rquickjs::Ctx<'_>, path: String) -> u64 {
+    /// Spawn a process with piped stdin (returns a handle to write to it)
+    ///
+    /// Output arrives through the `onProcessStdout`/`onProcessStderr` hooks,
+    /// split into lines or delivered in chunks (`opts.output`). Works on
+    /// remote (SSH) workspaces too.
+    #[plugin_api(
+        js_name = "spawnInteractiveProcess",
+        ts_return = "InteractiveProcessHandle"
+    )]
+    #[qjs(rename = "_spawnInteractiveProcessStart")]
+    pub fn spawn_interactive_process_start(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        command: String,
+        args: Vec<String>,
+        opts: rquickjs::function::Opt<fresh_core::api::InteractiveProcessOptions>,
+    ) -> rquickjs::Result<u64> {
+        self.require_spawn(&ctx, &command)?;
+        let options = opts.0.unwrap_or_default();
+        if let Some(output) = &options.output {
+            if crate::process::OutputMode::parse(output).is_none() {
+                return Err(rquickjs::Exception::throw_message(
+                    &ctx,
+                    &format!(
+                        "spawnInteractiveProcess: unknown output mode '{}' (expected \"lines\" or \"chunks\")",
+                        output
+                    ),
+                ));
+            }
+        }
         let id = {
             let mut id_ref = self.next_request_id.borrow_mut();
             let id = *id_ref;
@@ -2546,17 +3420,172 @@ impl JsEditorApi {
                 .insert(id, self.plugin_name.clone());
             id
         };
-        let _ = self.command_sender.send(PluginCommand::LoadPlugin {
-            path: std::path::PathBuf::from(path),
-            callback_id: JsCallbackId::new(id),
-        });
-        id
+        let _ = self
+            .command_sender
+            .send(PluginCommand::SpawnInteractiveProcess {
+                plugin_name: self.plugin_name.clone(),
+                process_id: id,
+                command,
+                args,
+                options,
+                callback_id: JsCallbackId::new(id),
+            });
+        Ok(id)
+    }
+
+    /// Write text to the stdin of an interactive process
+    pub fn write_process_stdin(&self, process_id: u64, data: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::WriteProcessStdin {
+                plugin_name: self.plugin_name.clone(),
+                process_id,
+                data,
+            })
+            .is_ok()
+    }
+
+    /// Close the stdin of an interactive process
+    pub fn close_process_stdin(&self, process_id: u64) -> bool {
+        self.command_sender
+            .send(PluginCommand::CloseProcessStdin {
+                plugin_name: self.plugin_name.clone(),
+                process_id,
+            })
+            .is_ok()
+    }
+
+    // === Terminals ===
+
+    /// Open an integrated terminal (async)
+    ///
+    /// Runs `opts.command` (or the user's shell) in a new terminal buffer,
+    /// in the active split, an existing split (`splitId`) or a new split
+    /// (`direction`). Restricted plugins need spawn permission for the
+    /// command, or for `$SHELL` when no command is given.
+    #[plugin_api(
+        async_promise,
+        js_name = "createTerminal",
+        ts_return = "TerminalResult"
+    )]
+    #[qjs(rename = "_createTerminalStart")]
+    pub fn create_terminal_start(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        opts: rquickjs::function::Opt<fresh_core::api::CreateTerminalOptions>,
+    ) -> rquickjs::Result<u64> {
+        let options = opts.0.unwrap_or_default();
+        self.require_spawn(&ctx, options.command.as_deref().unwrap_or("$SHELL"))?;
+        let id = self.next_callback_id();
+        let _ = self.command_sender.send(PluginCommand::CreateTerminal {
+            plugin_name: self.plugin_name.clone(),
+            options,
+            callback_id: JsCallbackId::new(id),
+        });
+        Ok(id)
+    }
+
+    /// Write input to a terminal's process, as if typed (use "\r" for Enter)
+    ///
+    /// Like the other terminal methods, this only works on terminals the
+    /// plugin opened with `createTerminal`.
+    pub fn send_terminal_input(&self, terminal_id: u32, data: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::SendTerminalInput {
+                plugin_name: self.plugin_name.clone(),
+                terminal_id: TerminalId(terminal_id as usize),
+                data,
+            })
+            .is_ok()
+    }
+
+    /// Get a terminal's scrollback and visible screen as plain text (async)
+    #[plugin_api(async_promise, js_name = "getTerminalScrollback", ts_return = "string")]
+    #[qjs(rename = "_getTerminalScrollbackStart")]
+    pub fn get_terminal_scrollback_start(&self, terminal_id: u32) -> u64 {
+        let id = self.next_callback_id();
+        let _ = self
+            .command_sender
+            .send(PluginCommand::GetTerminalScrollback {
+                plugin_name: self.plugin_name.clone(),
+                terminal_id: TerminalId(terminal_id as usize),
+                callback_id: JsCallbackId::new(id),
+            });
+        id
+    }
+
+    /// Fire the `terminal_output_matched` hook for each line a terminal
+    /// prints that matches `pattern` (a regular expression)
+    ///
+    /// Output printed before the first watch was added is matched too. The
+    /// hook only reaches the plugin that opened the terminal.
+    pub fn watch_terminal_output(&self, terminal_id: u32, pattern: String) -> bool {
+        self.command_sender
+            .send(PluginCommand::WatchTerminalOutput {
+                plugin_name: self.plugin_name.clone(),
+                terminal_id: TerminalId(terminal_id as usize),
+                pattern,
+            })
+            .is_ok()
+    }
+
+    /// Close a terminal, stopping its process and closing its buffer
+    pub fn close_terminal(&self, terminal_id: u32) -> bool {
+        self.command_sender
+            .send(PluginCommand::CloseTerminal {
+                plugin_name: self.plugin_name.clone(),
+                terminal_id: TerminalId(terminal_id as usize),
+            })
+            .is_ok()
+    }
+
+    // === Misc ===
+
+    /// Force refresh of line display
+    pub fn refresh_lines(&self, buffer_id: u32) -> bool {
+        self.command_sender
+            .send(PluginCommand::RefreshLines {
+                buffer_id: BufferId(buffer_id as usize),
+            })
+            .is_ok()
+    }
+
+    /// Get the current locale
+    pub fn get_current_locale(&self) -> String {
+        self.services.current_locale()
+    }
+
+    // === Plugin Management ===
+
+    /// Load a plugin from a file path (async)
+    #[plugin_api(async_promise, js_name = "loadPlugin", ts_return = "boolean")]
+    #[qjs(rename = "_loadPluginStart")]
+    pub fn load_plugin_start(&self, ctx: rquickjs::Ctx<'_>, path: String) -> rquickjs::Result<u64> {
+        self.require_unrestricted(&ctx, "manage plugins")?;
+        let id = {
+            let mut id_ref = self.next_request_id.borrow_mut();
+            let id = *id_ref;
+            *id_ref += 1;
+            self.callback_contexts
+                .borrow_mut()
+                .insert(id, self.plugin_name.clone());
+            id
+        };
+        let _ = self.command_sender.send(PluginCommand::LoadPlugin {
+            path: std::path::PathBuf::from(path),
+            callback_id: JsCallbackId::new(id),
+        });
+        Ok(id)
     }
 
     /// Unload a plugin by name (async)
     #[plugin_api(async_promise, js_name = "unloadPlugin", ts_return = "boolean")]
     #[qjs(rename = "_unloadPluginStart")]
-    pub fn unload_plugin_start(&self, _ctx: rquickjs::Ctx<'_>, name: String) -> u64 {
+    pub fn unload_plugin_start(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        name: String,
+    ) -> rquickjs::Result<u64> {
+        self.require_unrestricted(&ctx, "manage plugins")?;
         let id = {
             let mut id_ref = self.next_request_id.borrow_mut();
             let id = *id_ref;
@@ -2570,13 +3599,18 @@ impl JsEditorApi {
             name,
             callback_id: JsCallbackId::new(id),
         });
-        id
+        Ok(id)
     }
 
     /// Reload a plugin by name (async)
     #[plugin_api(async_promise, js_name = "reloadPlugin", ts_return = "boolean")]
     #[qjs(rename = "_reloadPluginStart")]
-    pub fn reload_plugin_start(&self, _ctx: rquickjs::Ctx<'_>, name: String) -> u64 {
+    pub fn reload_plugin_start(
+        &self,
+        ctx: rquickjs::Ctx<'_>,
+        name: String,
+    ) -> rquickjs::Result<u64> {
+        self.require_unrestricted(&ctx, "manage plugins")?;
         let id = {
             let mut id_ref = self.next_request_id.borrow_mut();
             let id = *id_ref;
@@ -2590,7 +3624,7 @@ impl JsEditorApi {
             name,
             callback_id: JsCallbackId::new(id),
         });
-        id
+        Ok(id)
     }
 
     /// List all loaded plugins (async)
@@ -2642,6 +3676,24 @@ pub struct QuickJsBackend {
     callback_contexts: Rc<RefCell<HashMap<u64, String>>>,
     /// Bridge for editor services (i18n, theme, etc.)
     pub services: Arc<dyn fresh_core::services::PluginServiceBridge>,
+    /// Time budget and memory limit enforcement
+    watchdog: Rc<Watchdog>,
+    /// Heap limit checked after each call into plugin code
+    memory_limit: Option<usize>,
+    /// What each restricted plugin may do (plugins not listed are unrestricted)
+    capabilities: Rc<RefCell<HashMap<String, CapabilityScope>>>,
+    /// Capabilities the user granted, by plugin name
+    granted: HashMap<String, PluginCapabilities>,
+    /// Persistent key-value data, shared by all plugin contexts
+    storage: Rc<RefCell<PluginStorage>>,
+    /// Language feature providers registered by plugins
+    language_providers: Rc<RefCell<Vec<LanguageProvider>>>,
+    /// Hook handler call statistics: (hook, plugin, handler) -> timing
+    hook_timings: RefCell<HashMap<(String, String, String), HookTiming>>,
+    /// Exported plugin APIs and messages between plugins
+    bus: Rc<RefCell<PluginBus>>,
+    /// Plugins waiting for their dependencies to load: name -> entry file
+    deferred_plugins: HashMap<String, PathBuf>,
 }
 
 impl QuickJsBackend {
@@ -2702,6 +3754,13 @@ impl QuickJsBackend {
             },
         )));
 
+        // Interrupt plugin code that runs past its time budget
+        let watchdog = Rc::new(Watchdog::default());
+        let interrupt_watchdog = Rc::clone(&watchdog);
+        runtime.set_interrupt_handler(Some(Box::new(move || {
+            interrupt_watchdog.should_interrupt()
+        })));
+
         let main_context = Context::full(&runtime)
             .map_err(|e| anyhow!("Failed to create QuickJS context: {}", e))?;
 
@@ -2710,8 +3769,9 @@ impl QuickJsBackend {
         let registered_actions = Rc::new(RefCell::new(HashMap::new()));
         let next_request_id = Rc::new(RefCell::new(1u64));
         let callback_contexts = Rc::new(RefCell::new(HashMap::new()));
+        let storage = Rc::new(RefCell::new(PluginStorage::new(&services.data_dir())));
 
-        let backend = Self {
+        let mut backend = Self {
             runtime,
             main_context,
             plugin_contexts,
@@ -2723,7 +3783,17 @@ impl QuickJsBackend {
             next_request_id,
             callback_contexts,
             services,
+            watchdog,
+            memory_limit: None,
+            capabilities: Rc::new(RefCell::new(HashMap::new())),
+            granted: HashMap::new(),
+            storage,
+            language_providers: Rc::new(RefCell::new(Vec::new())),
+            hook_timings: RefCell::new(HashMap::new()),
+            bus: Rc::new(RefCell::new(PluginBus::default())),
+            deferred_plugins: HashMap::new(),
         };
+        backend.set_limits(PluginLimits::default());
 
         // Initialize main context (for internal utilities if needed)
         backend.setup_context_api(&backend.main_context.clone(), "internal")?;
@@ -2732,6 +3802,391 @@ impl QuickJsBackend {
         Ok(backend)
     }
 
+    /// Set the time budget and heap limit for plugin code
+    pub fn set_limits(&mut self, limits: PluginLimits) {
+        self.watchdog.call_timeout.set(limits.call_timeout);
+        self.memory_limit = limits.memory_limit;
+        // QuickJS treats -1 (usize::MAX) as "no limit"
+        self.runtime
+            .set_memory_limit(limits.memory_limit.unwrap_or(usize::MAX));
+        self.storage.borrow_mut().set_quota(limits.storage_quota);
+    }
+
+    /// Run `f` as a call into `plugin_name`'s code, under the watchdog
+    fn guarded<R>(&self, plugin_name: &str, f: impl FnOnce() -> R) -> R {
+        let outer = self.watchdog.begin(plugin_name);
+        let result = f();
+        self.watchdog.end(outer);
+        self.check_memory(plugin_name);
+        result
+    }
+
+    /// Blame `plugin_name` if the heap stays near the limit after its call
+    fn check_memory(&self, plugin_name: &str) {
+        let Some(limit) = self.memory_limit else {
+            return;
+        };
+        let threshold = limit / 10 * 9;
+        let heap_size = || self.runtime.memory_usage().malloc_size.max(0) as usize;
+        if heap_size() < threshold {
+            return;
+        }
+        // Only memory that survives a collection is really held by the plugin
+        self.runtime.run_gc();
+        if heap_size() >= threshold {
+            self.watchdog.trip(
+                plugin_name,
+                format!("exceeded the {} MB memory limit", limit / (1024 * 1024)),
+            );
+        }
+    }
+
+    /// Take the plugins that exceeded a limit since the last call, with the reason
+    ///
+    /// The caller is expected to disable them (see `disable_plugin`).
+    pub fn take_tripped_plugins(&self) -> Vec<(String, String)> {
+        std::mem::take(&mut *self.watchdog.tripped.borrow_mut())
+    }
+
+    /// Remove a plugin's event handlers, actions and context so none of its
+    /// code runs again, and tell the editor why
+    pub fn disable_plugin(&mut self, name: &str, reason: &str) {
+        for handlers in self.event_handlers.borrow_mut().values_mut() {
+            handlers.retain(|handler| handler.plugin_name != name);
+        }
+        self.registered_actions
+            .borrow_mut()
+            .retain(|_, handler| handler.plugin_name != name);
+        self.callback_contexts
+            .borrow_mut()
+            .retain(|_, plugin_name| plugin_name != name);
+        self.remove_language_providers(name);
+        self.plugin_contexts.borrow_mut().remove(name);
+        let _ = self.command_sender.send(PluginCommand::PluginDisabled {
+            name: name.to_string(),
+            reason: reason.to_string(),
+        });
+        self.remove_path_watches(name);
+        self.remove_plugin_api(name);
+    }
+
+    /// Tell the editor to drop a plugin's status bar items
+    pub fn remove_status_bar_items(&self, name: &str) {
+        let _ = self
+            .command_sender
+            .send(PluginCommand::RemoveStatusBarItems {
+                plugin_name: name.to_string(),
+            });
+    }
+
+    /// Tell the editor to kill a plugin's interactive processes
+    pub fn kill_interactive_processes(&self, name: &str) {
+        let _ = self
+            .command_sender
+            .send(PluginCommand::KillInteractiveProcesses {
+                plugin_name: name.to_string(),
+            });
+    }
+
+    /// Tell the editor to close the terminals a plugin opened
+    pub fn close_plugin_terminals(&self, name: &str) {
+        let _ = self.command_sender.send(PluginCommand::CloseTerminals {
+            plugin_name: name.to_string(),
+        });
+    }
+
+    /// Tell the editor to stop a plugin's path watches
+    pub fn remove_path_watches(&self, name: &str) {
+        let _ = self.command_sender.send(PluginCommand::UnwatchPaths {
+            plugin_name: name.to_string(),
+        });
+    }
+
+    /// Withdraw a plugin's exported API, failing the calls it was running
+    pub fn remove_plugin_api(&self, name: &str) {
+        self.bus.borrow_mut().remove_plugin(name);
+    }
+
+    /// Hold back a plugin until the plugins it depends on are loaded
+    pub fn defer_plugin(&mut self, name: &str, path: &Path) {
+        self.deferred_plugins
+            .insert(name.to_string(), path.to_path_buf());
+    }
+
+    /// Take the deferred plugins whose dependencies are now all loaded
+    pub fn take_ready_plugins(&mut self, is_loaded: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+        let ready: Vec<String> = self
+            .deferred_plugins
+            .iter()
+            .filter(|(_, path)| {
+                crate::bus::declared_dependencies(path)
+                    .iter()
+                    .all(|dependency| is_loaded(dependency))
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        ready
+            .iter()
+            .filter_map(|name| self.deferred_plugins.remove(name))
+            .collect()
+    }
+
+    /// Note that the editor has loaded its plugins at startup
+    ///
+    /// Plugins still waiting for dependencies will not get them, so the user
+    /// is told, and requests for APIs of plugins that are not loaded fail.
+    pub fn finish_startup(&mut self) {
+        let mut deferred: Vec<&String> = self.deferred_plugins.keys().collect();
+        deferred.sort();
+        for name in deferred {
+            let path = &self.deferred_plugins[name];
+            let contexts = self.plugin_contexts.borrow();
+            let missing: Vec<String> = crate::bus::declared_dependencies(path)
+                .into_iter()
+                .filter(|dependency| !contexts.contains_key(dependency))
+                .collect();
+            let message = format!(
+                "Plugin '{}' not loaded: missing dependencies {}",
+                name,
+                missing.join(", ")
+            );
+            tracing::warn!("{}", message);
+            self.send_status(message);
+        }
+        self.bus.borrow_mut().finish_startup();
+    }
+
+    /// Whether cross-plugin calls, replies or events are waiting
+    pub fn has_plugin_messages(&self) -> bool {
+        self.bus.borrow().has_messages()
+    }
+
+    /// Deliver queued cross-plugin calls, replies and events
+    ///
+    /// Messages sent while delivering are left for the next call.
+    pub async fn dispatch_plugin_messages(&mut self) {
+        {
+            let contexts = self.plugin_contexts.borrow();
+            self.bus
+                .borrow_mut()
+                .fail_missing(|name| contexts.contains_key(name));
+        }
+        let messages = self.bus.borrow_mut().take_messages();
+        for message in messages {
+            match message {
+                BusMessage::Call {
+                    callback_id,
+                    target,
+                    method,
+                    args,
+                } => self.deliver_api_call(callback_id, &target, &method, &args),
+                BusMessage::Reply {
+                    callback_id,
+                    result: Ok(result),
+                } => self.resolve_callback(JsCallbackId::new(callback_id), &result.to_string()),
+                BusMessage::Reply {
+                    callback_id,
+                    result: Err(error),
+                } => self.reject_callback(JsCallbackId::new(callback_id), &error),
+                BusMessage::Event { name, data } => {
+                    if let Err(e) = self.emit(&name, &data).await {
+                        tracing::error!("Plugin event '{}' failed: {}", name, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Run a method of `target`'s exported API; its answer comes back
+    /// through the bus
+    fn deliver_api_call(
+        &self,
+        callback_id: u64,
+        target: &str,
+        method: &str,
+        args: &serde_json::Value,
+    ) {
+        let context = self.plugin_contexts.borrow().get(target).cloned();
+        let context = match context {
+            Some(context)
+                if self
+                    .bus
+                    .borrow_mut()
+                    .start_call(callback_id, target, method) =>
+            {
+                context
+            }
+            _ => {
+                let error = format!("Plugin '{}' exports no method '{}'", target, method);
+                self.bus.borrow_mut().reply(callback_id, Err(error));
+                return;
+            }
+        };
+
+        self.services
+            .set_js_execution_state(format!("API call '{}.{}'", target, method));
+        self.guarded(target, || {
+            context.with(|ctx| {
+                let call = || -> rquickjs::Result<()> {
+                    let args = rquickjs_serde::to_value(ctx.clone(), args).map_err(|e| {
+                        rquickjs::Error::new_from_js_message("json", "value", &e.to_string())
+                    })?;
+                    let call_exported: Function = ctx.globals().get("_callExportedApi")?;
+                    call_exported.call::<_, ()>((callback_id, method, args))
+                };
+                if let Err(e) = call() {
+                    let error = format_js_error(&ctx, e, &format!("{}.{}", target, method));
+                    self.bus
+                        .borrow_mut()
+                        .finish_call(target, callback_id, Err(error.to_string()));
+                }
+                run_pending_jobs_checked(&ctx, &format!("API call {}.{}", target, method));
+            })
+        });
+        self.services.clear_js_execution_state();
+    }
+
+    /// Drop a plugin's language feature providers and tell the editor
+    pub fn remove_language_providers(&self, name: &str) {
+        let mut providers = self.language_providers.borrow_mut();
+        for provider in providers.iter().filter(|p| p.plugin_name == name) {
+            let _ = self
+                .command_sender
+                .send(PluginCommand::UnregisterLanguageProvider {
+                    plugin_name: provider.plugin_name.clone(),
+                    feature: provider.feature,
+                    language: provider.language.clone(),
+                });
+        }
+        providers.retain(|p| p.plugin_name != name);
+    }
+
+    /// Ask every provider registered for the request's feature and language
+    ///
+    /// Each provider answers with a `LanguageProviderResult` command carrying
+    /// `request_id`; a provider that throws or is missing answers `null`, so
+    /// the editor always hears back from every provider it counted.
+    pub fn request_language_feature(&mut self, request_id: u64, request: &LanguageFeatureRequest) {
+        let providers: Vec<LanguageProvider> = self
+            .language_providers
+            .borrow()
+            .iter()
+            .filter(|p| p.feature == request.feature && p.language == request.language)
+            .cloned()
+            .collect();
+        // Double-encode so the request becomes a JavaScript string literal
+        let Ok(request_literal) =
+            serde_json::to_string(request).and_then(|json| serde_json::to_string(&json))
+        else {
+            return;
+        };
+
+        for provider in providers {
+            let Some(context) = self
+                .plugin_contexts
+                .borrow()
+                .get(&provider.plugin_name)
+                .cloned()
+            else {
+                continue;
+            };
+            let handler_name = &provider.handler_name;
+            let code = format!(
+                r#"
+                (function() {{
+                    const reply = function(result) {{
+                        editor._languageProviderResult({id}, result === undefined ? null : result);
+                    }};
+                    try {{
+                        const handler = globalThis["{handler}"];
+                        if (typeof handler !== 'function') {{
+                            console.error('Language provider {handler} is not defined as a global function');
+                            reply(null);
+                            return;
+                        }}
+                        Promise.resolve(handler(JSON.parse({request}))).then(reply, function(e) {{
+                            console.error('Language provider {handler} error:', e);
+                            reply(null);
+                        }});
+                    }} catch (e) {{
+                        console.error('Language provider {handler} error:', e);
+                        reply(null);
+                    }}
+                }})();
+                "#,
+                id = request_id,
+                handler = handler_name,
+                request = request_literal,
+            );
+
+            self.services.set_js_execution_state(format!(
+                "language provider '{}' (fn: {})",
+                provider.plugin_name, handler_name
+            ));
+            self.guarded(&provider.plugin_name, || {
+                context.with(|ctx| {
+                    if let Err(e) = ctx.eval::<(), _>(code.as_bytes()) {
+                        log_js_error(&ctx, e, &format!("language provider {}", handler_name));
+                    }
+                    run_pending_jobs_checked(&ctx, &format!("language provider {}", handler_name));
+                })
+            });
+            self.services.clear_js_execution_state();
+        }
+    }
+
+    /// Record the capabilities the user granted to a plugin
+    pub fn set_granted_capabilities(&mut self, name: &str, granted: PluginCapabilities) {
+        self.granted.insert(name.to_string(), granted);
+    }
+
+    /// Capabilities the user granted to a plugin (none if never asked)
+    pub fn granted_capabilities(&self, name: &str) -> PluginCapabilities {
+        self.granted.get(name).cloned().unwrap_or_default()
+    }
+
+    /// Hold a plugin package to the given capabilities from now on
+    pub fn restrict_plugin(
+        &mut self,
+        name: &str,
+        capabilities: &PluginCapabilities,
+        plugin_dir: &Path,
+    ) {
+        let workspace = self
+            .state_snapshot
+            .read()
+            .map(|s| s.working_dir.clone())
+            .unwrap_or_default();
+        let config_dir = self.services.config_dir();
+        let scope = CapabilityScope::new(
+            capabilities,
+            &ScopeRoots {
+                plugin_dir,
+                workspace: &workspace,
+                config_dir: &config_dir,
+            },
+        );
+        self.capabilities
+            .borrow_mut()
+            .insert(name.to_string(), scope);
+    }
+
+    /// Let a plugin use the whole API (plugins that are not packages)
+    pub fn unrestrict_plugin(&mut self, name: &str) {
+        self.capabilities.borrow_mut().remove(name);
+    }
+
+    /// Ask the editor to get the user's consent for a plugin's capabilities
+    pub fn request_capabilities(&self, name: &str, path: &Path, requested: PluginCapabilities) {
+        let _ = self
+            .command_sender
+            .send(PluginCommand::RequestPluginCapabilities {
+                name: name.to_string(),
+                path: path.to_path_buf(),
+                requested,
+            });
+    }
+
     /// Set up the editor API in a specific JavaScript context
     fn setup_context_api(&self, context: &Context, plugin_name: &str) -> Result<()> {
         let state_snapshot = Arc::clone(&self.state_snapshot);
@@ -2756,6 +4211,10 @@ impl QuickJsBackend {
                 next_request_id: Rc::clone(&next_request_id),
                 callback_contexts: Rc::clone(&self.callback_contexts),
                 services: self.services.clone(),
+                capabilities: Rc::clone(&self.capabilities),
+                storage: Rc::clone(&self.storage),
+                language_providers: Rc::clone(&self.language_providers),
+                bus: Rc::clone(&self.bus),
                 plugin_name: plugin_name.to_string(),
             };
             let editor = rquickjs::Class::<JsEditorApi>::instance(ctx.clone(), js_api)?;
@@ -2876,15 +4335,96 @@ impl QuickJsBackend {
                 editor.createVirtualBufferInExistingSplit = _wrapAsync("_createVirtualBufferInExistingSplitStart", "createVirtualBufferInExistingSplit");
                 editor.sendLspRequest = _wrapAsync("_sendLspRequestStart", "sendLspRequest");
                 editor.spawnBackgroundProcess = _wrapAsyncThenable("_spawnBackgroundProcessStart", "spawnBackgroundProcess");
+                // Interactive processes: a thenable like spawnBackgroundProcess,
+                // plus methods to talk to the process's stdin
+                editor.spawnInteractiveProcess = function(...args) {
+                    const processId = editor._spawnInteractiveProcessStart(...args);
+                    const resultPromise = new Promise((resolve, reject) => {
+                        globalThis._pendingCallbacks.set(processId, { resolve, reject });
+                    });
+                    return {
+                        processId,
+                        get result() { return resultPromise; },
+                        write(data) { return editor.writeProcessStdin(processId, data); },
+                        closeStdin() { return editor.closeProcessStdin(processId); },
+                        kill() { return Promise.resolve(editor.killProcess(processId)); },
+                        then(onFulfilled, onRejected) {
+                            return resultPromise.then(onFulfilled, onRejected);
+                        },
+                        catch(onRejected) {
+                            return resultPromise.catch(onRejected);
+                        }
+                    };
+                };
+                // Path watches: the id plus a way to stop watching
+                editor.watchPath = function(...args) {
+                    const watchId = editor._watchPathStart(...args);
+                    return {
+                        watchId,
+                        close() { return editor.unwatchPath(watchId); }
+                    };
+                };
+                // Other plugins' APIs: resolves to an object whose methods
+                // run in the exporting plugin and return promises
+                const getPluginApiRaw = _wrapAsync("_getPluginApiStart", "getPluginApi");
+                editor.getPluginApi = function(name) {
+                    return getPluginApiRaw(name).then(function(methods) {
+                        const api = {};
+                        for (const method of methods) {
+                            api[method] = function(...args) {
+                                const callbackId = editor._callPluginApiStart(name, method, args);
+                                return new Promise((resolve, reject) => {
+                                    globalThis._pendingCallbacks.set(callbackId, { resolve, reject });
+                                });
+                            };
+                        }
+                        return Object.freeze(api);
+                    });
+                };
+                // Run a method of this plugin's exported API for another
+                // plugin (called from Rust)
+                globalThis._callExportedApi = function(callbackId, method, args) {
+                    new Promise((resolve) => resolve(globalThis._exportedApi[method](...args))).then(
+                        (result) => editor._replyPluginApi(callbackId, result, null),
+                        (error) => editor._replyPluginApi(
+                            callbackId, null, String(error && error.message || error)
+                        )
+                    );
+                };
                 editor.spawnProcessWait = _wrapAsync("_spawnProcessWaitStart", "spawnProcessWait");
                 editor.getBufferText = _wrapAsync("_getBufferTextStart", "getBufferText");
                 editor.createCompositeBuffer = _wrapAsync("_createCompositeBufferStart", "createCompositeBuffer");
                 editor.getHighlights = _wrapAsync("_getHighlightsStart", "getHighlights");
+                editor.getSyntaxNodePath = _wrapAsync("_getSyntaxNodePathStart", "getSyntaxNodePath");
+                editor.getEnclosingSyntaxNode = _wrapAsync("_getEnclosingSyntaxNodeStart", "getEnclosingSyntaxNode");
+                editor.querySyntax = _wrapAsync("_querySyntaxStart", "querySyntax");
+                editor.createTerminal = _wrapAsync("_createTerminalStart", "createTerminal");
+                editor.getTerminalScrollback = _wrapAsync("_getTerminalScrollbackStart", "getTerminalScrollback");
                 editor.loadPlugin = _wrapAsync("_loadPluginStart", "loadPlugin");
                 editor.unloadPlugin = _wrapAsync("_unloadPluginStart", "unloadPlugin");
                 editor.reloadPlugin = _wrapAsync("_reloadPluginStart", "reloadPlugin");
                 editor.listPlugins = _wrapAsync("_listPluginsStart", "listPlugins");
 
+                // applyEdits resolves to the new buffer version; a stale
+                // expectedVersion becomes a StaleVersionError the plugin can
+                // catch, re-read the buffer and retry on
+                const applyEditsRaw = _wrapAsync("_applyEditsStart", "applyEdits");
+                editor.applyEdits = function(...args) {
+                    return applyEditsRaw(...args).then(function(result) {
+                        if (result.applied) {
+                            return result.version;
+                        }
+                        const error = new Error(
+                            `Buffer ${args[0]} changed (expected version ${args[1]}, now ${result.version})`
+                        );
+                        error.name = "StaleVersionError";
+                        error.bufferId = args[0];
+                        error.expectedVersion = args[1];
+                        error.currentVersion = result.version;
+                        throw error;
+                    });
+                };
+
                 // Wrapper for deleteTheme - wraps sync function in Promise
                 editor.deleteTheme = function(name) {
                     return new Promise(function(resolve, reject) {
@@ -2996,29 +4536,42 @@ impl QuickJsBackend {
         let wrapped_code = format!("(function() {{ {} }})();", code);
         let wrapped = wrapped_code.as_str();
 
-        context.with(|ctx| {
-            tracing::debug!("execute_js: executing plugin code for '{}'", plugin_name);
-
-            // Execute the plugin code with filename for better stack traces
-            let mut eval_options = rquickjs::context::EvalOptions::default();
-            eval_options.global = true;
-            eval_options.filename = Some(source_name.to_string());
-            let result = ctx
-                .eval_with_options::<(), _>(wrapped.as_bytes(), eval_options)
-                .map_err(|e| format_js_error(&ctx, e, source_name));
-
-            tracing::debug!(
-                "execute_js: plugin code execution finished for '{}', result: {:?}",
-                plugin_name,
-                result.is_ok()
-            );
+        self.guarded(plugin_name, || {
+            context.with(|ctx| {
+                tracing::debug!("execute_js: executing plugin code for '{}'", plugin_name);
+
+                // Execute the plugin code with filename for better stack traces
+                let mut eval_options = rquickjs::context::EvalOptions::default();
+                eval_options.global = true;
+                eval_options.filename = Some(source_name.to_string());
+                let result = ctx
+                    .eval_with_options::<(), _>(wrapped.as_bytes(), eval_options)
+                    .map_err(|e| format_js_error(&ctx, e, source_name));
+
+                tracing::debug!(
+                    "execute_js: plugin code execution finished for '{}', result: {:?}",
+                    plugin_name,
+                    result.is_ok()
+                );
 
-            result
+                result
+            })
         })
     }
 
     /// Emit an event to all registered handlers
     pub async fn emit(&mut self, event_name: &str, event_data: &serde_json::Value) -> Result<bool> {
+        self.emit_to(event_name, event_data, None).await
+    }
+
+    /// Emit an event to the handlers of one plugin, or of all plugins when
+    /// `plugin_name` is `None`
+    pub async fn emit_to(
+        &mut self,
+        event_name: &str,
+        event_data: &serde_json::Value,
+        plugin_name: Option<&str>,
+    ) -> Result<bool> {
         let _event_data_str = event_data.to_string();
         tracing::trace!("emit: event '{}' with data: {:?}", event_name, event_data);
 
@@ -3026,7 +4579,17 @@ impl QuickJsBackend {
         self.services
             .set_js_execution_state(format!("hook '{}'", event_name));
 
-        let handlers = self.event_handlers.borrow().get(event_name).cloned();
+        let handlers = self
+            .event_handlers
+            .borrow()
+            .get(event_name)
+            .map(|handlers| {
+                handlers
+                    .iter()
+                    .filter(|handler| plugin_name.is_none_or(|name| handler.plugin_name == name))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            });
 
         if let Some(handler_pairs) = handlers {
             if handler_pairs.is_empty() {
@@ -3071,13 +4634,20 @@ impl QuickJsBackend {
                         js_string_literal, handler_name, handler_name, handler_name, handler_name
                     );
 
-                    context.with(|ctx| {
-                        if let Err(e) = ctx.eval::<(), _>(code.as_bytes()) {
-                            log_js_error(&ctx, e, &format!("handler {}", handler_name));
-                        }
-                        // Run pending jobs to process any Promise continuations and catch errors
-                        run_pending_jobs_checked(&ctx, &format!("emit handler {}", handler_name));
+                    let started = Instant::now();
+                    self.guarded(&handler.plugin_name, || {
+                        context.with(|ctx| {
+                            if let Err(e) = ctx.eval::<(), _>(code.as_bytes()) {
+                                log_js_error(&ctx, e, &format!("handler {}", handler_name));
+                            }
+                            // Run pending jobs to process any Promise continuations and catch errors
+                            run_pending_jobs_checked(
+                                &ctx,
+                                &format!("emit handler {}", handler_name),
+                            );
+                        })
                     });
+                    self.record_hook_timing(event_name, &handler, started.elapsed());
                 }
             }
         }
@@ -3086,6 +4656,193 @@ impl QuickJsBackend {
         Ok(true)
     }
 
+    /// Run the handlers of a cancellable hook in order and collect their decision
+    ///
+    /// A handler returning `false` cancels the operation; the handlers after
+    /// it do not run. A handler returning an object replaces the hook's
+    /// replaceable fields it sets to strings, and later handlers see the new
+    /// values. Anything else, including a promise, lets the operation go ahead.
+    pub fn emit_cancellable(
+        &mut self,
+        hook_name: &str,
+        mut data: serde_json::Value,
+    ) -> HookOutcome {
+        let mut outcome = HookOutcome::default();
+        let handlers = self
+            .event_handlers
+            .borrow()
+            .get(hook_name)
+            .cloned()
+            .unwrap_or_default();
+        let fields = replaceable_hook_fields(hook_name);
+
+        self.services
+            .set_js_execution_state(format!("hook '{}'", hook_name));
+        for handler in handlers {
+            let context = self
+                .plugin_contexts
+                .borrow()
+                .get(&handler.plugin_name)
+                .cloned();
+            let Some(context) = context else {
+                continue;
+            };
+            let handler_name = &handler.handler_name;
+            let started = Instant::now();
+            let decision = self.guarded(&handler.plugin_name, || {
+                context.with(|ctx| {
+                    let call = || -> rquickjs::Result<serde_json::Value> {
+                        let handler_fn: Value = ctx.globals().get(handler_name.as_str())?;
+                        let Some(handler_fn) = handler_fn.as_function() else {
+                            return Ok(serde_json::Value::Null);
+                        };
+                        let arg = rquickjs_serde::to_value(ctx.clone(), &data).map_err(|e| {
+                            rquickjs::Error::new_from_js_message("json", "value", &e.to_string())
+                        })?;
+                        let result: Value = handler_fn.call((arg,))?;
+                        if result.as_promise().is_some() {
+                            return Ok(serde_json::Value::Null);
+                        }
+                        Ok(js_to_json(&ctx, result))
+                    };
+                    let decision = call().unwrap_or_else(|e| {
+                        log_js_error(&ctx, e, &format!("handler {}", handler_name));
+                        serde_json::Value::Null
+                    });
+                    run_pending_jobs_checked(&ctx, &format!("emit handler {}", handler_name));
+                    decision
+                })
+            });
+            self.record_hook_timing(hook_name, &handler, started.elapsed());
+
+            match decision {
+                serde_json::Value::Bool(false) => {
+                    outcome.cancelled_by = Some(handler.plugin_name.clone());
+                    break;
+                }
+                serde_json::Value::Object(changes) => {
+                    for field in fields {
+                        if let Some(value) = changes.get(*field).and_then(|v| v.as_str()) {
+                            data[*field] = value.into();
+                            outcome
+                                .replacements
+                                .insert(field.to_string(), value.to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.services.clear_js_execution_state();
+        outcome
+    }
+
+    fn record_hook_timing(&self, hook_name: &str, handler: &PluginHandler, elapsed: Duration) {
+        let key = (
+            hook_name.to_string(),
+            handler.plugin_name.clone(),
+            handler.handler_name.clone(),
+        );
+        let mut timings = self.hook_timings.borrow_mut();
+        let timing = timings.entry(key).or_default();
+        timing.calls += 1;
+        timing.total_time += elapsed;
+        timing.max_time = timing.max_time.max(elapsed);
+    }
+
+    /// All registered hook handlers with their call statistics, sorted by
+    /// hook and plugin
+    pub fn hook_handlers(&self) -> Vec<HookHandlerInfo> {
+        let timings = self.hook_timings.borrow();
+        let mut handlers: Vec<HookHandlerInfo> = self
+            .event_handlers
+            .borrow()
+            .iter()
+            .flat_map(|(hook_name, handlers)| {
+                handlers.iter().map(|handler| {
+                    let timing = timings
+                        .get(&(
+                            hook_name.clone(),
+                            handler.plugin_name.clone(),
+                            handler.handler_name.clone(),
+                        ))
+                        .copied()
+                        .unwrap_or_default();
+                    HookHandlerInfo {
+                        hook_name: hook_name.clone(),
+                        plugin_name: handler.plugin_name.clone(),
+                        handler_name: handler.handler_name.clone(),
+                        calls: timing.calls,
+                        total_time: timing.total_time,
+                        max_time: timing.max_time,
+                    }
+                })
+            })
+            .collect();
+        handlers.sort_by(|a, b| {
+            (&a.hook_name, &a.plugin_name, &a.handler_name).cmp(&(
+                &b.hook_name,
+                &b.plugin_name,
+                &b.handler_name,
+            ))
+        });
+        handlers
+    }
+
+    /// Evaluate a JavaScript or TypeScript snippet in a plugin's context
+    ///
+    /// The snippet runs at the top level of the context, so it sees the
+    /// plugin's globals (`editor`, handlers assigned to `globalThis`) but not
+    /// the variables local to the plugin's file. Returns a description of
+    /// the result; a promise that settles right away is unwrapped.
+    pub fn evaluate_in_plugin(&mut self, plugin_name: &str, code: &str) -> Result<String> {
+        let context = self
+            .plugin_contexts
+            .borrow()
+            .get(plugin_name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Plugin '{}' is not loaded", plugin_name))?;
+        let js_code = transpile_typescript(code, "console.ts")?;
+
+        self.services
+            .set_js_execution_state(format!("console eval in '{}'", plugin_name));
+        let result = self.guarded(plugin_name, || {
+            context.with(|ctx| {
+                let mut eval_options = rquickjs::context::EvalOptions::default();
+                eval_options.global = true;
+                eval_options.filename = Some("<console>".to_string());
+                let mut value = ctx
+                    .eval_with_options::<Value, _>(js_code.as_bytes(), eval_options)
+                    .map_err(|e| format_js_error(&ctx, e, "<console>"))?;
+
+                if let Some(promise) = value.as_promise().cloned() {
+                    // Mark the promise handled; its outcome is reported below
+                    let mark_handled: Function =
+                        ctx.eval("(function(p) { p.catch(function() {}); })")?;
+                    mark_handled.call::<_, ()>((promise.clone(),))?;
+                    while ctx.execute_pending_job() {}
+                    value = match promise.state() {
+                        rquickjs::promise::PromiseState::Pending => {
+                            return Ok("Promise { <pending> }".to_string());
+                        }
+                        _ => match promise.result::<Value>() {
+                            Some(Ok(resolved)) => resolved,
+                            Some(Err(e)) => return Err(format_js_error(&ctx, e, "<console>")),
+                            None => return Ok("Promise { <pending> }".to_string()),
+                        },
+                    };
+                } else {
+                    while ctx.execute_pending_job() {}
+                }
+
+                let describe: Function = ctx.eval(DESCRIBE_VALUE_JS)?;
+                Ok(describe.call::<_, String>((value,))?)
+            })
+        });
+        self.services.clear_js_execution_state();
+        result
+    }
+
     /// Check if any handlers are registered for an event
     pub fn has_handlers(&self, event_name: &str) -> bool {
         self.event_handlers
@@ -3143,14 +4900,17 @@ impl QuickJsBackend {
         );
 
         tracing::info!("start_action: evaluating JS code");
-        context.with(|ctx| {
-            if let Err(e) = ctx.eval::<rquickjs::Value, _>(code.as_bytes()) {
-                log_js_error(&ctx, e, &format!("action {}", action_name));
-            }
-            tracing::info!("start_action: running pending microtasks");
-            // Run any immediate microtasks
-            let count = run_pending_jobs_checked(&ctx, &format!("start_action {}", action_name));
-            tracing::info!("start_action: executed {} pending jobs", count);
+        self.guarded(&plugin_name, || {
+            context.with(|ctx| {
+                if let Err(e) = ctx.eval::<rquickjs::Value, _>(code.as_bytes()) {
+                    log_js_error(&ctx, e, &format!("action {}", action_name));
+                }
+                tracing::info!("start_action: running pending microtasks");
+                // Run any immediate microtasks
+                let count =
+                    run_pending_jobs_checked(&ctx, &format!("start_action {}", action_name));
+                tracing::info!("start_action: executed {} pending jobs", count);
+            })
         });
 
         tracing::info!("start_action: END '{}'", action_name);
@@ -3205,29 +4965,31 @@ impl QuickJsBackend {
             action = action_name
         );
 
-        context.with(|ctx| {
-            // Eval returns a Promise for the async IIFE, which we need to drive
-            match ctx.eval::<rquickjs::Value, _>(code.as_bytes()) {
-                Ok(value) => {
-                    // If it's a Promise, we need to drive the runtime to completion
-                    if value.is_object() {
-                        if let Some(obj) = value.as_object() {
-                            // Check if it's a Promise by looking for 'then' method
-                            if obj.get::<_, rquickjs::Function>("then").is_ok() {
-                                // Drive the runtime to process the promise
-                                // QuickJS processes promises synchronously when we call execute_pending_job
-                                run_pending_jobs_checked(
-                                    &ctx,
-                                    &format!("execute_action {} promise", action_name),
-                                );
+        self.guarded(&plugin_name, || {
+            context.with(|ctx| {
+                // Eval returns a Promise for the async IIFE, which we need to drive
+                match ctx.eval::<rquickjs::Value, _>(code.as_bytes()) {
+                    Ok(value) => {
+                        // If it's a Promise, we need to drive the runtime to completion
+                        if value.is_object() {
+                            if let Some(obj) = value.as_object() {
+                                // Check if it's a Promise by looking for 'then' method
+                                if obj.get::<_, rquickjs::Function>("then").is_ok() {
+                                    // Drive the runtime to process the promise
+                                    // QuickJS processes promises synchronously when we call execute_pending_job
+                                    run_pending_jobs_checked(
+                                        &ctx,
+                                        &format!("execute_action {} promise", action_name),
+                                    );
+                                }
                             }
                         }
                     }
+                    Err(e) => {
+                        log_js_error(&ctx, e, &format!("action {}", action_name));
+                    }
                 }
-                Err(e) => {
-                    log_js_error(&ctx, e, &format!("action {}", action_name));
-                }
-            }
+            })
         });
 
         Ok(())
@@ -3248,11 +5010,14 @@ impl QuickJsBackend {
         // Poll all plugin contexts
         let contexts = self.plugin_contexts.borrow().clone();
         for (name, context) in contexts {
-            context.with(|ctx| {
-                let count = run_pending_jobs_checked(&ctx, &format!("poll_event_loop {}", name));
-                if count > 0 {
-                    had_work = true;
-                }
+            self.guarded(&name, || {
+                context.with(|ctx| {
+                    let count =
+                        run_pending_jobs_checked(&ctx, &format!("poll_event_loop {}", name));
+                    if count > 0 {
+                        had_work = true;
+                    }
+                })
             });
         }
         had_work
@@ -3294,59 +5059,61 @@ impl QuickJsBackend {
             return;
         };
 
-        context.with(|ctx| {
-            // Parse JSON string to serde_json::Value
-            let json_value: serde_json::Value = match serde_json::from_str(result_json) {
-                Ok(v) => v,
-                Err(e) => {
-                    tracing::error!(
-                        "resolve_callback: failed to parse JSON for callback_id={}: {}",
-                        id,
-                        e
-                    );
-                    return;
-                }
-            };
+        self.guarded(&name, || {
+            context.with(|ctx| {
+                // Parse JSON string to serde_json::Value
+                let json_value: serde_json::Value = match serde_json::from_str(result_json) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        tracing::error!(
+                            "resolve_callback: failed to parse JSON for callback_id={}: {}",
+                            id,
+                            e
+                        );
+                        return;
+                    }
+                };
 
-            // Convert to JS value using rquickjs_serde
-            let js_value = match rquickjs_serde::to_value(ctx.clone(), &json_value) {
-                Ok(v) => v,
-                Err(e) => {
-                    tracing::error!(
+                // Convert to JS value using rquickjs_serde
+                let js_value = match rquickjs_serde::to_value(ctx.clone(), &json_value) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        tracing::error!(
                         "resolve_callback: failed to convert to JS value for callback_id={}: {}",
                         id,
                         e
                     );
-                    return;
-                }
-            };
+                        return;
+                    }
+                };
 
-            // Get _resolveCallback function from globalThis
-            let globals = ctx.globals();
-            let resolve_fn: rquickjs::Function = match globals.get("_resolveCallback") {
-                Ok(f) => f,
-                Err(e) => {
-                    tracing::error!(
-                        "resolve_callback: _resolveCallback not found for callback_id={}: {:?}",
-                        id,
-                        e
-                    );
-                    return;
-                }
-            };
+                // Get _resolveCallback function from globalThis
+                let globals = ctx.globals();
+                let resolve_fn: rquickjs::Function = match globals.get("_resolveCallback") {
+                    Ok(f) => f,
+                    Err(e) => {
+                        tracing::error!(
+                            "resolve_callback: _resolveCallback not found for callback_id={}: {:?}",
+                            id,
+                            e
+                        );
+                        return;
+                    }
+                };
 
-            // Call the function with callback_id (as u64) and the JS value
-            if let Err(e) = resolve_fn.call::<_, ()>((id, js_value)) {
-                log_js_error(&ctx, e, &format!("resolving callback {}", id));
-            }
+                // Call the function with callback_id (as u64) and the JS value
+                if let Err(e) = resolve_fn.call::<_, ()>((id, js_value)) {
+                    log_js_error(&ctx, e, &format!("resolving callback {}", id));
+                }
 
-            // IMPORTANT: Run pending jobs to process Promise continuations
-            let job_count = run_pending_jobs_checked(&ctx, &format!("resolve_callback {}", id));
-            tracing::info!(
-                "resolve_callback: executed {} pending jobs for callback_id={}",
-                job_count,
-                id
-            );
+                // IMPORTANT: Run pending jobs to process Promise continuations
+                let job_count = run_pending_jobs_checked(&ctx, &format!("resolve_callback {}", id));
+                tracing::info!(
+                    "resolve_callback: executed {} pending jobs for callback_id={}",
+                    job_count,
+                    id
+                );
+            })
         });
     }
 
@@ -3371,28 +5138,30 @@ impl QuickJsBackend {
             return;
         };
 
-        context.with(|ctx| {
-            // Get _rejectCallback function from globalThis
-            let globals = ctx.globals();
-            let reject_fn: rquickjs::Function = match globals.get("_rejectCallback") {
-                Ok(f) => f,
-                Err(e) => {
-                    tracing::error!(
-                        "reject_callback: _rejectCallback not found for callback_id={}: {:?}",
-                        id,
-                        e
-                    );
-                    return;
-                }
-            };
+        self.guarded(&name, || {
+            context.with(|ctx| {
+                // Get _rejectCallback function from globalThis
+                let globals = ctx.globals();
+                let reject_fn: rquickjs::Function = match globals.get("_rejectCallback") {
+                    Ok(f) => f,
+                    Err(e) => {
+                        tracing::error!(
+                            "reject_callback: _rejectCallback not found for callback_id={}: {:?}",
+                            id,
+                            e
+                        );
+                        return;
+                    }
+                };
 
-            // Call the function with callback_id (as u64) and error string
-            if let Err(e) = reject_fn.call::<_, ()>((id, error)) {
-                log_js_error(&ctx, e, &format!("rejecting callback {}", id));
-            }
+                // Call the function with callback_id (as u64) and error string
+                if let Err(e) = reject_fn.call::<_, ()>((id, error)) {
+                    log_js_error(&ctx, e, &format!("rejecting callback {}", id));
+                }
 
-            // IMPORTANT: Run pending jobs to process Promise continuations
-            run_pending_jobs_checked(&ctx, &format!("reject_callback {}", id));
+                // IMPORTANT: Run pending jobs to process Promise continuations
+                run_pending_jobs_checked(&ctx, &format!("reject_callback {}", id));
+            })
         });
     }
 }
@@ -3462,6 +5231,9 @@ mod tests {
         fn config_dir(&self) -> std::path::PathBuf {
             std::path::PathBuf::from("/tmp/config")
         }
+        fn data_dir(&self) -> std::path::PathBuf {
+            std::path::PathBuf::from("/tmp/data")
+        }
     }
 
     #[test]
@@ -3515,13 +5287,405 @@ mod tests {
             )
             .unwrap();
 
-        let cmd = rx.try_recv().unwrap();
-        match cmd {
-            PluginCommand::SetStatus { message } => {
-                assert_eq!(message, "Hello from test");
+        let cmd = rx.try_recv().unwrap();
+        match cmd {
+            PluginCommand::SetStatus { message } => {
+                assert_eq!(message, "Hello from test");
+            }
+            _ => panic!("Expected SetStatus command, got {:?}", cmd),
+        }
+    }
+
+    #[test]
+    fn test_api_status_bar_items() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.setStatusBarItem("branch", { text: "main", fg: "ui.status_bar_fg", alignment: "left", priority: 5 });
+            editor.setStatusBarItemText("branch", "dev");
+            editor.removeStatusBarItem("branch");
+            let threw = false;
+            try {
+                editor.setStatusBarItem("bad", { text: "x", alignment: "middle" });
+            } catch (e) {
+                threw = true;
+            }
+            if (!threw) throw new Error("expected unknown alignment to throw");
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let commands: Vec<_> = rx.try_iter().collect();
+        assert_eq!(commands.len(), 3);
+        match &commands[0] {
+            PluginCommand::SetStatusBarItem {
+                plugin_name,
+                id,
+                options,
+            } => {
+                assert_eq!(plugin_name, "test");
+                assert_eq!(id, "branch");
+                assert_eq!(options.text, "main");
+                assert_eq!(options.fg.as_deref(), Some("ui.status_bar_fg"));
+                assert_eq!(options.bg, None);
+                assert_eq!(options.alignment.as_deref(), Some("left"));
+                assert_eq!(options.priority, Some(5));
+            }
+            cmd => panic!("Expected SetStatusBarItem, got {:?}", cmd),
+        }
+        assert!(matches!(
+            &commands[1],
+            PluginCommand::SetStatusBarItemText { id, text, .. } if id == "branch" && text == "dev"
+        ));
+        assert!(matches!(
+            &commands[2],
+            PluginCommand::RemoveStatusBarItem { id, .. } if id == "branch"
+        ));
+    }
+
+    #[test]
+    fn test_api_spawn_interactive_process() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            const proc = editor.spawnInteractiveProcess("cat", ["-u"], {
+                cwd: "/tmp",
+                env: { LANG: "C" },
+                output: "chunks",
+            });
+            if (typeof proc.processId !== "number") throw new Error("missing processId");
+            proc.write("hello\n");
+            proc.closeStdin();
+            let threw = false;
+            try {
+                editor.spawnInteractiveProcess("cat", [], { output: "bytes" });
+            } catch (e) {
+                threw = true;
+            }
+            if (!threw) throw new Error("expected unknown output mode to throw");
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let commands: Vec<_> = rx.try_iter().collect();
+        assert_eq!(commands.len(), 3);
+        let id = match &commands[0] {
+            PluginCommand::SpawnInteractiveProcess {
+                plugin_name,
+                process_id,
+                command,
+                args,
+                options,
+                callback_id,
+            } => {
+                assert_eq!(plugin_name, "test");
+                assert_eq!(command, "cat");
+                assert_eq!(args, &["-u"]);
+                assert_eq!(options.cwd.as_deref(), Some("/tmp"));
+                assert_eq!(options.env.as_ref().unwrap()["LANG"], "C");
+                assert_eq!(options.output.as_deref(), Some("chunks"));
+                assert_eq!(callback_id.as_u64(), *process_id);
+                *process_id
+            }
+            cmd => panic!("Expected SpawnInteractiveProcess, got {:?}", cmd),
+        };
+        assert!(matches!(
+            &commands[1],
+            PluginCommand::WriteProcessStdin { plugin_name, process_id, data }
+                if plugin_name == "test" && *process_id == id && data == "hello\n"
+        ));
+        assert!(matches!(
+            &commands[2],
+            PluginCommand::CloseProcessStdin { plugin_name, process_id }
+                if plugin_name == "test" && *process_id == id
+        ));
+    }
+
+    #[test]
+    fn test_api_watch_path() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            const watch = editor.watchPath("/tmp/reports", { recursive: true, glob: "*.xml" });
+            if (typeof watch.watchId !== "number") throw new Error("missing watchId");
+            watch.close();
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let commands: Vec<_> = rx.try_iter().collect();
+        assert_eq!(commands.len(), 2);
+        let id = match &commands[0] {
+            PluginCommand::WatchPath {
+                watch_id,
+                path,
+                options,
+                ..
+            } => {
+                assert_eq!(path, "/tmp/reports");
+                assert_eq!(options.recursive, Some(true));
+                assert_eq!(options.glob.as_deref(), Some("*.xml"));
+                *watch_id
+            }
+            cmd => panic!("Expected WatchPath, got {:?}", cmd),
+        };
+        assert!(matches!(
+            &commands[1],
+            PluginCommand::UnwatchPath { watch_id, .. } if *watch_id == id
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_in_plugin() {
+        let (mut backend, _rx) = create_test_backend();
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            const hidden = 1;
+            globalThis.counter = 41;
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let eval = |backend: &mut QuickJsBackend, code: &str| {
+            backend
+                .evaluate_in_plugin("test", code)
+                .map_err(|e| e.to_string())
+        };
+        assert_eq!(eval(&mut backend, "counter + 1").unwrap(), "42");
+        assert_eq!(
+            eval(&mut backend, "const n: number = 2; ({ n, s: 'x' })").unwrap(),
+            "{\n  \"n\": 2,\n  \"s\": \"x\"\n}"
+        );
+        // Declarations persist between evaluations, like a REPL
+        assert_eq!(eval(&mut backend, "n * 10").unwrap(), "20");
+        assert_eq!(
+            eval(&mut backend, "Promise.resolve('done')").unwrap(),
+            "\"done\""
+        );
+        assert_eq!(
+            eval(&mut backend, "typeof editor.setStatus").unwrap(),
+            "\"function\""
+        );
+        assert!(eval(&mut backend, "hidden").unwrap_err().contains("hidden"));
+        assert!(eval(&mut backend, "Promise.reject(new Error('nope'))")
+            .unwrap_err()
+            .contains("nope"));
+        assert!(eval(&mut backend, "throw new Error('boom')")
+            .unwrap_err()
+            .contains("boom"));
+        assert!(backend.evaluate_in_plugin("missing", "1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plugin_api_calls_and_events() {
+        let (mut backend, rx) = create_test_backend();
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.exportApi({
+                add(a, b) { return a + b; },
+                async slow(name) { return { hello: name }; },
+                fail() { throw new Error("broken"); },
+                version: 2,
+            });
+            globalThis.onChanged = function(data) {
+                editor.setStatus(`changed ${data.count}`);
+            };
+            editor.on("provider:changed", "onChanged");
+        "#,
+                "provider.js",
+            )
+            .unwrap();
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.getPluginApi("provider").then(async (api) => {
+                const results = [Object.keys(api).sort().join(","), await api.add(2, 3)];
+                results.push((await api.slow("x")).hello);
+                try { await api.fail(); } catch (e) { results.push(e.message); }
+                editor.setStatus(results.join(" "));
+                editor.emitEvent("provider:changed", { count: 7 });
+            });
+            editor.getPluginApi("missing").catch((e) => editor.setStatus(e.message));
+            try { editor.emitEvent("after_file_save"); } catch (e) { editor.setStatus("rejected"); }
+        "#,
+                "consumer.js",
+            )
+            .unwrap();
+
+        backend.finish_startup();
+        for _ in 0..10 {
+            backend.dispatch_plugin_messages().await;
+            backend.poll_event_loop_once();
+        }
+        assert!(!backend.has_plugin_messages());
+
+        let statuses: Vec<String> = rx
+            .try_iter()
+            .filter_map(|cmd| match cmd {
+                PluginCommand::SetStatus { message } => Some(message),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                "rejected",
+                "Plugin 'missing' is not loaded",
+                "add,fail,slow 5 x broken",
+                "changed 7",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cancellable_hooks() {
+        let (mut backend, _rx) = create_test_backend();
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis.smartQuotes = function(data) {
+                if (data.text === '"') return { text: "\u201c", position: 0 };
+            };
+            editor.on("before_insert", "smartQuotes");
+        "#,
+                "quotes.js",
+            )
+            .unwrap();
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis.noBangs = function(data) { return data.text !== "!"; };
+            globalThis.seenText = function(data) { return { text: data.text + "|" }; };
+            globalThis.later = async function() { return false; };
+            editor.on("before_insert", "noBangs");
+            editor.on("before_insert", "seenText");
+            editor.on("before_insert", "later");
+        "#,
+                "guard.js",
+            )
+            .unwrap();
+
+        let insert =
+            |text: &str| serde_json::json!({ "buffer_id": 1, "position": 0, "text": text });
+        let outcome = backend.emit_cancellable("before_insert", insert("\""));
+        assert!(!outcome.is_cancelled());
+        // Later handlers see earlier replacements; other fields stay put
+        assert_eq!(outcome.replacement("text"), Some("\u{201c}|"));
+        assert_eq!(outcome.replacements.len(), 1);
+
+        let outcome = backend.emit_cancellable("before_insert", insert("!"));
+        assert_eq!(outcome.cancelled_by.as_deref(), Some("guard"));
+        assert_eq!(outcome.replacement("text"), None);
+
+        let outcome = backend.emit_cancellable("before_delete", insert("!"));
+        assert_eq!(outcome, HookOutcome::default());
+    }
+
+    #[tokio::test]
+    async fn test_hook_handlers_record_timings() {
+        let (mut backend, _rx) = create_test_backend();
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis.onSaved = function() {};
+            globalThis.onOpened = function() {};
+            editor.on("buffer_saved", "onSaved");
+            editor.on("buffer_opened", "onOpened");
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let data = serde_json::json!({});
+        backend.emit("buffer_saved", &data).await.unwrap();
+        backend.emit("buffer_saved", &data).await.unwrap();
+
+        let handlers = backend.hook_handlers();
+        assert_eq!(handlers.len(), 2);
+        assert_eq!(handlers[0].hook_name, "buffer_opened");
+        assert_eq!(handlers[0].calls, 0);
+        assert_eq!(handlers[1].hook_name, "buffer_saved");
+        assert_eq!(handlers[1].plugin_name, "test");
+        assert_eq!(handlers[1].handler_name, "onSaved");
+        assert_eq!(handlers[1].calls, 2);
+        assert!(handlers[1].max_time <= handlers[1].total_time);
+    }
+
+    #[test]
+    fn test_api_show_form() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.showForm({
+                id: "new-file",
+                title: "New File",
+                fields: [
+                    { id: "name", label: "Name", kind: "text", placeholder: "main.rs" },
+                    { id: "width", label: "Width", kind: "number", value: 80, min: 40, max: 120 },
+                    { id: "ext", label: "Type", kind: "dropdown", options: ["rs", "ts"], value: "ts" },
+                    { id: "tags", label: "Tags", kind: "textList", value: ["a", "b"] },
+                ],
+                buttons: [{ id: "create", label: "Create" }],
+            });
+            editor.closeForm("new-file");
+            for (const fields of [
+                [{ id: "x", label: "X", kind: "slider" }],
+                [{ id: "x", label: "X", kind: "dropdown" }],
+                [{ id: "x", label: "X", kind: "text" }, { id: "x", label: "Y", kind: "toggle" }],
+            ]) {
+                let threw = false;
+                try {
+                    editor.showForm({ id: "bad", title: "Bad", fields });
+                } catch (e) {
+                    threw = true;
+                }
+                if (!threw) throw new Error("expected invalid form to throw");
+            }
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let commands: Vec<_> = rx.try_iter().collect();
+        assert_eq!(commands.len(), 2);
+        match &commands[0] {
+            PluginCommand::ShowForm { options } => {
+                assert_eq!(options.id, "new-file");
+                assert_eq!(options.fields.len(), 4);
+                assert_eq!(options.fields[1].max, Some(120));
+                assert_eq!(options.fields[3].value, Some(serde_json::json!(["a", "b"])));
+                assert_eq!(options.buttons.as_ref().unwrap()[0].id, "create");
             }
-            _ => panic!("Expected SetStatus command, got {:?}", cmd),
+            cmd => panic!("Expected ShowForm, got {:?}", cmd),
         }
+        assert!(matches!(
+            &commands[1],
+            PluginCommand::CloseForm { form_id } if form_id == "new-file"
+        ));
     }
 
     #[test]
@@ -3765,6 +5929,374 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_runaway_action_is_interrupted() {
+        let (mut backend, rx) = create_test_backend();
+        backend.set_limits(PluginLimits {
+            call_timeout: Some(Duration::from_millis(100)),
+            memory_limit: None,
+            storage_quota: None,
+            hook_timeout: None,
+        });
+
+        backend.registered_actions.borrow_mut().insert(
+            "spin".to_string(),
+            PluginHandler {
+                plugin_name: "test".to_string(),
+                handler_name: "spin".to_string(),
+            },
+        );
+        backend
+            .execute_js(
+                "globalThis.spin = function() { while (true) {} };",
+                "test.js",
+            )
+            .unwrap();
+        assert!(backend.take_tripped_plugins().is_empty());
+
+        // Returns instead of hanging the plugin thread
+        backend.execute_action("spin").await.unwrap();
+
+        let tripped = backend.take_tripped_plugins();
+        assert_eq!(tripped.len(), 1);
+        assert_eq!(tripped[0].0, "test");
+        assert!(tripped[0].1.contains("100 ms"), "reason: {}", tripped[0].1);
+
+        while rx.try_recv().is_ok() {}
+        backend.disable_plugin("test", &tripped[0].1);
+        assert!(!backend.registered_actions.borrow().contains_key("spin"));
+        assert!(!backend.plugin_contexts.borrow().contains_key("test"));
+        match rx.try_recv().unwrap() {
+            PluginCommand::PluginDisabled { name, reason } => {
+                assert_eq!(name, "test");
+                assert_eq!(reason, tripped[0].1);
+            }
+            cmd => panic!("Expected PluginDisabled, got {:?}", cmd),
+        }
+    }
+
+    #[test]
+    fn test_nested_watched_call_restores_outer_deadline() {
+        let watchdog = Watchdog::default();
+        watchdog.call_timeout.set(Some(Duration::ZERO));
+
+        let outer = watchdog.begin("outer");
+        assert!(outer.is_none());
+        let inner = watchdog.begin("inner");
+        assert_eq!(inner.as_ref().map(|(name, _)| name.as_str()), Some("outer"));
+        watchdog.end(inner);
+
+        // The rest of the outer call is still watched
+        assert!(watchdog.should_interrupt());
+        assert_eq!(watchdog.tripped.borrow()[0].0, "outer");
+        watchdog.end(outer);
+        assert!(!watchdog.should_interrupt());
+    }
+
+    #[test]
+    fn test_leaking_plugin_exceeds_memory_limit() {
+        let (mut backend, _rx) = create_test_backend();
+        backend.set_limits(PluginLimits {
+            call_timeout: None,
+            memory_limit: Some(32 * 1024 * 1024),
+            storage_quota: None,
+            hook_timeout: None,
+        });
+
+        // The allocation failure ends the loop; the arrays stay reachable
+        let _ = backend.execute_js(
+            r#"
+            globalThis.leak = [];
+            try {
+                while (true) { leak.push(new Array(4096).fill(leak.length)); }
+            } catch (e) {}
+        "#,
+            "leaky.js",
+        );
+
+        let tripped = backend.take_tripped_plugins();
+        assert_eq!(tripped.len(), 1);
+        assert_eq!(tripped[0].0, "leaky");
+        assert!(tripped[0].1.contains("32 MB"), "reason: {}", tripped[0].1);
+    }
+
+    #[test]
+    fn test_plugin_limits_from_config() {
+        let limits = PluginLimits::from(&PluginRuntimeConfig {
+            call_timeout_ms: 250,
+            memory_limit_mb: 0,
+            storage_quota_kb: 64,
+            hook_timeout_ms: 0,
+        });
+        assert_eq!(limits.call_timeout, Some(Duration::from_millis(250)));
+        assert_eq!(limits.memory_limit, None);
+        assert_eq!(limits.storage_quota, Some(64 * 1024));
+        assert_eq!(limits.hook_timeout, None);
+    }
+
+    #[test]
+    fn test_language_providers() {
+        use fresh_core::api::{LanguageFeature, LanguageFeatureRequest};
+
+        let (mut backend, rx) = create_test_backend();
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis.dslComplete = (req) => [{ label: "key_" + req.line }];
+            globalThis.dslHover = async (req) => "**" + req.language + "**";
+            globalThis.dslDefinition = () => { throw new Error("boom"); };
+            editor.registerLanguageProvider("completion", "dsl", "dslComplete");
+            editor.registerLanguageProvider("hover", "dsl", "dslHover");
+            editor.registerLanguageProvider("definition", "dsl", "dslDefinition");
+            globalThis._unknownThrows = (() => {
+                try { editor.registerLanguageProvider("rename", "dsl", "x"); return false; }
+                catch (e) { return true; }
+            })();
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let registered: Vec<(LanguageFeature, String)> = rx
+            .try_iter()
+            .filter_map(|cmd| match cmd {
+                PluginCommand::RegisterLanguageProvider {
+                    feature, language, ..
+                } => Some((feature, language)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            registered,
+            vec![
+                (LanguageFeature::Completion, "dsl".to_string()),
+                (LanguageFeature::Hover, "dsl".to_string()),
+                (LanguageFeature::Definition, "dsl".to_string()),
+            ]
+        );
+
+        let request = |feature, language: &str| LanguageFeatureRequest {
+            feature,
+            language: language.to_string(),
+            buffer_id: BufferId(1),
+            path: PathBuf::from("/w/app.dsl"),
+            line: 3,
+            character: 0,
+        };
+        let mut result_of = |request_id, request: LanguageFeatureRequest| {
+            backend.request_language_feature(request_id, &request);
+            rx.try_iter().find_map(|cmd| match cmd {
+                PluginCommand::LanguageProviderResult {
+                    plugin_name,
+                    request_id: id,
+                    result,
+                } if id == request_id => {
+                    assert_eq!(plugin_name, "test");
+                    Some(result)
+                }
+                _ => None,
+            })
+        };
+
+        assert_eq!(
+            result_of(1, request(LanguageFeature::Completion, "dsl")),
+            Some(serde_json::json!([{ "label": "key_3" }]))
+        );
+        assert_eq!(
+            result_of(2, request(LanguageFeature::Hover, "dsl")),
+            Some(serde_json::json!("**dsl**"))
+        );
+        // A failing provider still answers, with null
+        assert_eq!(
+            result_of(3, request(LanguageFeature::Definition, "dsl")),
+            Some(serde_json::Value::Null)
+        );
+        // Nobody provides completions for other languages
+        assert_eq!(
+            result_of(4, request(LanguageFeature::Completion, "rust")),
+            None
+        );
+
+        // Unloading a plugin unregisters its providers
+        backend.remove_language_providers("test");
+        let unregistered = rx
+            .try_iter()
+            .filter(|cmd| matches!(cmd, PluginCommand::UnregisterLanguageProvider { .. }))
+            .count();
+        assert_eq!(unregistered, 3);
+        backend.request_language_feature(5, &request(LanguageFeature::Completion, "dsl"));
+        assert!(rx.try_recv().is_err());
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let unknown_throws: bool = ctx.globals().get("_unknownThrows").unwrap();
+                assert!(unknown_throws);
+            });
+    }
+
+    #[test]
+    fn test_api_storage() {
+        let (mut backend, _rx) = create_test_backend();
+        let dir = tempfile::tempdir().unwrap();
+        *backend.storage.borrow_mut() = PluginStorage::new(dir.path());
+        backend.set_limits(PluginLimits {
+            call_timeout: None,
+            memory_limit: None,
+            storage_quota: Some(256),
+            hook_timeout: None,
+        });
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._missing = editor.storageGet("count");
+            editor.storageSet("count", 1);
+            editor.storageSet("count", { n: 2, tags: ["a"] }, "workspace");
+            globalThis._global = editor.storageGet("count");
+            globalThis._workspace = editor.storageGet("count", "workspace").n;
+            globalThis._keys = editor.storageKeys().join(",");
+            globalThis._deleted = editor.storageDelete("count");
+            try { editor.storageSet("big", "x".repeat(1000)); globalThis._quota = "stored"; }
+            catch (e) { globalThis._quota = String(e); }
+            try { editor.storageGet("count", "session"); globalThis._scope = "ok"; }
+            catch (e) { globalThis._scope = String(e); }
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let global = ctx.globals();
+                assert!(global.get::<_, Value>("_missing").unwrap().is_undefined());
+                assert_eq!(global.get::<_, i32>("_global").unwrap(), 1);
+                assert_eq!(global.get::<_, i32>("_workspace").unwrap(), 2);
+                assert_eq!(global.get::<_, String>("_keys").unwrap(), "count");
+                assert!(global.get::<_, bool>("_deleted").unwrap());
+                let quota: String = global.get("_quota").unwrap();
+                assert!(quota.contains("quota"), "{}", quota);
+                let scope: String = global.get("_scope").unwrap();
+                assert!(scope.contains("Unknown storage scope"), "{}", scope);
+            });
+
+        // The workspace value was persisted for the plugin
+        let mut storage = PluginStorage::new(dir.path());
+        let workspace = backend.state_snapshot.read().unwrap().working_dir.clone();
+        assert_eq!(
+            storage.get("test", StorageScope::Workspace, &workspace, "count"),
+            Some(serde_json::json!({ "n": 2, "tags": ["a"] }))
+        );
+    }
+
+    #[test]
+    fn test_restricted_plugin_is_held_to_its_capabilities() {
+        let (mut backend, rx) = create_test_backend();
+        let dir = tempfile::tempdir().unwrap();
+        let allowed = dir.path().join("allowed.txt");
+        let secret = dir.path().join("secret.txt");
+        std::fs::write(&allowed, "ok").unwrap();
+        std::fs::write(&secret, "key").unwrap();
+
+        backend.restrict_plugin(
+            "test",
+            &PluginCapabilities {
+                read: vec![allowed.to_string_lossy().to_string()],
+                spawn: vec!["git".to_string()],
+                ..Default::default()
+            },
+            &dir.path().join("plugin"),
+        );
+
+        let code = format!(
+            r#"
+            const editor = getEditor();
+            const denied = (f) => {{
+                try {{ f(); return false; }}
+                catch (e) {{ return String(e).includes("Permission denied"); }}
+            }};
+            globalThis._allowed = editor.readFile({allowed:?});
+            globalThis._secretDenied = denied(() => editor.readFile({secret:?}));
+            globalThis._writeDenied = denied(() => editor.writeFile({allowed:?}, "x"));
+            globalThis._envDenied = denied(() => editor.getEnv("HOME"));
+            globalThis._clipboardDenied = denied(() => editor.setClipboard("x"));
+            globalThis._spawnDenied = denied(() => editor._spawnProcessStart("sh", [], ""));
+            globalThis._watchDenied = denied(() => editor.watchPath({secret:?}));
+            globalThis._openDenied = denied(() => editor.openFile({secret:?}, null, null));
+            globalThis._openSplitDenied = denied(() => editor.openFileInSplit(1, {secret:?}, 1, 1));
+            globalThis._actionDenied = denied(() => editor.executeAction("quit"));
+            globalThis._actionsDenied = denied(() => editor.executeActions([{{ action: "quit" }}]));
+            globalThis._spawnId = editor._spawnProcessStart("git", ["status"], "");
+        "#,
+            allowed = allowed.to_string_lossy(),
+            secret = secret.to_string_lossy(),
+        );
+        backend.execute_js(&code, "test.js").unwrap();
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let global = ctx.globals();
+                let read: Option<String> = global.get("_allowed").unwrap();
+                assert_eq!(read, Some("ok".to_string()));
+                for name in [
+                    "_secretDenied",
+                    "_writeDenied",
+                    "_envDenied",
+                    "_clipboardDenied",
+                    "_spawnDenied",
+                    "_watchDenied",
+                    "_openDenied",
+                    "_openSplitDenied",
+                    "_actionDenied",
+                    "_actionsDenied",
+                ] {
+                    let denied: bool = global.get(name).unwrap();
+                    assert!(denied, "{} was not denied", name);
+                }
+            });
+        assert_eq!(std::fs::read_to_string(&allowed).unwrap(), "ok");
+
+        // Only the allowed command was sent to the editor
+        let commands: Vec<_> = rx.try_iter().collect();
+        assert!(!commands.iter().any(|c| matches!(
+            c,
+            PluginCommand::SetClipboard { .. } | PluginCommand::WatchPath { .. }
+        )));
+        let spawned: Vec<_> = commands
+            .iter()
+            .filter_map(|c| match c {
+                PluginCommand::SpawnProcess { command, .. } => Some(command.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(spawned, vec!["git"]);
+
+        // Lifting the restriction restores the full API
+        backend.unrestrict_plugin("test");
+        backend
+            .execute_js(r#"getEditor().setClipboard("x");"#, "test.js")
+            .unwrap();
+        assert!(rx
+            .try_iter()
+            .any(|c| matches!(c, PluginCommand::SetClipboard { .. })));
+    }
+
     #[tokio::test]
     async fn test_execute_action_with_registered_handler() {
         let (mut backend, rx) = create_test_backend();
@@ -3999,6 +6531,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_api_set_selections() {
+        use fresh_core::api::{SelectionKind, SelectionSpec};
+
+        let (mut backend, rx) = create_test_backend();
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._emptyAccepted = editor.setSelections([]);
+            editor.setSelections([
+                { position: 4 },
+                { anchor: 10, position: 14, mode: "block" },
+            ]);
+            globalThis._badFieldThrows = (() => {
+                try { editor.setSelections([{ pos: 1 }]); return false; }
+                catch (e) { return true; }
+            })();
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let commands: Vec<_> = rx.try_iter().collect();
+        assert_eq!(commands.len(), 1);
+        match &commands[0] {
+            PluginCommand::SetSelections { selections } => assert_eq!(
+                selections,
+                &vec![
+                    SelectionSpec {
+                        anchor: None,
+                        position: 4,
+                        mode: None,
+                    },
+                    SelectionSpec {
+                        anchor: Some(10),
+                        position: 14,
+                        mode: Some(SelectionKind::Block),
+                    },
+                ]
+            ),
+            cmd => panic!("Expected SetSelections, got {:?}", cmd),
+        }
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let global = ctx.globals();
+                assert!(!global.get::<_, bool>("_emptyAccepted").unwrap());
+                assert!(global.get::<_, bool>("_badFieldThrows").unwrap());
+            });
+    }
+
     #[test]
     fn test_api_get_cursor_position_from_state() {
         let (tx, _rx) = mpsc::channel();
@@ -4201,6 +6790,188 @@ mod tests {
             });
     }
 
+    #[test]
+    fn test_api_apply_edits_stale_version_error() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._outcomes = [];
+            const edits = [{ start: 0, end: 3, text: "let" }, { start: 10, end: 10, text: ";" }];
+            editor.applyEdits(1, 7, edits, "Rename").then(
+                (version) => globalThis._outcomes.push("applied " + version),
+                (e) => globalThis._outcomes.push("error"),
+            );
+            editor.applyEdits(1, 7, edits).then(
+                (version) => globalThis._outcomes.push("applied " + version),
+                (e) => globalThis._outcomes.push(e.name + " " + e.expectedVersion + "->" + e.currentVersion),
+            );
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let ids: Vec<JsCallbackId> = rx
+            .try_iter()
+            .map(|cmd| match cmd {
+                PluginCommand::ApplyEdits {
+                    buffer_id,
+                    expected_version,
+                    edits,
+                    label,
+                    callback_id,
+                } => {
+                    assert_eq!(buffer_id.0, 1);
+                    assert_eq!(expected_version, 7);
+                    assert_eq!(edits.len(), 2);
+                    assert_eq!(edits[1].text, ";");
+                    assert!(label.is_none() || label.as_deref() == Some("Rename"));
+                    callback_id
+                }
+                cmd => panic!("Expected ApplyEdits, got {:?}", cmd),
+            })
+            .collect();
+        assert_eq!(ids.len(), 2);
+
+        backend.resolve_callback(ids[0], r#"{"applied":true,"version":9}"#);
+        backend.resolve_callback(ids[1], r#"{"applied":false,"version":8}"#);
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let outcomes: Vec<String> = ctx.globals().get("_outcomes").unwrap();
+                assert_eq!(outcomes, vec!["applied 9", "StaleVersionError 7->8"]);
+            });
+    }
+
+    #[test]
+    fn test_api_query_syntax() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            globalThis._outcomes = [];
+            editor.querySyntax(1, "(identifier) @id").then(
+                (captures) => globalThis._outcomes.push(captures.map(c => c.name + ":" + c.start).join(",")),
+            );
+            editor.querySyntax(1, "(oops", 10).catch(
+                (e) => globalThis._outcomes.push(e.message),
+            );
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let ids: Vec<JsCallbackId> = rx
+            .try_iter()
+            .map(|cmd| match cmd {
+                PluginCommand::QuerySyntax {
+                    buffer_id,
+                    query,
+                    range,
+                    callback_id,
+                } => {
+                    assert_eq!(buffer_id.0, 1);
+                    if query == "(oops" {
+                        assert_eq!(range, Some(10..usize::MAX));
+                    } else {
+                        assert_eq!(range, None);
+                    }
+                    callback_id
+                }
+                cmd => panic!("Expected QuerySyntax, got {:?}", cmd),
+            })
+            .collect();
+        assert_eq!(ids.len(), 2);
+
+        backend.resolve_callback(
+            ids[0],
+            r#"[{"name":"id","kind":"identifier","start":3,"end":7}]"#,
+        );
+        backend.reject_callback(ids[1], "Invalid query: unexpected end");
+
+        backend
+            .plugin_contexts
+            .borrow()
+            .get("test")
+            .unwrap()
+            .clone()
+            .with(|ctx| {
+                let outcomes: Vec<String> = ctx.globals().get("_outcomes").unwrap();
+                assert_eq!(outcomes, vec!["id:3", "Invalid query: unexpected end"]);
+            });
+    }
+
+    #[test]
+    fn test_api_create_terminal() {
+        let (mut backend, rx) = create_test_backend();
+
+        backend
+            .execute_js(
+                r#"
+            const editor = getEditor();
+            editor.createTerminal({
+                command: "python3",
+                args: ["-i"],
+                env: { PYTHONUNBUFFERED: "1" },
+                direction: "vertical",
+            }).then((term) => {
+                globalThis._terminal = term.terminalId;
+                editor.sendTerminalInput(term.terminalId, "1 + 1\r");
+            });
+        "#,
+                "test.js",
+            )
+            .unwrap();
+
+        let callback_id = match rx.try_recv() {
+            Ok(PluginCommand::CreateTerminal {
+                plugin_name,
+                options,
+                callback_id,
+            }) => {
+                assert_eq!(plugin_name, "test");
+                assert_eq!(options.command.as_deref(), Some("python3"));
+                assert_eq!(options.args, Some(vec!["-i".to_string()]));
+                assert_eq!(
+                    options
+                        .env
+                        .unwrap()
+                        .get("PYTHONUNBUFFERED")
+                        .map(String::as_str),
+                    Some("1")
+                );
+                assert_eq!(options.direction.as_deref(), Some("vertical"));
+                assert_eq!(options.focus, None);
+                callback_id
+            }
+            other => panic!("Expected CreateTerminal, got {:?}", other),
+        };
+
+        backend.resolve_callback(callback_id, r#"{"terminalId":3,"bufferId":7,"splitId":2}"#);
+
+        match rx.try_recv() {
+            Ok(PluginCommand::SendTerminalInput {
+                plugin_name,
+                terminal_id,
+                data,
+            }) => {
+                assert_eq!(plugin_name, "test");
+                assert_eq!(terminal_id, TerminalId(3));
+                assert_eq!(data, "1 + 1\r");
+            }
+            other => panic!("Expected SendTerminalInput, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_plugin_translation() {
         let (mut backend, _rx) = create_test_backend();
@@ -4700,6 +7471,7 @@ mod tests {
                     path: Some(PathBuf::from("/test1.txt")),
                     modified: false,
                     length: 100,
+                    version: 0,
                 },
             );
             state.buffers.insert(
@@ -4709,6 +7481,7 @@ mod tests {
                     path: Some(PathBuf::from("/test2.txt")),
                     modified: true,
                     length: 200,
+                    version: 0,
                 },
             );
         }
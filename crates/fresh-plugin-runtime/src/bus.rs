@@ -0,0 +1,311 @@
+//! Plugin Bus: exported plugin APIs and plugin-defined events
+//!
+//! A plugin exports an API object with `editor.exportApi(api)`, under its own
+//! name. Other plugins obtain it with `await editor.getPluginApi(name)` and
+//! call its methods, which always return promises. Plugins can also publish
+//! their own events with `editor.emitEvent("git:changed", data)`; any plugin
+//! subscribes to them with `editor.on`, like a hook.
+//!
+//! Plugin code runs in one context at a time, so nothing here calls from one
+//! plugin straight into another: requests are queued and the plugin thread
+//! delivers them between other work (see
+//! `QuickJsBackend::dispatch_plugin_messages`). Arguments, results and event
+//! data are copied as JSON.
+//!
+//! Packages declare the plugins they rely on in their `package.json`:
+//!
+//! ```json
+//! {
+//!   "name": "my-plugin",
+//!   "type": "plugin",
+//!   "fresh": {
+//!     "dependencies": ["git_log"]
+//!   }
+//! }
+//! ```
+//!
+//! A package is only loaded once all of its dependencies are, so it can ask
+//! for their APIs right away.
+
+use crate::capabilities::{package_root, MANIFEST_FILE};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Read the plugins a plugin package depends on from its manifest
+///
+/// Plugins without a manifest, or whose manifest lists none, depend on nothing.
+pub fn declared_dependencies(plugin_path: &Path) -> Vec<String> {
+    let Some(content) = package_root(plugin_path)
+        .and_then(|root| std::fs::read_to_string(root.join(MANIFEST_FILE)).ok())
+    else {
+        return Vec::new();
+    };
+    // An unparseable manifest is reported when its capabilities are read
+    let Ok(manifest) = serde_json::from_str::<Value>(&content) else {
+        return Vec::new();
+    };
+    manifest
+        .pointer("/fresh/dependencies")
+        .and_then(Value::as_array)
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Check that an event name is namespaced (`"namespace:event"`), so plugin
+/// events never collide with the editor's own hooks
+pub fn is_plugin_event_name(name: &str) -> bool {
+    matches!(name.split_once(':'), Some((namespace, event)) if !namespace.is_empty() && !event.is_empty())
+}
+
+/// Work for the plugin thread to deliver
+#[derive(Debug, Clone, PartialEq)]
+pub enum BusMessage {
+    /// Run a method of `target`'s exported API; the answer settles the
+    /// caller's `callback_id`
+    Call {
+        callback_id: u64,
+        target: String,
+        method: String,
+        args: Value,
+    },
+    /// Settle a caller's pending promise
+    Reply {
+        callback_id: u64,
+        result: Result<Value, String>,
+    },
+    /// Run the handlers subscribed to a plugin-defined event
+    Event { name: String, data: Value },
+}
+
+/// Exported APIs and the messages waiting to be delivered between plugins
+#[derive(Debug, Default)]
+pub struct PluginBus {
+    /// Method names of each plugin's exported API
+    exports: HashMap<String, Vec<String>>,
+    /// `getPluginApi` requests waiting for their plugin to export: (plugin, callback)
+    waiting: Vec<(String, u64)>,
+    /// API calls being run by their plugin: callback -> plugin
+    in_flight: HashMap<u64, String>,
+    queue: Vec<BusMessage>,
+    /// Set once the editor has loaded its plugins; from then on asking for
+    /// a plugin that is not loaded fails
+    startup_done: bool,
+}
+
+impl PluginBus {
+    /// Record the API `plugin` exports, answering anyone waiting for it
+    pub fn export(&mut self, plugin: &str, methods: Vec<String>) {
+        let answer = Value::from(methods.clone());
+        self.exports.insert(plugin.to_string(), methods);
+        let (ready, waiting) = std::mem::take(&mut self.waiting)
+            .into_iter()
+            .partition(|(target, _)| target == plugin);
+        self.waiting = waiting;
+        for (_, callback_id) in ready {
+            self.reply(callback_id, Ok(answer.clone()));
+        }
+    }
+
+    /// Ask for `target`'s API, answered with its method names once exported
+    pub fn request_api(&mut self, target: &str, callback_id: u64) {
+        if let Some(methods) = self.exports.get(target) {
+            let answer = Value::from(methods.clone());
+            self.reply(callback_id, Ok(answer));
+        } else {
+            self.waiting.push((target.to_string(), callback_id));
+        }
+    }
+
+    /// Queue a call to a method of `target`'s API
+    pub fn call(&mut self, callback_id: u64, target: &str, method: &str, args: Value) {
+        self.queue.push(BusMessage::Call {
+            callback_id,
+            target: target.to_string(),
+            method: method.to_string(),
+            args,
+        });
+    }
+
+    /// Check whether `target` exports `method`, and if so mark the call as
+    /// being run by `target`
+    pub fn start_call(&mut self, callback_id: u64, target: &str, method: &str) -> bool {
+        let exported = self
+            .exports
+            .get(target)
+            .is_some_and(|methods| methods.iter().any(|m| m == method));
+        if exported {
+            self.in_flight.insert(callback_id, target.to_string());
+        }
+        exported
+    }
+
+    /// Answer a call `plugin` was running; ignored for calls it was not given
+    pub fn finish_call(&mut self, plugin: &str, callback_id: u64, result: Result<Value, String>) {
+        if self.in_flight.get(&callback_id).map(String::as_str) == Some(plugin) {
+            self.in_flight.remove(&callback_id);
+            self.reply(callback_id, result);
+        }
+    }
+
+    /// Queue a plugin-defined event
+    pub fn emit(&mut self, name: &str, data: Value) {
+        self.queue.push(BusMessage::Event {
+            name: name.to_string(),
+            data,
+        });
+    }
+
+    pub fn reply(&mut self, callback_id: u64, result: Result<Value, String>) {
+        self.queue.push(BusMessage::Reply {
+            callback_id,
+            result,
+        });
+    }
+
+    /// Take the queued messages, oldest first
+    pub fn take_messages(&mut self) -> Vec<BusMessage> {
+        std::mem::take(&mut self.queue)
+    }
+
+    pub fn has_messages(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    /// Withdraw a plugin's API; calls it was running and requests waiting
+    /// for it fail
+    pub fn remove_plugin(&mut self, plugin: &str) {
+        self.exports.remove(plugin);
+        let gone = format!("Plugin '{}' was unloaded", plugin);
+        let (stopped, in_flight) = std::mem::take(&mut self.in_flight)
+            .into_iter()
+            .partition::<HashMap<_, _>, _>(|(_, target)| target == plugin);
+        self.in_flight = in_flight;
+        let (failed, waiting) = std::mem::take(&mut self.waiting)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(target, _)| target == plugin);
+        self.waiting = waiting;
+        let mut callbacks: Vec<u64> = stopped
+            .into_keys()
+            .chain(failed.into_iter().map(|(_, id)| id))
+            .collect();
+        callbacks.sort_unstable();
+        for callback_id in callbacks {
+            self.reply(callback_id, Err(gone.clone()));
+        }
+    }
+
+    /// Note that the editor has loaded its plugins: from now on, nobody
+    /// waits for a plugin that is not loaded
+    pub fn finish_startup(&mut self) {
+        self.startup_done = true;
+    }
+
+    /// After startup, fail the requests for plugins that are not loaded
+    pub fn fail_missing(&mut self, is_loaded: impl Fn(&str) -> bool) {
+        if !self.startup_done {
+            return;
+        }
+        let (failed, waiting) = std::mem::take(&mut self.waiting)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(target, _)| !is_loaded(target));
+        self.waiting = waiting;
+        for (target, callback_id) in failed {
+            self.reply(
+                callback_id,
+                Err(format!("Plugin '{}' is not loaded", target)),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn reply(callback_id: u64, result: Result<Value, String>) -> BusMessage {
+        BusMessage::Reply {
+            callback_id,
+            result,
+        }
+    }
+
+    #[test]
+    fn test_declared_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = dir.path().join("my-plugin.ts");
+        assert!(declared_dependencies(&entry).is_empty());
+
+        std::fs::write(
+            dir.path().join(MANIFEST_FILE),
+            r#"{"name": "my-plugin", "fresh": {"dependencies": ["git_log", "finder"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(declared_dependencies(&entry), vec!["git_log", "finder"]);
+    }
+
+    #[test]
+    fn test_plugin_event_names() {
+        assert!(is_plugin_event_name("git:status_changed"));
+        assert!(!is_plugin_event_name("after_file_save"));
+        assert!(!is_plugin_event_name(":changed"));
+        assert!(!is_plugin_event_name("git:"));
+    }
+
+    #[test]
+    fn test_api_requests_wait_for_export() {
+        let mut bus = PluginBus::default();
+        bus.request_api("git_log", 1);
+        bus.fail_missing(|_| false);
+        assert!(!bus.has_messages());
+
+        bus.export("git_log", vec!["log".into()]);
+        bus.request_api("git_log", 2);
+        assert_eq!(
+            bus.take_messages(),
+            vec![reply(1, Ok(json!(["log"]))), reply(2, Ok(json!(["log"])))]
+        );
+
+        // After startup, plugins that are not loaded never will be
+        bus.request_api("missing", 3);
+        bus.request_api("slow", 4);
+        bus.finish_startup();
+        bus.fail_missing(|name| name == "slow");
+        assert_eq!(
+            bus.take_messages(),
+            vec![reply(3, Err("Plugin 'missing' is not loaded".into()))]
+        );
+    }
+
+    #[test]
+    fn test_calls_are_answered_by_their_plugin_only() {
+        let mut bus = PluginBus::default();
+        bus.export("git_log", vec!["log".into()]);
+        assert!(!bus.start_call(1, "git_log", "push"));
+        assert!(bus.start_call(2, "git_log", "log"));
+
+        bus.finish_call("intruder", 2, Ok(json!("forged")));
+        assert!(!bus.has_messages());
+        bus.finish_call("git_log", 2, Ok(json!("abc")));
+        bus.finish_call("git_log", 2, Ok(json!("again")));
+        assert_eq!(bus.take_messages(), vec![reply(2, Ok(json!("abc")))]);
+
+        // Unloading fails what the plugin was running or was waited for by
+        assert!(bus.start_call(3, "git_log", "log"));
+        bus.remove_plugin("git_log");
+        bus.request_api("git_log", 4);
+        bus.remove_plugin("git_log");
+        let gone = || Err("Plugin 'git_log' was unloaded".to_string());
+        assert_eq!(
+            bus.take_messages(),
+            vec![reply(3, gone()), reply(4, gone())]
+        );
+    }
+}
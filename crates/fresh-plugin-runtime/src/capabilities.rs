@@ -0,0 +1,393 @@
+//! Plugin Capabilities: what installed plugin packages may do
+//!
+//! Plugins installed as packages declare the capabilities they need in their
+//! `package.json` manifest:
+//!
+//! ```json
+//! {
+//!   "name": "my-plugin",
+//!   "type": "plugin",
+//!   "fresh": {
+//!     "capabilities": {
+//!       "read": ["${workspace}"],
+//!       "write": ["${workspace}/.my-plugin"],
+//!       "spawn": ["git"],
+//!       "env": ["HOME"],
+//!       "clipboard": true
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! A package is only loaded once the user has granted everything it declares,
+//! and is then held to its declaration by the guarded editor API methods.
+//! Plugins outside the packages directory without a manifest (built-in ones,
+//! or files dropped into the plugins directory by hand) are trusted and run
+//! unrestricted. Anything installed under the packages directory is held to
+//! its manifest, and runs with no capabilities if it has none.
+
+use fresh_core::config::PluginCapabilities;
+use std::path::{Component, Path, PathBuf};
+
+/// Name of the package manifest at the root of a package
+pub const MANIFEST_FILE: &str = "package.json";
+
+/// Check that a directory is where packages are installed
+/// (`<config>/plugins/packages`)
+fn is_packages_dir(dir: &Path) -> bool {
+    dir.file_name().is_some_and(|name| name == "packages")
+        && dir
+            .parent()
+            .and_then(Path::file_name)
+            .is_some_and(|name| name == "plugins")
+}
+
+/// Find the root directory of the package a plugin belongs to
+///
+/// The entry file of an installed package may be nested inside it (through
+/// `fresh.entry`, e.g. `lib/main.ts`), so its root is the directory directly
+/// below the packages directory. A plugin elsewhere is a package only if its
+/// own directory has a manifest.
+pub fn package_root(plugin_path: &Path) -> Option<PathBuf> {
+    let dir = plugin_path.parent()?;
+    if let Some(root) = dir
+        .ancestors()
+        .find(|ancestor| ancestor.parent().is_some_and(is_packages_dir))
+    {
+        return Some(root.to_path_buf());
+    }
+    dir.join(MANIFEST_FILE).is_file().then(|| dir.to_path_buf())
+}
+
+/// Read the capabilities a plugin package declares in its manifest
+///
+/// Returns `None` when the plugin is not part of a package. A manifest that
+/// declares nothing, is missing from an installed package, or cannot be read
+/// or parsed yields empty capabilities, so the package still runs restricted.
+pub fn declared_capabilities(plugin_path: &Path) -> Option<PluginCapabilities> {
+    let manifest_path = package_root(plugin_path)?.join(MANIFEST_FILE);
+    let content = match std::fs::read_to_string(&manifest_path) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::warn!("Cannot read plugin manifest {:?}: {}", manifest_path, e);
+            return Some(PluginCapabilities::default());
+        }
+    };
+
+    let manifest: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            tracing::warn!("Invalid plugin manifest {:?}: {}", manifest_path, e);
+            return Some(PluginCapabilities::default());
+        }
+    };
+    let Some(declared) = manifest.pointer("/fresh/capabilities") else {
+        return Some(PluginCapabilities::default());
+    };
+    match serde_json::from_value(declared.clone()) {
+        Ok(capabilities) => Some(capabilities),
+        Err(e) => {
+            tracing::warn!(
+                "Invalid capabilities in plugin manifest {:?}: {}",
+                manifest_path,
+                e
+            );
+            Some(PluginCapabilities::default())
+        }
+    }
+}
+
+/// Directories a path scope may be relative to
+pub struct ScopeRoots<'a> {
+    /// The package's own directory (`${plugin}`)
+    pub plugin_dir: &'a Path,
+    /// The editor's working directory (`${workspace}`)
+    pub workspace: &'a Path,
+    /// The editor's config directory (`${config}`)
+    pub config_dir: &'a Path,
+}
+
+/// The capabilities a restricted plugin is held to, with path scopes resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityScope {
+    read: Vec<PathBuf>,
+    write: Vec<PathBuf>,
+    spawn: Vec<String>,
+    env: Vec<String>,
+    clipboard: bool,
+}
+
+impl CapabilityScope {
+    pub fn new(capabilities: &PluginCapabilities, roots: &ScopeRoots) -> Self {
+        let expand = |entries: &[String]| -> Vec<PathBuf> {
+            entries
+                .iter()
+                .filter_map(|entry| expand_scope(entry, roots))
+                .collect()
+        };
+        // A package may always read its own files
+        let mut read = expand(&capabilities.read);
+        read.push(resolve_path(roots.plugin_dir));
+
+        Self {
+            read,
+            write: expand(&capabilities.write),
+            spawn: capabilities.spawn.clone(),
+            env: capabilities.env.clone(),
+            clipboard: capabilities.clipboard,
+        }
+    }
+
+    pub fn can_read(&self, path: &Path) -> bool {
+        within(path, &self.read)
+    }
+
+    pub fn can_write(&self, path: &Path) -> bool {
+        within(path, &self.write)
+    }
+
+    /// Commands must be listed exactly as the plugin runs them, so allowing
+    /// `git` does not allow `/tmp/git`
+    pub fn can_spawn(&self, command: &str) -> bool {
+        self.spawn
+            .iter()
+            .any(|allowed| allowed == "*" || allowed == command)
+    }
+
+    pub fn can_read_env(&self, name: &str) -> bool {
+        self.env
+            .iter()
+            .any(|allowed| allowed == "*" || allowed == name)
+    }
+
+    pub fn can_set_clipboard(&self) -> bool {
+        self.clipboard
+    }
+}
+
+/// Expand a path scope's prefix; `None` for entries with an unknown `${...}`
+fn expand_scope(entry: &str, roots: &ScopeRoots) -> Option<PathBuf> {
+    let prefixes: [(&str, &Path); 3] = [
+        ("${plugin}", roots.plugin_dir),
+        ("${workspace}", roots.workspace),
+        ("${config}", roots.config_dir),
+    ];
+    let expanded = if let Some((root, rest)) = prefixes
+        .iter()
+        .find_map(|(prefix, root)| entry.strip_prefix(prefix).map(|rest| (*root, rest)))
+    {
+        root.join(rest.trim_start_matches(['/', '\\']))
+    } else if entry.starts_with("${") {
+        tracing::warn!("Unknown plugin capability path prefix in '{}'", entry);
+        return None;
+    } else if let Some(rest) = entry.strip_prefix('~') {
+        dirs::home_dir()?.join(rest.trim_start_matches(['/', '\\']))
+    } else {
+        PathBuf::from(entry)
+    };
+    Some(resolve_path(&expanded))
+}
+
+/// Whether `path` is one of `scopes` or below one of them
+fn within(path: &Path, scopes: &[PathBuf]) -> bool {
+    let path = resolve_path(path);
+    scopes.iter().any(|scope| path.starts_with(scope))
+}
+
+/// Make a path absolute and resolve `..` and symlinks, so a scope check
+/// cannot be escaped through either
+///
+/// The path need not exist: the longest existing ancestor is canonicalized
+/// and the rest appended.
+fn resolve_path(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+
+    let mut missing = Vec::new();
+    let mut existing = normalized.as_path();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |path, name| path.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return normalized,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope_in(dir: &Path, capabilities: PluginCapabilities) -> CapabilityScope {
+        let plugin_dir = dir.join("plugin");
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+        CapabilityScope::new(
+            &capabilities,
+            &ScopeRoots {
+                plugin_dir: &plugin_dir,
+                workspace: &dir.join("workspace"),
+                config_dir: &dir.join("config"),
+            },
+        )
+    }
+
+    #[test]
+    fn test_declared_capabilities() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = dir.path().join("my-plugin.ts");
+
+        // No manifest: not a package, so unrestricted
+        assert_eq!(declared_capabilities(&entry), None);
+
+        std::fs::write(
+            dir.path().join(MANIFEST_FILE),
+            r#"{"name": "my-plugin", "type": "plugin"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            declared_capabilities(&entry),
+            Some(PluginCapabilities::default())
+        );
+
+        std::fs::write(
+            dir.path().join(MANIFEST_FILE),
+            r#"{"name": "my-plugin", "type": "plugin",
+                "fresh": {"capabilities": {"spawn": ["git"], "clipboard": true}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            declared_capabilities(&entry),
+            Some(PluginCapabilities {
+                spawn: vec!["git".to_string()],
+                clipboard: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_declared_capabilities_of_installed_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = dir.path().join("plugins/packages/my-plugin");
+        let entry = package.join("lib/main.ts");
+        std::fs::create_dir_all(entry.parent().unwrap()).unwrap();
+
+        // Installed but without a manifest: restricted to nothing
+        assert_eq!(package_root(&entry), Some(package.clone()));
+        assert_eq!(
+            declared_capabilities(&entry),
+            Some(PluginCapabilities::default())
+        );
+
+        // A nested entry file is held to the manifest at the package root
+        std::fs::write(
+            package.join(MANIFEST_FILE),
+            r#"{"name": "my-plugin", "fresh": {"entry": "lib/main.ts",
+                "capabilities": {"spawn": ["git"]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            declared_capabilities(&entry),
+            Some(PluginCapabilities {
+                spawn: vec!["git".to_string()],
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_path_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let scope = scope_in(
+            dir.path(),
+            PluginCapabilities {
+                read: vec!["${workspace}".to_string()],
+                write: vec!["${workspace}/out".to_string()],
+                ..Default::default()
+            },
+        );
+        let workspace = dir.path().join("workspace");
+
+        assert!(scope.can_read(&workspace.join("src/main.rs")));
+        assert!(scope.can_read(&dir.path().join("plugin/data.json")));
+        assert!(!scope.can_read(&dir.path().join("config/config.json")));
+        assert!(!scope.can_read(&workspace.join("../config/config.json")));
+
+        assert!(scope.can_write(&workspace.join("out/report.txt")));
+        assert!(!scope.can_write(&workspace.join("output.txt")));
+        assert!(!scope.can_write(&workspace.join("out/../src/main.rs")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_cannot_escape_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("workspace");
+        std::fs::create_dir_all(&workspace).unwrap();
+        std::fs::create_dir_all(dir.path().join("secret")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("secret"), workspace.join("link")).unwrap();
+
+        let scope = scope_in(
+            dir.path(),
+            PluginCapabilities {
+                read: vec!["${workspace}".to_string()],
+                ..Default::default()
+            },
+        );
+        assert!(!scope.can_read(&workspace.join("link/key")));
+    }
+
+    #[test]
+    fn test_spawn_env_and_clipboard() {
+        let dir = tempfile::tempdir().unwrap();
+        let scope = scope_in(
+            dir.path(),
+            PluginCapabilities {
+                spawn: vec!["git".to_string()],
+                env: vec!["HOME".to_string()],
+                ..Default::default()
+            },
+        );
+        assert!(scope.can_spawn("git"));
+        assert!(!scope.can_spawn("/tmp/git"));
+        assert!(!scope.can_spawn("sh"));
+        assert!(scope.can_read_env("HOME"));
+        assert!(!scope.can_read_env("AWS_SECRET_ACCESS_KEY"));
+        assert!(!scope.can_set_clipboard());
+
+        let anything = scope_in(
+            dir.path(),
+            PluginCapabilities {
+                spawn: vec!["*".to_string()],
+                env: vec!["*".to_string()],
+                clipboard: true,
+                ..Default::default()
+            },
+        );
+        assert!(anything.can_spawn("sh"));
+        assert!(anything.can_read_env("PATH"));
+        assert!(anything.can_set_clipboard());
+    }
+}
@@ -1,6 +1,10 @@
 pub mod backend;
+pub mod bus;
+pub mod capabilities;
 pub mod process;
+pub mod storage;
 pub mod thread;
 pub mod ts_export;
 
+pub use backend::PluginLimits;
 pub use thread::{PluginConfig, PluginThreadHandle};
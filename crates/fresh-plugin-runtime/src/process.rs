@@ -2,13 +2,22 @@
 //!
 //! This module enables plugins to spawn external processes asynchronously,
 //! capturing stdout/stderr and notifying via callbacks when complete.
+//!
+//! It also provides the plumbing behind interactive processes: plugins keep
+//! a handle to write to (and close) the process's stdin, while its output is
+//! streamed back as `ProcessStdout`/`ProcessStderr` messages. These helpers
+//! work on any async pipes, so the editor can connect them to local or
+//! remote (SSH) processes alike.
 
 use fresh_core::api::PluginAsyncMessage as AsyncMessage;
 use std::process::Stdio;
 use std::sync::mpsc;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::process::Command;
 
+/// Size of the reads used to stream output in chunk mode
+const OUTPUT_CHUNK_SIZE: usize = 8192;
+
 /// Spawn an external process for a plugin
 ///
 /// This function:
@@ -112,6 +121,138 @@ pub async fn spawn_plugin_process(
     });
 }
 
+/// How the output of an interactive process is split into messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// One message per line, newline included
+    #[default]
+    Lines,
+    /// One message per read, for prompts and other output without newlines
+    Chunks,
+}
+
+impl OutputMode {
+    /// Parse the mode name used by the plugin API
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "lines" => Some(Self::Lines),
+            "chunks" => Some(Self::Chunks),
+            _ => None,
+        }
+    }
+}
+
+/// Which output stream of a process is being forwarded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+impl OutputStream {
+    fn message(self, process_id: u64, data: String) -> AsyncMessage {
+        match self {
+            Self::Stdout => AsyncMessage::ProcessStdout { process_id, data },
+            Self::Stderr => AsyncMessage::ProcessStderr { process_id, data },
+        }
+    }
+}
+
+/// Input a plugin sends to an interactive process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StdinMessage {
+    /// Write text to stdin
+    Write(String),
+    /// Close stdin, so the process sees end of input
+    Close,
+}
+
+/// Forward a process output stream to the plugin until it reaches EOF
+///
+/// Output is decoded as UTF-8 (invalid bytes are replaced); in chunk mode a
+/// character split across two reads is held back until it is complete.
+pub async fn forward_process_output<R, F>(
+    process_id: u64,
+    reader: R,
+    stream: OutputStream,
+    mode: OutputMode,
+    send: F,
+) where
+    R: AsyncRead + Unpin,
+    F: Fn(AsyncMessage),
+{
+    let mut reader = BufReader::new(reader);
+    match mode {
+        OutputMode::Lines => {
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                match reader.read_until(b'\n', &mut line).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => send(
+                        stream.message(process_id, String::from_utf8_lossy(&line).into_owned()),
+                    ),
+                }
+            }
+        }
+        OutputMode::Chunks => {
+            let mut buf = vec![0u8; OUTPUT_CHUNK_SIZE];
+            let mut pending = Vec::new();
+            loop {
+                match reader.read(&mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        pending.extend_from_slice(&buf[..n]);
+                        let complete = complete_utf8_len(&pending);
+                        if complete > 0 {
+                            let data = String::from_utf8_lossy(&pending[..complete]).into_owned();
+                            pending.drain(..complete);
+                            send(stream.message(process_id, data));
+                        }
+                    }
+                }
+            }
+            if !pending.is_empty() {
+                send(stream.message(process_id, String::from_utf8_lossy(&pending).into_owned()));
+            }
+        }
+    }
+}
+
+/// Length of `bytes` without a trailing, incomplete UTF-8 sequence
+fn complete_utf8_len(bytes: &[u8]) -> usize {
+    match std::str::from_utf8(bytes) {
+        Ok(_) => bytes.len(),
+        // An incomplete sequence at the end: wait for the rest of it
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        // Invalid bytes: decode everything lossily
+        Err(_) => bytes.len(),
+    }
+}
+
+/// Write the plugin's input to a process's stdin
+///
+/// Runs until the plugin closes stdin, the sender is dropped or the process
+/// stops reading; stdin is closed when this returns.
+pub async fn feed_process_stdin<W>(
+    mut stdin: W,
+    mut input: tokio::sync::mpsc::UnboundedReceiver<StdinMessage>,
+) where
+    W: AsyncWrite + Unpin,
+{
+    while let Some(message) = input.recv().await {
+        match message {
+            StdinMessage::Write(data) => {
+                if stdin.write_all(data.as_bytes()).await.is_err() || stdin.flush().await.is_err() {
+                    break;
+                }
+            }
+            StdinMessage::Close => break,
+        }
+    }
+    let _ = stdin.shutdown().await;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,4 +377,68 @@ mod tests {
             _ => panic!("Expected PluginProcessOutput"),
         }
     }
+
+    fn collect_output(mode: OutputMode, input: &'static [u8]) -> Vec<String> {
+        let (sender, receiver) = mpsc::channel();
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(forward_process_output(
+                7,
+                input,
+                OutputStream::Stderr,
+                mode,
+                move |msg| sender.send(msg).unwrap(),
+            ));
+        receiver
+            .try_iter()
+            .map(|msg| match msg {
+                AsyncMessage::ProcessStderr { process_id, data } => {
+                    assert_eq!(process_id, 7);
+                    data
+                }
+                other => panic!("Expected ProcessStderr, got {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_forward_output_lines() {
+        assert_eq!(
+            collect_output(OutputMode::Lines, b"one\ntwo\nprompt> "),
+            vec!["one\n", "two\n", "prompt> "]
+        );
+    }
+
+    #[test]
+    fn test_forward_output_chunks() {
+        let output = collect_output(OutputMode::Chunks, b"one\ntwo\nprompt> ");
+        assert_eq!(output.concat(), "one\ntwo\nprompt> ");
+    }
+
+    #[test]
+    fn test_complete_utf8_len_holds_back_split_character() {
+        let text = "héllo".as_bytes();
+        // Cut in the middle of the two-byte "é"
+        assert_eq!(complete_utf8_len(&text[..2]), 1);
+        assert_eq!(complete_utf8_len(text), text.len());
+        assert_eq!(complete_utf8_len(b"\xff\xfe"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_feed_stdin_until_closed() {
+        let (writer, mut reader) = tokio::io::duplex(64);
+        let (input, rx) = tokio::sync::mpsc::unbounded_channel();
+        input
+            .send(StdinMessage::Write("{\"a\": 1}\n".into()))
+            .unwrap();
+        input.send(StdinMessage::Close).unwrap();
+        input.send(StdinMessage::Write("ignored".into())).unwrap();
+
+        feed_process_stdin(writer, rx).await;
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(written, "{\"a\": 1}\n");
+    }
 }
@@ -0,0 +1,321 @@
+//! Plugin Storage: persistent key-value data for plugins
+//!
+//! Each plugin gets a JSON object per scope, stored under the data directory
+//! next to sessions:
+//!
+//! - `plugin_storage/{plugin}/global.json` - shared by all workspaces
+//! - `plugin_storage/{plugin}/workspaces/{encoded_path}.json` - one per workspace
+//!
+//! Files are loaded on first access and written atomically (temp file +
+//! rename) on every change, so a crash never leaves a half-written file.
+
+use anyhow::{anyhow, Result};
+use fresh_core::path_encoding::encode_path_for_filename;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Directory under the data dir holding all plugin storage
+pub const STORAGE_DIR: &str = "plugin_storage";
+
+/// Which of a plugin's stores a key lives in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageScope {
+    /// Shared across all workspaces
+    Global,
+    /// Specific to the current working directory
+    Workspace,
+}
+
+impl StorageScope {
+    /// Parse the scope name used by the JS API (defaults to global)
+    pub fn parse(name: Option<&str>) -> Result<Self> {
+        match name {
+            None | Some("global") => Ok(Self::Global),
+            Some("workspace") => Ok(Self::Workspace),
+            Some(other) => Err(anyhow!(
+                "Unknown storage scope '{}' (expected 'global' or 'workspace')",
+                other
+            )),
+        }
+    }
+}
+
+/// All plugins' stored data, cached in memory after first access
+pub struct PluginStorage {
+    root: PathBuf,
+    /// Largest serialized size of one store, in bytes
+    quota: Option<usize>,
+    cache: HashMap<PathBuf, Map<String, Value>>,
+}
+
+impl PluginStorage {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            root: data_dir.join(STORAGE_DIR),
+            quota: None,
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn set_quota(&mut self, quota: Option<usize>) {
+        self.quota = quota;
+    }
+
+    pub fn get(
+        &mut self,
+        plugin: &str,
+        scope: StorageScope,
+        workspace: &Path,
+        key: &str,
+    ) -> Option<Value> {
+        let path = self.store_path(plugin, scope, workspace);
+        self.load(&path).get(key).cloned()
+    }
+
+    pub fn keys(&mut self, plugin: &str, scope: StorageScope, workspace: &Path) -> Vec<String> {
+        let path = self.store_path(plugin, scope, workspace);
+        self.load(&path).keys().cloned().collect()
+    }
+
+    /// Store a value, refusing writes that would exceed the quota
+    pub fn set(
+        &mut self,
+        plugin: &str,
+        scope: StorageScope,
+        workspace: &Path,
+        key: &str,
+        value: Value,
+    ) -> Result<()> {
+        let path = self.store_path(plugin, scope, workspace);
+        let mut store = self.load(&path).clone();
+        store.insert(key.to_string(), value);
+        self.save(path, store)
+    }
+
+    /// Remove a key; returns whether it was present
+    pub fn delete(
+        &mut self,
+        plugin: &str,
+        scope: StorageScope,
+        workspace: &Path,
+        key: &str,
+    ) -> Result<bool> {
+        let path = self.store_path(plugin, scope, workspace);
+        let mut store = self.load(&path).clone();
+        if store.remove(key).is_none() {
+            return Ok(false);
+        }
+        self.save(path, store)?;
+        Ok(true)
+    }
+
+    /// Delete everything a plugin has stored, in every scope
+    pub fn remove_plugin(&mut self, plugin: &str) -> Result<()> {
+        let dir = self.plugin_dir(plugin);
+        self.cache.retain(|path, _| !path.starts_with(&dir));
+        match std::fs::remove_dir_all(&dir) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(anyhow!("Failed to remove {:?}: {}", dir, e)),
+        }
+    }
+
+    fn plugin_dir(&self, plugin: &str) -> PathBuf {
+        self.root.join(encode_path_for_filename(Path::new(plugin)))
+    }
+
+    fn store_path(&self, plugin: &str, scope: StorageScope, workspace: &Path) -> PathBuf {
+        let dir = self.plugin_dir(plugin);
+        match scope {
+            StorageScope::Global => dir.join("global.json"),
+            StorageScope::Workspace => dir
+                .join("workspaces")
+                .join(format!("{}.json", encode_path_for_filename(workspace))),
+        }
+    }
+
+    fn load(&mut self, path: &Path) -> &Map<String, Value> {
+        self.cache.entry(path.to_path_buf()).or_insert_with(|| {
+            match std::fs::read_to_string(path) {
+                Ok(content) => match serde_json::from_str(&content) {
+                    Ok(store) => store,
+                    Err(e) => {
+                        tracing::warn!("Ignoring corrupt plugin storage {:?}: {}", path, e);
+                        Map::new()
+                    }
+                },
+                Err(_) => Map::new(),
+            }
+        })
+    }
+
+    fn save(&mut self, path: PathBuf, store: Map<String, Value>) -> Result<()> {
+        let content = serde_json::to_string_pretty(&store)?;
+        if let Some(quota) = self.quota {
+            if content.len() > quota {
+                return Err(anyhow!(
+                    "Plugin storage quota exceeded ({} bytes, limit {} bytes)",
+                    content.len(),
+                    quota
+                ));
+            }
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write atomically: temp file + rename
+        let temp_path = path.with_extension("json.tmp");
+        {
+            let mut file = std::fs::File::create(&temp_path)?;
+            file.write_all(content.as_bytes())?;
+            file.sync_all()?;
+        }
+        std::fs::rename(&temp_path, &path)?;
+
+        self.cache.insert(path, store);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_scopes_are_separate_and_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = Path::new("/home/user/project");
+
+        let mut storage = PluginStorage::new(dir.path());
+        storage
+            .set(
+                "git_blame",
+                StorageScope::Global,
+                workspace,
+                "mode",
+                json!("inline"),
+            )
+            .unwrap();
+        storage
+            .set(
+                "git_blame",
+                StorageScope::Workspace,
+                workspace,
+                "mode",
+                json!({"a": 1}),
+            )
+            .unwrap();
+        assert_eq!(
+            storage.get("git_blame", StorageScope::Global, workspace, "mode"),
+            Some(json!("inline"))
+        );
+        assert_eq!(
+            storage.get("other", StorageScope::Global, workspace, "mode"),
+            None
+        );
+
+        // A fresh instance reads what was written to disk
+        let mut storage = PluginStorage::new(dir.path());
+        assert_eq!(
+            storage.get("git_blame", StorageScope::Workspace, workspace, "mode"),
+            Some(json!({"a": 1}))
+        );
+        assert_eq!(
+            storage.get(
+                "git_blame",
+                StorageScope::Workspace,
+                Path::new("/elsewhere"),
+                "mode"
+            ),
+            None
+        );
+        assert!(dir
+            .path()
+            .join(STORAGE_DIR)
+            .join("git%5Fblame/workspaces/home_user_project.json")
+            .is_file());
+
+        assert!(storage
+            .delete("git_blame", StorageScope::Global, workspace, "mode")
+            .unwrap());
+        assert!(!storage
+            .delete("git_blame", StorageScope::Global, workspace, "mode")
+            .unwrap());
+        assert!(storage
+            .keys("git_blame", StorageScope::Global, workspace)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_quota_refuses_oversized_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = Path::new("/w");
+        let mut storage = PluginStorage::new(dir.path());
+        storage.set_quota(Some(64));
+
+        storage
+            .set("p", StorageScope::Global, workspace, "small", json!(1))
+            .unwrap();
+        let err = storage
+            .set(
+                "p",
+                StorageScope::Global,
+                workspace,
+                "big",
+                json!("x".repeat(100)),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("quota"), "{}", err);
+
+        // The refused write left the store unchanged
+        assert_eq!(
+            storage.keys("p", StorageScope::Global, workspace),
+            vec!["small".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_remove_plugin_deletes_all_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = Path::new("/w");
+        let mut storage = PluginStorage::new(dir.path());
+        storage
+            .set("p", StorageScope::Global, workspace, "k", json!(1))
+            .unwrap();
+        storage
+            .set("p", StorageScope::Workspace, workspace, "k", json!(2))
+            .unwrap();
+        storage
+            .set("q", StorageScope::Global, workspace, "k", json!(3))
+            .unwrap();
+
+        storage.remove_plugin("p").unwrap();
+        assert_eq!(storage.get("p", StorageScope::Global, workspace, "k"), None);
+        assert_eq!(
+            storage.get("p", StorageScope::Workspace, workspace, "k"),
+            None
+        );
+        assert_eq!(
+            storage.get("q", StorageScope::Global, workspace, "k"),
+            Some(json!(3))
+        );
+        assert!(!dir.path().join(STORAGE_DIR).join("p").exists());
+
+        // Removing a plugin that never stored anything is fine
+        storage.remove_plugin("never").unwrap();
+    }
+
+    #[test]
+    fn test_parse_scope() {
+        assert_eq!(StorageScope::parse(None).unwrap(), StorageScope::Global);
+        assert_eq!(
+            StorageScope::parse(Some("workspace")).unwrap(),
+            StorageScope::Workspace
+        );
+        assert!(StorageScope::parse(Some("session")).is_err());
+    }
+}
@@ -9,21 +9,25 @@
 //! - Results are sent back via the existing PluginCommand channel
 //! - Async operations complete naturally without runtime destruction
 
-use crate::backend::quickjs_backend::{PendingResponses, TsPluginInfo};
+use crate::backend::quickjs_backend::{
+    HookHandlerInfo, PendingResponses, PluginLimits, TsPluginInfo,
+};
 use crate::backend::QuickJsBackend;
+use crate::bus;
+use crate::capabilities;
 use anyhow::{anyhow, Result};
-use fresh_core::api::{EditorStateSnapshot, PluginCommand};
-use fresh_core::hooks::HookArgs;
+use fresh_core::api::{EditorStateSnapshot, LanguageFeatureRequest, PluginCommand};
+use fresh_core::hooks::{HookArgs, HookOutcome, CANCELLABLE_HOOKS};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 // Re-export PluginConfig from fresh-core
-pub use fresh_core::config::PluginConfig;
+pub use fresh_core::config::{PluginCapabilities, PluginConfig};
 
 /// Request messages sent to the plugin thread
 #[derive(Debug)]
@@ -82,17 +86,59 @@ pub enum PluginRequest {
     /// Run a hook (fire-and-forget, no response needed)
     RunHook { hook_name: String, args: HookArgs },
 
+    /// Run a hook for one plugin's handlers only (fire-and-forget)
+    RunPluginHook {
+        plugin_name: String,
+        hook_name: String,
+        args: HookArgs,
+    },
+
+    /// Run cancellable hooks in order and report what their handlers
+    /// decided, stopping at the first cancelled one
+    RunCancellableHooks {
+        hooks: Vec<(String, HookArgs)>,
+        /// When the editor stops waiting; hooks not run by then are skipped
+        deadline: Instant,
+        response: oneshot::Sender<Vec<HookOutcome>>,
+    },
+
     /// Check if any handlers are registered for a hook
     HasHookHandlers {
         hook_name: String,
         response: oneshot::Sender<bool>,
     },
 
+    /// Record the capabilities the user granted to a plugin package
+    /// (takes effect the next time the plugin is loaded)
+    SetCapabilityGrant {
+        name: String,
+        granted: PluginCapabilities,
+    },
+
+    /// Ask the plugins providing a language feature for their results
+    /// (fire-and-forget, each answers with a `LanguageProviderResult` command)
+    RequestLanguageFeature {
+        request_id: u64,
+        request: LanguageFeatureRequest,
+    },
+
     /// List all loaded plugins
     ListPlugins {
         response: oneshot::Sender<Vec<TsPluginInfo>>,
     },
 
+    /// Evaluate a snippet in a plugin's context (developer console)
+    EvaluateInPlugin {
+        plugin_name: String,
+        code: String,
+        response: oneshot::Sender<Result<String>>,
+    },
+
+    /// List registered hook handlers with their call statistics
+    ListHookHandlers {
+        response: oneshot::Sender<Vec<HookHandlerInfo>>,
+    },
+
     /// Shutdown the plugin thread
     Shutdown,
 }
@@ -165,11 +211,21 @@ pub struct PluginThreadHandle {
 
     /// Receiver for plugin commands (polled by editor directly)
     command_receiver: std::sync::mpsc::Receiver<PluginCommand>,
+
+    /// Cancellable hooks that have handlers (kept up to date by the plugin
+    /// thread, so the editor can skip the others without asking)
+    cancellable_hooks: Arc<RwLock<HashSet<String>>>,
+
+    /// Longest to wait for the handlers of a cancellable hook
+    hook_timeout: Option<Duration>,
 }
 
 impl PluginThreadHandle {
     /// Create a new plugin thread and return its handle
-    pub fn spawn(services: Arc<dyn fresh_core::services::PluginServiceBridge>) -> Result<Self> {
+    pub fn spawn(
+        services: Arc<dyn fresh_core::services::PluginServiceBridge>,
+        limits: PluginLimits,
+    ) -> Result<Self> {
         tracing::debug!("PluginThreadHandle::spawn: starting plugin thread creation");
 
         // Create channel for plugin commands
@@ -189,6 +245,9 @@ impl PluginThreadHandle {
         // Clone state snapshot for the thread
         let thread_state_snapshot = Arc::clone(&state_snapshot);
 
+        let cancellable_hooks = Arc::new(RwLock::new(HashSet::new()));
+        let thread_cancellable_hooks = Arc::clone(&cancellable_hooks);
+
         // Spawn the plugin thread
         tracing::debug!("PluginThreadHandle::spawn: spawning OS thread for plugin runtime");
         let thread_handle = thread::spawn(move || {
@@ -210,7 +269,7 @@ impl PluginThreadHandle {
 
             // Create QuickJS runtime with state
             tracing::debug!("Plugin thread: creating QuickJS runtime");
-            let runtime = match QuickJsBackend::with_state_and_responses(
+            let mut runtime = match QuickJsBackend::with_state_and_responses(
                 Arc::clone(&thread_state_snapshot),
                 command_sender,
                 thread_pending_responses,
@@ -225,6 +284,7 @@ impl PluginThreadHandle {
                     return;
                 }
             };
+            runtime.set_limits(limits);
 
             // Create internal manager state
             let mut plugins: HashMap<String, TsPluginInfo> = HashMap::new();
@@ -236,7 +296,13 @@ impl PluginThreadHandle {
                 // Wrap runtime in RefCell for interior mutability during concurrent operations
                 let runtime = Rc::new(RefCell::new(runtime));
                 tracing::debug!("Plugin thread: entering plugin_thread_loop");
-                plugin_thread_loop(runtime, &mut plugins, request_receiver).await;
+                plugin_thread_loop(
+                    runtime,
+                    &mut plugins,
+                    request_receiver,
+                    thread_cancellable_hooks,
+                )
+                .await;
             });
 
             tracing::info!("Plugin thread shutting down");
@@ -251,6 +317,8 @@ impl PluginThreadHandle {
             state_snapshot,
             pending_responses,
             command_receiver,
+            cancellable_hooks,
+            hook_timeout: limits.hook_timeout,
         })
     }
 
@@ -483,6 +551,103 @@ impl PluginThreadHandle {
         }
     }
 
+    /// Run a hook for one plugin's handlers only (non-blocking), for events
+    /// about something that plugin owns
+    pub fn run_plugin_hook(&self, plugin_name: &str, hook_name: &str, args: HookArgs) {
+        if let Some(sender) = self.request_sender.as_ref() {
+            let _ = sender.send(PluginRequest::RunPluginHook {
+                plugin_name: plugin_name.to_string(),
+                hook_name: hook_name.to_string(),
+                args,
+            });
+        }
+    }
+
+    /// Record the capabilities the user granted to a plugin package (non-blocking)
+    ///
+    /// Requests are handled in order, so a following `load_plugin` sees it.
+    pub fn set_capability_grant(&self, name: &str, granted: PluginCapabilities) {
+        if let Some(sender) = self.request_sender.as_ref() {
+            let _ = sender.send(PluginRequest::SetCapabilityGrant {
+                name: name.to_string(),
+                granted,
+            });
+        }
+    }
+
+    /// Ask the plugins providing a language feature for their results (non-blocking)
+    pub fn request_language_feature(&self, request_id: u64, request: LanguageFeatureRequest) {
+        if let Some(sender) = self.request_sender.as_ref() {
+            let _ = sender.send(PluginRequest::RequestLanguageFeature {
+                request_id,
+                request,
+            });
+        }
+    }
+
+    /// Check whether plugins handle a cancellable hook (non-blocking)
+    pub fn handles_cancellable_hook(&self, hook_name: &str) -> bool {
+        self.hook_timeout.is_some()
+            && self
+                .cancellable_hooks
+                .read()
+                .map(|hooks| hooks.contains(hook_name))
+                .unwrap_or(false)
+    }
+
+    /// Run a cancellable hook and wait for its handlers' decision (blocking,
+    /// up to the hook timeout)
+    ///
+    /// When no plugin handles the hook, or the handlers do not answer in time,
+    /// the outcome lets the operation go ahead unchanged.
+    pub fn run_cancellable_hook(&self, hook_name: &str, args: HookArgs) -> HookOutcome {
+        self.run_cancellable_hooks(vec![(hook_name.to_string(), args)])
+            .pop()
+            .unwrap_or_default()
+    }
+
+    /// Run several cancellable hooks in one round trip and wait for their
+    /// handlers' decisions (blocking, up to the hook timeout for all of them)
+    ///
+    /// Returns the outcomes in order, ending at the first cancelled hook.
+    /// When no plugin handles the hooks, or the handlers do not answer in
+    /// time, the result is empty and the operation goes ahead unchanged.
+    pub fn run_cancellable_hooks(&self, hooks: Vec<(String, HookArgs)>) -> Vec<HookOutcome> {
+        let Some(timeout) = self.hook_timeout else {
+            return Vec::new();
+        };
+        if !hooks
+            .iter()
+            .any(|(hook_name, _)| self.handles_cancellable_hook(hook_name))
+        {
+            return Vec::new();
+        }
+        let Some(sender) = self.request_sender.as_ref() else {
+            return Vec::new();
+        };
+        let hook_names: Vec<String> = hooks.iter().map(|(name, _)| name.clone()).collect();
+        let (tx, rx) = oneshot::channel();
+        if sender
+            .send(PluginRequest::RunCancellableHooks {
+                hooks,
+                deadline: Instant::now() + timeout,
+                response: tx,
+            })
+            .is_err()
+        {
+            return Vec::new();
+        }
+
+        rx.recv_timeout(timeout).unwrap_or_else(|_| {
+            tracing::warn!(
+                "Plugins did not answer {:?} within {:?}; going ahead",
+                hook_names,
+                timeout
+            );
+            Vec::new()
+        })
+    }
+
     /// Check if any handlers are registered for a hook (blocking)
     pub fn has_hook_handlers(&self, hook_name: &str) -> bool {
         let (tx, rx) = oneshot::channel();
@@ -518,6 +683,38 @@ impl PluginThreadHandle {
         rx.recv().unwrap_or_default()
     }
 
+    /// Evaluate a snippet in a plugin's context and describe the result (blocking)
+    pub fn evaluate_in_plugin(&self, plugin_name: &str, code: &str) -> Result<String> {
+        let (tx, rx) = oneshot::channel();
+        self.request_sender
+            .as_ref()
+            .ok_or_else(|| anyhow!("Plugin thread shut down"))?
+            .send(PluginRequest::EvaluateInPlugin {
+                plugin_name: plugin_name.to_string(),
+                code: code.to_string(),
+                response: tx,
+            })
+            .map_err(|_| anyhow!("Plugin thread not responding"))?;
+
+        rx.recv().map_err(|_| anyhow!("Plugin thread closed"))?
+    }
+
+    /// List registered hook handlers with their call statistics (blocking)
+    pub fn list_hook_handlers(&self) -> Vec<HookHandlerInfo> {
+        let (tx, rx) = oneshot::channel();
+        let Some(sender) = self.request_sender.as_ref() else {
+            return vec![];
+        };
+        if sender
+            .send(PluginRequest::ListHookHandlers { response: tx })
+            .is_err()
+        {
+            return vec![];
+        }
+
+        rx.recv().unwrap_or_default()
+    }
+
     /// Process pending plugin commands (non-blocking)
     ///
     /// Returns immediately with any pending commands by polling the command queue directly.
@@ -698,6 +895,7 @@ async fn plugin_thread_loop(
     runtime: Rc<RefCell<QuickJsBackend>>,
     plugins: &mut HashMap<String, TsPluginInfo>,
     mut request_receiver: tokio::sync::mpsc::UnboundedReceiver<PluginRequest>,
+    cancellable_hooks: Arc<RwLock<HashSet<String>>>,
 ) {
     tracing::info!("Plugin thread event loop started");
 
@@ -756,6 +954,61 @@ async fn plugin_thread_loop(
                 has_pending_work = runtime.borrow_mut().poll_event_loop_once();
             }
         }
+
+        disable_tripped_plugins(&runtime, plugins);
+        publish_cancellable_hooks(&runtime, &cancellable_hooks);
+
+        if runtime.borrow().has_plugin_messages() {
+            dispatch_plugin_messages(&runtime).await;
+            has_pending_work = true;
+        }
+    }
+}
+
+/// Deliver calls, replies and events between plugins
+///
+/// # Safety (clippy::await_holding_refcell_ref)
+/// The RefCell borrow held across await is safe because:
+/// - This runs on a single-threaded tokio runtime (no parallel task execution)
+/// - No spawn_local calls exist that could create concurrent access to `runtime`
+/// - The runtime Rc<RefCell<>> is never shared with other concurrent tasks
+#[allow(clippy::await_holding_refcell_ref)]
+async fn dispatch_plugin_messages(runtime: &Rc<RefCell<QuickJsBackend>>) {
+    runtime.borrow_mut().dispatch_plugin_messages().await;
+}
+
+/// Share which cancellable hooks have handlers with the editor
+fn publish_cancellable_hooks(
+    runtime: &Rc<RefCell<QuickJsBackend>>,
+    cancellable_hooks: &RwLock<HashSet<String>>,
+) {
+    let handled: HashSet<String> = CANCELLABLE_HOOKS
+        .iter()
+        .filter(|name| runtime.borrow().has_handlers(name))
+        .map(|name| name.to_string())
+        .collect();
+    let changed = cancellable_hooks
+        .read()
+        .map(|hooks| *hooks != handled)
+        .unwrap_or(false);
+    if changed {
+        if let Ok(mut hooks) = cancellable_hooks.write() {
+            *hooks = handled;
+        }
+    }
+}
+
+/// Disable plugins that ran past their time budget or memory limit
+fn disable_tripped_plugins(
+    runtime: &Rc<RefCell<QuickJsBackend>>,
+    plugins: &mut HashMap<String, TsPluginInfo>,
+) {
+    let tripped = runtime.borrow().take_tripped_plugins();
+    for (name, reason) in tripped {
+        tracing::warn!("Plugin '{}' disabled: {}", name, reason);
+        runtime.borrow_mut().disable_plugin(&name, &reason);
+        // A plugin interrupted while loading was never registered
+        let _ = unload_plugin_internal(Rc::clone(runtime), plugins, &name);
     }
 }
 
@@ -782,6 +1035,7 @@ async fn run_hook_internal_rc(
     runtime: Rc<RefCell<QuickJsBackend>>,
     hook_name: &str,
     args: &HookArgs,
+    plugin_name: Option<&str>,
 ) -> Result<()> {
     // Convert HookArgs to JSON using hook_args_to_json which produces flat JSON
     // (not enum-tagged JSON from serde's default Serialize)
@@ -796,7 +1050,10 @@ async fn run_hook_internal_rc(
 
     // Emit to TypeScript handlers
     let emit_start = std::time::Instant::now();
-    runtime.borrow_mut().emit(hook_name, &json_data).await?;
+    runtime
+        .borrow_mut()
+        .emit_to(hook_name, &json_data, plugin_name)
+        .await?;
     tracing::trace!(
         hook = hook_name,
         emit_ms = emit_start.elapsed().as_millis(),
@@ -816,6 +1073,9 @@ async fn handle_request(
         PluginRequest::LoadPlugin { path, response } => {
             let result = load_plugin_internal(Rc::clone(&runtime), plugins, &path).await;
             let _ = response.send(result);
+            for err in load_deferred_plugins(Rc::clone(&runtime), plugins).await {
+                runtime.borrow().send_status(err);
+            }
         }
 
         PluginRequest::LoadPluginsFromDir { dir, response } => {
@@ -846,6 +1106,9 @@ async fn handle_request(
         PluginRequest::ReloadPlugin { name, response } => {
             let result = reload_plugin_internal(Rc::clone(&runtime), plugins, &name).await;
             let _ = response.send(result);
+            for err in load_deferred_plugins(Rc::clone(&runtime), plugins).await {
+                runtime.borrow().send_status(err);
+            }
         }
 
         PluginRequest::ExecuteAction {
@@ -872,12 +1135,17 @@ async fn handle_request(
             } else {
                 tracing::trace!(hook = %hook_name, "RunHook request received");
             }
-            if let Err(e) = run_hook_internal_rc(Rc::clone(&runtime), &hook_name, &args).await {
+            if let Err(e) = run_hook_internal_rc(Rc::clone(&runtime), &hook_name, &args, None).await
+            {
                 let error_msg = format!("Plugin error in '{}': {}", hook_name, e);
                 tracing::error!("{}", error_msg);
                 // Surface the error to the UI
                 runtime.borrow_mut().send_status(error_msg);
             }
+            // The editor fires this once it has loaded all plugins
+            if matches!(args, HookArgs::EditorInitialized) {
+                runtime.borrow_mut().finish_startup();
+            }
             if hook_name == "prompt_confirmed" || hook_name == "prompt_cancelled" {
                 tracing::info!(
                     hook = %hook_name,
@@ -893,6 +1161,53 @@ async fn handle_request(
             }
         }
 
+        PluginRequest::RunPluginHook {
+            plugin_name,
+            hook_name,
+            args,
+        } => {
+            tracing::trace!(hook = %hook_name, plugin = %plugin_name, "RunPluginHook request received");
+            if let Err(e) =
+                run_hook_internal_rc(Rc::clone(&runtime), &hook_name, &args, Some(&plugin_name))
+                    .await
+            {
+                let error_msg = format!("Plugin error in '{}': {}", hook_name, e);
+                tracing::error!("{}", error_msg);
+                runtime.borrow_mut().send_status(error_msg);
+            }
+        }
+
+        PluginRequest::RunCancellableHooks {
+            hooks,
+            deadline,
+            response,
+        } => {
+            let mut outcomes = Vec::new();
+            for (hook_name, args) in hooks {
+                if Instant::now() >= deadline {
+                    // The editor went ahead without the answer; running the
+                    // handlers now would only delay the requests behind this one
+                    tracing::debug!("Skipping stale '{}' hook", hook_name);
+                    break;
+                }
+                let outcome = match fresh_core::hooks::hook_args_to_json(&args)
+                    .and_then(|json| Ok(serde_json::from_str(&json)?))
+                {
+                    Ok(data) => runtime.borrow_mut().emit_cancellable(&hook_name, data),
+                    Err(e) => {
+                        tracing::error!("Cannot run hook '{}': {}", hook_name, e);
+                        HookOutcome::default()
+                    }
+                };
+                let cancelled = outcome.is_cancelled();
+                outcomes.push(outcome);
+                if cancelled {
+                    break;
+                }
+            }
+            let _ = response.send(outcomes);
+        }
+
         PluginRequest::HasHookHandlers {
             hook_name,
             response,
@@ -901,11 +1216,39 @@ async fn handle_request(
             let _ = response.send(has_handlers);
         }
 
+        PluginRequest::SetCapabilityGrant { name, granted } => {
+            runtime
+                .borrow_mut()
+                .set_granted_capabilities(&name, granted);
+        }
+
+        PluginRequest::RequestLanguageFeature {
+            request_id,
+            request,
+        } => {
+            runtime
+                .borrow_mut()
+                .request_language_feature(request_id, &request);
+        }
+
         PluginRequest::ListPlugins { response } => {
             let plugin_list: Vec<TsPluginInfo> = plugins.values().cloned().collect();
             let _ = response.send(plugin_list);
         }
 
+        PluginRequest::EvaluateInPlugin {
+            plugin_name,
+            code,
+            response,
+        } => {
+            let result = runtime.borrow_mut().evaluate_in_plugin(&plugin_name, &code);
+            let _ = response.send(result);
+        }
+
+        PluginRequest::ListHookHandlers { response } => {
+            let _ = response.send(runtime.borrow().hook_handlers());
+        }
+
         PluginRequest::ResolveCallback {
             callback_id,
             result_json,
@@ -958,6 +1301,44 @@ async fn load_plugin_internal(
         .ok_or_else(|| anyhow!("Invalid plugin filename"))?
         .to_string();
 
+    // Packages run restricted to the capabilities they declare, and only
+    // once the user has granted them all
+    if let Some(declared) = capabilities::declared_capabilities(path) {
+        let granted = runtime.borrow().granted_capabilities(&plugin_name);
+        if !granted.covers(&declared) {
+            tracing::info!(
+                "Plugin '{}' needs the user's consent for its capabilities before loading",
+                plugin_name
+            );
+            runtime
+                .borrow()
+                .request_capabilities(&plugin_name, path, declared);
+            return Ok(());
+        }
+        let plugin_dir = capabilities::package_root(path)
+            .unwrap_or_else(|| path.parent().unwrap_or(Path::new(".")).to_path_buf());
+        runtime
+            .borrow_mut()
+            .restrict_plugin(&plugin_name, &declared, &plugin_dir);
+    } else {
+        runtime.borrow_mut().unrestrict_plugin(&plugin_name);
+    }
+
+    // Packages load after the plugins they depend on
+    let missing: Vec<String> = bus::declared_dependencies(path)
+        .into_iter()
+        .filter(|dependency| !plugins.contains_key(dependency))
+        .collect();
+    if !missing.is_empty() {
+        tracing::info!(
+            "Plugin '{}' waits for its dependencies: {}",
+            plugin_name,
+            missing.join(", ")
+        );
+        runtime.borrow_mut().defer_plugin(&plugin_name, path);
+        return Ok(());
+    }
+
     tracing::info!("Loading TypeScript plugin: {} from {:?}", plugin_name, path);
     tracing::debug!(
         "load_plugin_internal: starting module load for plugin '{}'",
@@ -1055,6 +1436,8 @@ async fn load_plugins_from_dir_internal(
                 }
             }
 
+            errors.extend(load_deferred_plugins(Rc::clone(&runtime), plugins).await);
+
             tracing::debug!(
                 "load_plugins_from_dir_internal: finished loading from {:?}, {} errors",
                 dir,
@@ -1130,12 +1513,17 @@ async fn load_plugins_from_dir_with_config_internal(
             PluginConfig {
                 enabled: existing_config.enabled,
                 path: Some(path.clone()),
+                granted: existing_config.granted.clone(),
             }
         } else {
             // Create new config with default enabled = true
             PluginConfig::new_with_path(path.clone())
         };
 
+        runtime
+            .borrow_mut()
+            .set_granted_capabilities(&plugin_name, config.granted.clone());
+
         // Add to discovered plugins
         discovered_plugins.insert(plugin_name.clone(), config.clone());
 
@@ -1158,6 +1546,8 @@ async fn load_plugins_from_dir_with_config_internal(
         }
     }
 
+    errors.extend(load_deferred_plugins(Rc::clone(&runtime), plugins).await);
+
     tracing::debug!(
         "load_plugins_from_dir_with_config_internal: finished. Discovered {} plugins, {} errors",
         discovered_plugins.len(),
@@ -1167,6 +1557,31 @@ async fn load_plugins_from_dir_with_config_internal(
     (errors, discovered_plugins)
 }
 
+/// Load the plugins that were waiting for dependencies which are now loaded
+///
+/// Returns the errors of the plugins that failed to load.
+async fn load_deferred_plugins(
+    runtime: Rc<RefCell<QuickJsBackend>>,
+    plugins: &mut HashMap<String, TsPluginInfo>,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    loop {
+        let ready = runtime
+            .borrow_mut()
+            .take_ready_plugins(|name| plugins.contains_key(name));
+        if ready.is_empty() {
+            return errors;
+        }
+        for path in ready {
+            if let Err(e) = load_plugin_internal(Rc::clone(&runtime), plugins, &path).await {
+                let err = format!("Failed to load {:?}: {}", path, e);
+                tracing::error!("{}", err);
+                errors.push(err);
+            }
+        }
+    }
+}
+
 /// Unload a plugin
 fn unload_plugin_internal(
     runtime: Rc<RefCell<QuickJsBackend>>,
@@ -1188,6 +1603,24 @@ fn unload_plugin_internal(
             .services
             .unregister_commands_by_plugin(name);
 
+        // Stop asking it for language features
+        runtime.borrow().remove_language_providers(name);
+
+        // Clear its status bar items
+        runtime.borrow().remove_status_bar_items(name);
+
+        // Stop its path watches
+        runtime.borrow().remove_path_watches(name);
+
+        // Close its terminals
+        runtime.borrow().close_plugin_terminals(name);
+
+        // Kill its interactive processes
+        runtime.borrow().kill_interactive_processes(name);
+
+        // Withdraw its exported API
+        runtime.borrow().remove_plugin_api(name);
+
         Ok(())
     } else {
         Err(anyhow!("Plugin '{}' not found", name))
@@ -1242,4 +1675,129 @@ mod tests {
         assert_eq!(parsed["prompt_type"], "search");
         assert_eq!(parsed["input"], "test");
     }
+
+    #[test]
+    fn test_packages_load_after_their_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = |name: &str, manifest: &str| {
+            let package_dir = dir.path().join(name);
+            std::fs::create_dir_all(&package_dir).unwrap();
+            std::fs::write(package_dir.join(capabilities::MANIFEST_FILE), manifest).unwrap();
+            std::fs::write(
+                package_dir.join(format!("{}.js", name)),
+                "getEditor().exportApi({ ping() { return 'pong'; } });",
+            )
+            .unwrap();
+            package_dir
+        };
+        let base = package("base", r#"{"name": "base"}"#);
+        let tool = package(
+            "tool",
+            r#"{"name": "tool", "fresh": {"dependencies": ["base"]}}"#,
+        );
+
+        let mut handle = PluginThreadHandle::spawn(
+            Arc::new(fresh_core::services::NoopServiceBridge),
+            PluginLimits::default(),
+        )
+        .unwrap();
+        let loaded = |handle: &PluginThreadHandle| {
+            let mut names: Vec<String> =
+                handle.list_plugins().into_iter().map(|p| p.name).collect();
+            names.sort();
+            names
+        };
+
+        assert!(handle.load_plugins_from_dir(&tool).is_empty());
+        assert!(loaded(&handle).is_empty());
+        assert!(handle.load_plugins_from_dir(&base).is_empty());
+        assert_eq!(loaded(&handle), vec!["base", "tool"]);
+        handle.shutdown();
+    }
+
+    #[test]
+    fn test_cancellable_hooks_answer_within_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = dir.path().join("guard.js");
+        std::fs::write(
+            &plugin,
+            r#"
+            const editor = getEditor();
+            globalThis.onSave = (data) => ({ content: data.content.trimEnd() + "\n" });
+            let commands = 0;
+            globalThis.onCommand = (data) => {
+                commands += 1;
+                const end = Date.now() + 300;
+                while (Date.now() < end) {}
+                return false;
+            };
+            globalThis.onInsert = (data) => {
+                if (data.text === "!") return false;
+                return { text: `${data.text}${commands}` };
+            };
+            editor.on("before_file_save", "onSave");
+            editor.on("pre_command", "onCommand");
+            editor.on("before_insert", "onInsert");
+            "#,
+        )
+        .unwrap();
+
+        let mut handle = PluginThreadHandle::spawn(
+            Arc::new(fresh_core::services::NoopServiceBridge),
+            PluginLimits {
+                hook_timeout: Some(Duration::from_millis(100)),
+                ..PluginLimits::default()
+            },
+        )
+        .unwrap();
+        assert!(!handle.handles_cancellable_hook("before_file_save"));
+        handle.load_plugin(&plugin).unwrap();
+        // Published by the plugin thread once it is done with the request
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !handle.handles_cancellable_hook("before_file_save")
+            && std::time::Instant::now() < deadline
+        {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(handle.handles_cancellable_hook("before_file_save"));
+        assert!(!handle.handles_cancellable_hook("before_delete"));
+
+        let outcome = handle.run_cancellable_hook(
+            "before_file_save",
+            HookArgs::BeforeFileSave {
+                buffer_id: fresh_core::BufferId(1),
+                path: PathBuf::from("/tmp/a.txt"),
+                content: "text  \n\n".to_string(),
+            },
+        );
+        assert_eq!(outcome.replacement("content"), Some("text\n"));
+
+        // A handler too slow to answer cannot hold the operation back, and a
+        // request queued behind it is dropped once the editor stopped waiting
+        let command = || HookArgs::PreCommand {
+            action: "Save".to_string(),
+        };
+        let outcome = handle.run_cancellable_hook("pre_command", command());
+        assert!(!outcome.is_cancelled());
+        let outcome = handle.run_cancellable_hook("pre_command", command());
+        assert!(!outcome.is_cancelled());
+        std::thread::sleep(Duration::from_millis(400));
+
+        // One round trip for several hooks, ending at the first cancelled one
+        let insert = |text: &str| {
+            (
+                "before_insert".to_string(),
+                HookArgs::BeforeInsert {
+                    buffer_id: fresh_core::BufferId(1),
+                    position: 0,
+                    text: text.to_string(),
+                },
+            )
+        };
+        let outcomes = handle.run_cancellable_hooks(vec![insert("a"), insert("!"), insert("b")]);
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].replacement("text"), Some("a1"));
+        assert!(outcomes[1].is_cancelled());
+        handle.shutdown();
+    }
 }
@@ -15,14 +15,18 @@ use oxc_span::SourceType;
 use ts_rs::TS;
 
 use fresh_core::api::{
-    ActionPopupAction, ActionPopupOptions, ActionSpec, BackgroundProcessResult, BufferInfo,
-    BufferSavedDiff, CompositeHunk, CompositeLayoutConfig, CompositePaneStyle,
-    CompositeSourceConfig, CreateCompositeBufferOptions, CreateVirtualBufferInExistingSplitOptions,
-    CreateVirtualBufferInSplitOptions, CreateVirtualBufferOptions, CursorInfo, DirEntry,
-    FormatterPackConfig, JsDiagnostic, JsPosition, JsRange, JsTextPropertyEntry,
-    LanguagePackConfig, LayoutHints, LspServerPackConfig, SpawnResult, TextPropertiesAtCursor,
-    TsHighlightSpan, ViewTokenStyle, ViewTokenWire, ViewTokenWireKind, ViewportInfo,
-    VirtualBufferResult,
+    ActionPopupAction, ActionPopupOptions, ActionSpec, BackgroundProcessResult, BufferEdit,
+    BufferInfo, BufferSavedDiff, CompositeHunk, CompositeLayoutConfig, CompositePaneStyle,
+    CompositeSourceConfig, CreateCompositeBufferOptions, CreateTerminalOptions,
+    CreateVirtualBufferInExistingSplitOptions, CreateVirtualBufferInSplitOptions,
+    CreateVirtualBufferOptions, CursorInfo, DirEntry, FormButton, FormField, FormOptions,
+    FormatterPackConfig, InteractiveProcessOptions, JsDiagnostic, JsPosition, JsRange,
+    JsTextPropertyEntry, LanguageFeature, LanguageFeatureRequest, LanguagePackConfig, LayoutHints,
+    LspServerPackConfig, ProviderCodeAction, ProviderCompletionItem, ProviderDiagnostic,
+    ProviderLocation, SelectionKind, SelectionSpec, SpawnResult, StatusBarItemOptions,
+    SyntaxCapture, SyntaxNode, TerminalResult, TextPropertiesAtCursor, TsHighlightSpan,
+    ViewTokenStyle, ViewTokenWire, ViewTokenWireKind, ViewportInfo, VirtualBufferResult,
+    WatchPathOptions,
 };
 use fresh_core::command::Suggestion;
 use fresh_core::file_explorer::FileExplorerDecoration;
@@ -37,6 +41,9 @@ fn get_type_decl(type_name: &str) -> Option<String> {
     match type_name {
         // Core types
         "BufferInfo" => Some(BufferInfo::decl()),
+        "BufferEdit" => Some(BufferEdit::decl()),
+        "SelectionSpec" => Some(SelectionSpec::decl()),
+        "SelectionKind" => Some(SelectionKind::decl()),
         "CursorInfo" => Some(CursorInfo::decl()),
         "ViewportInfo" => Some(ViewportInfo::decl()),
         "ActionSpec" => Some(ActionSpec::decl()),
@@ -46,6 +53,10 @@ fn get_type_decl(type_name: &str) -> Option<String> {
         // Process types
         "SpawnResult" => Some(SpawnResult::decl()),
         "BackgroundProcessResult" => Some(BackgroundProcessResult::decl()),
+        "InteractiveProcessOptions" => Some(InteractiveProcessOptions::decl()),
+
+        // File system watch types
+        "WatchPathOptions" => Some(WatchPathOptions::decl()),
 
         // Composite buffer types (ts-rs renames these with Ts prefix)
         "TsCompositeLayoutConfig" | "CompositeLayoutConfig" => Some(CompositeLayoutConfig::decl()),
@@ -65,6 +76,14 @@ fn get_type_decl(type_name: &str) -> Option<String> {
         "TsActionPopupAction" | "ActionPopupAction" => Some(ActionPopupAction::decl()),
         "ActionPopupOptions" => Some(ActionPopupOptions::decl()),
         "TsHighlightSpan" => Some(TsHighlightSpan::decl()),
+        "SyntaxNode" => Some(SyntaxNode::decl()),
+        "SyntaxCapture" => Some(SyntaxCapture::decl()),
+        "CreateTerminalOptions" => Some(CreateTerminalOptions::decl()),
+        "TerminalResult" => Some(TerminalResult::decl()),
+        "StatusBarItemOptions" => Some(StatusBarItemOptions::decl()),
+        "FormField" => Some(FormField::decl()),
+        "FormButton" => Some(FormButton::decl()),
+        "FormOptions" => Some(FormOptions::decl()),
         "FileExplorerDecoration" => Some(FileExplorerDecoration::decl()),
 
         // Virtual buffer option types
@@ -88,6 +107,14 @@ fn get_type_decl(type_name: &str) -> Option<String> {
         "JsRange" => Some(JsRange::decl()),
         "JsPosition" => Some(JsPosition::decl()),
 
+        // Language feature provider types
+        "LanguageFeature" => Some(LanguageFeature::decl()),
+        "LanguageFeatureRequest" => Some(LanguageFeatureRequest::decl()),
+        "ProviderCompletionItem" => Some(ProviderCompletionItem::decl()),
+        "ProviderLocation" => Some(ProviderLocation::decl()),
+        "ProviderCodeAction" => Some(ProviderCodeAction::decl()),
+        "ProviderDiagnostic" => Some(ProviderDiagnostic::decl()),
+
         // Language pack types
         "LanguagePackConfig" => Some(LanguagePackConfig::decl()),
         "LspServerPackConfig" => Some(LspServerPackConfig::decl()),
@@ -119,10 +146,19 @@ const DEPENDENCY_TYPES: &[&str] = &[
     "JsRange",                        // Used by JsDiagnostic
     "JsPosition",                     // Used by JsRange
     "ActionSpec",                     // Used by executeActions
+    "SelectionKind",                  // Used by SelectionSpec
     "TsActionPopupAction",            // Used by ActionPopupOptions.actions
     "ActionPopupOptions",             // Used by showActionPopup
+    "FormField",                      // Used by FormOptions.fields
+    "FormButton",                     // Used by FormOptions.buttons
     "FileExplorerDecoration",         // Used by setFileExplorerDecorations
     "FormatterPackConfig",            // Used by LanguagePackConfig.formatter
+    "LanguageFeature",                // Used by LanguageFeatureRequest
+    "LanguageFeatureRequest",         // Passed to language provider handlers
+    "ProviderCompletionItem",         // Returned by completion providers
+    "ProviderLocation",               // Returned by definition providers
+    "ProviderCodeAction",             // Returned by code action providers
+    "ProviderDiagnostic",             // Returned by diagnostics providers
 ];
 
 /// Collect TypeScript type declarations based on referenced types from proc macro
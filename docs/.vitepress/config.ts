@@ -36,6 +36,7 @@ export default defineConfig({
           { text: "Navigation", link: "/features/navigation" },
           { text: "File Explorer", link: "/features/file-explorer" },
           { text: "Search and Replace", link: "/features/search-replace" },
+          { text: "Comparing Files", link: "/features/compare" },
           { text: "Integrated Terminal", link: "/features/terminal" },
           { text: "LSP Integration", link: "/features/lsp" },
           { text: "Themes", link: "/features/themes" },